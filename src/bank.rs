@@ -0,0 +1,208 @@
+//! A bank of decoders for multi-channel input.
+//!
+//! CW skimmers decode many signals of an SDR passband at once, and classroom hardware
+//! has a key per student. [DecoderBank] keeps a decoder per channel in a plain array,
+//! so signal events of all channels go through a single call with a channel index
+//! and the results can be walked over together.
+//!
+//! ```rust
+//! use morse_codec::{
+//!     bank::DecoderBank,
+//!     decoder::{Decoder, DecodeEvent, Precision},
+//! };
+//!
+//! let builder = Decoder::<16, u8>::new()
+//!     .with_precision(Precision::Accurate)
+//!     .with_reference_short_ms(100);
+//! let mut bank = DecoderBank::<4, 16, u8>::from_builder(builder);
+//!
+//! bank.signal_event(0, 100, true);
+//! bank.signal_event(2, 300, true);
+//! assert!(matches!(bank.signal_event(0, 300, false), Some(DecodeEvent::CharCompleted(_))));
+//! bank.signal_event(2, 300, false);
+//!
+//! // No such channel
+//! assert_eq!(bank.signal_event(4, 100, true), None);
+//!
+//! let messages: Vec<(usize, &str)> = bank.messages()
+//!     .filter(|(_, message)| !message.is_empty())
+//!     .map(|(channel, message)| (channel, message.as_str()))
+//!     .collect();
+//! assert_eq!(messages, [(0, "E"), (2, "T")]);
+//! ```
+//!
+//! With the utf8 feature, `CodebookDetector` is an experimental use of a bank for monitoring traffic
+//! of an unknown language or region. Every decoder of the bank decodes the same signals with another
+//! codebook, and the one making the fewest decoding errors is most likely the one the traffic is in.
+
+use crate::{
+    decoder::{DecodeEvent, Decoder, MorseDecoder},
+    message::Message,
+    Character,
+    MorseCharacter,
+};
+
+#[cfg(feature = "utf8")]
+use crate::charsets::Codebook;
+
+/// Decoders of CH channels with messages of MSG_MAX characters.
+#[derive(Clone, Debug)]
+pub struct DecoderBank<const CH: usize, const MSG_MAX: usize, C: MorseCharacter = Character> {
+    decoders: [MorseDecoder<MSG_MAX, C>; CH],
+}
+
+impl<const CH: usize, const MSG_MAX: usize, C: MorseCharacter> DecoderBank<CH, MSG_MAX, C> {
+    /// Create a bank from built decoders, one for each channel.
+    pub fn new(decoders: [MorseDecoder<MSG_MAX, C>; CH]) -> Self {
+        Self { decoders }
+    }
+
+    /// Create a bank with a decoder built from the same builder for every channel.
+    pub fn from_builder(builder: Decoder<MSG_MAX, C>) -> Self {
+        Self {
+            decoders: core::array::from_fn(|_| builder.clone().build()),
+        }
+    }
+
+    /// Send a signal event to the decoder of the channel, same as its `signal_event_ex`.
+    ///
+    /// Returns what happened to the signal event, or None if there's no such channel.
+    pub fn signal_event(&mut self, channel: usize, duration_ms: u16, is_high: bool) -> Option<DecodeEvent<C>> {
+        self.decoders
+            .get_mut(channel)
+            .map(|decoder| decoder.signal_event_ex(duration_ms, is_high))
+    }
+
+    /// End the characters being decoded on all channels, see `signal_event_end` of the decoder.
+    pub fn signal_event_end_all(&mut self, end_word: bool) {
+        self.decoders
+            .iter_mut()
+            .for_each(|decoder| decoder.signal_event_end(end_word));
+    }
+
+    /// Returns the decoder of the channel.
+    pub fn decoder(&self, channel: usize) -> Option<&MorseDecoder<MSG_MAX, C>> {
+        self.decoders.get(channel)
+    }
+
+    /// Returns the decoder of the channel for sending signals or editing its message.
+    pub fn decoder_mut(&mut self, channel: usize) -> Option<&mut MorseDecoder<MSG_MAX, C>> {
+        self.decoders.get_mut(channel)
+    }
+
+    /// Get an iterator of (channel, decoder) pairs of all channels.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &MorseDecoder<MSG_MAX, C>)> + '_ {
+        self.decoders.iter().enumerate()
+    }
+
+    /// Get an iterator of (channel, decoder) pairs of all channels for changing them.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (usize, &mut MorseDecoder<MSG_MAX, C>)> + '_ {
+        self.decoders.iter_mut().enumerate()
+    }
+
+    /// Get an iterator of (channel, message) pairs of all channels.
+    pub fn messages(&self) -> impl Iterator<Item = (usize, &Message<MSG_MAX, C>)> + '_ {
+        self.iter().map(|(channel, decoder)| (channel, &decoder.message))
+    }
+}
+
+/// Decoders of the same signals with different codebooks, to find out which one the traffic is in.
+///
+/// This is experimental. Codebooks share most of their codes, so it takes a few words
+/// with characters only some codebooks have before the errors tell them apart. Until then,
+/// codebooks earlier in the array win the ties, so the most likely one should come first.
+///
+/// ```rust
+/// use morse_codec::{
+///     bank::CodebookDetector,
+///     charsets::by_name,
+///     decoder::{Decoder, Precision},
+/// };
+///
+/// let builder = Decoder::<32>::new()
+///     .with_precision(Precision::Accurate)
+///     .with_reference_short_ms(100);
+/// let codebooks = [by_name("international").unwrap(), by_name("cyrillic").unwrap()];
+/// let mut detector = CodebookDetector::new(builder, codebooks);
+///
+/// // ---- is Ш in cyrillic, international doesn't have it
+/// for _ in 0..4 {
+///     detector.signal_event(300, true);
+///     detector.signal_event(100, false);
+/// }
+/// detector.signal_event_end(true);
+///
+/// let (codebook, decoder) = detector.best().unwrap();
+/// assert_eq!(codebook.name, "cyrillic");
+/// assert_eq!(decoder.message.as_str(), "Ш ");
+/// ```
+#[cfg(feature = "utf8")]
+#[derive(Clone, Debug)]
+pub struct CodebookDetector<const N: usize, const MSG_MAX: usize> {
+    bank: DecoderBank<N, MSG_MAX, char>,
+    codebooks: [Codebook; N],
+}
+
+#[cfg(feature = "utf8")]
+impl<const N: usize, const MSG_MAX: usize> CodebookDetector<N, MSG_MAX> {
+    /// Create a detector with a decoder for each codebook, built from the same builder.
+    pub fn new(builder: Decoder<MSG_MAX, char>, codebooks: [Codebook; N]) -> Self {
+        let decoders = codebooks.map(|codebook| {
+            builder
+                .clone()
+                .with_character_set(codebook.character_set)
+                .with_morse_code_set(codebook.morse_code_set)
+                .build()
+        });
+
+        Self {
+            bank: DecoderBank::new(decoders),
+            codebooks,
+        }
+    }
+
+    /// Send a signal event to the decoders of all codebooks.
+    pub fn signal_event(&mut self, duration_ms: u16, is_high: bool) {
+        for channel in 0..N {
+            self.bank.signal_event(channel, duration_ms, is_high);
+        }
+    }
+
+    /// End the characters being decoded with all codebooks, see `signal_event_end` of the decoder.
+    pub fn signal_event_end(&mut self, end_word: bool) {
+        self.bank.signal_event_end_all(end_word);
+    }
+
+    /// Get an iterator of (codebook, decoding error count) pairs of all codebooks.
+    pub fn errors(&self) -> impl Iterator<Item = (&Codebook, u32)> + '_ {
+        self.codebooks
+            .iter()
+            .zip(self.bank.iter())
+            .map(|(codebook, (_, decoder))| (codebook, decoder.get_error_count()))
+    }
+
+    /// Returns the codebook with the fewest decoding errors and its decoder.
+    ///
+    /// Returns None if nothing was decoded yet.
+    pub fn best(&self) -> Option<(&Codebook, &MorseDecoder<MSG_MAX, char>)> {
+        self.codebooks
+            .iter()
+            .zip(self.bank.iter())
+            .filter(|(_, (_, decoder))| decoder.get_decoded_char_count() + decoder.get_error_count() > 0)
+            .min_by_key(|(_, (_, decoder))| decoder.get_error_count())
+            .map(|(codebook, (_, decoder))| (codebook, decoder))
+    }
+
+    /// Returns the bank of decoders, one channel for each codebook in order.
+    pub fn bank(&mut self) -> &mut DecoderBank<N, MSG_MAX, char> {
+        &mut self.bank
+    }
+
+    /// Clear the messages and error counts of all codebooks to start detecting from scratch.
+    pub fn reset(&mut self) {
+        for (_, decoder) in self.bank.iter_mut() {
+            decoder.message.clear();
+            decoder.reset_metrics();
+        }
+    }
+}
@@ -0,0 +1,128 @@
+//! Helpers to play an audible sidetone on a buzzer or speaker connected to an MCU.
+//!
+//! Passive buzzers need a square wave at audio rate to make a sound. This can be
+//! done either with a PWM peripheral set to the tone frequency, or by toggling a plain GPIO pin
+//! at every half period of the tone. Both need some timer tick arithmetic that everyone ends up
+//! writing by hand, so [Buzzer] does it given the frequency of the timer and the tone.
+//!
+//! ```rust
+//! use morse_codec::{
+//!     buzzer::{Buzzer, BuzzerStep},
+//!     encoder::{Encoder, SDM},
+//! };
+//!
+//! // 1 MHz timer and a 500 Hz sidetone
+//! let buzzer = Buzzer::new(1_000_000, 500);
+//!
+//! let mut encoder = Encoder::<8>::new().build();
+//! encoder.encode_character(&(b'E' as morse_codec::Character)).unwrap();
+//! let sdm = encoder.get_last_char_as_sdm().unwrap();
+//!
+//! // E is a dit followed by a character space
+//! let mut steps = buzzer.gpio_schedule(&sdm, 100);
+//! // A dit of 100 ms at 500 Hz is 50 periods, so 100 toggles of the pin
+//! // waiting 1000 timer ticks between each.
+//! assert_eq!(steps.next(), Some(BuzzerStep::Tone { toggles: 100, half_period_ticks: 1000 }));
+//! // Character space of 300 ms is 300_000 timer ticks of silence
+//! assert_eq!(steps.next(), Some(BuzzerStep::Silence { ticks: 300_000 }));
+//! assert_eq!(steps.next(), None);
+//! ```
+
+use crate::encoder::{SDMArray, SDM};
+
+/// A step in the GPIO toggling schedule of a buzzer.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BuzzerStep {
+    /// Toggle the pin `toggles` times waiting `half_period_ticks` timer ticks after each toggle.
+    Tone { toggles: u32, half_period_ticks: u32 },
+    /// Keep the pin low for `ticks` timer ticks.
+    Silence { ticks: u32 },
+}
+
+/// Settings for a PWM peripheral to generate the tone.
+///
+/// Set the PWM period (or top value) to `period_ticks` and
+/// the duty (or compare value) to `duty_ticks` during high signals.
+/// During low signals set the duty to 0 to silence the buzzer.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PwmSettings {
+    pub period_ticks: u32,
+    pub duty_ticks: u32,
+}
+
+/// Sidetone generator settings calculated from a timer frequency and a tone frequency.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Buzzer {
+    timer_hz: u32,
+    tone_hz: u32,
+}
+
+impl Buzzer {
+    /// Create a new buzzer helper.
+    ///
+    /// `timer_hz` is the tick frequency of the timer driving the PWM or GPIO toggling
+    /// and `tone_hz` is the desired sidetone frequency. A tone frequency of 0 is
+    /// changed to 1 Hz to avoid divisions by zero.
+    pub fn new(timer_hz: u32, tone_hz: u32) -> Self {
+        Self {
+            timer_hz,
+            tone_hz: tone_hz.max(1),
+        }
+    }
+
+    /// Returns timer ticks of a full period of the tone.
+    pub fn period_ticks(&self) -> u32 {
+        self.timer_hz / self.tone_hz
+    }
+
+    /// Returns timer ticks of half a period of the tone. A GPIO pin should be
+    /// toggled every time this many ticks pass to generate the tone.
+    pub fn half_period_ticks(&self) -> u32 {
+        self.period_ticks() / 2
+    }
+
+    /// Returns timer ticks of a signal duration in milliseconds.
+    pub fn ms_to_ticks(&self, duration_ms: u32) -> u32 {
+        (self.timer_hz as u64 * duration_ms as u64 / 1000) as u32
+    }
+
+    /// Returns how many times a GPIO pin should be toggled to play the tone for a duration.
+    ///
+    /// The result is always even, so the pin will be back to low at the end of the signal.
+    pub fn toggles_for(&self, duration_ms: u32) -> u32 {
+        let periods = self.tone_hz as u64 * duration_ms as u64 / 1000;
+
+        (periods * 2) as u32
+    }
+
+    /// Returns PWM settings for a square wave tone with a duty cycle in percents.
+    ///
+    /// Duty cycle is clamped between 0 and 100. 50 percent gives the loudest tone
+    /// on most passive buzzers.
+    pub fn pwm_settings(&self, duty_percent: u8) -> PwmSettings {
+        let period_ticks = self.period_ticks();
+
+        PwmSettings {
+            period_ticks,
+            duty_ticks: period_ticks * duty_percent.min(100) as u32 / 100,
+        }
+    }
+
+    /// Get the GPIO toggling schedule of an SDM array.
+    ///
+    /// `short_ms` is the duration of a short signal (dit) that SDM values will be multiplied with.
+    /// High signals will become [BuzzerStep::Tone] and low signals [BuzzerStep::Silence].
+    /// Empty SDM values are skipped.
+    pub fn gpio_schedule<'a>(&'a self, sdm_array: &'a SDMArray, short_ms: u16) -> impl Iterator<Item = BuzzerStep> + 'a {
+        sdm_array.iter().filter_map(move |sdm| match sdm {
+            SDM::High(mul) => Some(BuzzerStep::Tone {
+                toggles: self.toggles_for(*mul as u32 * short_ms as u32),
+                half_period_ticks: self.half_period_ticks(),
+            }),
+            SDM::Low(mul) => Some(BuzzerStep::Silence {
+                ticks: self.ms_to_ticks(*mul as u32 * short_ms as u32),
+            }),
+            SDM::Empty => None,
+        })
+    }
+}
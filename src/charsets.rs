@@ -2,9 +2,11 @@
 
 use crate::{
     MorseSignal::{Long as L, Short as S},
+    MORSE_ARRAY_LENGTH,
     MORSE_DEFAULT_CHAR,
     MorseCodeArray,
     Character,
+    MorseCharacter,
 };
 
 /// Maximum number of characters in default mapping set of morse code to letters.
@@ -26,17 +28,22 @@ pub const DEFAULT_CHARACTER_SET_LENGTH: usize = 53;
 /// // Then
 /// let decoder = Decoder::<128>::new().with_character_set(my_set).build();
 /// ```
-pub type CharacterSet = &'static [Character];
+pub type CharacterSet<C = Character> = &'static [C];
 
 /// Default international morse code characters. It includes English language letters, numbers and
 /// punctuation marks. In utf8 mode a custom version of this array can be used while building an Encoder or Decoder
 /// using 'with_character_set' functions. Corresponding [MORSE_CODE_SET]
 /// can also be changed to support different languages.
-#[cfg(not(feature = "utf8"))]
-pub const DEFAULT_CHARACTER_SET: CharacterSet = b" ABCDEFGHIJKLMNOPQRSTUVWXYZ1234567890,?:-\"(=X.;/'_)+@";
+///
+/// This is either [DEFAULT_ASCII_CHARACTER_SET] or [DEFAULT_UTF8_CHARACTER_SET]
+/// depending on the "utf8" feature.
+pub const DEFAULT_CHARACTER_SET: CharacterSet = <Character as MorseCharacter>::DEFAULT_CHARACTER_SET;
 
-#[cfg(feature = "utf8")]
-pub const DEFAULT_CHARACTER_SET: CharacterSet = &[
+/// Default international morse code characters as ASCII bytes.
+pub const DEFAULT_ASCII_CHARACTER_SET: CharacterSet<u8> = b" ABCDEFGHIJKLMNOPQRSTUVWXYZ1234567890,?:-\"(=X.;/'_)+@";
+
+/// Default international morse code characters as UTF-8 chars.
+pub const DEFAULT_UTF8_CHARACTER_SET: CharacterSet<char> = &[
         ' ',
         'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S',
         'T', 'U', 'V', 'W', 'X', 'Y', 'Z',
@@ -44,6 +51,47 @@ pub const DEFAULT_CHARACTER_SET: CharacterSet = &[
         ',', '?', ':', '-', '"', '(', '=', 'X', '.', ';', '/', '\'', '_', ')', '+', '@',
     ];
 
+/// Maps characters to replacements that are applied before encoding.
+///
+/// Accented Latin letters that are not in the character set can be transliterated
+/// to plain letters this way, so European text can be keyed without a fully custom character set.
+/// Lookups ignore case. Setting a transliteration on the encoder is available with the "utf8" feature.
+///
+/// ```ignore
+/// let my_map: Transliteration = &[('å', "AA"), ('ø', "OE")];
+/// let encoder = Encoder::<64>::new().with_transliteration(my_map).build();
+/// ```
+pub type Transliteration = &'static [(char, &'static str)];
+
+/// German umlauts and sharp s as they're commonly written without them.
+#[cfg(feature = "utf8")]
+pub const GERMAN_TRANSLITERATION: Transliteration = &[
+    ('ä', "AE"), ('ö', "OE"), ('ü', "UE"), ('ß', "SS"),
+];
+
+/// Spanish accented letters as plain letters.
+#[cfg(feature = "utf8")]
+pub const SPANISH_TRANSLITERATION: Transliteration = &[
+    ('á', "A"), ('é', "E"), ('í', "I"), ('ó', "O"), ('ú', "U"), ('ü', "U"), ('ñ', "N"),
+    ('¿', "?"), ('¡', ""),
+];
+
+/// French accented letters and ligatures as plain letters.
+#[cfg(feature = "utf8")]
+pub const FRENCH_TRANSLITERATION: Transliteration = &[
+    ('à', "A"), ('â', "A"), ('æ', "AE"), ('ç', "C"), ('é', "E"), ('è', "E"), ('ê', "E"), ('ë', "E"),
+    ('î', "I"), ('ï', "I"), ('ô', "O"), ('œ', "OE"), ('ù', "U"), ('û', "U"), ('ü', "U"), ('ÿ', "Y"),
+];
+
+// Replacement of a character in a transliteration map ignoring case.
+#[cfg(feature = "encoder")]
+pub(crate) fn transliterate(transliteration: Transliteration, ch: char) -> Option<&'static str> {
+    transliteration
+        .iter()
+        .find(|(from, _)| *from == ch || from.to_lowercase().eq(ch.to_lowercase()))
+        .map(|(_, to)| *to)
+}
+
 /// Allows creating a custom morse code set.
 ///
 /// Client code can use this type to construct a different morse code mapping to characters
@@ -119,3 +167,573 @@ pub const DEFAULT_MORSE_CODE_SET: MorseCodeSet =
         [Some(S), Some(L), Some(L), Some(S), Some(L), Some(S)], // At sign              @
     ];
 
+/// Common accented letters with standard morse codes.
+///
+/// They can be added to the default UTF-8 character set with [EXTENDED_UTF8_CHARACTER_SET]
+/// and [EXTENDED_MORSE_CODE_SET], so German, Spanish and French operators get correct encodings.
+pub const ACCENTED_UTF8_CHARACTERS: CharacterSet<char> = &['Ä', 'Ö', 'Ü', 'É', 'Ñ', 'Ç'];
+
+/// Morse codes of [ACCENTED_UTF8_CHARACTERS].
+pub const ACCENTED_MORSE_CODES: MorseCodeSet = &[
+        [Some(S), Some(L), Some(S), Some(L), None, None],       // A with diaeresis     Ä
+        [Some(L), Some(L), Some(L), Some(S), None, None],       // O with diaeresis     Ö
+        [Some(S), Some(S), Some(L), Some(L), None, None],       // U with diaeresis     Ü
+        [Some(S), Some(S), Some(L), Some(S), Some(S), None],    // E with acute         É
+        [Some(L), Some(L), Some(S), Some(L), Some(L), None],    // N with tilde         Ñ
+        [Some(L), Some(S), Some(L), Some(S), Some(S), None],    // C with cedilla       Ç
+    ];
+
+/// Number of characters in the extended character set.
+pub const EXTENDED_CHARACTER_SET_LENGTH: usize = DEFAULT_CHARACTER_SET_LENGTH + 6;
+
+/// Default UTF-8 character set extended with [ACCENTED_UTF8_CHARACTERS].
+///
+/// ```ignore
+/// let decoder = Decoder::<64, char>::new()
+///     .with_character_set(EXTENDED_UTF8_CHARACTER_SET)
+///     .with_morse_code_set(EXTENDED_MORSE_CODE_SET)
+///     .build();
+/// ```
+pub const EXTENDED_UTF8_CHARACTER_SET: CharacterSet<char> =
+    &extend_set::<char, EXTENDED_CHARACTER_SET_LENGTH>(DEFAULT_UTF8_CHARACTER_SET, ACCENTED_UTF8_CHARACTERS, ' ');
+
+/// Default morse code set extended with [ACCENTED_MORSE_CODES].
+pub const EXTENDED_MORSE_CODE_SET: MorseCodeSet =
+    &extend_set::<MorseCodeArray, EXTENDED_CHARACTER_SET_LENGTH>(DEFAULT_MORSE_CODE_SET, ACCENTED_MORSE_CODES, MORSE_DEFAULT_CHAR);
+
+/// Punctuation marks that are not in the default character set, only the exclamation mark for now.
+///
+/// Dollar sign (7 signals) and the error prosign (8 dits) are left out, because they don't fit
+/// into morse code arrays of 6 signals.
+pub const EXTRA_PUNCTUATION_CHARACTERS: CharacterSet<u8> = b"!";
+
+/// Morse codes of [EXTRA_PUNCTUATION_CHARACTERS].
+pub const EXTRA_PUNCTUATION_MORSE_CODES: MorseCodeSet = &[
+        [Some(L), Some(S), Some(L), Some(S), Some(L), Some(L)], // Exclamation mark     !
+    ];
+
+/// Default ASCII character set extended with [EXTRA_PUNCTUATION_CHARACTERS].
+pub const PUNCTUATION_EXTENDED_ASCII_CHARACTER_SET: CharacterSet<u8> =
+    &extend_set::<u8, { DEFAULT_CHARACTER_SET_LENGTH + 1 }>(DEFAULT_ASCII_CHARACTER_SET, EXTRA_PUNCTUATION_CHARACTERS, b' ');
+
+/// Default morse code set extended with [EXTRA_PUNCTUATION_MORSE_CODES].
+pub const PUNCTUATION_EXTENDED_MORSE_CODE_SET: MorseCodeSet =
+    &extend_set::<MorseCodeArray, { DEFAULT_CHARACTER_SET_LENGTH + 1 }>(DEFAULT_MORSE_CODE_SET, EXTRA_PUNCTUATION_MORSE_CODES, MORSE_DEFAULT_CHAR);
+
+/// Concatenate a base set and an extension to it in const context.
+///
+/// This can be used to build custom character and morse code sets out of the sets
+/// in this module. N is the total length, which is checked at compile time.
+/// Both character and morse code sets should be extended the same way.
+///
+/// ```rust
+/// use morse_codec::charsets::{
+///     extend_set, CharacterSet, DEFAULT_CHARACTER_SET_LENGTH, DEFAULT_UTF8_CHARACTER_SET,
+///     PUNCTUATION_EXTENDED_MORSE_CODE_SET,
+/// };
+///
+/// // UTF-8 version of the extra punctuation characters
+/// const CHARS: CharacterSet<char> = &extend_set::<char, { DEFAULT_CHARACTER_SET_LENGTH + 1 }>(
+///     DEFAULT_UTF8_CHARACTER_SET,
+///     &['!'],
+///     ' ',
+/// );
+///
+/// assert_eq!(CHARS.len(), PUNCTUATION_EXTENDED_MORSE_CODE_SET.len());
+/// ```
+pub const fn extend_set<T: Copy, const N: usize>(base: &[T], extension: &[T], filler: T) -> [T; N] {
+    let mut set = [filler; N];
+    let mut i = 0;
+
+    while i < N {
+        set[i] = if i < base.len() { base[i] } else { extension[i - base.len()] };
+        i += 1;
+    }
+
+    set
+}
+
+/// Digits and punctuation marks of the default set, shared by the non-Latin codebooks.
+///
+/// The multiplication sign X is left out, its code is a letter in Cyrillic and Greek.
+#[cfg(feature = "utf8")]
+const COMMON_UTF8_CHARACTERS: CharacterSet<char> = &[
+    '1', '2', '3', '4', '5', '6', '7', '8', '9', '0', ',', '?', ':', '-', '"', '(', '=', '.', ';', '/',
+    '\'', '_', ')', '+', '@',
+];
+
+#[cfg(feature = "utf8")]
+const COMMON_MORSE_CODES: MorseCodeSet = &[
+        [Some(S), Some(L), Some(L), Some(L), Some(L), None],    // One                  1
+        [Some(S), Some(S), Some(L), Some(L), Some(L), None],    // Two                  2
+        [Some(S), Some(S), Some(S), Some(L), Some(L), None],    // Three                3
+        [Some(S), Some(S), Some(S), Some(S), Some(L), None],    // Four                 4
+        [Some(S), Some(S), Some(S), Some(S), Some(S), None],    // Five                 5
+        [Some(L), Some(S), Some(S), Some(S), Some(S), None],    // Six                  6
+        [Some(L), Some(L), Some(S), Some(S), Some(S), None],    // Seven                7
+        [Some(L), Some(L), Some(L), Some(S), Some(S), None],    // Eight                8
+        [Some(L), Some(L), Some(L), Some(L), Some(S), None],    // Nine                 9
+        [Some(L), Some(L), Some(L), Some(L), Some(L), None],    // Zero                 0
+        [Some(L), Some(L), Some(S), Some(S), Some(L), Some(L)], // Comma                ,
+        [Some(S), Some(S), Some(L), Some(L), Some(S), Some(S)], // Question mark        ?
+        [Some(L), Some(L), Some(L), Some(S), Some(S), Some(S)], // Colon                :
+        [Some(L), Some(S), Some(S), Some(S), Some(S), Some(L)], // Dash                 -
+        [Some(S), Some(L), Some(S), Some(S), Some(L), Some(S)], // Double quote         "
+        [Some(L), Some(S), Some(L), Some(L), Some(S), None],    // Left bracket         (
+        [Some(L), Some(S), Some(S), Some(S), Some(L), None],    // Equals               =
+        [Some(S), Some(L), Some(S), Some(L), Some(S), Some(L)], // Full stop (period)   .
+        [Some(L), Some(S), Some(L), Some(S), Some(L), Some(S)], // Semicolon            ;
+        [Some(L), Some(S), Some(S), Some(L), Some(S), None],    // Slash                /
+        [Some(S), Some(L), Some(L), Some(L), Some(L), Some(S)], // Apostrophe           '
+        [Some(S), Some(S), Some(L), Some(L), Some(S), Some(L)], // Underscore           _
+        [Some(L), Some(S), Some(L), Some(L), Some(S), Some(L)], // Right bracket        )
+        [Some(S), Some(L), Some(S), Some(L), Some(S), None],    // Addition             +
+        [Some(S), Some(L), Some(L), Some(S), Some(L), Some(S)], // At sign              @
+    ];
+
+/// Cyrillic letters of Russian morse code, in the order of the alphabet.
+pub const CYRILLIC_UTF8_CHARACTERS: CharacterSet<char> = &[
+    'А', 'Б', 'В', 'Г', 'Д', 'Е', 'Ж', 'З', 'И', 'Й', 'К', 'Л', 'М', 'Н', 'О', 'П',
+    'Р', 'С', 'Т', 'У', 'Ф', 'Х', 'Ц', 'Ч', 'Ш', 'Щ', 'Ъ', 'Ы', 'Ь', 'Э', 'Ю', 'Я',
+];
+
+/// Morse codes of [CYRILLIC_UTF8_CHARACTERS].
+pub const CYRILLIC_MORSE_CODES: MorseCodeSet = &[
+        [Some(S), Some(L), None, None, None, None],             // A                    А
+        [Some(L), Some(S), Some(S), Some(S), None, None],       // Be                   Б
+        [Some(S), Some(L), Some(L), None, None, None],          // Ve                   В
+        [Some(L), Some(L), Some(S), None, None, None],          // Ge                   Г
+        [Some(L), Some(S), Some(S), None, None, None],          // De                   Д
+        [Some(S), None, None, None, None, None],                // Ye                   Е
+        [Some(S), Some(S), Some(S), Some(L), None, None],       // Zhe                  Ж
+        [Some(L), Some(L), Some(S), Some(S), None, None],       // Ze                   З
+        [Some(S), Some(S), None, None, None, None],             // I                    И
+        [Some(S), Some(L), Some(L), Some(L), None, None],       // Short I              Й
+        [Some(L), Some(S), Some(L), None, None, None],          // Ka                   К
+        [Some(S), Some(L), Some(S), Some(S), None, None],       // El                   Л
+        [Some(L), Some(L), None, None, None, None],             // Em                   М
+        [Some(L), Some(S), None, None, None, None],             // En                   Н
+        [Some(L), Some(L), Some(L), None, None, None],          // O                    О
+        [Some(S), Some(L), Some(L), Some(S), None, None],       // Pe                   П
+        [Some(S), Some(L), Some(S), None, None, None],          // Er                   Р
+        [Some(S), Some(S), Some(S), None, None, None],          // Es                   С
+        [Some(L), None, None, None, None, None],                // Te                   Т
+        [Some(S), Some(S), Some(L), None, None, None],          // U                    У
+        [Some(S), Some(S), Some(L), Some(S), None, None],       // Ef                   Ф
+        [Some(S), Some(S), Some(S), Some(S), None, None],       // Kha                  Х
+        [Some(L), Some(S), Some(L), Some(S), None, None],       // Tse                  Ц
+        [Some(L), Some(L), Some(L), Some(S), None, None],       // Che                  Ч
+        [Some(L), Some(L), Some(L), Some(L), None, None],       // Sha                  Ш
+        [Some(L), Some(L), Some(S), Some(L), None, None],       // Shcha                Щ
+        [Some(L), Some(L), Some(S), Some(L), Some(L), None],    // Hard sign            Ъ
+        [Some(L), Some(S), Some(L), Some(L), None, None],       // Yery                 Ы
+        [Some(L), Some(S), Some(S), Some(L), None, None],       // Soft sign            Ь
+        [Some(S), Some(S), Some(L), Some(S), Some(S), None],    // E                    Э
+        [Some(S), Some(S), Some(L), Some(L), None, None],       // Yu                   Ю
+        [Some(S), Some(L), Some(S), Some(L), None, None],       // Ya                   Я
+    ];
+
+/// Greek letters of Greek morse code, in the order of the alphabet.
+pub const GREEK_UTF8_CHARACTERS: CharacterSet<char> = &[
+    'Α', 'Β', 'Γ', 'Δ', 'Ε', 'Ζ', 'Η', 'Θ', 'Ι', 'Κ', 'Λ', 'Μ',
+    'Ν', 'Ξ', 'Ο', 'Π', 'Ρ', 'Σ', 'Τ', 'Υ', 'Φ', 'Χ', 'Ψ', 'Ω',
+];
+
+/// Morse codes of [GREEK_UTF8_CHARACTERS].
+pub const GREEK_MORSE_CODES: MorseCodeSet = &[
+        [Some(S), Some(L), None, None, None, None],             // Alpha                Α
+        [Some(L), Some(S), Some(S), Some(S), None, None],       // Beta                 Β
+        [Some(L), Some(L), Some(S), None, None, None],          // Gamma                Γ
+        [Some(L), Some(S), Some(S), None, None, None],          // Delta                Δ
+        [Some(S), None, None, None, None, None],                // Epsilon              Ε
+        [Some(L), Some(L), Some(S), Some(S), None, None],       // Zeta                 Ζ
+        [Some(S), Some(S), Some(S), Some(S), None, None],       // Eta                  Η
+        [Some(L), Some(S), Some(L), Some(S), None, None],       // Theta                Θ
+        [Some(S), Some(S), None, None, None, None],             // Iota                 Ι
+        [Some(L), Some(S), Some(L), None, None, None],          // Kappa                Κ
+        [Some(S), Some(L), Some(S), Some(S), None, None],       // Lambda               Λ
+        [Some(L), Some(L), None, None, None, None],             // Mu                   Μ
+        [Some(L), Some(S), None, None, None, None],             // Nu                   Ν
+        [Some(L), Some(S), Some(S), Some(L), None, None],       // Xi                   Ξ
+        [Some(L), Some(L), Some(L), None, None, None],          // Omicron              Ο
+        [Some(S), Some(L), Some(L), Some(S), None, None],       // Pi                   Π
+        [Some(S), Some(L), Some(S), None, None, None],          // Rho                  Ρ
+        [Some(S), Some(S), Some(S), None, None, None],          // Sigma                Σ
+        [Some(L), None, None, None, None, None],                // Tau                  Τ
+        [Some(L), Some(S), Some(L), Some(L), None, None],       // Upsilon              Υ
+        [Some(S), Some(S), Some(L), Some(S), None, None],       // Phi                  Φ
+        [Some(L), Some(L), Some(L), Some(L), None, None],       // Chi                  Χ
+        [Some(L), Some(L), Some(S), Some(L), None, None],       // Psi                  Ψ
+        [Some(S), Some(L), Some(L), None, None, None],          // Omega                Ω
+    ];
+
+#[cfg(feature = "utf8")]
+const CYRILLIC_CHARACTER_SET_LENGTH: usize = 1 + 32 + 25;
+
+#[cfg(feature = "utf8")]
+const GREEK_CHARACTER_SET_LENGTH: usize = 1 + 24 + 25;
+
+#[cfg(feature = "utf8")]
+const CYRILLIC_CHARACTER_SET: [char; CYRILLIC_CHARACTER_SET_LENGTH] =
+    extend_set(&extend_set::<char, 33>(&[' '], CYRILLIC_UTF8_CHARACTERS, ' '), COMMON_UTF8_CHARACTERS, ' ');
+
+#[cfg(feature = "utf8")]
+const CYRILLIC_MORSE_CODE_SET: [MorseCodeArray; CYRILLIC_CHARACTER_SET_LENGTH] =
+    extend_set(&extend_set::<MorseCodeArray, 33>(&[MORSE_DEFAULT_CHAR], CYRILLIC_MORSE_CODES, MORSE_DEFAULT_CHAR), COMMON_MORSE_CODES, MORSE_DEFAULT_CHAR);
+
+#[cfg(feature = "utf8")]
+const GREEK_CHARACTER_SET: [char; GREEK_CHARACTER_SET_LENGTH] =
+    extend_set(&extend_set::<char, 25>(&[' '], GREEK_UTF8_CHARACTERS, ' '), COMMON_UTF8_CHARACTERS, ' ');
+
+#[cfg(feature = "utf8")]
+const GREEK_MORSE_CODE_SET: [MorseCodeArray; GREEK_CHARACTER_SET_LENGTH] =
+    extend_set(&extend_set::<MorseCodeArray, 25>(&[MORSE_DEFAULT_CHAR], GREEK_MORSE_CODES, MORSE_DEFAULT_CHAR), COMMON_MORSE_CODES, MORSE_DEFAULT_CHAR);
+
+/// A character set and its morse code set packaged under a name, ie. for a language menu.
+#[cfg(feature = "utf8")]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Codebook {
+    pub name: &'static str,
+    pub character_set: CharacterSet<char>,
+    pub morse_code_set: MorseCodeSet,
+}
+
+/// All codebooks that can be looked up with [by_name].
+///
+/// * "international": Default UTF-8 character set.
+/// * "accented": Default set extended with [ACCENTED_UTF8_CHARACTERS].
+/// * "cyrillic": Russian letters with digits and punctuation marks.
+/// * "greek": Greek letters with digits and punctuation marks.
+#[cfg(feature = "utf8")]
+pub const CODEBOOKS: &[Codebook] = &[
+    Codebook { name: "international", character_set: DEFAULT_UTF8_CHARACTER_SET, morse_code_set: DEFAULT_MORSE_CODE_SET },
+    Codebook { name: "accented", character_set: EXTENDED_UTF8_CHARACTER_SET, morse_code_set: EXTENDED_MORSE_CODE_SET },
+    Codebook { name: "cyrillic", character_set: &CYRILLIC_CHARACTER_SET, morse_code_set: &CYRILLIC_MORSE_CODE_SET },
+    Codebook { name: "greek", character_set: &GREEK_CHARACTER_SET, morse_code_set: &GREEK_MORSE_CODE_SET },
+];
+
+/// Find a codebook in [CODEBOOKS] by its name, ignoring case.
+///
+/// ```rust
+/// use morse_codec::{charsets::by_name, decoder::Decoder};
+///
+/// let codebook = by_name("Cyrillic").unwrap();
+/// let decoder = Decoder::<64>::new()
+///     .with_character_set(codebook.character_set)
+///     .with_morse_code_set(codebook.morse_code_set)
+///     .build();
+///
+/// assert!(by_name("klingon").is_none());
+/// ```
+#[cfg(feature = "utf8")]
+pub fn by_name(name: &str) -> Option<Codebook> {
+    CODEBOOKS
+        .iter()
+        .find(|codebook| codebook.name.eq_ignore_ascii_case(name))
+        .copied()
+}
+
+/// Problems of a character set and morse code set pair found by [validate].
+///
+/// Indices are positions in the sets. `index` is the first entry and `duplicate` the one repeating it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CharsetError {
+    /// Sets have different lengths.
+    LengthMismatch { characters: usize, codes: usize },
+    /// Same character has two different morse codes.
+    DuplicateCharacter { index: usize, duplicate: usize },
+    /// Same morse code is given to two different characters.
+    DuplicateCode { index: usize, duplicate: usize },
+    /// Morse code has a signal after an empty slot, ie. [Some(S), None, Some(L), ...].
+    MalformedCode { index: usize },
+}
+
+impl CharsetError {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CharsetError::LengthMismatch { .. } => "Character set and morse code set have different lengths.",
+            CharsetError::DuplicateCharacter { .. } => "Character has more than one morse code.",
+            CharsetError::DuplicateCode { .. } => "Morse code is given to more than one character.",
+            CharsetError::MalformedCode { .. } => "Morse code has a signal after an empty slot.",
+        }
+    }
+}
+
+impl core::fmt::Display for CharsetError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Check that a character set and a morse code set make a sound pair.
+///
+/// A typo in a custom table doesn't cause an error anywhere else, characters are just
+/// decoded or encoded wrong. Builders run this in `try_build`, so tables can be checked at startup.
+/// An entry repeating both the character and the code of an earlier entry is allowed,
+/// like the multiplication sign X of the default set, or fillers of [extend_set].
+///
+/// ```rust
+/// use morse_codec::{
+///     charsets::{validate, CharsetError},
+///     MorseSignal::{Long as L, Short as S},
+///     MORSE_DEFAULT_CHAR,
+///     DEFAULT_CHARACTER_SET,
+///     DEFAULT_MORSE_CODE_SET,
+/// };
+///
+/// assert_eq!(validate(DEFAULT_CHARACTER_SET, DEFAULT_MORSE_CODE_SET), Ok(()));
+///
+/// // Greek Beta and Gamma with the same code by mistake
+/// let characters: &[char] = &[' ', 'Α', 'Β', 'Γ'];
+/// let codes = &[
+///     MORSE_DEFAULT_CHAR,
+///     [Some(S), Some(L), None, None, None, None],
+///     [Some(L), Some(S), Some(S), Some(S), None, None],
+///     [Some(L), Some(S), Some(S), Some(S), None, None],
+/// ];
+/// assert_eq!(validate(characters, codes), Err(CharsetError::DuplicateCode { index: 2, duplicate: 3 }));
+/// ```
+pub fn validate<C: MorseCharacter>(character_set: CharacterSet<C>, morse_code_set: MorseCodeSet) -> Result<(), CharsetError> {
+    if character_set.len() != morse_code_set.len() {
+        return Err(CharsetError::LengthMismatch {
+            characters: character_set.len(),
+            codes: morse_code_set.len(),
+        });
+    }
+
+    for (duplicate, (ch, morse_char)) in character_set.iter().zip(morse_code_set).enumerate() {
+        if morse_tree_node(morse_char).is_none() {
+            return Err(CharsetError::MalformedCode { index: duplicate });
+        }
+
+        let earlier = character_set[..duplicate].iter().zip(&morse_code_set[..duplicate]);
+        for (index, (earlier_ch, earlier_morse_char)) in earlier.enumerate() {
+            match (earlier_ch == ch, earlier_morse_char == morse_char) {
+                (true, false) => return Err(CharsetError::DuplicateCharacter { index, duplicate }),
+                (false, true) => return Err(CharsetError::DuplicateCode { index, duplicate }),
+                _ => (),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Run together morse code of a prosign written as the letters it's made of, ie. "AR".
+// Returns None if a letter is not in the character set or the signals don't fit a character.
+#[cfg(any(feature = "decoder", feature = "encoder"))]
+pub(crate) fn get_prosign_morse_char<C: MorseCharacter>(
+    character_set: CharacterSet<C>,
+    morse_code_set: MorseCodeSet,
+    letters: &str,
+) -> Option<MorseCodeArray> {
+    let mut morse_array = MORSE_DEFAULT_CHAR;
+    let mut count = 0;
+
+    for letter in letters.chars() {
+        let ch = C::from_char(letter).and_then(|ch| ch.to_upper())?;
+        let index = character_set.iter().position(|setchar| *setchar == ch)?;
+
+        for signal in morse_code_set.get(index)?.iter().flatten() {
+            *morse_array.get_mut(count)? = Some(*signal);
+            count += 1;
+        }
+    }
+
+    (count > 0).then_some(morse_array)
+}
+
+// Nodes of a full binary tree as deep as the longest morse code, root included.
+const MORSE_TREE_LENGTH: usize = (1 << (MORSE_ARRAY_LENGTH + 1)) - 1;
+
+// Character set indices that fit into a tree node. The last u8 value marks an empty node.
+const MORSE_TREE_EMPTY: u8 = u8::MAX;
+
+/// Dichotomic morse tree of a character set for looking characters up by their morse code.
+///
+/// This is the classic tree of morse code charts, where every short signal goes down to the left
+/// and every long signal to the right. A lookup takes a step per signal instead of comparing
+/// the code with every code of the set, and all characters a partial code can still become
+/// are the ones under its node, see `completions`.
+///
+/// The tree is built from a character set and its morse code set, so it works the same way
+/// with custom sets. When codes are repeated in a set, the first character with the code is in the tree.
+///
+/// ```rust
+/// use morse_codec::{
+///     charsets::MorseTree,
+///     MorseSignal::{Long as L, Short as S},
+///     DEFAULT_CHARACTER_SET,
+///     DEFAULT_MORSE_CODE_SET,
+/// };
+///
+/// let tree = MorseTree::new(DEFAULT_CHARACTER_SET, DEFAULT_MORSE_CODE_SET);
+///
+/// let a = [Some(S), Some(L), None, None, None, None];
+/// assert_eq!(tree.get(&a), Some(b'A'.into()));
+///
+/// let completions: String = tree.completions(&a).take(6).map(|ch| ch as char).collect();
+/// assert_eq!(completions, "ARWLPJ");
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct MorseTree<C: MorseCharacter = Character> {
+    character_set: CharacterSet<C>,
+    morse_code_set: MorseCodeSet,
+    // Character set indices laid out as a binary heap. The root is the empty code, and the children
+    // of node i are 2i + 1 for a short signal and 2i + 2 for a long signal.
+    nodes: [u8; MORSE_TREE_LENGTH],
+    // Some characters didn't fit into the nodes, because the set has more than 255 of them
+    is_partial: bool,
+}
+
+impl<C: MorseCharacter> MorseTree<C> {
+    /// Build the tree of a character set and its morse code set.
+    pub fn new(character_set: CharacterSet<C>, morse_code_set: MorseCodeSet) -> Self {
+        let mut tree = Self {
+            character_set,
+            morse_code_set,
+            nodes: [MORSE_TREE_EMPTY; MORSE_TREE_LENGTH],
+            is_partial: false,
+        };
+
+        for (index, morse_char) in morse_code_set.iter().enumerate().take(character_set.len()) {
+            let Some(node) = morse_tree_node(morse_char) else {
+                continue;
+            };
+
+            if index >= MORSE_TREE_EMPTY as usize {
+                tree.is_partial = true;
+            } else if tree.nodes[node] == MORSE_TREE_EMPTY {
+                // First code wins like a linear search would do
+                tree.nodes[node] = index as u8;
+            }
+        }
+
+        tree
+    }
+
+    /// Returns the character of a morse code, or None if the set doesn't have the code.
+    ///
+    /// Codes with gaps in them, and codes of characters after the 255th character of a set
+    /// are searched linearly, so the result is always the same as a search of the sets.
+    pub fn get(&self, morse_char: &MorseCodeArray) -> Option<C> {
+        let index = match morse_tree_node(morse_char).map(|node| self.nodes[node]) {
+            Some(index) if index != MORSE_TREE_EMPTY => Some(index as usize),
+            Some(_) if !self.is_partial => None,
+            _ => self.morse_code_set.iter().position(|mchar| mchar == morse_char),
+        };
+
+        index.and_then(|i| self.character_set.get(i).copied())
+    }
+
+    /// Get an iterator of the characters a partial morse code can still become, the character of the code included.
+    ///
+    /// Characters are ordered by the length of their codes, and codes of the same length
+    /// by short signals before long ones. A code with gaps in it can't become anything.
+    /// Only the first 255 characters of a set are in the tree, so the rest are never given.
+    pub fn completions(&self, prefix: &MorseCodeArray) -> impl Iterator<Item = C> + '_ {
+        morse_subtree_nodes(prefix)
+            .filter_map(|node| match self.nodes[node] {
+                MORSE_TREE_EMPTY => None,
+                index => self.character_set.get(index as usize).copied(),
+            })
+    }
+}
+
+// ASCII codes a character index looks up directly.
+#[cfg(all(feature = "encoder", not(feature = "small-footprint")))]
+const CHAR_INDEX_LENGTH: usize = 128;
+
+// Character set indices by ASCII code, for looking morse codes up by their character.
+// This is the other way around of [MorseTree], so the encoder doesn't search the character set
+// for every character it encodes. Characters out of ASCII and after the 255th character
+// of a set are searched linearly, so the result is always the same as a search of the sets.
+#[cfg(all(feature = "encoder", not(feature = "small-footprint")))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct CharIndex<C: MorseCharacter> {
+    character_set: CharacterSet<C>,
+    morse_code_set: MorseCodeSet,
+    indices: [u8; CHAR_INDEX_LENGTH],
+    is_partial: bool,
+}
+
+#[cfg(all(feature = "encoder", not(feature = "small-footprint")))]
+impl<C: MorseCharacter> CharIndex<C> {
+    pub(crate) fn new(character_set: CharacterSet<C>, morse_code_set: MorseCodeSet) -> Self {
+        let mut char_index = Self {
+            character_set,
+            morse_code_set,
+            indices: [MORSE_TREE_EMPTY; CHAR_INDEX_LENGTH],
+            is_partial: false,
+        };
+
+        for (index, ch) in character_set.iter().enumerate() {
+            let Some(slot) = char_index.indices.get_mut(ch.to_char() as usize) else {
+                continue;
+            };
+
+            if index >= MORSE_TREE_EMPTY as usize {
+                char_index.is_partial = true;
+            } else if *slot == MORSE_TREE_EMPTY {
+                // First character wins like a linear search would do
+                *slot = index as u8;
+            }
+        }
+
+        char_index
+    }
+
+    pub(crate) fn get(&self, ch: &C) -> Option<MorseCodeArray> {
+        let index = match self.indices.get(ch.to_char() as usize) {
+            Some(&index) if index != MORSE_TREE_EMPTY => Some(index as usize),
+            Some(_) if !self.is_partial => None,
+            _ => self.character_set.iter().position(|setchar| setchar == ch),
+        };
+
+        index.and_then(|i| self.morse_code_set.get(i).copied())
+    }
+}
+
+// Node of a morse code in the heap layout of [MorseTree],
+// or None if there are signals after the end of it.
+fn morse_tree_node(morse_char: &MorseCodeArray) -> Option<usize> {
+    let length = morse_char.iter().take_while(|signal| signal.is_some()).count();
+
+    if morse_char[length..].iter().any(|signal| signal.is_some()) {
+        return None;
+    }
+
+    Some(morse_char[..length].iter().flatten().fold(0, |node, signal| match signal {
+        S => 2 * node + 1,
+        L => 2 * node + 2,
+    }))
+}
+
+// Nodes under the node of a morse code level by level, the node itself included.
+// Nodes under a node at each level are a continuous range in the heap layout.
+pub(crate) fn morse_subtree_nodes(prefix: &MorseCodeArray) -> impl Iterator<Item = usize> {
+    let root = morse_tree_node(prefix);
+    let depth = prefix.iter().take_while(|signal| signal.is_some()).count();
+    let levels = root.map_or(0, |_| MORSE_ARRAY_LENGTH - depth + 1);
+
+    (0..levels).flat_map(move |level| {
+        let root = root.unwrap_or(0);
+
+        ((root + 1) << level) - 1..((root + 2) << level) - 1
+    })
+}
+
+// Morse code of a node in the heap layout of [MorseTree]. Bits of node + 1 after the leading one
+// are the signals, 0 for short and 1 for long.
+#[cfg(feature = "decoder")]
+pub(crate) fn morse_tree_node_code(node: usize) -> MorseCodeArray {
+    let path = node + 1;
+    let depth = (usize::BITS - 1 - path.leading_zeros()) as usize;
+    let mut morse_char = MORSE_DEFAULT_CHAR;
+
+    for (i, signal) in morse_char.iter_mut().enumerate().take(depth) {
+        *signal = Some(if path >> (depth - 1 - i) & 1 == 0 { S } else { L });
+    }
+
+    morse_char
+}
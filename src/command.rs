@@ -0,0 +1,154 @@
+//! Dispatching of commands keyed in morse code.
+//!
+//! Morse controlled gadgets usually wait for a word or a phrase like "LIGHT ON"
+//! and then do something. [CommandSet] maps phrases to handler ids of the client code
+//! and reports the id when a registered phrase is completed by a word space in the decoder message.
+//! Phrases are matched at the end of the message, so whatever was keyed before them doesn't matter.
+//!
+//! ```rust
+//! use morse_codec::{
+//!     command::CommandSet,
+//!     decoder::{Decoder, Precision},
+//! };
+//!
+//! const LIGHT_ON: u16 = 1;
+//! const LIGHT_OFF: u16 = 2;
+//!
+//! let mut commands = CommandSet::<4>::new();
+//! commands.register("LIGHT ON", LIGHT_ON).unwrap();
+//! commands.register("LIGHT OFF", LIGHT_OFF).unwrap();
+//!
+//! let mut decoder = Decoder::<32>::new()
+//!     .with_precision(Precision::Accurate)
+//!     .with_reference_short_ms(100)
+//!     .build();
+//!
+//! decoder.decode_text(".-.. .. --. .... - / ---", &Default::default());
+//!
+//! // Keying N of "ON" followed by a word space completes the command
+//! assert_eq!(commands.feed(&mut decoder, 300, true), None);
+//! assert_eq!(commands.feed(&mut decoder, 100, false), None);
+//! assert_eq!(commands.feed(&mut decoder, 100, true), None);
+//! assert_eq!(commands.feed(&mut decoder, 700, false), Some(LIGHT_ON));
+//! ```
+
+use crate::{
+    decoder::{DecodeEvent, MorseDecoder},
+    message::MessageStorage,
+    MorseCharacter,
+};
+
+/// Id of a command handler, picked by the client code.
+pub type CommandId = u16;
+
+/// A fixed capacity set of command phrases mapped to handler ids.
+///
+/// N is the maximum number of commands that can be registered.
+#[derive(Clone, Debug)]
+pub struct CommandSet<const N: usize> {
+    commands: [(&'static str, CommandId); N],
+    count: usize,
+}
+
+impl<const N: usize> Default for CommandSet<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> CommandSet<N> {
+    pub fn new() -> Self {
+        Self {
+            commands: [("", 0); N],
+            count: 0,
+        }
+    }
+
+    /// Register a phrase to be reported with the id when it's keyed.
+    ///
+    /// Words of the phrase are separated by single spaces. Matching is case insensitive.
+    /// Returns an error if the phrase is empty or the set is full.
+    pub fn register(&mut self, phrase: &'static str, id: CommandId) -> Result<(), &'static str> {
+        if phrase.trim().is_empty() {
+            return Err("Command phrase is empty.");
+        }
+
+        if self.count == N {
+            return Err("Command set is full.");
+        }
+
+        self.commands[self.count] = (phrase.trim(), id);
+        self.count += 1;
+
+        Ok(())
+    }
+
+    /// Returns the number of registered commands.
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    /// Returns true if no commands are registered.
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Remove all registered commands.
+    pub fn clear(&mut self) {
+        self.count = 0;
+    }
+
+    /// Check the decoder message for a command after a signal event.
+    ///
+    /// Pass the result of `signal_event_ex` of the decoder. If the event completed a word
+    /// and the message ends with a registered phrase, id of the phrase is returned.
+    /// When more than one phrase matches, ie. "ON" and "LIGHT ON", the longest one wins.
+    pub fn match_event<const MSG_MAX: usize, C: MorseCharacter, S: MessageStorage<C>>(
+        &self,
+        event: DecodeEvent<C>,
+        decoder: &MorseDecoder<MSG_MAX, C, S>,
+    ) -> Option<CommandId> {
+        if event != DecodeEvent::WordCompleted {
+            return None;
+        }
+
+        let chars = decoder.message.as_chars();
+        let words = &chars[..decoder.completed_word_end().min(chars.len())];
+
+        self.commands[..self.count]
+            .iter()
+            .filter(|(phrase, _)| ends_with_phrase(words, phrase))
+            .max_by_key(|(phrase, _)| phrase.len())
+            .map(|&(_, id)| id)
+    }
+
+    /// Send a signal event to the decoder and check the message for a command.
+    ///
+    /// Shortcut for `signal_event_ex` followed by `match_event`.
+    pub fn feed<const MSG_MAX: usize, C: MorseCharacter, S: MessageStorage<C>>(
+        &self,
+        decoder: &mut MorseDecoder<MSG_MAX, C, S>,
+        duration_ms: u16,
+        is_high: bool,
+    ) -> Option<CommandId> {
+        let event = decoder.signal_event_ex(duration_ms, is_high);
+
+        self.match_event(event, decoder)
+    }
+}
+
+// Phrase should be at the end of the words, either at the start of the message or after a space.
+fn ends_with_phrase<C: MorseCharacter>(words: &[C], phrase: &str) -> bool {
+    let phrase_length = phrase.chars().count();
+    if phrase_length > words.len() {
+        return false;
+    }
+
+    let (before, tail) = words.split_at(words.len() - phrase_length);
+
+    let matches = tail.iter().zip(phrase.chars()).all(|(ch, phrase_ch)| {
+        C::from_char(phrase_ch).map(|phrase_ch| phrase_ch.to_upper()) == Some(ch.to_upper())
+    });
+
+    matches && before.last().is_none_or(|ch| *ch == C::from_ascii(b' '))
+}
@@ -0,0 +1,175 @@
+//! Reference signal timings and their expected decoding.
+//!
+//! Integrators that measure key or audio signals on their own hardware can replay these vectors
+//! through their timing plumbing and compare what comes out with the expected text.
+//! Each [TestVector] has the keyed text, its speed and the exact (duration in milliseconds, is_high)
+//! signal events of it. Vectors cover standard timing, hand keying jitter, Farnsworth timing and prosigns.
+//! Every vector ends with a word space, so the expected text ends with a space.
+//!
+//! ```rust
+//! use morse_codec::conformance::VECTORS;
+//!
+//! for vector in VECTORS {
+//!     let mut decoder = vector.decoder::<32>().build();
+//!     for &(duration_ms, is_high) in vector.signals {
+//!         decoder.signal_event(duration_ms, is_high);
+//!     }
+//!
+//!     assert_eq!(decoder.message.as_str(), vector.expected, "{}", vector.name);
+//! }
+//! ```
+
+#[cfg(feature = "decoder")]
+use crate::decoder::{Decoder, Precision};
+
+/// Signal events of a keyed text and what they decode to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TestVector {
+    /// Short unique name of the vector.
+    pub name: &'static str,
+    /// Text that was keyed. Prosigns are written in angle brackets, ie. <AR>.
+    pub text: &'static str,
+    /// Speed of the characters in words per minute.
+    pub character_wpm: u16,
+    /// Overall speed in words per minute. Same as `character_wpm` unless the vector has Farnsworth timing.
+    pub effective_wpm: u16,
+    /// Signal events as (duration in milliseconds, is_high) pairs.
+    pub signals: &'static [(u16, bool)],
+    /// Message of a decoder after all signal events are sent to it.
+    pub expected: &'static str,
+}
+
+impl TestVector {
+    /// Returns true if spaces of the vector are stretched with Farnsworth timing.
+    pub const fn is_farnsworth(&self) -> bool {
+        self.effective_wpm < self.character_wpm
+    }
+
+    /// Returns a decoder builder with the reference settings of the vector.
+    ///
+    /// Precision is Accurate with the reference short duration at the character speed,
+    /// or FarnsworthWpm with both speeds for Farnsworth vectors.
+    #[cfg(feature = "decoder")]
+    pub fn decoder<const MSG_MAX: usize>(&self) -> Decoder<MSG_MAX> {
+        let builder = Decoder::<MSG_MAX>::new();
+
+        if self.is_farnsworth() {
+            builder.with_precision(Precision::FarnsworthWpm {
+                character_wpm: self.character_wpm,
+                effective_wpm: self.effective_wpm,
+            })
+        } else {
+            builder
+                .with_precision(Precision::Accurate)
+                .with_reference_short_ms(1200 / self.character_wpm.max(1))
+        }
+    }
+}
+
+/// All test vectors of this module.
+pub const VECTORS: &[TestVector] = &[PARIS, SOS, CALL, JITTER, FARNSWORTH, PROSIGNS];
+
+/// Standard word PARIS at 20 WPM with exact timing.
+pub const PARIS: TestVector = TestVector {
+    name: "paris-20wpm",
+    text: "PARIS",
+    character_wpm: 20,
+    effective_wpm: 20,
+    signals: &[
+        (60, true), (60, false), (180, true), (60, false), (180, true), (60, false), (60, true), (180, false), // P
+        (60, true), (60, false), (180, true), (180, false), // A
+        (60, true), (60, false), (180, true), (60, false), (60, true), (180, false), // R
+        (60, true), (60, false), (60, true), (180, false), // I
+        (60, true), (60, false), (60, true), (60, false), (60, true), (420, false), // S
+    ],
+    expected: "PARIS ",
+};
+
+/// SOS at 12 WPM with exact timing.
+pub const SOS: TestVector = TestVector {
+    name: "sos-12wpm",
+    text: "SOS",
+    character_wpm: 12,
+    effective_wpm: 12,
+    signals: &[
+        (100, true), (100, false), (100, true), (100, false), (100, true), (300, false), // S
+        (300, true), (100, false), (300, true), (100, false), (300, true), (300, false), // O
+        (100, true), (100, false), (100, true), (100, false), (100, true), (700, false), // S
+    ],
+    expected: "SOS ",
+};
+
+/// Words, a number and word spaces at 20 WPM with exact timing.
+pub const CALL: TestVector = TestVector {
+    name: "cq-call-20wpm",
+    text: "CQ DE K1ABC",
+    character_wpm: 20,
+    effective_wpm: 20,
+    signals: &[
+        (180, true), (60, false), (60, true), (60, false), (180, true), (60, false), (60, true), (180, false), // C
+        (180, true), (60, false), (180, true), (60, false), (60, true), (60, false), (180, true), (420, false), // Q
+        (180, true), (60, false), (60, true), (60, false), (60, true), (180, false), // D
+        (60, true), (420, false), // E
+        (180, true), (60, false), (60, true), (60, false), (180, true), (180, false), // K
+        (60, true), (60, false), (180, true), (60, false), (180, true), (60, false), (180, true), (60, false), (180, true), (180, false), // 1
+        (60, true), (60, false), (180, true), (180, false), // A
+        (180, true), (60, false), (60, true), (60, false), (60, true), (60, false), (60, true), (180, false), // B
+        (180, true), (60, false), (60, true), (60, false), (180, true), (60, false), (60, true), (420, false), // C
+    ],
+    expected: "CQ DE K1ABC ",
+};
+
+/// Same as [CALL] with signals off by up to 20 percent, as keyed by hand.
+/// Word spaces are only made longer, since a word space shorter than 7 dits is a decoding error in Accurate precision.
+pub const JITTER: TestVector = TestVector {
+    name: "cq-call-jitter",
+    text: "CQ DE K1ABC",
+    character_wpm: 20,
+    effective_wpm: 20,
+    signals: &[
+        (167, true), (52, false), (64, true), (50, false), (183, true), (57, false), (49, true), (181, false), // C
+        (147, true), (58, false), (149, true), (50, false), (58, true), (68, false), (153, true), (439, false), // Q
+        (189, true), (71, false), (62, true), (58, false), (71, true), (147, false), // D
+        (69, true), (444, false), // E
+        (154, true), (51, false), (55, true), (68, false), (157, true), (186, false), // K
+        (63, true), (57, false), (183, true), (50, false), (148, true), (53, false), (193, true), (58, false), (167, true), (186, false), // 1
+        (59, true), (55, false), (201, true), (194, false), // A
+        (162, true), (62, false), (61, true), (69, false), (66, true), (55, false), (72, true), (153, false), // B
+        (174, true), (66, false), (52, true), (60, false), (147, true), (64, false), (66, true), (468, false), // C
+    ],
+    expected: "CQ DE K1ABC ",
+};
+
+/// Characters at 18 WPM with spaces at an overall speed of 5 WPM, see [FarnsworthTiming](crate::FarnsworthTiming).
+pub const FARNSWORTH: TestVector = TestVector {
+    name: "farnsworth-18-5wpm",
+    text: "TEST",
+    character_wpm: 18,
+    effective_wpm: 5,
+    signals: &[
+        (198, true), (1568, false), // T
+        (66, true), (1568, false), // E
+        (66, true), (66, false), (66, true), (66, false), (66, true), (1568, false), // S
+        (198, true), (3659, false), // T
+    ],
+    expected: "TEST ",
+};
+
+/// Prosigns AR and KN at 15 WPM. They share their codes with + and ( of the default character set.
+pub const PROSIGNS: TestVector = TestVector {
+    name: "prosigns-15wpm",
+    text: "QSL <AR> TNX <KN>",
+    character_wpm: 15,
+    effective_wpm: 15,
+    signals: &[
+        (240, true), (80, false), (240, true), (80, false), (80, true), (80, false), (240, true), (240, false), // Q
+        (80, true), (80, false), (80, true), (80, false), (80, true), (240, false), // S
+        (80, true), (80, false), (240, true), (80, false), (80, true), (80, false), (80, true), (560, false), // L
+        (80, true), (80, false), (240, true), (80, false), (80, true), (80, false), (240, true), (80, false), (80, true), (560, false), // <AR>
+        (240, true), (240, false), // T
+        (240, true), (80, false), (80, true), (240, false), // N
+        (240, true), (80, false), (80, true), (80, false), (80, true), (80, false), (240, true), (560, false), // X
+        (240, true), (80, false), (80, true), (80, false), (240, true), (80, false), (240, true), (80, false), (80, true), (560, false), // <KN>
+    ],
+    expected: "QSL + TNX ( ",
+};
@@ -0,0 +1,202 @@
+//! Contest exchanges built from templates and cut numbers in received ones.
+//!
+//! Contest keyers send the same exchange over and over with a few fields changing,
+//! ie. "TU {callsign} 5NN {serial}". [format_exchange] fills the placeholders of such a template
+//! into a buffer given by the caller, so keyer firmware can build every exchange without a heap
+//! and hand it to the encoder.
+//!
+//! Digits of number fields can be sent as cut numbers, letters with shorter codes
+//! that contesters use in place of digits (T for 0, N for 9). Text fields are never cut,
+//! so digits of callsigns stay as they are. On the receiving side [uncut_numbers]
+//! turns cut numbers in decoded messages back to digits.
+//!
+//! ```rust
+//! use morse_codec::contest::{format_exchange, CutNumbers, Field};
+//!
+//! let fields = [
+//!     ("callsign", Field::Text("K1ABC")),
+//!     ("serial", Field::Number { value: 90, width: 3 }),
+//! ];
+//!
+//! let mut buffer = [0u8; 32];
+//! let exchange = format_exchange("TU {callsign} 5NN {serial}", &fields, CutNumbers::ZeroAndNine, &mut buffer).unwrap();
+//! assert_eq!(exchange, "TU K1ABC 5NN TNT");
+//! ```
+
+use crate::{
+    message::{Message, MessageStorage},
+    recognizer::{is_callsign, is_rst},
+    MorseCharacter,
+};
+
+/// Digits sent as cut numbers by [format_exchange], or received as cut numbers by [uncut_numbers].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum CutNumbers {
+    /// Digits are sent as they are.
+    #[default]
+    Off,
+    /// 0 is sent as T and 9 as N, as most contesters do.
+    ZeroAndNine,
+    /// Every digit with a cut is sent as one: 1 as A, 2 as U, 3 as V, 5 as E,
+    /// 7 as B, 8 as D, 9 as N and 0 as T. 4 and 6 have no cut.
+    All,
+}
+
+impl CutNumbers {
+    /// Returns the cut of a digit, or the character itself if it isn't cut.
+    pub fn cut(&self, ch: char) -> char {
+        match (self, ch) {
+            (CutNumbers::Off, _) => ch,
+            (_, '0') => 'T',
+            (_, '9') => 'N',
+            (CutNumbers::ZeroAndNine, _) => ch,
+            (CutNumbers::All, '1') => 'A',
+            (CutNumbers::All, '2') => 'U',
+            (CutNumbers::All, '3') => 'V',
+            (CutNumbers::All, '5') => 'E',
+            (CutNumbers::All, '7') => 'B',
+            (CutNumbers::All, '8') => 'D',
+            (CutNumbers::All, _) => ch,
+        }
+    }
+
+    /// Returns the digit of a cut number, or the character itself if it isn't a cut number.
+    pub fn uncut(&self, ch: char) -> char {
+        ('0'..='9')
+            .find(|&digit| digit != ch && self.cut(digit) == ch)
+            .unwrap_or(ch)
+    }
+}
+
+/// Value of a template placeholder.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Field<'a> {
+    /// Text written as it is, ie. a callsign or a name.
+    Text(&'a str),
+    /// Number padded with leading zeros to at least `width` digits, ie. a serial number.
+    /// Its digits are cut as the [CutNumbers] option says.
+    Number { value: u32, width: u8 },
+}
+
+const PLACEHOLDER_START: char = '{';
+const PLACEHOLDER_END: char = '}';
+// Digits of the largest u32
+const MAX_DIGITS: usize = 10;
+
+/// Fill the placeholders of a template with fields and write the result into the buffer.
+///
+/// Placeholders are field names in curly braces, ie. `{serial}`. Fields are (name, value) pairs,
+/// see [Field]. Text outside the placeholders is written as it is.
+/// Returns the written part of the buffer as a string, or an error if a placeholder isn't closed,
+/// there's no field for it or the buffer is too small.
+pub fn format_exchange<'b>(
+    template: &str,
+    fields: &[(&str, Field)],
+    cut_numbers: CutNumbers,
+    buffer: &'b mut [u8],
+) -> Result<&'b str, &'static str> {
+    let mut length = 0;
+    let mut write = |ch: char| -> Result<(), &'static str> {
+        if length + ch.len_utf8() > buffer.len() {
+            return Err("Buffer is too small for the exchange.");
+        }
+
+        length += ch.encode_utf8(&mut buffer[length..]).len();
+
+        Ok(())
+    };
+
+    let mut rest = template;
+    while let Some(start) = rest.find(PLACEHOLDER_START) {
+        rest[..start].chars().try_for_each(&mut write)?;
+
+        let end = rest[start..]
+            .find(PLACEHOLDER_END)
+            .ok_or("Placeholder is not closed.")?;
+        let name = &rest[start + 1..start + end];
+
+        let (_, field) = fields
+            .iter()
+            .find(|(field_name, _)| *field_name == name)
+            .ok_or("There's no field for the placeholder.")?;
+
+        match *field {
+            Field::Text(text) => text.chars().try_for_each(&mut write)?,
+            Field::Number { value, width } => {
+                let mut digits = [0u8; MAX_DIGITS];
+                let mut count = 0;
+                let mut value = value;
+                while count == 0 || value > 0 {
+                    digits[count] = (value % 10) as u8;
+                    value /= 10;
+                    count += 1;
+                }
+
+                for _ in count..width as usize {
+                    write(cut_numbers.cut('0'))?;
+                }
+
+                for &digit in digits[..count].iter().rev() {
+                    write(cut_numbers.cut((b'0' + digit) as char))?;
+                }
+            }
+        }
+
+        rest = &rest[start + end + 1..];
+    }
+
+    rest.chars().try_for_each(&mut write)?;
+
+    // Only whole UTF-8 encoded chars are written, so this can't fail.
+    Ok(core::str::from_utf8(&buffer[..length]).unwrap_or(""))
+}
+
+/// Rewrite likely cut numbers in the message as digits and return the number of rewritten characters.
+///
+/// Only words that look like numeric fields are rewritten. These are RST reports such as 5NN,
+/// and words of digits and cut numbers with at least one digit in them, such as serial numbers like 1T5.
+/// Callsigns are left alone, as are words without any digits, since "TNT" or "ATE" might be
+/// plain words as well.
+///
+/// ```rust
+/// use morse_codec::{
+///     contest::{uncut_numbers, CutNumbers},
+///     message::Message,
+/// };
+///
+/// let mut message = Message::<32>::new("TU 9A1A 5NN TT1 ATE", false, false);
+/// assert_eq!(uncut_numbers(&mut message, CutNumbers::All), 4);
+/// assert_eq!(message.as_str(), "TU 9A1A 599 001 ATE");
+/// ```
+pub fn uncut_numbers<const MSG_MAX: usize, C: MorseCharacter, S: MessageStorage<C>>(
+    message: &mut Message<MSG_MAX, C, S>,
+    cut_numbers: CutNumbers,
+) -> usize {
+    let chars = message.as_chars_mut();
+    let mut rewritten = 0;
+
+    let words = chars.split_mut(|ch| *ch == C::from_ascii(b' ') || *ch == C::FILLER);
+    for word in words.filter(|word| is_numeric_field(word, cut_numbers)) {
+        for ch in word.iter_mut() {
+            let digit = cut_numbers.uncut(ch.to_char());
+
+            if digit != ch.to_char() {
+                *ch = C::from_ascii(digit as u8);
+                rewritten += 1;
+            }
+        }
+    }
+
+    rewritten
+}
+
+fn is_numeric_field<C: MorseCharacter>(word: &[C], cut_numbers: CutNumbers) -> bool {
+    let is_digit = |ch: &C| ch.to_char().is_ascii_digit();
+    let is_digit_or_cut = |ch: &C| cut_numbers.uncut(ch.to_char()).is_ascii_digit();
+
+    if is_rst(word) {
+        return true;
+    }
+
+    word.iter().any(is_digit) && word.iter().all(is_digit_or_cut) && !is_callsign(word)
+}
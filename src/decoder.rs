@@ -51,22 +51,62 @@
 use core::ops::RangeInclusive;
 
 use crate::{
-    message::Message,
+    charsets::{get_prosign_morse_char, morse_subtree_nodes, morse_tree_node_code, validate},
+    message::{LetterCase, Message, MessageStorage},
+    message_log::MessageLog,
     Character,
     CharacterSet,
+    Error,
+    FarnsworthTiming,
+    MorseCharacter,
     MorseCodeArray,
     MorseCodeSet,
     MorseSignal::{self, Long as L, Short as S},
-    DECODING_ERROR_CHAR,
-    DEFAULT_CHARACTER_SET,
     DEFAULT_MORSE_CODE_SET,
-    LONG_SIGNAL_MULTIPLIER,
     MORSE_ARRAY_LENGTH,
     MORSE_DEFAULT_CHAR,
-    WORD_SPACE_MULTIPLIER,
+    SignalEvent,
+    source::SignalSource,
+    sos::SosDetector,
+    text::{TextFormat, TextToken},
+    TimingProfile,
 };
 
-/// Decoding precision is either Lazy, Accurate or Farnsworth(speed_reduction_factor: f32).
+#[cfg(feature = "language-model")]
+use crate::language_model::LanguageModel;
+
+#[cfg(not(feature = "small-footprint"))]
+use crate::charsets::MorseTree;
+
+/// Factor type of the signal tolerance and Farnsworth speed reduction.
+///
+/// It's f32 by default. With the "fixed-point" feature it's u16 in thousandths, so 500 is
+/// a factor of 0.5, and decoding is done with integer math only. This keeps soft-float routines
+/// out of the firmware on MCUs without an FPU such as AVR or Cortex-M0.
+#[cfg(not(feature = "fixed-point"))]
+pub type Factor = f32;
+#[cfg(feature = "fixed-point")]
+pub type Factor = u16;
+
+/// Factor value that equals 1.0.
+#[cfg(not(feature = "fixed-point"))]
+pub const FACTOR_ONE: Factor = 1.0;
+#[cfg(feature = "fixed-point")]
+pub const FACTOR_ONE: Factor = 1000;
+
+/// Make a [Factor] from thousandths of it.
+///
+/// Client code using this works the same with and without the "fixed-point" feature.
+#[cfg(not(feature = "fixed-point"))]
+pub const fn factor_from_thousandths(thousandths: u16) -> Factor {
+    thousandths as f32 / 1000.0
+}
+#[cfg(feature = "fixed-point")]
+pub const fn factor_from_thousandths(thousandths: u16) -> Factor {
+    thousandths
+}
+
+/// Decoding precision is either Lazy, Accurate or Farnsworth(speed_reduction_factor: [Factor]).
 ///
 /// If Lazy is selected, short and long signals will be considered to saturate their
 /// fields on the extreme ends. For example a short signal can be 1 ms to short range end
@@ -84,65 +124,566 @@ use crate::{
 /// the length of the delays. The reduced decoding speed is determined by the factor value
 /// passed to the enum variant Farnsworth. This value will be multiplied by the current speed
 /// to find a reduction in overall speed. Factor value is clamped between 0.01 and 0.99.
-#[derive(Debug, PartialEq)]
+///
+/// FarnsworthWpm precision is the same idea with speeds given in words per minute, as the
+/// [ARRL standard](https://www.arrl.org/files/file/Technology/x9004008.pdf) and most training
+/// software do. Character and word spaces are resolved with [FarnsworthTiming] of the two speeds, so they
+/// don't follow the speed tracking of the decoder. If reference short duration isn't given to the builder,
+/// it's calculated from the character speed.
+///
+/// Training precision is Accurate precision for learning apps. A character with a signal
+/// or a space between its signals out of tolerance is written as a decoding error, and
+/// the first of these is reported as a [TimingFault] with [DecodeEvent::Faulted], so students
+/// can be told which element was off and by how much. Tighter signal tolerances than the default
+/// make it stricter. Short spaces don't change the reference short duration in Training precision,
+/// it only follows the speed of the student with speed tracking.
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Precision {
     Lazy,
     Accurate,
-    Farnsworth(f32),
+    Farnsworth(Factor),
+    FarnsworthWpm { character_wpm: u16, effective_wpm: u16 },
+    Training,
 }
 
-use Precision::{Lazy, Accurate, Farnsworth};
+use Precision::{Lazy, Accurate, Farnsworth, FarnsworthWpm, Training};
 
 type MilliSeconds = u16;
 
+/// Function to map a decoded character that's not in the allowed characters
+/// to something else. Returning None rejects the character altogether.
+pub type CharacterMapper<C = Character> = fn(C) -> Option<C>;
+
+/// Function called with a decoded character when the message is full.
+///
+/// Return true to write the character anyway, overwriting an existing character,
+/// or false to drop it.
+pub type MessageFullHandler<C = Character> = fn(C) -> bool;
+
+/// Function receiving the decisions the decoder makes along the way. See `with_trace_handler`.
+pub type TraceHandler = fn(&TraceEvent);
+
+/// Function transforming a decoded character before it's written to the message.
+///
+/// It's called with the character and the character decoded before it, if any.
+/// Returning None drops the character.
+pub type CharProcessor<C = Character> = fn(C, Option<C>) -> Option<C>;
+
+/// Function rewriting a word when it's completed by a word space.
+///
+/// It's called with the characters of the word. To replace the word, write the new characters
+/// to the start of the buffer and return how many there are. Returning None keeps the word as it is.
+pub type WordProcessor<C = Character> = fn(&[C], &mut [C; MAX_WORD_LENGTH]) -> Option<usize>;
+
+/// A step of the post-processing pipeline of the decoder. See `with_post_processor`.
+#[derive(Clone, Copy, Debug)]
+pub enum PostProcessor<C = Character> {
+    /// Transforms each character, word spaces included.
+    Char(CharProcessor<C>),
+    /// Rewrites each completed word.
+    Word(WordProcessor<C>),
+}
+
+/// Maximum number of post-processors a decoder runs.
+pub const MAX_POST_PROCESSORS: usize = 4;
+
+/// Maximum length of words given to and returned by [WordProcessor]s.
+/// Longer words are left alone.
+pub const MAX_WORD_LENGTH: usize = 16;
+
+/// What a signal duration is resolved to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SignalClass {
+    /// A dit, or a space between signals.
+    Short,
+    /// A dah, or a space between characters.
+    Long,
+    /// A space between words. Any low signal from the start of the word space on is one.
+    WordSpace,
+    /// Neither of them, a signal out of tolerance.
+    Other,
+}
+
+/// Elements of a character keyed in morse code.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TimingElement {
+    Dit,
+    Dah,
+    /// Space between two signals of a character.
+    SignalSpace,
+    /// Space after the last signal of a character.
+    CharSpace,
+}
+
+/// A signal out of tolerance in [Precision::Training], reported with [DecodeEvent::Faulted].
+///
+/// Signal is taken as the element it's closest to, ie. a 200 ms high signal with a reference short
+/// of 100 ms is a dah 33% too short.
+///
+/// ```rust
+/// use morse_codec::decoder::{factor_from_thousandths, Decoder, DecodeEvent, Precision, TimingElement};
+///
+/// let mut decoder = Decoder::<16, u8>::new()
+///     .with_precision(Precision::Training)
+///     .with_reference_short_ms(100)
+///     .with_signal_tolerance(factor_from_thousandths(200))
+///     .build();
+///
+/// // K with its second dah too short
+/// let signals = [(300, true), (100, false), (100, true), (100, false), (210, true)];
+/// for (duration_ms, is_high) in signals {
+///     decoder.signal_event(duration_ms, is_high);
+/// }
+///
+/// let DecodeEvent::Faulted(fault) = decoder.signal_event_ex(300, false) else {
+///     panic!("Character should be faulted");
+/// };
+/// assert_eq!((fault.element, fault.ordinal), (TimingElement::Dah, 2));
+/// assert_eq!(fault.deviation_percent(), -30);
+/// assert_eq!(decoder.message.as_str(), "?");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TimingFault {
+    pub element: TimingElement,
+    /// Count of the element in the character starting from 1, ie. 3 for the third dah.
+    pub ordinal: u8,
+    pub expected_ms: MilliSeconds,
+    pub actual_ms: MilliSeconds,
+}
+
+impl TimingFault {
+    /// Returns how much the signal is off in percents of the expected duration.
+    /// It's negative if the signal is too short.
+    pub fn deviation_percent(&self) -> i16 {
+        let expected_ms = self.expected_ms.max(1) as i32;
+        let percent = (self.actual_ms as i32 - expected_ms) * 100 / expected_ms;
+
+        percent.clamp(i16::MIN as i32, i16::MAX as i32) as i16
+    }
+}
+
+/// A step of decoding, given to the trace handler of the decoder.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TraceEvent {
+    /// First high signal of a character is received.
+    CharacterStarted,
+    /// Reference short duration is changed to a signal duration, either because it wasn't set yet,
+    /// or because a low signal was shorter than it.
+    ReferenceShortUpdated(MilliSeconds),
+    /// Slower short duration of Farnsworth precision is calculated for character and word spaces.
+    /// Reduced WPM is rounded down to whole words per minute.
+    FarnsworthShort {
+        current_wpm: u16,
+        reduced_wpm: u16,
+        short_ms: MilliSeconds,
+    },
+    /// A signal duration is resolved.
+    SignalResolved {
+        duration_ms: MilliSeconds,
+        is_high: bool,
+        /// Position of the signal in the signal buffer.
+        position: usize,
+        /// Durations within the signal tolerance of this signal. Reference durations in the range match.
+        tolerance_range: RangeInclusive<MilliSeconds>,
+        reference_short_ms: MilliSeconds,
+        class: SignalClass,
+    },
+    /// A character space ends the character being decoded.
+    CharacterEnded,
+    /// A word space ends the character being decoded and the word.
+    WordEnded,
+    /// Low signals are long enough to end the transmission.
+    TransmissionEnded,
+    /// Signals of a continuous SOS prosign are received.
+    SosDetected,
+    /// Signal buffer is decoded to these signals at the end of a character.
+    BufferDecoded([Option<MorseSignal>; MORSE_ARRAY_LENGTH]),
+    /// Signal buffer is full before the character ended, so a decoding error character is written.
+    BufferOverflow,
+}
+
+/// What happened to a signal event, returned by `signal_event_ex`.
+///
+/// When an event causes more than one thing, like a long low signal ending
+/// a character and a word, the most significant one is returned.
+/// SosDetected is the most significant, then TransmissionEnded, WordCompleted, CharCompleted, Error, Faulted and Corrected, then Rejected.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DecodeEvent<C = Character> {
+    /// The signal is added to the character being decoded.
+    Buffered,
+    /// A character is decoded and written to the message.
+    CharCompleted(C),
+    /// A word space is written to the message.
+    WordCompleted,
+    /// The signal or the character it completed is not used.
+    Rejected(RejectReason),
+    /// Signals couldn't be decoded to a character, so a decoding error character is written to the message.
+    Error,
+    /// Error prosign is received and the last word is removed from the message.
+    /// See `with_error_prosign_correction`.
+    Corrected,
+    /// A signal of the character is out of tolerance in [Precision::Training],
+    /// so a decoding error character is written to the message.
+    Faulted(TimingFault),
+    /// Low signals are long enough to end the transmission. See `with_transmission_gap`.
+    TransmissionEnded,
+    /// The signal completes a continuous SOS prosign. See `with_sos_detection`.
+    SosDetected,
+}
+
+/// Reason of a [DecodeEvent::Rejected] event.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RejectReason {
+    /// Signal is shorter than the minimum signal duration.
+    TooShort,
+    /// Low signal while no character is being decoded.
+    IdleLow,
+    /// Decoded character is filtered out by allowed characters or the message full handler.
+    Filtered,
+    /// Decoded character belongs to the preamble. See `with_preamble_lock`.
+    Preamble,
+}
+
+/// Timing of an operator learned from a known text with [Decoder::calibrate_from_known].
+///
+/// Weights are measured durations relative to the ones of the [TimingProfile] of the decoder,
+/// 1.0 being as the profile says.
+/// An operator keying dahs four times as long as dits has a dah weight of 4 / 3, ie. 1.333.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Calibration {
+    /// Average short signal duration of the operator.
+    pub reference_short_ms: MilliSeconds,
+    /// Smallest tolerance factor that fits all signals and character spaces with a margin of 0.1.
+    /// It's between 0.1 and 0.5.
+    pub signal_tolerance: Factor,
+    /// Dah weight relative to 3 shorts. None if there were no dahs in the text.
+    pub dah_weight: Option<Factor>,
+    /// Character space weight relative to 3 shorts. None if there was a single character.
+    pub char_space_weight: Option<Factor>,
+    /// Word space weight relative to 7 shorts. None if there was a single word.
+    pub word_space_weight: Option<Factor>,
+}
+
+/// Signal durations the decoder currently accepts as each signal type, for drawing
+/// acceptance bands in tuning UIs. Get them with `current_thresholds`.
+///
+/// Bands are None if no duration falls into them with the current settings.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Thresholds {
+    /// High signal durations decoded as dits.
+    pub dit: Option<RangeInclusive<MilliSeconds>>,
+    /// High signal durations decoded as dahs.
+    pub dah: Option<RangeInclusive<MilliSeconds>>,
+    /// Low signal durations ending a character.
+    pub char_space: Option<RangeInclusive<MilliSeconds>>,
+    /// Low signal durations from this on end a word.
+    pub word_space_min: Option<MilliSeconds>,
+}
+
+impl<C> DecodeEvent<C> {
+    fn rank(&self) -> u8 {
+        match self {
+            DecodeEvent::Buffered => 0,
+            DecodeEvent::Rejected(_) => 1,
+            DecodeEvent::CharCompleted(_) | DecodeEvent::Error | DecodeEvent::Faulted(_) | DecodeEvent::Corrected => 2,
+            DecodeEvent::WordCompleted => 3,
+            DecodeEvent::TransmissionEnded => 4,
+            DecodeEvent::SosDetected => 5,
+        }
+    }
+}
+
 #[derive(PartialEq, Copy, Clone, Debug)]
 enum SignalDuration {
     Empty,
     Short(MilliSeconds),
     Long(MilliSeconds),
+    // Low signals from the start of the word space on, with no upper limit
+    WordSpace(MilliSeconds),
     Other(MilliSeconds),
 }
-use SignalDuration::{Empty as SDEmpty, Short as SDShort, Long as SDLong, Other as SDOther};
+use SignalDuration::{Empty as SDEmpty, Short as SDShort, Long as SDLong, WordSpace as SDWordSpace, Other as SDOther};
 
 // Signal buffer length is morse array length + 1, because we need to be able to
 // resolve a character ending long signal (either 3x or word space 7x) at the end
 // of each character.
 const SIGNAL_BUFFER_LENGTH: usize = MORSE_ARRAY_LENGTH + 1;
+
+// Element classes of calibration
+const CLASS_DIT: usize = 0;
+const CLASS_DAH: usize = 1;
+const CLASS_SIGNAL_SPACE: usize = 2;
+const CLASS_CHAR_SPACE: usize = 3;
+const CLASS_WORD_SPACE: usize = 4;
+
+// A signal period as it was received, along with the signal position
+// of the decoder at the time.
+#[derive(Clone, Copy, Debug)]
+struct LastSignal {
+    duration_ms: MilliSeconds,
+    is_high: bool,
+    signal_pos: usize,
+}
+
+// Duration thresholds that only change with the reference short duration.
+// They're calculated when the reference short changes instead of for every signal event.
+#[derive(Clone, Copy, Debug, Default)]
+struct SignalThresholds {
+    // Reference short duration the thresholds are calculated for
+    reference_short_ms: MilliSeconds,
+    // Long signal durations to resolve marks [0] and spaces [1] with
+    long_ms: [MilliSeconds; 2],
+    // Ends of short signal ranges of marks and spaces with Lazy precision
+    lazy_short_end_ms: [MilliSeconds; 2],
+    // Start of the word space, low signals from this on are word spaces
+    word_space_ms: MilliSeconds,
+}
+
+// Multiply a duration with a factor.
+#[cfg(not(feature = "fixed-point"))]
+fn scale_ms(duration_ms: MilliSeconds, factor: Factor) -> MilliSeconds {
+    (duration_ms as f32 * factor) as MilliSeconds
+}
+
+#[cfg(feature = "fixed-point")]
+fn scale_ms(duration_ms: MilliSeconds, factor: Factor) -> MilliSeconds {
+    (duration_ms as u32 * factor as u32 / FACTOR_ONE as u32) as MilliSeconds
+}
+
+// Multiply a timing multiplier with a factor, rounded down.
+#[cfg(not(feature = "fixed-point"))]
+fn scale_mult(mult: u16, factor: Factor) -> u16 {
+    (mult as f32 * factor) as u16
+}
+
+#[cfg(feature = "fixed-point")]
+fn scale_mult(mult: u16, factor: Factor) -> u16 {
+    (mult as u32 * factor as u32 / FACTOR_ONE as u32).min(u16::MAX as u32) as u16
+}
+
+// Durations from start to end in milliseconds, or None if there aren't any.
+fn duration_band(start_ms: u32, end_ms: u32) -> Option<RangeInclusive<MilliSeconds>> {
+    let end_ms = end_ms.min(MilliSeconds::MAX as u32);
+
+    (start_ms <= end_ms).then_some(start_ms as MilliSeconds..=end_ms as MilliSeconds)
+}
+
+// Signals and spaces of the known text as (units, is_high, class) in the order they're sent.
+// Every character is followed by a character space, or a word space if another word follows.
+fn known_elements<C: MorseCharacter>(
+    character_set: CharacterSet<C>,
+    morse_code_set: MorseCodeSet,
+    timing_profile: TimingProfile,
+    text: &str,
+) -> impl Iterator<Item = Result<(MilliSeconds, bool, usize), Error>> + '_ {
+    let mut words = text.split_whitespace().peekable();
+    let mut word_chars = "".chars().peekable();
+
+    core::iter::from_fn(move || {
+        while word_chars.peek().is_none() {
+            word_chars = words.next()?.chars().peekable();
+        }
+
+        let ch = word_chars.next()?;
+        let is_word_end = word_chars.peek().is_none();
+        let space = if is_word_end && words.peek().is_some() {
+            (timing_profile.word_gap_mult as MilliSeconds, false, CLASS_WORD_SPACE)
+        } else {
+            (timing_profile.char_gap_mult as MilliSeconds, false, CLASS_CHAR_SPACE)
+        };
+
+        let morse_char = C::from_char(ch)
+            .and_then(|ch| ch.to_upper())
+            .and_then(|ch| character_set.iter().position(|set_ch| *set_ch == ch))
+            .map(|index| morse_code_set[index])
+            .filter(|morse_char| *morse_char != MORSE_DEFAULT_CHAR);
+
+        let mut elements = [None; 2 * MORSE_ARRAY_LENGTH];
+        match morse_char {
+            Some(morse_char) => {
+                let mut count = 0;
+                for signal in morse_char.iter().flatten() {
+                    if count > 0 {
+                        elements[count] = Some(Ok((1, false, CLASS_SIGNAL_SPACE)));
+                        count += 1;
+                    }
+
+                    elements[count] = Some(Ok(match signal {
+                        S => (1, true, CLASS_DIT),
+                        L => (timing_profile.dah_mult as MilliSeconds, true, CLASS_DAH),
+                    }));
+                    count += 1;
+                }
+
+                elements[count] = Some(Ok(space));
+            }
+            None => elements[0] = Some(Err(Error::UnknownCharacter)),
+        }
+
+        Some(elements)
+    })
+    .flatten()
+    .flatten()
+}
+
+/// Number of characters the speed history of the decoder covers.
+///
+/// It covers only the last 4 characters with the "small-footprint" feature.
+pub const SPEED_HISTORY_LENGTH: usize = if cfg!(feature = "small-footprint") { 4 } else { 16 };
+
+// Decode times of message characters. They take 8 bytes per character,
+// so they're not kept at all with the "small-footprint" feature.
+#[cfg(not(feature = "small-footprint"))]
+type CharTimestamps<const MSG_MAX: usize> = [Option<u32>; MSG_MAX];
+#[cfg(feature = "small-footprint")]
+type CharTimestamps<const MSG_MAX: usize> = [Option<u32>; 0];
+
+/// Number of raw signal events the decoder keeps to decode them again with `redecode_with`.
+///
+/// Signal events aren't kept at all with the "small-footprint" feature.
+pub const REPLAY_LENGTH: usize = if cfg!(feature = "small-footprint") { 0 } else { 32 };
+
+// A signal event as it was received, along with the decoder state
+// needed to decode it again from there.
+#[derive(Clone, Copy, Debug, Default)]
+struct RecordedEvent {
+    duration_ms: MilliSeconds,
+    is_high: bool,
+    // True if the event is the first signal of a character
+    starts_char: bool,
+    reference_short_ms: MilliSeconds,
+    edit_pos: usize,
+}
+
 type SignalBuffer = [SignalDuration; SIGNAL_BUFFER_LENGTH];
 
+// Progress of locking the reference short duration on the preamble, see `with_preamble_lock`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+enum PreambleLock {
+    // No preamble, or signals didn't match it
+    #[default]
+    Off,
+    // Signals are matched with the first word of the preamble
+    Matching {
+        // Index of the next element of the word
+        element: u8,
+        // Sums of durations and units of signals and signal spaces so far
+        total_ms: u32,
+        total_units: u16,
+        // Shortest and longest duration of a unit so far
+        unit_range_ms: (MilliSeconds, MilliSeconds),
+        // Message position the preamble started at
+        start_pos: usize,
+    },
+    // Timing is locked, characters of the preamble are left out of the message
+    Discarding,
+    Locked,
+}
+
 /// This is the builder, or public interface of the decoder using builder pattern.
 /// It builds a MorseDecoder which is the concrete implementation and returns it with `build()`.
 /// For details on how to use the decoder, refer to [MorseDecoder] documentation.
-pub struct Decoder<const MSG_MAX: usize> {
+#[derive(Clone, Debug)]
+pub struct Decoder<const MSG_MAX: usize, C: MorseCharacter = Character, S: MessageStorage<C> = [C; MSG_MAX]> {
     // User defined
     precision: Precision,
-    character_set: CharacterSet,
+    character_set: CharacterSet<C>,
     morse_code_set: MorseCodeSet,
-    signal_tolerance: f32,
+    mark_tolerance: Factor,
+    space_tolerance: Factor,
+    lazy_padding: Factor,
+    word_space_tolerance: Factor,
     reference_short_ms: MilliSeconds,
-    message: Message<MSG_MAX>,
+    message: Message<MSG_MAX, C, S>,
+    allowed_chars: Option<&'static [C]>,
+    disallowed_char_mapper: Option<CharacterMapper<C>>,
+    speed_tracking_step_ms: Option<MilliSeconds>,
+    minimum_signal_ms: MilliSeconds,
+    message_full_handler: Option<MessageFullHandler<C>>,
+    trace_handler: Option<TraceHandler>,
+    inverted_input: bool,
+    timing_profile: TimingProfile,
+    error_prosign_correction: bool,
+    transmission_gap_mult: u16,
+    transmission_separator: Option<C>,
+    #[cfg(feature = "language-model")]
+    language_model: Option<&'static LanguageModel>,
+    post_processors: [Option<PostProcessor<C>>; MAX_POST_PROCESSORS],
+    preamble: Option<&'static str>,
+    sos_detection: bool,
+    // Edit position as it was given, before the message clamps it
+    requested_edit_pos: Option<usize>,
     // Internal stuff
     current_character: MorseCodeArray,
     signal_pos: usize,
     signal_buffer: SignalBuffer,
 }
 
-impl<const MSG_MAX: usize> Default for Decoder<MSG_MAX> {
+impl<const MSG_MAX: usize, C: MorseCharacter> Default for Decoder<MSG_MAX, C> {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl<const MSG_MAX: usize> Decoder<MSG_MAX> {
+impl<const MSG_MAX: usize, C: MorseCharacter> Decoder<MSG_MAX, C> {
     pub fn new() -> Self {
+        Self::from_message(Message::default())
+    }
+}
+
+impl<const MSG_MAX: usize, C: MorseCharacter, S: MessageStorage<C>> Decoder<MSG_MAX, C, S> {
+    /// Get a builder of a decoder that decodes into the given message.
+    ///
+    /// This is how a decoder is built over a [MessageRef](crate::message::MessageRef), a message in
+    /// a buffer sized at runtime. Character decode times are kept for MSG_MAX characters,
+    /// so decoders over a message reference don't keep them.
+    ///
+    /// ```rust
+    /// use morse_codec::{
+    ///     decoder::{Decoder, Precision},
+    ///     message::MessageRef,
+    /// };
+    ///
+    /// let mut buffer = vec![0u8; 100];
+    /// let mut decoder = Decoder::from_message(MessageRef::from_storage(&mut buffer[..]))
+    ///     .with_precision(Precision::Accurate)
+    ///     .with_reference_short_ms(100)
+    ///     .build();
+    ///
+    /// decoder.signal_event(100, true);
+    /// decoder.signal_event(300, false);
+    ///
+    /// assert_eq!(decoder.message.as_str(), "E");
+    /// ```
+    pub fn from_message(message: Message<MSG_MAX, C, S>) -> Self {
         Self {
             // User defined
             precision: Lazy,
-            character_set: DEFAULT_CHARACTER_SET,
+            character_set: C::DEFAULT_CHARACTER_SET,
             morse_code_set: DEFAULT_MORSE_CODE_SET,
-            signal_tolerance: 0.50,
+            mark_tolerance: factor_from_thousandths(500),
+            space_tolerance: factor_from_thousandths(500),
+            lazy_padding: factor_from_thousandths(500),
+            word_space_tolerance: factor_from_thousandths(0),
             reference_short_ms: 0,
-            message: Message::default(),
+            message,
+            allowed_chars: None,
+            disallowed_char_mapper: None,
+            speed_tracking_step_ms: None,
+            minimum_signal_ms: 0,
+            message_full_handler: None,
+            trace_handler: None,
+            inverted_input: false,
+            timing_profile: TimingProfile::STANDARD,
+            error_prosign_correction: false,
+            transmission_gap_mult: 0,
+            transmission_separator: None,
+            #[cfg(feature = "language-model")]
+            language_model: None,
+            post_processors: [None; MAX_POST_PROCESSORS],
+            preamble: None,
+            sos_detection: false,
+            requested_edit_pos: None,
             // Internal stuff
             current_character: MORSE_DEFAULT_CHAR,
             signal_pos: 0,
@@ -155,7 +696,8 @@ impl<const MSG_MAX: usize> Decoder<MSG_MAX> {
     /// `edit_pos_end` means we'll continue decoding from the end of this string.
     /// If you pass false to it, we'll start from the beginning.
     pub fn with_message(mut self, message_str: &str, edit_pos_end: bool) -> Self {
-        self.message = Message::new(message_str, edit_pos_end, self.message.is_edit_clamped());
+        self.message.fill_from_str(message_str);
+        self.message.set_edit_pos(if edit_pos_end { self.message.len() } else { 0 });
 
         self
     }
@@ -166,6 +708,7 @@ impl<const MSG_MAX: usize> Decoder<MSG_MAX> {
     /// storage in web and wants to continue from that.
     pub fn with_edit_position(mut self, pos: usize) -> Self {
         self.message.set_edit_pos(pos);
+        self.requested_edit_pos = Some(pos);
 
         self
     }
@@ -174,6 +717,7 @@ impl<const MSG_MAX: usize> Decoder<MSG_MAX> {
     ///
     /// * Precision::Lazy is more human friendly,
     /// * Precision::Accurate is for learning or a challenge - contest.
+    /// * Precision::Training is Accurate with [TimingFault]s reported for coaching students.
     /// * Precision::Farnsworth means extra delays will be added to spaces between characters and
     ///     words but intracharacter speed is not affected.
     ///     Difference between current decoding speed and a reduced decoding speed will determine
@@ -191,11 +735,15 @@ impl<const MSG_MAX: usize> Decoder<MSG_MAX> {
     /// // the difference in time among spaces between chars and words.
     /// ```
     pub fn with_precision(mut self, precision: Precision) -> Self {
-        if let Farnsworth(factor) = precision {
-            self.precision = Farnsworth(factor.clamp(0.01, 0.99));
-        } else {
-            self.precision = precision;
-        }
+        self.precision = match precision {
+            Farnsworth(factor) => Farnsworth(factor.clamp(factor_from_thousandths(10), factor_from_thousandths(990))),
+            FarnsworthWpm { character_wpm, effective_wpm } => {
+                let character_wpm = character_wpm.max(1);
+
+                FarnsworthWpm { character_wpm, effective_wpm: effective_wpm.clamp(1, character_wpm) }
+            }
+            precision => precision,
+        };
 
         self
     }
@@ -207,7 +755,7 @@ impl<const MSG_MAX: usize> Decoder<MSG_MAX> {
     /// stronger encryption can be used. These kind of encryptions can
     /// easily be broken with powerful algorithms and AI.
     /// **DON'T** use it for secure communication.
-    pub fn with_character_set(mut self, character_set: CharacterSet) -> Self {
+    pub fn with_character_set(mut self, character_set: CharacterSet<C>) -> Self {
         self.character_set = character_set;
 
         self
@@ -228,9 +776,60 @@ impl<const MSG_MAX: usize> Decoder<MSG_MAX> {
     /// Tolerance factors higher than 0.5 tend to overlap and result in wrong decoding.
     /// You can lower this value though for stricter morse signalling.
     /// In any case the value will be clamped between 0.0 and 1.0 so values
-    /// higher than 1.0 will be 1.0.
-    pub fn with_signal_tolerance(mut self, signal_tolerance: f32) -> Self {
-        self.signal_tolerance = signal_tolerance.clamp(0.0, 1.0);
+    /// higher than 1.0 will be 1.0. With the "fixed-point" feature the value is
+    /// in thousandths, see [Factor].
+    ///
+    /// This sets the tolerance of both high (mark) and low (space) signals.
+    /// Use `with_mark_tolerance` and `with_space_tolerance` to set them separately.
+    pub fn with_signal_tolerance(self, signal_tolerance: Factor) -> Self {
+        self.with_mark_tolerance(signal_tolerance)
+            .with_space_tolerance(signal_tolerance)
+    }
+
+    /// Use a different tolerance range factor for high signals (dits and dahs) than the default 0.5.
+    ///
+    /// Clamped the same way as `with_signal_tolerance`.
+    pub fn with_mark_tolerance(mut self, mark_tolerance: Factor) -> Self {
+        self.mark_tolerance = mark_tolerance.clamp(factor_from_thousandths(0), FACTOR_ONE);
+
+        self
+    }
+
+    /// Use a different tolerance range factor for low signals (spaces) than the default 0.5.
+    ///
+    /// Operators tend to have consistent marks but sloppy spaces. A higher space tolerance
+    /// forgives the gaps without accepting malformed dits and dahs.
+    /// Clamped the same way as `with_signal_tolerance`.
+    pub fn with_space_tolerance(mut self, space_tolerance: Factor) -> Self {
+        self.space_tolerance = space_tolerance.clamp(factor_from_thousandths(0), FACTOR_ONE);
+
+        self
+    }
+
+    /// Use a different padding of short signals in Lazy precision than the default 0.5.
+    ///
+    /// Lazy precision accepts signals a bit longer than the tolerance range of a short signal
+    /// as short, which gives better results with humans. The padding is this factor times the reference short
+    /// duration, so it scales with the speed: 50 ms at 100 ms shorts by default, 3 ms at high speed.
+    /// Machine generated signals don't need it, set it to 0.0 for them.
+    /// The value will be clamped between 0.0 and 1.0. With the "fixed-point" feature
+    /// the value is in thousandths, see [Factor].
+    pub fn with_lazy_padding(mut self, lazy_padding: Factor) -> Self {
+        self.lazy_padding = lazy_padding.clamp(factor_from_thousandths(0), FACTOR_ONE);
+
+        self
+    }
+
+    /// Accept word spaces shorter than the nominal word space by this factor, default is 0.0.
+    ///
+    /// Word spaces have no upper limit in any precision: low signals from the start of the word space on
+    /// always end a word, so long thinking pauses between words never cause decoding errors.
+    /// This tolerance moves that start earlier, ie. 0.3 accepts word spaces of 4.9 dits instead of 7 in Accurate precision.
+    /// Word spaces still start after the nominal character space, which always ends only a character.
+    /// The value will be clamped between 0.0 and 1.0. With the "fixed-point" feature
+    /// the value is in thousandths, see [Factor].
+    pub fn with_word_space_tolerance(mut self, word_space_tolerance: Factor) -> Self {
+        self.word_space_tolerance = word_space_tolerance.clamp(factor_from_thousandths(0), FACTOR_ONE);
 
         self
     }
@@ -238,16 +837,176 @@ impl<const MSG_MAX: usize> Decoder<MSG_MAX> {
     /// Change initial reference short signal duration from 0 to some other value.
     ///
     /// This value will determine the reference durations of signal types (short, long or very long).
-    /// The value will be multiplied by LONG_SIGNAL_MULTIPLIER (x3) and WORD_SPACE_MULTIPLIER (x7) to
+    /// The value will be multiplied by the multipliers of the [TimingProfile] (x3 and x7 by default) to
     /// determine long signals and very long word separator signals.
     /// Default value of 0 means MorseDecoder will try to calculate the reference short duration
     /// from incoming signals. This might not work well if the message starts with a 'T'.
+    ///
+    /// Durations are u16 milliseconds, so with the standard timing profile a reference short
+    /// longer than 9362 ms (8191 ms in Lazy precision) has a word space that doesn't fit.
+    /// Such word spaces saturate at `MilliSeconds::MAX`, and `try_build` rejects them.
     pub fn with_reference_short_ms(mut self, reference_short_ms: MilliSeconds) -> Self {
         self.reference_short_ms = reference_short_ms;
 
         self
     }
 
+    /// Learn the timing of an operator from a known text and the signals received when it was sent.
+    ///
+    /// Trainers usually have a calibration step like "send V V V" before decoding. The signals
+    /// are aligned with the text encoded with the character set of the decoder, and the
+    /// reference short duration, signal tolerance and weights of the operator are derived from them.
+    /// Signals are (duration in milliseconds, is_high) pairs, the same as `signal_event` takes.
+    /// The low signal after the last character can be left out.
+    ///
+    /// Returns [Error::UnknownCharacter] if the text has characters not in the character set,
+    /// [Error::EmptyKnownText] if it has nothing to encode, [Error::KnownTextMismatch] if the signals
+    /// don't match the text and [Error::ZeroSignalDurations] if the signals are all zero long.
+    ///
+    /// ```rust
+    /// use morse_codec::decoder::Decoder;
+    ///
+    /// // V is ...- and the operator keys at around 80 ms per short
+    /// let signals = [(80, true), (75, false), (85, true), (80, false), (80, true), (80, false), (250, true), (240, false)];
+    ///
+    /// let calibration = Decoder::<16>::new().calibrate_from_known("V", &signals).unwrap();
+    /// assert_eq!(calibration.reference_short_ms, 81);
+    ///
+    /// let decoder = Decoder::<16>::new().with_calibration(&calibration).build();
+    /// assert_eq!(decoder.get_reference_short(), 81);
+    /// ```
+    pub fn calibrate_from_known(&self, text: &str, signals: &[(MilliSeconds, bool)]) -> Result<Calibration, Error> {
+        // Sums of (duration, units, count) of each element class
+        let mut sums = [(0u32, 0u32, 0u32); 5];
+        let mut expected_count = 0;
+        let mut missing_low_count = 0;
+
+        for (index, element) in known_elements(self.character_set, self.morse_code_set, self.timing_profile, text).enumerate() {
+            let (units, is_high, class) = element?;
+            expected_count += 1;
+
+            match signals.get(index) {
+                Some(&(duration_ms, signal_high)) if signal_high == is_high => {
+                    let sum = &mut sums[class];
+                    *sum = (sum.0 + duration_ms as u32, sum.1 + units as u32, sum.2 + 1);
+                }
+                None if !is_high => missing_low_count += 1,
+                _ => return Err(Error::KnownTextMismatch),
+            }
+        }
+
+        if expected_count == 0 {
+            return Err(Error::EmptyKnownText);
+        }
+
+        // Only the low signal after the last character can be missing
+        if missing_low_count > 1 || signals.len() + missing_low_count != expected_count {
+            return Err(Error::KnownTextMismatch);
+        }
+
+        // Reference short comes from signals and signal spaces, because the operator might prefer
+        // longer or shorter character and word spaces.
+        let (total_ms, total_units) = sums[..CLASS_CHAR_SPACE]
+            .iter()
+            .fold((0, 0), |(ms, units), sum| (ms + sum.0, units + sum.1));
+        let reference_short_ms = ((total_ms + total_units / 2) / total_units.max(1)).min(MilliSeconds::MAX as u32);
+
+        if reference_short_ms == 0 {
+            return Err(Error::ZeroSignalDurations);
+        }
+
+        let max_deviation = known_elements(self.character_set, self.morse_code_set, self.timing_profile, text)
+            .flatten()
+            .zip(signals)
+            // Word spaces only have a minimum duration
+            .filter(|((_, _, class), _)| *class != CLASS_WORD_SPACE)
+            .map(|((units, _, _), (duration_ms, _))| {
+                let ideal_ms = units as u32 * reference_short_ms;
+
+                (*duration_ms as u32).abs_diff(ideal_ms) * 1000 / ideal_ms
+            })
+            .max()
+            .unwrap_or(0);
+
+        let weight = |(total_ms, units, count): (u32, u32, u32)| {
+            (count > 0).then(|| {
+                let thousandths = total_ms as u64 * 1000 / (units as u64 * reference_short_ms as u64);
+
+                factor_from_thousandths(thousandths.min(u16::MAX as u64) as u16)
+            })
+        };
+
+        Ok(Calibration {
+            reference_short_ms: reference_short_ms as MilliSeconds,
+            signal_tolerance: factor_from_thousandths((max_deviation + 100).clamp(100, 500) as u16),
+            dah_weight: weight(sums[CLASS_DAH]),
+            char_space_weight: weight(sums[CLASS_CHAR_SPACE]),
+            word_space_weight: weight(sums[CLASS_WORD_SPACE]),
+        })
+    }
+
+    /// Use the reference short duration, signal tolerance and weights of a [Calibration].
+    ///
+    /// Weights scale the multipliers of the current [TimingProfile] to whole shorts, so an operator
+    /// with a dah weight of 1.333 gets dahs of 4 shorts. Dahs and character spaces are rounded to the nearest short.
+    /// Word spaces only have a minimum, so they are rounded down to keep the measured word spaces in.
+    /// The calibration should come from a decoder with the same timing profile.
+    pub fn with_calibration(self, calibration: &Calibration) -> Self {
+        let profile = self.timing_profile;
+        let weighted = |mult: u8, weight: Option<Factor>, round: bool| {
+            weight.map_or(mult, |weight| {
+                let half_mults = scale_mult(2 * mult as u16, weight) + round as u16;
+
+                (half_mults / 2).min(u8::MAX as u16) as u8
+            })
+        };
+
+        self.with_reference_short_ms(calibration.reference_short_ms)
+            .with_signal_tolerance(calibration.signal_tolerance)
+            .with_timing_profile(TimingProfile {
+                dah_mult: weighted(profile.dah_mult, calibration.dah_weight, true),
+                char_gap_mult: weighted(profile.char_gap_mult, calibration.char_space_weight, true),
+                word_gap_mult: weighted(profile.word_gap_mult, calibration.word_space_weight, false),
+            })
+    }
+
+    /// Lock the reference short duration on a preamble the sender starts with, ie. "VVV" or "CQ CQ".
+    ///
+    /// Senders usually start with a preamble so receivers can tune in and get the speed,
+    /// see `with_preamble` of the encoder. Signals are matched with the first word of the preamble
+    /// as they're received, regardless of the reference short duration of the decoder.
+    /// When the word is complete, the reference short duration is set to the average short of its
+    /// signals and signal spaces, and the characters decoded from them are removed from the message.
+    /// After that, characters of the preamble and word spaces are left out of the message until another character is decoded.
+    ///
+    /// Signals of a word are matched if they're in the right order, dits and dahs are
+    /// within 1.5 times of each other per short and character spaces are longer than signal spaces.
+    /// If they don't match, decoding goes on as usual. Only the beginning of the transmission is matched,
+    /// and the preamble can only have characters of the character set, not prosigns.
+    ///
+    /// ```rust
+    /// use morse_codec::decoder::Decoder;
+    ///
+    /// let mut decoder = Decoder::<16, u8>::new()
+    ///     .with_preamble_lock("VVV")
+    ///     .build();
+    ///
+    /// // Sender keys VVV at around 70 ms per short, then E
+    /// for _ in 0..3 {
+    ///     decoder.extend_signals([(70, true), (65, false), (75, true), (70, false), (70, true), (70, false), (210, true), (210, false)]);
+    /// }
+    /// assert!(decoder.is_preamble_locked());
+    /// assert_eq!(decoder.get_reference_short(), 70);
+    ///
+    /// decoder.extend_signals([(280, false), (70, true), (210, false)]);
+    /// assert_eq!(decoder.message.as_str(), "E");
+    /// ```
+    pub fn with_preamble_lock(mut self, preamble: &'static str) -> Self {
+        self.preamble = Some(preamble);
+
+        self
+    }
+
     /// Change the wrapping behaviour of message position to clamping.
     ///
     /// This will prevent the position cycling back to 0 when overflows or
@@ -265,212 +1024,1725 @@ impl<const MSG_MAX: usize> Decoder<MSG_MAX> {
         self
     }
 
-    /// Build and get yourself a shiny new [MorseDecoder].
+    /// Change the letter case of the message, see [LetterCase].
     ///
-    /// The ring is yours now...
-    pub fn build(self) -> MorseDecoder<MSG_MAX> {
-        let Decoder {
-            precision,
-            character_set,
-            morse_code_set,
-            signal_tolerance,
-            reference_short_ms,
-            message,
-            current_character,
-            signal_pos,
-            signal_buffer,
-        } = self;
+    /// With [LetterCase::Lower] decoded characters are written to the message in lowercase,
+    /// so UI text isn't shouted back at users. [LetterCase::Preserve] keeps the case
+    /// of a starting message, so it should be set before `with_message`.
+    pub fn with_letter_case(mut self, letter_case: LetterCase) -> Self {
+        self.message.set_letter_case(letter_case);
 
-        MorseDecoder::<MSG_MAX> {
-            precision,
-            character_set,
-            morse_code_set,
-            signal_tolerance,
-            reference_short_ms,
-            message,
-            current_character,
-            signal_pos,
-            signal_buffer,
-        }
+        self
     }
-}
-
-/// This is the concrete implementation of the decoder.
-///
-/// It doesn't have a new function, or public data members,
-/// so to get an instance of it, use public builder interface [Decoder].
-pub struct MorseDecoder<const MSG_MAX: usize> {
-    // User defined
-    precision: Precision,
-    character_set: CharacterSet,
-    morse_code_set: MorseCodeSet,
-    signal_tolerance: f32,
-    reference_short_ms: MilliSeconds,
-    pub message: Message<MSG_MAX>,
-    // Internal stuff
-    current_character: MorseCodeArray,
-    signal_pos: usize,
-    signal_buffer: SignalBuffer,
-}
 
-// Private stuff.. Don' look at it
-impl<const MSG_MAX: usize> MorseDecoder<MSG_MAX> {
-    fn get_char_from_morse_char(&self, morse_char: &MorseCodeArray) -> Character {
-        let index = self.morse_code_set
-            .iter()
-            .position(|mchar| mchar == morse_char);
+    /// Restrict decoded characters to a subset of the character set.
+    ///
+    /// Characters outside of this subset will be rejected and won't be written to the message,
+    /// unless a mapper function is provided with `with_disallowed_char_mapper`.
+    /// Note that the word space ' ' and [DECODING_ERROR_CHAR](crate::DECODING_ERROR_CHAR) are also subject to this
+    /// filter, so add them to the allowed characters if you want them in the message.
+    ///
+    /// ```ignore
+    /// // Numeric keypad application only accepts digits
+    /// let decoder = Decoder::<16>::new().with_allowed_chars(b"0123456789").build();
+    /// ```
+    pub fn with_allowed_chars(mut self, allowed_chars: &'static [C]) -> Self {
+        self.allowed_chars = Some(allowed_chars);
 
-        if let Some(i) = index {
-            self.character_set[i]
-        } else {
-            DECODING_ERROR_CHAR
-        }
+        self
     }
 
-    fn add_to_signal_buffer(&mut self, signal_duration: SignalDuration) {
-        if self.signal_pos < SIGNAL_BUFFER_LENGTH {
-            self.signal_buffer[self.signal_pos] = signal_duration;
-            self.signal_pos += 1;
-        }
+    /// Map decoded characters outside of the allowed characters to something else.
+    ///
+    /// The mapper function receives the decoded character and returns a replacement
+    /// or None to reject it. It only takes effect when allowed characters are set
+    /// with `with_allowed_chars`.
+    ///
+    /// ```ignore
+    /// // Map cut numbers to digits and reject everything else
+    /// let decoder = Decoder::<16>::new()
+    ///     .with_allowed_chars(b"0123456789")
+    ///     .with_disallowed_char_mapper(|ch| match ch {
+    ///         b'T' => Some(b'0'),
+    ///         b'N' => Some(b'9'),
+    ///         _ => None,
+    ///     })
+    ///     .build();
+    /// ```
+    pub fn with_disallowed_char_mapper(mut self, mapper: CharacterMapper<C>) -> Self {
+        self.disallowed_char_mapper = Some(mapper);
+
+        self
     }
 
-    fn decode_signal_buffer(&mut self) -> MorseCodeArray {
-        let mut morse_array: MorseCodeArray = MORSE_DEFAULT_CHAR;
+    /// Adapt the reference short duration gradually to the speed of the sender.
+    ///
+    /// By default the decoder replaces the reference short duration as soon as it receives a
+    /// shorter signal, and never slows down on its own. With speed tracking, after every character
+    /// the reference short moves towards the average speed of that character, but
+    /// by at most `max_step_ms` milliseconds. So a partner gradually speeding up from 15 to 25 WPM
+    /// is followed smoothly, while a single sloppy character can't throw the decoder off.
+    ///
+    /// ```ignore
+    /// // Follow the sender by 5 ms per character at most
+    /// let decoder = Decoder::<64>::new()
+    ///     .with_reference_short_ms(80)
+    ///     .with_speed_tracking(5)
+    ///     .build();
+    /// ```
+    pub fn with_speed_tracking(mut self, max_step_ms: MilliSeconds) -> Self {
+        self.speed_tracking_step_ms = Some(max_step_ms);
 
-        //DBG
-        //println!("Signal buffer decoding: {:?}", self.signal_buffer);
+        self
+    }
 
-        self.signal_buffer
-            .iter()
-            .take(6)
-            .enumerate()
-            .for_each(|(i, signal)| match signal {
-                SDShort(_) => {
-                    morse_array[i] = Some(S);
-                }
-                SDLong(_) => morse_array[i] = Some(L),
-                _ => {}
-            });
+    /// Ignore signals shorter than `minimum_ms` milliseconds.
+    ///
+    /// Noise bursts on a radio channel and contact bounce of a mechanical key produce very
+    /// short signals that would otherwise be decoded as dits or break up the real signals.
+    /// Signals shorter than the minimum are merged into the signal period before them,
+    /// and if the signal period after them has the same level as the period before,
+    /// these two are merged into one. Default value of 0 disables the filtering.
+    ///
+    /// ```ignore
+    /// // Key bounces for up to 8 milliseconds
+    /// let decoder = Decoder::<64>::new().with_minimum_signal_ms(10).build();
+    /// ```
+    pub fn with_minimum_signal_ms(mut self, minimum_ms: MilliSeconds) -> Self {
+        self.minimum_signal_ms = minimum_ms;
 
-        morse_array
+        self
     }
 
-    fn resolve_signal_duration(
-        &mut self,
-        duration_ms: MilliSeconds,
-        tolerance_range: &RangeInclusive<MilliSeconds>,
+    /// Flip the signal level of all inputs.
+    ///
+    /// Some hardware idles high and keys low, ie. a key switching an input with a pull-up resistor to ground.
+    /// With inverted input `is_high` parameters of signal events and binary samples given to `push_samples`
+    /// are taken as they are read from the pin, and false means the key is down.
+    ///
+    /// ```ignore
+    /// let decoder = Decoder::<64>::new().with_inverted_input().build();
+    /// ```
+    pub fn with_inverted_input(mut self) -> Self {
+        self.inverted_input = true;
+
+        self
+    }
+
+    /// Decode with other lengths of dahs and spaces than standard morse timing.
+    ///
+    /// Signals are resolved against multiples of the reference short duration given by the profile.
+    /// FarnsworthWpm precision resolves spaces between characters and words with [FarnsworthTiming]
+    /// regardless of the profile.
+    /// See [TimingProfile] for the limits of the values.
+    ///
+    /// ```ignore
+    /// // Trainer with word spaces 4 shorts long
+    /// let decoder = Decoder::<64>::new()
+    ///     .with_timing_profile(TimingProfile { word_gap_mult: 4, ..TimingProfile::STANDARD })
+    ///     .build();
+    /// ```
+    pub fn with_timing_profile(mut self, timing_profile: TimingProfile) -> Self {
+        self.timing_profile = timing_profile.sanitized();
+
+        self
+    }
+
+    /// Remove the last word from the message when the operator sends the error prosign.
+    ///
+    /// Error prosign is eight or more dits in a row. By CW convention it means the last word
+    /// was wrong and the operator sends it again, so the characters back to the last word space
+    /// are removed, including a word space right before the prosign. Decoding continues from where
+    /// the word started. Signal events that end the prosign are reported as [DecodeEvent::Corrected],
+    /// so UIs can flash a correction indicator.
+    /// Without this, the error prosign is decoded as a [DECODING_ERROR_CHAR](crate::DECODING_ERROR_CHAR).
+    ///
+    /// ```ignore
+    /// let decoder = Decoder::<64>::new().with_error_prosign_correction().build();
+    /// ```
+    pub fn with_error_prosign_correction(mut self) -> Self {
+        self.error_prosign_correction = true;
+
+        self
+    }
+
+    /// End a transmission when the key is idle for this many word spaces.
+    ///
+    /// Monitors of a frequency hear distinct transmissions with long idle gaps between them.
+    /// Without this they are glued together with a single word space. When low signals since the last
+    /// high signal add up to `word_spaces` times the word space, the word is ended, the signal event is reported
+    /// as [DecodeEvent::TransmissionEnded] and the separator, if any, replaces the word space in the message.
+    /// It's a good point to call `finish_message` as well. A gap ends a transmission only once,
+    /// and not before any high signals. Value of 0 turns this off, which is the default.
+    ///
+    /// ```ignore
+    /// // New line between transmissions after 5 word spaces of silence
+    /// let decoder = Decoder::<256, char>::new().with_transmission_gap(5, Some('\n')).build();
+    /// ```
+    pub fn with_transmission_gap(mut self, word_spaces: u16, separator: Option<C>) -> Self {
+        self.transmission_gap_mult = word_spaces;
+        self.transmission_separator = separator;
+
+        self
+    }
+
+    /// Break ties between dits and dahs with letter frequencies of a language.
+    ///
+    /// When a high signal falls between a dit and a dah, or outside of both of their tolerance ranges
+    /// but between them, both readings are tried at the end of the character. The character that
+    /// scores higher in the [LanguageModel] after the character decoded before it is written to the message.
+    /// Available with the "language-model" feature.
+    ///
+    /// ```ignore
+    /// let decoder = Decoder::<64>::new()
+    ///     .with_language_model(&morse_codec::language_model::ENGLISH)
+    ///     .build();
+    /// ```
+    #[cfg(feature = "language-model")]
+    pub fn with_language_model(mut self, language_model: &'static LanguageModel) -> Self {
+        self.language_model = Some(language_model);
+
+        self
+    }
+
+    /// Pass decoded characters or completed words through a processor before they land in the message.
+    ///
+    /// Processors run in the order they're added, each getting the output of the one before it.
+    /// Character processors run after allowed characters are checked, so they can autocapitalize,
+    /// mask or log characters. Word processors run when a word space completes a word at the end of the message,
+    /// so they can expand abbreviations. Up to [MAX_POST_PROCESSORS] processors are kept, the ones added after that are ignored.
+    ///
+    /// ```ignore
+    /// // Expand TNX to THANKS
+    /// let decoder = Decoder::<64>::new()
+    ///     .with_post_processor(PostProcessor::Word(|word, buffer| {
+    ///         (word == b"TNX").then(|| {
+    ///             buffer[..6].copy_from_slice(b"THANKS");
+    ///             6
+    ///         })
+    ///     }))
+    ///     .build();
+    /// ```
+    pub fn with_post_processor(mut self, processor: PostProcessor<C>) -> Self {
+        if let Some(slot) = self.post_processors.iter_mut().find(|slot| slot.is_none()) {
+            *slot = Some(processor);
+        }
+
+        self
+    }
+
+    /// Get notified when a character is decoded but the message is already full.
+    ///
+    /// By default the decoder keeps writing characters when the message is full, overwriting
+    /// the beginning of the message (or the last character with clamping edit position) silently.
+    /// The handler is called with the decoded character instead and decides whether it's written or dropped.
+    /// A UI can warn the operator at this point.
+    ///
+    /// ```ignore
+    /// // Keep the message intact and drop characters once it's full
+    /// let decoder = Decoder::<64>::new()
+    ///     .with_message_full_handler(|_ch| false)
+    ///     .build();
+    /// ```
+    pub fn with_message_full_handler(mut self, handler: MessageFullHandler<C>) -> Self {
+        self.message_full_handler = Some(handler);
+
+        self
+    }
+
+    /// Follow what the decoder does with the signals it receives.
+    ///
+    /// The handler is called with every decision the decoder makes: characters starting and ending,
+    /// signal durations resolved against tolerance ranges, reference short updates and signal buffers
+    /// decoded to characters. It's meant for debugging bad decodes. Print the events on a development machine,
+    /// or send them over a serial line or RTT on a device.
+    ///
+    /// ```ignore
+    /// let decoder = Decoder::<64>::new()
+    ///     .with_trace_handler(|event| println!("{:?}", event))
+    ///     .build();
+    /// ```
+    pub fn with_trace_handler(mut self, handler: TraceHandler) -> Self {
+        self.trace_handler = Some(handler);
+
+        self
+    }
+
+    /// Watch raw signal events for the continuous SOS prosign, ...---... without character spaces.
+    ///
+    /// Signals are compared with each other by a [SosDetector], not with the reference short duration,
+    /// so an SOS is detected even if the decoder is calibrated for a very different speed. The signal event
+    /// completing it returns [DecodeEvent::SosDetected] from `signal_event_ex`, which is the most significant event.
+    /// Signals are decoded as usual too.
+    ///
+    /// ```rust
+    /// use morse_codec::decoder::{Decoder, DecodeEvent, Precision};
+    ///
+    /// let mut decoder = Decoder::<16, u8>::new()
+    ///     .with_precision(Precision::Accurate)
+    ///     .with_reference_short_ms(300)
+    ///     .with_sos_detection()
+    ///     .build();
+    ///
+    /// // Sent way faster than the decoder expects
+    /// let signals = [50, 50, 50, 150, 150, 150, 50, 50, 50];
+    /// let events: Vec<DecodeEvent<u8>> = signals
+    ///     .iter()
+    ///     .flat_map(|&duration_ms| [(duration_ms, true), (50, false)])
+    ///     .map(|(duration_ms, is_high)| decoder.signal_event_ex(duration_ms, is_high))
+    ///     .collect();
+    ///
+    /// assert_eq!(events[16], DecodeEvent::SosDetected);
+    /// ```
+    pub fn with_sos_detection(mut self) -> Self {
+        self.sos_detection = true;
+
+        self
+    }
+
+    /// Check the configuration and build a [MorseDecoder] if it makes sense.
+    ///
+    /// `build` silently clamps bad values, which can produce garbage decodes later on.
+    /// This returns an error instead, so misconfiguration can be caught at startup:
+    /// * [Error::EditPositionOutOfRange] if the edit position is not less than the message capacity.
+    /// * [Error::InvalidFactor] if a signal tolerance, Lazy padding or Farnsworth factor is NaN.
+    /// * [Error::MissingReferenceShort] if precision is Accurate but reference short is 0.
+    /// * [Error::ReferenceShortTooLong] if the word space of the reference short is longer than `MilliSeconds::MAX`.
+    /// * [Error::InvalidCharacterSet] if the character set and morse code set don't match,
+    ///   see [validate](crate::charsets::validate).
+    pub fn try_build(self) -> Result<MorseDecoder<MSG_MAX, C, S>, Error> {
+        if self.requested_edit_pos.is_some_and(|pos| pos >= self.message.capacity()) {
+            return Err(Error::EditPositionOutOfRange);
+        }
+
+        validate(self.character_set, self.morse_code_set).map_err(Error::InvalidCharacterSet)?;
+
+        #[cfg(not(feature = "fixed-point"))]
+        {
+            let farnsworth_is_nan = matches!(self.precision, Farnsworth(factor) if factor.is_nan());
+            let tolerance_is_nan = self.mark_tolerance.is_nan()
+                || self.space_tolerance.is_nan()
+                || self.word_space_tolerance.is_nan();
+            if tolerance_is_nan || self.lazy_padding.is_nan() || farnsworth_is_nan {
+                return Err(Error::InvalidFactor);
+            }
+        }
+
+        if matches!(self.precision, Accurate | Training) && self.reference_short_ms == 0 {
+            return Err(Error::MissingReferenceShort);
+        }
+
+        // Lazy precision pads the word space by another short
+        let word_space_units = self.timing_profile.word_gap_mult as u32 + u32::from(self.precision == Lazy);
+        if self.reference_short_ms as u32 * word_space_units > MilliSeconds::MAX as u32 {
+            return Err(Error::ReferenceShortTooLong);
+        }
+
+        Ok(self.build())
+    }
+
+    /// Build and get yourself a shiny new [MorseDecoder].
+    ///
+    /// The ring is yours now...
+    pub fn build(self) -> MorseDecoder<MSG_MAX, C, S> {
+        let Decoder {
+            precision,
+            character_set,
+            morse_code_set,
+            mark_tolerance,
+            space_tolerance,
+            lazy_padding,
+            word_space_tolerance,
+            reference_short_ms,
+            message,
+            allowed_chars,
+            disallowed_char_mapper,
+            speed_tracking_step_ms,
+            minimum_signal_ms,
+            message_full_handler,
+            trace_handler,
+            inverted_input,
+            timing_profile,
+            error_prosign_correction,
+            transmission_gap_mult,
+            transmission_separator,
+            #[cfg(feature = "language-model")]
+            language_model,
+            post_processors,
+            preamble,
+            sos_detection,
+            requested_edit_pos: _,
+            current_character,
+            signal_pos,
+            signal_buffer,
+        } = self;
+
+        let reference_short_ms = match precision {
+            FarnsworthWpm { character_wpm, effective_wpm } if reference_short_ms == 0 => {
+                FarnsworthTiming::new(character_wpm, effective_wpm).short_ms as MilliSeconds
+            }
+            _ => reference_short_ms,
+        };
+
+        let preamble_lock = match preamble {
+            Some(_) => PreambleLock::Matching {
+                element: 0,
+                total_ms: 0,
+                total_units: 0,
+                unit_range_ms: (MilliSeconds::MAX, 0),
+                start_pos: message.get_edit_pos(),
+            },
+            None => PreambleLock::Off,
+        };
+
+        let mut decoder = MorseDecoder {
+            precision,
+            character_set,
+            morse_code_set,
+            mark_tolerance,
+            space_tolerance,
+            lazy_padding,
+            word_space_tolerance,
+            reference_short_ms,
+            message,
+            allowed_chars,
+            disallowed_char_mapper,
+            speed_tracking_step_ms,
+            minimum_signal_ms,
+            message_full_handler,
+            trace_handler,
+            inverted_input,
+            timing_profile,
+            error_prosign_correction,
+            transmission_gap_mult,
+            transmission_separator,
+            #[cfg(feature = "language-model")]
+            language_model,
+            post_processors,
+            #[cfg(not(feature = "small-footprint"))]
+            morse_tree: MorseTree::new(character_set, morse_code_set),
+            signal_thresholds: SignalThresholds::default(),
+            current_character,
+            signal_pos,
+            signal_buffer,
+            extra_dits: 0,
+            char_fault: None,
+            sample_level: false,
+            sample_run: 0,
+            decoded_char_count: 0,
+            error_count: 0,
+            speed_history: [0; SPEED_HISTORY_LENGTH],
+            speed_history_pos: 0,
+            last_signal: None,
+            elapsed_ms: 0,
+            char_timestamps: core::array::from_fn(|_| None),
+            last_event: DecodeEvent::Buffered,
+            completed_char: None,
+            last_decoded_char: None,
+            word_end_index: None,
+            transmission_gap_ms: None,
+            replay: [RecordedEvent::default(); REPLAY_LENGTH],
+            replay_pos: 0,
+            preamble,
+            preamble_lock,
+            sos_detector: sos_detection.then(SosDetector::new),
+        };
+        decoder.signal_thresholds = decoder.calculate_signal_thresholds();
+
+        decoder
+    }
+}
+
+/// This is the concrete implementation of the decoder.
+///
+/// It doesn't have a new function, or public data members,
+/// so to get an instance of it, use public builder interface [Decoder].
+#[derive(Clone, Debug)]
+pub struct MorseDecoder<const MSG_MAX: usize, C: MorseCharacter = Character, S: MessageStorage<C> = [C; MSG_MAX]> {
+    // User defined
+    precision: Precision,
+    character_set: CharacterSet<C>,
+    morse_code_set: MorseCodeSet,
+    mark_tolerance: Factor,
+    space_tolerance: Factor,
+    lazy_padding: Factor,
+    word_space_tolerance: Factor,
+    reference_short_ms: MilliSeconds,
+    pub message: Message<MSG_MAX, C, S>,
+    allowed_chars: Option<&'static [C]>,
+    disallowed_char_mapper: Option<CharacterMapper<C>>,
+    speed_tracking_step_ms: Option<MilliSeconds>,
+    minimum_signal_ms: MilliSeconds,
+    message_full_handler: Option<MessageFullHandler<C>>,
+    trace_handler: Option<TraceHandler>,
+    inverted_input: bool,
+    timing_profile: TimingProfile,
+    error_prosign_correction: bool,
+    transmission_gap_mult: u16,
+    transmission_separator: Option<C>,
+    #[cfg(feature = "language-model")]
+    language_model: Option<&'static LanguageModel>,
+    post_processors: [Option<PostProcessor<C>>; MAX_POST_PROCESSORS],
+    // Internal stuff
+    // Lookup of characters by morse code built from the character set. It takes 160 bytes on a 64-bit target,
+    // so characters are searched linearly with the "small-footprint" feature.
+    #[cfg(not(feature = "small-footprint"))]
+    morse_tree: MorseTree<C>,
+    signal_thresholds: SignalThresholds,
+    current_character: MorseCodeArray,
+    signal_pos: usize,
+    signal_buffer: SignalBuffer,
+    // Dits received after a signal buffer full of dits
+    extra_dits: u8,
+    // First signal of the character out of tolerance in training precision
+    char_fault: Option<TimingFault>,
+    // Binary sample stream state
+    sample_level: bool,
+    sample_run: u32,
+    // Quality metrics
+    decoded_char_count: u32,
+    error_count: u32,
+    // Ring buffer of reference short durations after each character
+    speed_history: [MilliSeconds; SPEED_HISTORY_LENGTH],
+    speed_history_pos: usize,
+    // Last signal period accepted when there's a minimum signal duration
+    last_signal: Option<LastSignal>,
+    // Total duration of signal events received and the time each character was decoded at
+    elapsed_ms: u32,
+    char_timestamps: CharTimestamps<MSG_MAX>,
+    // Most significant thing that happened during the last signal event
+    last_event: DecodeEvent<C>,
+    // Character written to the message by the last signal event and its index, word spaces excluded
+    completed_char: Option<(C, usize)>,
+    last_decoded_char: Option<C>,
+    // Message index of the word space that ended the last completed word
+    word_end_index: Option<usize>,
+    // Low signal time since the last high signal, None until a transmission starts
+    transmission_gap_ms: Option<u32>,
+    // Ring buffer of the last raw signal events to decode them again
+    replay: [RecordedEvent; REPLAY_LENGTH],
+    replay_pos: usize,
+    preamble: Option<&'static str>,
+    preamble_lock: PreambleLock,
+    sos_detector: Option<SosDetector>,
+}
+
+// Private stuff.. Don' look at it
+impl<const MSG_MAX: usize, C: MorseCharacter, S: MessageStorage<C>> MorseDecoder<MSG_MAX, C, S> {
+    #[cfg(not(feature = "small-footprint"))]
+    fn get_char_from_morse_char(&self, morse_char: &MorseCodeArray) -> Option<C> {
+        self.morse_tree.get(morse_char)
+    }
+
+    #[cfg(feature = "small-footprint")]
+    fn get_char_from_morse_char(&self, morse_char: &MorseCodeArray) -> Option<C> {
+        let index = self.morse_code_set
+            .iter()
+            .position(|mchar| mchar == morse_char);
+
+        index.map(|i| self.character_set[i])
+    }
+
+    // Check the character against allowed characters if there are any.
+    // Returns None if the character should not be written to the message.
+    fn filter_char(&self, ch: C) -> Option<C> {
+        match self.allowed_chars {
+            Some(allowed) if !allowed.contains(&ch) => {
+                self.disallowed_char_mapper.and_then(|mapper| mapper(ch))
+            }
+            _ => Some(ch),
+        }
+    }
+
+    fn process_char(&self, ch: C) -> Option<C> {
+        self.post_processors
+            .iter()
+            .flatten()
+            .try_fold(ch, |ch, processor| match processor {
+                PostProcessor::Char(process) => process(ch, self.last_decoded_char),
+                PostProcessor::Word(_) => Some(ch),
+            })
+    }
+
+    // Run word processors on the word ending with the word space at the index.
+    // Words are only rewritten at the end of the message, so nothing after them is moved.
+    fn process_word(&mut self, space_index: usize) {
+        let word_space = self.message.char_at(space_index);
+        if space_index + 1 != self.message.len() {
+            return;
+        }
+
+        let start = (0..space_index)
+            .rev()
+            .find(|&index| self.message.char_at(index) == word_space)
+            .map_or(0, |index| index + 1);
+
+        let mut word = [C::FILLER; MAX_WORD_LENGTH];
+        let mut length = space_index - start;
+        if length > MAX_WORD_LENGTH {
+            return;
+        }
+
+        for (index, ch) in word.iter_mut().take(length).enumerate() {
+            *ch = self.message.char_at(start + index);
+        }
+
+        let mut replaced = false;
+        for processor in self.post_processors.iter().flatten() {
+            if let PostProcessor::Word(process) = processor {
+                let mut replacement = [C::FILLER; MAX_WORD_LENGTH];
+
+                if let Some(new_length) = process(&word[..length], &mut replacement) {
+                    word = replacement;
+                    length = new_length.min(MAX_WORD_LENGTH);
+                    replaced = true;
+                }
+            }
+        }
+
+        if !replaced || start + length >= self.message.capacity() {
+            return;
+        }
+
+        while self.message.len() > start {
+            self.message.pop();
+        }
+
+        for &ch in word[..length].iter().chain(core::iter::once(&word_space)) {
+            self.message.add_char(ch);
+            self.message.shift_edit_right();
+        }
+
+        self.word_end_index = Some(start + length);
+    }
+
+    fn note_event(&mut self, event: DecodeEvent<C>) {
+        if event.rank() >= self.last_event.rank() {
+            self.last_event = event;
+        }
+    }
+
+    // Returns true if the character is written to the message.
+    fn add_char_to_message(&mut self, ch: C) -> bool {
+        if self.preamble_lock == PreambleLock::Discarding {
+            if self.is_preamble_char(ch) {
+                self.note_event(DecodeEvent::Rejected(RejectReason::Preamble));
+                self.reset_character();
+
+                return false;
+            }
+
+            self.preamble_lock = PreambleLock::Locked;
+        }
+
+        let ch = self.filter_char(ch).and_then(|ch| self.process_char(ch));
+        let ch = ch.filter(|ch| match self.message_full_handler {
+            Some(handler) if self.message.is_full() => handler(*ch),
+            _ => true,
+        });
+
+        self.note_event(match ch {
+            Some(ch) => DecodeEvent::CharCompleted(ch),
+            None => DecodeEvent::Rejected(RejectReason::Filtered),
+        });
+
+        let written = ch.is_some();
+
+        if let Some(ch) = ch {
+            self.message.add_char(ch);
+            self.last_decoded_char = Some(ch);
+            if let Some(timestamp) = self.char_timestamps.get_mut(self.message.get_edit_pos()) {
+                *timestamp = Some(self.elapsed_ms);
+            }
+
+            // If message position is clamping then this should not do anything.
+            // at the end of message position.
+            // If wrapping then it should reset the position to 0, so above condition
+            // should pass next time.
+            self.message.shift_edit_right();
+        }
+
+        self.reset_character();
+
+        written
+    }
+
+    fn add_error_char_to_message(&mut self) {
+        self.error_count = self.error_count.saturating_add(1);
+
+        if self.add_char_to_message(C::DECODING_ERROR_CHAR) {
+            self.completed_char = Some((C::DECODING_ERROR_CHAR, self.message.get_last_changed_index()));
+            self.note_event(DecodeEvent::Error);
+        }
+    }
+
+    // Rounds to the nearest millisecond. Durations shorter than that become a millisecond,
+    // so they aren't lost as zero length signals.
+    fn us_to_ms(duration_us: u32) -> MilliSeconds {
+        if duration_us == 0 {
+            return 0;
+        }
+
+        (duration_us.saturating_add(500) / 1000).clamp(1, MilliSeconds::MAX as u32) as MilliSeconds
+    }
+
+    fn samples_to_ms(samples: u32, sample_rate: u32) -> MilliSeconds {
+        let ms = samples as u64 * 1000 / sample_rate.max(1) as u64;
+
+        ms.min(MilliSeconds::MAX as u64) as MilliSeconds
+    }
+
+    fn add_to_signal_buffer(&mut self, signal_duration: SignalDuration) {
+        if self.signal_pos < SIGNAL_BUFFER_LENGTH {
+            self.signal_buffer[self.signal_pos] = signal_duration;
+            self.signal_pos += 1;
+        }
+    }
+
+    fn decode_signal_buffer(&mut self) -> MorseCodeArray {
+        #[cfg(feature = "language-model")]
+        if let Some(morse_array) = self.disambiguate_signal_buffer() {
+            self.trace(|| TraceEvent::BufferDecoded(morse_array));
+
+            return morse_array;
+        }
+
+        let mut morse_array: MorseCodeArray = MORSE_DEFAULT_CHAR;
+
+        self.signal_buffer
+            .iter()
+            .take(6)
+            .enumerate()
+            .for_each(|(i, signal)| match signal {
+                SDShort(_) => {
+                    morse_array[i] = Some(S);
+                }
+                SDLong(_) => morse_array[i] = Some(L),
+                _ => {}
+            });
+
+        self.trace(|| TraceEvent::BufferDecoded(morse_array));
+
+        morse_array
+    }
+
+    // Try both readings of the high signals between a dit and a dah and pick the most probable character.
+    // Returns None if there are no such signals or none of the readings is a character.
+    #[cfg(feature = "language-model")]
+    fn disambiguate_signal_buffer(&self) -> Option<MorseCodeArray> {
+        let language_model = self.language_model?;
+
+        let short_ms = self.reference_short_ms;
+        let long_ms = self.long_signal_ms(true);
+        // Resolved signals are ambiguous only in the middle third between a dit and a dah
+        let margin = long_ms.saturating_sub(short_ms) / 3;
+        let is_ambiguous = |signal: &SignalDuration| match *signal {
+            SDOther(ms) => ms > short_ms && ms < long_ms,
+            SDShort(ms) | SDLong(ms) => ms > short_ms + margin && ms < long_ms - margin,
+            SDWordSpace(_) | SDEmpty => false,
+        };
+
+        let signals = &self.signal_buffer[..self.signal_pos.min(MORSE_ARRAY_LENGTH)];
+        let ambiguous_count = signals.iter().filter(|signal| is_ambiguous(signal)).count();
+        if ambiguous_count == 0 {
+            return None;
+        }
+
+        let previous = self.last_decoded_char.map_or(' ', |ch| ch.to_char());
+
+        // Each bit of a reading is an ambiguous signal, set for a dah
+        (0..1u8 << ambiguous_count)
+            .filter_map(|reading| {
+                let mut morse_array = MORSE_DEFAULT_CHAR;
+                let mut bit = 0;
+
+                for (signal, morse_signal) in signals.iter().zip(morse_array.iter_mut()) {
+                    *morse_signal = if is_ambiguous(signal) {
+                        bit += 1;
+
+                        Some(if reading >> (bit - 1) & 1 == 1 { L } else { S })
+                    } else {
+                        match signal {
+                            SDShort(_) => Some(S),
+                            SDLong(_) => Some(L),
+                            _ => None,
+                        }
+                    };
+                }
+
+                let ch = self.get_char_from_morse_char(&morse_array)?;
+
+                Some((language_model.score(previous, ch.to_char()), morse_array))
+            })
+            .max_by_key(|(score, _)| *score)
+            .map(|(_, morse_array)| morse_array)
+    }
+
+    fn trace<F: FnOnce() -> TraceEvent>(&self, event: F) {
+        if let Some(handler) = self.trace_handler {
+            handler(&event());
+        }
+    }
+
+    fn trace_resolved(
+        &self,
+        duration_ms: MilliSeconds,
+        is_high: bool,
+        tolerance_range: &RangeInclusive<MilliSeconds>,
+        resolved_duration: SignalDuration,
+    ) {
+        self.trace(|| TraceEvent::SignalResolved {
+            duration_ms,
+            is_high,
+            position: self.signal_pos,
+            tolerance_range: tolerance_range.clone(),
+            reference_short_ms: self.reference_short_ms,
+            class: match resolved_duration {
+                SDShort(_) => SignalClass::Short,
+                SDLong(_) => SignalClass::Long,
+                SDWordSpace(_) => SignalClass::WordSpace,
+                SDOther(_) | SDEmpty => SignalClass::Other,
+            },
+        });
+    }
+
+    fn resolve_signal_duration(
+        &self,
+        duration_ms: MilliSeconds,
+        tolerance_range: &RangeInclusive<MilliSeconds>,
         is_high: bool,
     ) -> SignalDuration {
-        let resolve_accurate_or_farnsworth = |long_ms: MilliSeconds| -> SignalDuration {
-            if tolerance_range.contains(&self.reference_short_ms) {
-                SDShort(duration_ms)
-            } else if tolerance_range.contains(&long_ms) {
-                SDLong(duration_ms)
-            } else {
-                SDOther(duration_ms)
+        let thresholds = self.signal_thresholds();
+        let class = if is_high { 0 } else { 1 };
+
+        if !is_high && duration_ms >= thresholds.word_space_ms {
+            return SDWordSpace(duration_ms);
+        }
+
+        match self.precision {
+            Lazy => {
+                let short_range_end = thresholds.lazy_short_end_ms[class];
+
+                if (0u16..short_range_end).contains(&duration_ms) {
+                    SDShort(duration_ms)
+                } else if (short_range_end..thresholds.word_space_ms).contains(&duration_ms) {
+                    SDLong(duration_ms)
+                } else {
+                    SDOther(duration_ms)
+                }
+            }
+            Accurate | Training | Farnsworth(_) | FarnsworthWpm { .. } => {
+                if tolerance_range.contains(&self.reference_short_ms) {
+                    SDShort(duration_ms)
+                } else if tolerance_range.contains(&thresholds.long_ms[class]) {
+                    SDLong(duration_ms)
+                } else {
+                    SDOther(duration_ms)
+                }
+            }
+        }
+    }
+
+    fn signal_thresholds(&self) -> SignalThresholds {
+        if self.signal_thresholds.reference_short_ms == self.reference_short_ms {
+            self.signal_thresholds
+        } else {
+            self.calculate_signal_thresholds()
+        }
+    }
+
+    fn refresh_signal_thresholds(&mut self) {
+        if self.signal_thresholds.reference_short_ms != self.reference_short_ms {
+            self.signal_thresholds = self.calculate_signal_thresholds();
+        }
+    }
+
+    fn calculate_signal_thresholds(&self) -> SignalThresholds {
+        let long_space_ms = match self.precision {
+            Farnsworth(factor) => {
+                self.calculate_farnsworth_short(factor).saturating_mul(self.timing_profile.char_gap_mult as MilliSeconds)
+            }
+            FarnsworthWpm { character_wpm, effective_wpm } => {
+                let char_space_ms = FarnsworthTiming::new(character_wpm, effective_wpm).char_space_ms;
+
+                char_space_ms.min(MilliSeconds::MAX as u32) as MilliSeconds
+            }
+            Lazy | Accurate | Training => self.long_signal_ms(false),
+        };
+
+        let lazy_short_end_ms = |is_high: bool| {
+            let short_tolerance_range = self.signal_tolerance_range(self.reference_short_ms, is_high);
+
+            short_tolerance_range.end().saturating_add(scale_ms(self.reference_short_ms, self.lazy_padding))
+        };
+
+        SignalThresholds {
+            reference_short_ms: self.reference_short_ms,
+            long_ms: [self.long_signal_ms(true), long_space_ms],
+            lazy_short_end_ms: [lazy_short_end_ms(true), lazy_short_end_ms(false)],
+            word_space_ms: self.word_space_start_ms(long_space_ms),
+        }
+    }
+
+    fn signal_tolerance_range(&self, duration_ms: MilliSeconds, is_high: bool) -> RangeInclusive<MilliSeconds> {
+        let tolerance = if is_high { self.mark_tolerance } else { self.space_tolerance };
+        let diff = scale_ms(duration_ms, tolerance);
+
+        // Keep at least a millisecond of tolerance, so ranges of high speed signals
+        // only a few milliseconds long don't collapse to a single value.
+        let diff = diff.max(1).min(duration_ms);
+
+        duration_ms - diff..=duration_ms.saturating_add(diff)
+    }
+
+    // Durations with a tolerance range containing the given duration. Both ends of tolerance ranges grow
+    // with the duration, so these are a single band from the shortest to the longest one. Its ends are
+    // estimated from the tolerance in thousandths, then rounding of the estimates is fixed by stepping
+    // to the exact ends.
+    fn tolerance_band(&self, duration_ms: MilliSeconds, is_high: bool) -> RangeInclusive<MilliSeconds> {
+        let tolerance = if is_high { self.mark_tolerance } else { self.space_tolerance };
+        let tolerance_thousandths = scale_ms(1000, tolerance).min(1000) as u32;
+
+        let reaches = |ms: MilliSeconds| *self.signal_tolerance_range(ms, is_high).end() >= duration_ms;
+        let covers = |ms: MilliSeconds| *self.signal_tolerance_range(ms, is_high).start() <= duration_ms;
+        let clamp = |ms: u32| ms.clamp(1, MilliSeconds::MAX as u32) as MilliSeconds;
+
+        let scaled_ms = duration_ms as u32 * 1000;
+        let mut start = clamp(scaled_ms / (1000 + tolerance_thousandths));
+        let mut end = clamp(scaled_ms.checked_div(1000 - tolerance_thousandths).unwrap_or(u32::MAX));
+
+        while start > 1 && reaches(start - 1) {
+            start -= 1;
+        }
+        while !reaches(start) {
+            start += 1;
+        }
+        while end < MilliSeconds::MAX && covers(end + 1) {
+            end += 1;
+        }
+        while !covers(end) {
+            end -= 1;
+        }
+
+        start..=end
+    }
+
+    fn reset_character(&mut self) {
+        self.signal_buffer = [SDEmpty; SIGNAL_BUFFER_LENGTH];
+        self.signal_pos = 0;
+        self.extra_dits = 0;
+        self.char_fault = None;
+        self.current_character = MORSE_DEFAULT_CHAR;
+    }
+
+    fn is_all_dits(&self) -> bool {
+        self.signal_buffer.iter().all(|signal| matches!(signal, SDShort(_)))
+    }
+
+    fn update_reference_short_ms(&mut self, duration_ms: MilliSeconds) {
+        self.reference_short_ms = duration_ms;
+        self.refresh_signal_thresholds();
+        self.trace(|| TraceEvent::ReferenceShortUpdated(duration_ms));
+    }
+
+    // Average short duration of the signals in the buffer. Long signals count as
+    // a third of their duration.
+    fn measure_short_ms(&self) -> Option<MilliSeconds> {
+        let (total, count) = self.signal_buffer
+            .iter()
+            .fold((0u32, 0u32), |(total, count), signal| match signal {
+                SDShort(ms) => (total + *ms as u32, count + 1),
+                SDLong(ms) => (total + *ms as u32 / self.timing_profile.dah_mult as u32, count + 1),
+                _ => (total, count),
+            });
+
+        (count > 0).then(|| (total / count) as MilliSeconds)
+    }
+
+    // Applies the minimum signal duration to a signal event. Returns true if the event
+    // was consumed as a short glitch or merged into the previous signal period.
+    fn filter_short_signal(&mut self, duration_ms: MilliSeconds, is_high: bool) -> bool {
+        if duration_ms < self.minimum_signal_ms {
+            if let Some(last) = self.last_signal.as_mut() {
+                last.duration_ms = last.duration_ms.saturating_add(duration_ms);
+            }
+
+            return true;
+        }
+
+        match self.last_signal.take() {
+            // The signal period was split by a glitch. Decode it again as a whole.
+            Some(last) if last.is_high == is_high => {
+                let merged_ms = last.duration_ms.saturating_add(duration_ms);
+
+                if self.signal_pos > 0 {
+                    if is_high {
+                        // Replace the first part of the high signal in the buffer
+                        self.signal_pos -= 1;
+                    }
+
+                    self.resolve_signal_event(merged_ms, is_high);
+                } else if !is_high
+                    && last.signal_pos > 0
+                    && last.duration_ms < self.word_space_ms()
+                    && merged_ms >= self.word_space_ms() {
+                    // First part of the low signal has ended the character already,
+                    // but as a whole it's a word space.
+                    self.current_character = MORSE_DEFAULT_CHAR;
+                    self.add_current_char_to_message();
+                }
+
+                self.last_signal = Some(LastSignal { duration_ms: merged_ms, ..last });
+
+                true
+            }
+            _ => {
+                self.last_signal = Some(LastSignal { duration_ms, is_high, signal_pos: self.signal_pos });
+
+                false
+            }
+        }
+    }
+
+    // Resolve the duration of a signal event and add it to the signal buffer,
+    // ending characters and words along the way.
+    fn resolve_signal_event(&mut self, duration_ms: MilliSeconds, is_high: bool) {
+        let tolerance_range = self.signal_tolerance_range(duration_ms, is_high);
+
+        match self.signal_pos {
+            // Signal is the first in the series.
+            // Since this is the first signal we encounter, we'll treat it as if it's a short, when
+            // reference_short_ms == 0, otherwise try to resolve signal duration based on
+            // reference short learned from previous letters or based on
+            // initial_reference_short_ms provided to the constructor.
+            // If we have set it short preemptively, we later on check if this first short turns out to be long instead
+            // (see one of the match arms). We'll update the first buffer item with the correct value then don't worry.
+            0 => {
+                if is_high {
+                    self.trace(|| TraceEvent::CharacterStarted);
+
+                    if self.reference_short_ms == 0 {
+                        self.add_to_signal_buffer(SDShort(duration_ms));
+                        self.update_reference_short_ms(duration_ms);
+                    } else {
+                        let resolved_duration = self.resolve_signal_duration(duration_ms, &tolerance_range, is_high);
+
+                        self.trace_resolved(duration_ms, is_high, &tolerance_range, resolved_duration);
+                        self.check_timing_fault(resolved_duration, is_high);
+
+                        self.add_to_signal_buffer(resolved_duration);
+                    }
+                } else {
+                    // Do nothing if we receive a low signal at the start of a series.
+                    // This happens when event engine of the client code sends low signals
+                    // inadvertently perhaps while idling or outright sends a wrong low signal at the start of a letter
+                    // which is rude.
+                }
             }
-        };
 
-        match self.precision {
-            Lazy => {
-                let short_tolerance_range = self.signal_tolerance_range(self.reference_short_ms);
-                let short_range_end = short_tolerance_range.end() + 50; // 50 ms padding gives better results with humans
+            // Signal is not high. It can be one of three things at this point:
+            // 1. It's a short duration space signal (space between two signals)
+            // 2. It's a long duration space. At this point we decode the entire signal buffer and
+            // add resulting character to the message
+            // 3. It's a very long signal (x7 or more) to divide two words in the message. So
+            // we check the signal buffer and add the character, as well as a space after it.
+            _pos if !is_high => {
+                // Speed tracking takes care of reference short updates gradually at the end of characters
+                if self.speed_tracking_step_ms.is_none()
+                    && self.precision != Training
+                    && duration_ms < self.reference_short_ms
+                    && !tolerance_range.contains(&self.reference_short_ms) {
+                    self.update_reference_short_ms(duration_ms);
+                }
 
-                if (0u16..short_range_end).contains(&duration_ms) {
-                    SDShort(duration_ms)
-                } else if (short_range_end..self.word_space_ms()).contains(&duration_ms) {
-                    SDLong(duration_ms)
-                } else {
-                    SDOther(duration_ms)
+                let resolved_duration = self.resolve_signal_duration(duration_ms, &tolerance_range, is_high);
+
+                self.trace_resolved(duration_ms, is_high, &tolerance_range, resolved_duration);
+                self.check_timing_fault(resolved_duration, is_high);
+
+                match resolved_duration {
+                    SDWordSpace(_) => {
+                        self.trace(|| TraceEvent::WordEnded);
+
+                        self.signal_event_end(true);
+                    }
+                    SDLong(_) => {
+                        self.trace(|| TraceEvent::CharacterEnded);
+
+                        self.signal_event_end(false);
+                    }
+                    _ => (),
                 }
             }
-            Accurate => {
-                resolve_accurate_or_farnsworth(self.long_signal_ms())
+
+            // Signal is not the first in a series and there are signals to be fed to the buffer.
+            // At this point signal is high and we try to resolve signal duration and save the signal to character.
+            // Also we check if the first duration in the array was wrongly saved as short but
+            // should be long instead. We fix it to long duration if it's wrong.
+            // The reason why we check at this position starting from index 2+ is that
+            // we get a better calibrated short signal from the low signal before it (index 1)
+            pos if pos < SIGNAL_BUFFER_LENGTH && is_high => {
+                let resolved_duration = self.resolve_signal_duration(duration_ms, &tolerance_range, is_high);
+
+                self.trace_resolved(duration_ms, is_high, &tolerance_range, resolved_duration);
+                self.check_timing_fault(resolved_duration, is_high);
+
+                self.add_to_signal_buffer(resolved_duration);
+
+                if let SDShort(first_duration) = self.signal_buffer[0] {
+                    match resolved_duration {
+                        SDLong(_) => {
+                            // If current signal is long and it's tolerance range contains the
+                            // first short signal, the first short signal should be a long
+                            if tolerance_range.contains(&first_duration) {
+                                self.signal_buffer[0] = SDLong(duration_ms);
+                            }
+                        }
+                        SDShort(_) => {
+                            // This is an edge case we need to handle where the character being
+                            // decoded, has a long high signal as the first signal in it and
+                            // has only short signals after it (including this one). If tolerance range
+                            // of the short signal we just got happens to be in the range of first
+                            // short signal divided by long signal multiplier (by default 3),
+                            // first short signal was indeed a long one, but we missed it.
+                            if tolerance_range.contains(&(first_duration / self.timing_profile.dah_mult as MilliSeconds)) {
+                                self.signal_buffer[0] = SDLong(duration_ms);
+                            }
+                        }
+                        _ => (),
+                    }
+                }
             }
-            Farnsworth(factor) => {
-                if is_high {
-                    resolve_accurate_or_farnsworth(self.long_signal_ms())
-                } else {
-                    let farnsworth_long = self.calculate_farnsworth_short(factor) * LONG_SIGNAL_MULTIPLIER;
 
-                    resolve_accurate_or_farnsworth(farnsworth_long)
+            // This means we got the maximum amount of signals to the buffer, but still couldn't
+            // decode the character. Either because we never received a character ender low
+            // signal (3x short space) or a word ending long signal (7x short space)
+            // or outright couldn't decode them, but hey.
+            // We put a decoding error character at this point. And move on.
+            _ => {
+                if self.error_prosign_correction && self.is_all_dits() {
+                    let resolved_duration = self.resolve_signal_duration(duration_ms, &tolerance_range, is_high);
+
+                    self.trace_resolved(duration_ms, is_high, &tolerance_range, resolved_duration);
+
+                    // Error prosign goes on as long as the dits do
+                    if let SDShort(_) = resolved_duration {
+                        self.extra_dits = self.extra_dits.saturating_add(1);
+
+                        return;
+                    }
                 }
+
+                self.trace(|| TraceEvent::BufferOverflow);
+                self.add_error_char_to_message();
             }
         }
     }
 
-    fn signal_tolerance_range(&self, duration_ms: MilliSeconds) -> RangeInclusive<MilliSeconds> {
-        let diff = (duration_ms as f32 * self.signal_tolerance) as MilliSeconds;
+    // Keep the first signal of the character out of tolerance in training precision.
+    // It's taken as the element closest to it. Signals in the buffer so far count towards its ordinal.
+    fn check_timing_fault(&mut self, resolved_duration: SignalDuration, is_high: bool) {
+        let SDOther(actual_ms) = resolved_duration else {
+            return;
+        };
 
-        duration_ms - diff..=duration_ms.saturating_add(diff)
+        if self.precision != Training || self.char_fault.is_some() {
+            return;
+        }
+
+        let short_ms = self.reference_short_ms;
+        let long_ms = self.signal_thresholds().long_ms[if is_high { 0 } else { 1 }];
+        let is_long = actual_ms as u32 * 2 >= short_ms as u32 + long_ms as u32;
+
+        let count_of = |long: bool| {
+            self.signal_buffer[..self.signal_pos]
+                .iter()
+                .filter(|signal| matches!((signal, long), (SDShort(_), false) | (SDLong(_), true)))
+                .count()
+        };
+
+        let (element, ordinal) = match (is_high, is_long) {
+            (true, false) => (TimingElement::Dit, count_of(false) + 1),
+            (true, true) => (TimingElement::Dah, count_of(true) + 1),
+            (false, false) => (TimingElement::SignalSpace, self.signal_pos),
+            (false, true) => (TimingElement::CharSpace, 1),
+        };
+
+        self.char_fault = Some(TimingFault {
+            element,
+            ordinal: ordinal.min(u8::MAX as usize) as u8,
+            expected_ms: if is_long { long_ms } else { short_ms },
+            actual_ms,
+        });
     }
 
-    fn reset_character(&mut self) {
-        self.signal_buffer = [SDEmpty; SIGNAL_BUFFER_LENGTH];
-        self.signal_pos = 0;
-        self.current_character = MORSE_DEFAULT_CHAR;
+    // Match a signal with the next element of the first preamble word and lock the reference
+    // short duration when the word is complete. Returns true if the signal locked it.
+    fn match_preamble(&mut self, duration_ms: MilliSeconds, is_high: bool) -> bool {
+        let PreambleLock::Matching { element, total_ms, total_units, unit_range_ms, start_pos } = self.preamble_lock else {
+            return false;
+        };
+
+        // Idle before the preamble starts
+        if element == 0 && !is_high {
+            return false;
+        }
+
+        let word = self.preamble.and_then(|preamble| preamble.split_whitespace().next()).unwrap_or("");
+        let mut elements = known_elements(self.character_set, self.morse_code_set, self.timing_profile, word)
+            .skip(element as usize);
+
+        let Some(Ok((units, expected_high, class))) = elements.next() else {
+            self.preamble_lock = PreambleLock::Off;
+
+            return false;
+        };
+
+        // Space after the word can be any long, so the word is complete at its last signal
+        let is_complete = elements.nth(1).is_none();
+        let mut unit_range_ms = unit_range_ms;
+
+        let is_match = is_high == expected_high && match class {
+            CLASS_CHAR_SPACE => duration_ms as u32 * total_units as u32 >= 2 * total_ms,
+            _ => {
+                let unit_ms = duration_ms / units.max(1);
+                unit_range_ms = (unit_range_ms.0.min(unit_ms), unit_range_ms.1.max(unit_ms));
+
+                unit_range_ms.1 as u32 * 2 <= unit_range_ms.0 as u32 * 3
+            }
+        };
+
+        if !is_match {
+            self.preamble_lock = PreambleLock::Off;
+
+            return false;
+        }
+
+        let (total_ms, total_units) = match class {
+            CLASS_CHAR_SPACE => (total_ms, total_units),
+            _ => (total_ms + duration_ms as u32, total_units + units),
+        };
+
+        if !is_complete {
+            self.preamble_lock = PreambleLock::Matching {
+                element: element.saturating_add(1),
+                total_ms,
+                total_units,
+                unit_range_ms,
+                start_pos,
+            };
+
+            return false;
+        }
+
+        let reference_short_ms = (total_ms + total_units as u32 / 2) / total_units.max(1) as u32;
+        self.reference_short_ms = reference_short_ms.clamp(1, MilliSeconds::MAX as u32) as MilliSeconds;
+        self.refresh_signal_thresholds();
+
+        // Characters decoded from the preamble before the lock
+        if start_pos <= self.message.len() {
+            while self.message.len() > start_pos {
+                self.message.pop();
+            }
+
+            self.message.set_edit_pos(start_pos);
+        }
+
+        self.last_decoded_char = None;
+        self.word_end_index = None;
+        self.reset_character();
+        self.preamble_lock = PreambleLock::Discarding;
+
+        true
     }
 
-    fn update_reference_short_ms(&mut self, duration_ms: MilliSeconds) {
-        self.reference_short_ms = duration_ms;
+    // Characters of the preamble and word spaces are left out of the message right after the lock.
+    fn is_preamble_char(&self, ch: C) -> bool {
+        let preamble = self.preamble.unwrap_or("");
+
+        self.get_char_from_morse_char(&MORSE_DEFAULT_CHAR) == Some(ch)
+            || preamble.chars().any(|preamble_ch| C::from_char(preamble_ch).and_then(|ch| ch.to_upper()) == Some(ch))
+    }
+
+    // Keep a raw signal event in the replay ring buffer, before the input is inverted.
+    fn record_event(&mut self, duration_ms: MilliSeconds, is_high: bool) {
+        if REPLAY_LENGTH == 0 {
+            return;
+        }
+
+        self.replay[self.replay_pos % REPLAY_LENGTH] = RecordedEvent {
+            duration_ms,
+            is_high,
+            starts_char: is_high != self.inverted_input && self.signal_pos == 0,
+            reference_short_ms: self.reference_short_ms,
+            edit_pos: self.message.get_edit_pos(),
+        };
+        self.replay_pos = self.replay_pos.saturating_add(1);
+    }
+
+    // Called at the end of every character while the signal buffer is still intact.
+    fn track_speed(&mut self) {
+        if self.signal_pos == 0 {
+            return;
+        }
+
+        if let (Some(max_step), Some(measured)) = (self.speed_tracking_step_ms, self.measure_short_ms()) {
+            let current = self.reference_short_ms;
+
+            self.reference_short_ms = measured.clamp(current.saturating_sub(max_step), current.saturating_add(max_step));
+            self.refresh_signal_thresholds();
+        }
+
+        self.speed_history[self.speed_history_pos % SPEED_HISTORY_LENGTH] = self.reference_short_ms;
+        self.speed_history_pos = self.speed_history_pos.wrapping_add(1);
     }
 
-    fn long_signal_ms(&self) -> MilliSeconds {
-        self.reference_short_ms * LONG_SIGNAL_MULTIPLIER
+    fn long_signal_ms(&self, is_high: bool) -> MilliSeconds {
+        let TimingProfile { dah_mult, char_gap_mult, .. } = self.timing_profile;
+
+        let multiplier = if is_high { dah_mult } else { char_gap_mult };
+
+        self.reference_short_ms.saturating_mul(multiplier as MilliSeconds)
     }
 
     fn word_space_ms(&self) -> MilliSeconds {
+        self.signal_thresholds().word_space_ms
+    }
+
+    // Start of the open-ended word space class: nominal word space less its tolerance.
+    // Tolerance doesn't move it to the nominal character space or before it.
+    fn word_space_start_ms(&self, long_space_ms: MilliSeconds) -> MilliSeconds {
+        let word_space_ms = self.calculate_word_space_ms();
+        let tolerance_ms = scale_ms(word_space_ms, self.word_space_tolerance);
+
+        word_space_ms
+            .saturating_sub(tolerance_ms)
+            .max(word_space_ms.min(long_space_ms.saturating_add(1)))
+    }
+
+    fn calculate_word_space_ms(&self) -> MilliSeconds {
+        let word_gap_mult = self.timing_profile.word_gap_mult as MilliSeconds;
         let multiplier = match self.precision {
             // Adding some padding to the end of word space to aid the lazy sleazy operator
-            Lazy => WORD_SPACE_MULTIPLIER + 1,
-            Accurate => WORD_SPACE_MULTIPLIER,
+            Lazy => word_gap_mult + 1,
+            Accurate | Training => word_gap_mult,
             // Early return if we have a Farnsworth precision.
             // We calculate the word space from a slower
             // farnsworth short duration and return it.
             Farnsworth(factor) => {
-                return self.calculate_farnsworth_short(factor) * WORD_SPACE_MULTIPLIER
+                return self.calculate_farnsworth_short(factor).saturating_mul(word_gap_mult)
+            }
+            FarnsworthWpm { character_wpm, effective_wpm } => {
+                let word_space_ms = FarnsworthTiming::new(character_wpm, effective_wpm).word_space_ms;
+
+                return word_space_ms.min(MilliSeconds::MAX as u32) as MilliSeconds;
             }
         };
 
-        self.reference_short_ms * multiplier
+        self.reference_short_ms.saturating_mul(multiplier)
+    }
+
+    #[cfg(not(feature = "fixed-point"))]
+    fn short_ms_to_wpm(short_ms: MilliSeconds) -> u16 {
+        (1.2 / (short_ms as f32 / 1000.0)) as u16
     }
 
-    fn calculate_farnsworth_short(&self, speed_reduction_factor: f32) -> MilliSeconds {
+    #[cfg(feature = "fixed-point")]
+    fn short_ms_to_wpm(short_ms: MilliSeconds) -> u16 {
+        1200u16.checked_div(short_ms).unwrap_or(u16::MAX)
+    }
+
+    #[cfg(not(feature = "fixed-point"))]
+    fn calculate_farnsworth_short(&self, speed_reduction_factor: Factor) -> MilliSeconds {
         // WPM stands for Words per Minute
         let current_wpm = self.get_wpm() as f32;
-        //println!("FARNSWORTH: current WPM: {}", current_wpm);
+        let reduced_wpm = current_wpm * speed_reduction_factor;
+
+        let delay_time_ms = (((60.0 * current_wpm) - (37.2 * reduced_wpm)) / (current_wpm * reduced_wpm)) * 1000.0;
+        let short_ms = (delay_time_ms / 19.0) as MilliSeconds;
+
+        self.trace(|| TraceEvent::FarnsworthShort {
+            current_wpm: current_wpm as u16,
+            reduced_wpm: reduced_wpm as u16,
+            short_ms,
+        });
+
+        short_ms
+    }
+
+    // Same calculation as above in integer math. Reduced WPM is kept in thousandths
+    // and 37.2 is scaled by 10, so the delay time is
+    // (600000 * current - 372 * reduced) * 100 / (current * reduced) milliseconds.
+    #[cfg(feature = "fixed-point")]
+    fn calculate_farnsworth_short(&self, speed_reduction_factor: Factor) -> MilliSeconds {
+        let current_wpm = self.get_wpm() as u64;
+        let reduced_wpm = current_wpm * speed_reduction_factor as u64;
+
+        let numerator = (600_000 * current_wpm).saturating_sub(372 * reduced_wpm) * 100;
+        let delay_time_ms = numerator
+            .checked_div(current_wpm * reduced_wpm)
+            .unwrap_or(u64::MAX);
+
+        let short_ms = (delay_time_ms / 19).min(MilliSeconds::MAX as u64) as MilliSeconds;
+
+        self.trace(|| TraceEvent::FarnsworthShort {
+            current_wpm: current_wpm as u16,
+            reduced_wpm: (reduced_wpm / 1000) as u16,
+            short_ms,
+        });
+
+        short_ms
+    }
+}
+
+// Public API for the masses
+impl<const MSG_MAX: usize, C: MorseCharacter, S: MessageStorage<C>> MorseDecoder<MSG_MAX, C, S> {
+    /// Returns currently resolved reference short signal duration.
+    ///
+    /// Reference short signal is resolved continuously by the decoder as signal events pour in.
+    /// As longer signal durations are calculated by multiplying this value,
+    /// it might be useful for the client code.
+    pub fn get_reference_short(&self) -> MilliSeconds {
+        self.reference_short_ms
+    }
+
+    /// Returns true if the reference short duration is locked on the preamble. See `with_preamble_lock`.
+    pub fn is_preamble_locked(&self) -> bool {
+        matches!(self.preamble_lock, PreambleLock::Discarding | PreambleLock::Locked)
+    }
+
+    /// Returns the signal durations currently accepted as dits, dahs, character and word spaces.
+    ///
+    /// They depend on the precision mode, signal tolerance and the reference short duration,
+    /// so they change as the decoder tracks the speed of the operator. Returns None
+    /// if the reference short duration isn't known yet.
+    ///
+    /// ```rust
+    /// use morse_codec::decoder::{Decoder, Precision};
+    ///
+    /// let decoder = Decoder::<16>::new()
+    ///     .with_precision(Precision::Accurate)
+    ///     .with_reference_short_ms(100)
+    ///     .build();
+    ///
+    /// let thresholds = decoder.current_thresholds().unwrap();
+    /// assert_eq!(thresholds.dit, Some(67..=200));
+    /// assert_eq!(thresholds.dah, Some(201..=600));
+    /// assert_eq!(thresholds.char_space, Some(201..=600));
+    /// assert_eq!(thresholds.word_space_min, Some(700));
+    /// ```
+    pub fn current_thresholds(&self) -> Option<Thresholds> {
+        if self.reference_short_ms == 0 {
+            return None;
+        }
+
+        let thresholds = self.signal_thresholds();
+
+        // Low signals from the start of the word space on end a word. Bands start from a millisecond,
+        // and high signal bands reach up to the start of the word space.
+        let word_space_min = thresholds.word_space_ms.max(1) as u32;
+        let char_space_max = word_space_min - 1;
+
+        let (dit, dah, char_space) = match self.precision {
+            Lazy => {
+                let [mark_short_end, space_short_end] = thresholds.lazy_short_end_ms.map(|ms| ms as u32);
+
+                (
+                    duration_band(1, mark_short_end.saturating_sub(1).min(word_space_min)),
+                    duration_band(mark_short_end, char_space_max),
+                    duration_band(space_short_end, char_space_max),
+                )
+            }
+            Accurate | Training | Farnsworth(_) | FarnsworthWpm { .. } => {
+                // Short signals win over long ones when both are in the tolerance range
+                let long_band = |long_ms: MilliSeconds, is_high: bool, end_ms: u32| {
+                    let short_band = self.tolerance_band(self.reference_short_ms, is_high);
+                    let long_band = self.tolerance_band(long_ms, is_high);
+
+                    let (start, end) = (*long_band.start() as u32, *long_band.end() as u32);
+                    let (short_start, short_end) = (*short_band.start() as u32, *short_band.end() as u32);
+
+                    let start = if short_band.contains(long_band.start()) { short_end + 1 } else { start };
+                    let end = if short_band.contains(long_band.end()) { short_start.saturating_sub(1) } else { end };
+
+                    duration_band(start, end.min(end_ms))
+                };
+
+                let short_band = self.tolerance_band(self.reference_short_ms, true);
+
+                (
+                    duration_band(*short_band.start() as u32, (*short_band.end() as u32).min(word_space_min)),
+                    long_band(thresholds.long_ms[0], true, word_space_min),
+                    long_band(thresholds.long_ms[1], false, char_space_max),
+                )
+            }
+        };
+
+        Some(Thresholds {
+            dit,
+            dah,
+            char_space,
+            word_space_min: Some(word_space_min as MilliSeconds),
+        })
+    }
+
+    /// Returns the current signal entry speed in
+    /// Words Per Minute format.
+    pub fn get_wpm(&self) -> u16 {
+        Self::short_ms_to_wpm(self.reference_short_ms)
+    }
+
+    /// Returns the number of characters decoded successfully.
+    ///
+    /// Word spaces are not counted.
+    pub fn get_decoded_char_count(&self) -> u32 {
+        self.decoded_char_count
+    }
+
+    /// Returns the number of decoding errors.
+    ///
+    /// That's the number of times [DECODING_ERROR_CHAR](crate::DECODING_ERROR_CHAR)
+    /// was decoded instead of a proper character.
+    pub fn get_error_count(&self) -> u32 {
+        self.error_count
+    }
+
+    /// Returns the ratio of decoding errors to all decoded characters between 0.0 and 1.0.
+    ///
+    /// This can be displayed as an indicator of link quality, or used to signal the sending side
+    /// to slow down. Returns 0.0 if nothing was decoded yet.
+    pub fn error_rate(&self) -> f32 {
+        let total = self.decoded_char_count as f32 + self.error_count as f32;
+
+        if total == 0.0 {
+            0.0
+        } else {
+            self.error_count as f32 / total
+        }
+    }
+
+    /// Reset decoded character and error counts to start measuring from scratch.
+    pub fn reset_metrics(&mut self) {
+        self.decoded_char_count = 0;
+        self.error_count = 0;
+    }
+
+    /// Returns reference short durations after each of the last decoded characters.
+    ///
+    /// History is ordered from the oldest to the newest and covers up to
+    /// [SPEED_HISTORY_LENGTH] characters. Word spaces don't count as characters.
+    /// Useful to see how the speed of the sender changes through a QSO.
+    pub fn get_speed_history(&self) -> impl Iterator<Item = MilliSeconds> + '_ {
+        let count = self.speed_history_pos.min(SPEED_HISTORY_LENGTH);
+        let start = self.speed_history_pos.wrapping_sub(count);
+
+        (start..start.wrapping_add(count)).map(|i| self.speed_history[i % SPEED_HISTORY_LENGTH])
+    }
+
+    /// Returns the speeds in WPM after each of the last decoded characters.
+    ///
+    /// Same history as `get_speed_history` in Words Per Minute, ordered from the oldest
+    /// to the newest. Trainers can graph it to show how stable the speed of a student is through a session.
+    ///
+    /// ```rust
+    /// use morse_codec::decoder::{Decoder, Precision};
+    ///
+    /// let mut decoder = Decoder::<16>::new()
+    ///     .with_precision(Precision::Accurate)
+    ///     .with_reference_short_ms(100)
+    ///     .with_speed_tracking(20)
+    ///     .build();
+    ///
+    /// // E at 15 WPM, twice
+    /// for _ in 0..2 {
+    ///     decoder.signal_event(80, true);
+    ///     decoder.signal_event(240, false);
+    /// }
+    ///
+    /// assert_eq!(decoder.wpm_history().collect::<Vec<_>>(), [15, 15]);
+    /// ```
+    pub fn wpm_history(&self) -> impl Iterator<Item = u16> + '_ {
+        self.get_speed_history().map(Self::short_ms_to_wpm)
+    }
+
+    /// Returns total duration of all signal events received so far in milliseconds.
+    ///
+    /// This is the time since the start of the decoding session, as far as the decoder is concerned.
+    pub fn get_elapsed_ms(&self) -> u32 {
+        self.elapsed_ms
+    }
+
+    /// Returns the time a character at a message index was decoded at.
+    ///
+    /// Time is in milliseconds since the start of the session (see `get_elapsed_ms`).
+    /// Returns None if the character at the index was not decoded by signals,
+    /// for example it's a part of a starter message or the index is out of range.
+    /// Always returns None with the "small-footprint" feature.
+    pub fn get_char_timestamp(&self, index: usize) -> Option<u32> {
+        self.char_timestamps.get(index).copied().flatten()
+    }
+
+    /// Get an iterator of message characters along with the times they were decoded at.
+    ///
+    /// Playback UIs can use the timestamps to render the message with its original pacing,
+    /// loggers can timestamp the traffic.
+    pub fn get_timed_chars(&self) -> impl Iterator<Item = (C, Option<u32>)> + '_ {
+        self.message
+            .iter()
+            .enumerate()
+            .map(|(index, ch)| (*ch, self.get_char_timestamp(index)))
+    }
+
+    /// Remove the last decoded character from the message and return it.
+    ///
+    /// Edit position moves back to where the character was, so decoding continues from there.
+    /// This works the same with wrapping and clamping edit positions. Calling it repeatedly removes
+    /// the characters before it one by one. Signals of a character not decoded yet are kept.
+    /// Returns None if there's no character to remove.
+    pub fn undo_last(&mut self) -> Option<C> {
+        let index = self.message.get_last_changed_index();
+        let ch = self.message.remove_char_at(index)?;
+
+        if let Some(timestamp) = self.char_timestamps.get_mut(index) {
+            *timestamp = None;
+        }
+
+        // Character before the removed one is the last decoded one now
+        let word_space = self.get_char_from_morse_char(&MORSE_DEFAULT_CHAR);
+        let index = self.message.get_last_changed_index();
+        self.last_decoded_char = self.message
+            .try_char_at(index)
+            .ok()
+            .filter(|&ch| ch != C::FILLER && index < self.message.get_edit_pos());
+
+        if self.word_end_index.is_some_and(|end| end >= self.message.get_edit_pos()) {
+            self.word_end_index = self.last_decoded_char
+                .filter(|&ch| Some(ch) == word_space)
+                .map(|_| index);
+        }
+
+        Some(ch)
+    }
+
+    /// Returns the last character the decoder wrote to the message, including word spaces
+    /// and [DECODING_ERROR_CHAR](crate::DECODING_ERROR_CHAR)s.
+    ///
+    /// Returns None if nothing has been decoded yet. The character is kept by the decoder
+    /// itself, so it doesn't depend on the edit position of the message
+    /// wrapping around or being moved by the client code.
+    pub fn get_last_decoded_char(&self) -> Option<C> {
+        self.last_decoded_char
+    }
+
+    /// Returns true if a word is being keyed, meaning there are pending signals
+    /// or characters decoded after the last word space.
+    ///
+    /// A long enough low signal or `signal_event_end(true)` will complete the word.
+    pub fn is_word_boundary_pending(&self) -> bool {
+        self.signal_pos > 0 || !self.ends_with_word_space()
+    }
+
+    /// Returns the signals of the character being keyed so far.
+    ///
+    /// Signals that couldn't be resolved as short or long are None in the pattern.
+    pub fn current_pattern(&self) -> MorseCodeArray {
+        if self.current_character != MORSE_DEFAULT_CHAR {
+            return self.current_character;
+        }
 
-        let reduced_wpm = current_wpm * speed_reduction_factor;
-        //println!("FARNSWORTH: reduced WPM: {}", reduced_wpm);
+        let mut pattern = MORSE_DEFAULT_CHAR;
+        let signals = &self.signal_buffer[..self.signal_pos.min(MORSE_ARRAY_LENGTH)];
 
-        let delay_time_ms = (((60.0 * current_wpm) - (37.2 * reduced_wpm)) / (current_wpm * reduced_wpm)) * 1000.0;
-        //println!("FARNSWORTH: current reference short: {}", self.reference_short_ms);
-        //println!("FARNSWORTH: delay time total: {} and short: {}", delay_time_ms, (delay_time_ms / 19.0) as MilliSeconds);
+        for (signal, resolved) in pattern.iter_mut().zip(signals) {
+            *signal = match resolved {
+                SDShort(_) => Some(S),
+                SDLong(_) => Some(L),
+                _ => None,
+            };
+        }
 
-        (delay_time_ms / 19.0) as MilliSeconds
+        pattern
     }
-}
 
-// Public API for the masses
-impl<const MSG_MAX: usize> MorseDecoder<MSG_MAX> {
-    /// Returns currently resolved reference short signal duration.
+    /// Get an iterator of the characters the character being keyed can still become,
+    /// including the character of the signals so far.
     ///
-    /// Reference short signal is resolved continuously by the decoder as signal events pour in.
-    /// As longer signal durations are calculated by multiplying this value,
-    /// it might be useful for the client code.
-    pub fn get_reference_short(&self) -> MilliSeconds {
-        self.reference_short_ms
+    /// Trainer UIs can show these while the student is keying. Characters are ordered
+    /// by the length of their codes, see [MorseTree::completions](crate::charsets::MorseTree::completions).
+    /// Between characters every character of the set but the word space is possible.
+    /// A pattern with unresolved signals in it can't become anything.
+    ///
+    /// ```rust
+    /// use morse_codec::decoder::{Decoder, Precision};
+    ///
+    /// let mut decoder = Decoder::<16>::new()
+    ///     .with_precision(Precision::Accurate)
+    ///     .with_reference_short_ms(100)
+    ///     .build();
+    ///
+    /// // Dah dah dah so far
+    /// decoder.signal_event(300, true);
+    /// decoder.signal_event(100, false);
+    /// decoder.signal_event(300, true);
+    /// decoder.signal_event(100, false);
+    /// decoder.signal_event(300, true);
+    ///
+    /// let completions: String = decoder.possible_completions().map(|ch| ch as char).collect();
+    /// assert_eq!(completions, "O890:");
+    /// ```
+    pub fn possible_completions(&self) -> impl Iterator<Item = C> + '_ {
+        let is_resolved = !self.signal_buffer.iter().any(|signal| matches!(signal, SDOther(_)));
+        let pattern = is_resolved.then(|| self.current_pattern());
+
+        pattern
+            .into_iter()
+            .flat_map(|pattern| morse_subtree_nodes(&pattern))
+            // Root is the word space
+            .filter(|&node| node > 0)
+            .filter_map(|node| self.get_char_from_morse_char(&morse_tree_node_code(node)))
     }
 
-    /// Returns the current signal entry speed in
-    /// Words Per Minute format.
-    pub fn get_wpm(&self) -> u16 {
-        (1.2 / (self.reference_short_ms as f32 / 1000.0)) as u16
+    // Message index of the word space ending the last completed word, 0 if there's none.
+    pub(crate) fn completed_word_end(&self) -> usize {
+        self.word_end_index.unwrap_or(0)
     }
 
-    /// Returns last decoded character for easy access.
-    pub fn get_last_decoded_char(&self) -> Character {
-        self.message.get_last_changed_char()
+    /// Write the last word completed by a word space into the buffer as UTF-8 and return it.
+    ///
+    /// Returns an empty string if no word has been completed yet. If the buffer is too small,
+    /// the word is cut at the last character that fits.
+    ///
+    /// ```rust
+    /// use morse_codec::decoder::Decoder;
+    ///
+    /// let mut decoder = Decoder::<16>::new().build();
+    /// decoder.decode_text(".... .. / -.--", &Default::default());
+    ///
+    /// let mut buffer = [0u8; 16];
+    /// assert_eq!(decoder.last_completed_word(&mut buffer), "HI");
+    /// assert!(decoder.is_word_boundary_pending());
+    /// ```
+    pub fn last_completed_word<'a>(&self, buffer: &'a mut [u8]) -> &'a str {
+        let word_space = self.get_char_from_morse_char(&MORSE_DEFAULT_CHAR);
+        let end = self.completed_word_end().min(self.message.len());
+
+        let start = (0..end)
+            .rev()
+            .find(|&index| Some(self.message.char_at(index)) == word_space)
+            .map_or(0, |index| index + 1);
+
+        let mut length = 0;
+        for index in start..end {
+            let ch = self.message.char_at(index).to_char();
+            if length + ch.len_utf8() > buffer.len() {
+                break;
+            }
+
+            length += ch.encode_utf8(&mut buffer[length..]).len();
+        }
+
+        // Only whole UTF-8 encoded chars are written, so this can't fail.
+        core::str::from_utf8(&buffer[..length]).unwrap_or("")
     }
 
     /// Directly add a prepared signal to the character.
@@ -489,18 +2761,56 @@ impl<const MSG_MAX: usize> MorseDecoder<MSG_MAX> {
     /// Use this with `add_signal_to_character` directly with
     /// prepared [MorseSignal] enums.
     pub fn add_current_char_to_message(&mut self) {
-        if self.message.get_edit_pos() < MSG_MAX {
-            let ch = self.get_char_from_morse_char(&self.current_character);
-            self.message.add_char(ch);
+        if self.message.get_edit_pos() < self.message.capacity() {
+            match self.get_char_from_morse_char(&self.current_character) {
+                Some(ch) => {
+                    // Word spaces are not counted as decoded characters
+                    let is_word_space = self.current_character == MORSE_DEFAULT_CHAR;
+                    if !is_word_space {
+                        self.decoded_char_count = self.decoded_char_count.saturating_add(1);
+                    }
 
-            // If message position is clamping then this should not do anything.
-            // at the end of message position.
-            // If wrapping then it should reset the position to 0, so above condition
-            // should pass next time.
-            self.message.shift_edit_right();
+                    if self.add_char_to_message(ch) {
+                        if is_word_space {
+                            self.word_end_index = Some(self.message.get_last_changed_index());
+                            self.process_word(self.message.get_last_changed_index());
+                            self.note_event(DecodeEvent::WordCompleted);
+                        } else {
+                            self.completed_char = Some((ch, self.message.get_last_changed_index()));
+                        }
+                    }
+                }
+                None => self.add_error_char_to_message(),
+            }
+        }
+    }
+
+    /// Decode morse code written as text, ie. "... --- ... / <BT>", and add it to the message.
+    ///
+    /// [TextFormat] describes the glyphs and separators of the text. Prosigns in brackets
+    /// are decoded as the character with the same code, so `<BT>` is '='.
+    /// Characters that can't be decoded are written as [DECODING_ERROR_CHAR](crate::DECODING_ERROR_CHAR).
+    /// Signals of a character not decoded yet are discarded.
+    pub fn decode_text(&mut self, text: &str, format: &TextFormat) {
+        for token in format.tokens(text) {
+            let morse_char = match token {
+                TextToken::WordSpace => Some(MORSE_DEFAULT_CHAR),
+                TextToken::Signals(morse_char) => morse_char,
+                TextToken::Prosign(letters) => {
+                    get_prosign_morse_char(self.character_set, self.morse_code_set, letters)
+                }
+            };
 
-            self.reset_character();
+            match morse_char {
+                Some(morse_char) => {
+                    self.current_character = morse_char;
+                    self.add_current_char_to_message();
+                }
+                None => self.add_error_char_to_message(),
+            }
         }
+
+        self.reset_character();
     }
 
     /// Manually end a sequence of signals.
@@ -511,16 +2821,204 @@ impl<const MSG_MAX: usize> MorseDecoder<MSG_MAX> {
     /// input by the operator ended, because no other high signal is
     /// following the low signal at the end. At that point a separate button
     /// or whatever can be used to trigger this function.
+    ///
+    /// It's safe to call this defensively, for example on UI timeouts. If there are no signals
+    /// pending, no character is added. The word space is added only if the last decoded
+    /// character isn't a word space already, and not at all before anything is decoded.
     pub fn signal_event_end(&mut self, end_word: bool) {
-        self.current_character = self.decode_signal_buffer();
-        self.add_current_char_to_message();
+        if self.signal_pos > 0 {
+            self.track_speed();
+
+            if self.extra_dits > 0 {
+                self.remove_last_word();
+            } else if let Some(fault) = self.char_fault {
+                self.add_error_char_to_message();
+                self.note_event(DecodeEvent::Faulted(fault));
+            } else {
+                self.current_character = self.decode_signal_buffer();
+                self.add_current_char_to_message();
+            }
+        }
 
-        if end_word {
+        if end_word && !self.ends_with_word_space() {
             self.current_character = MORSE_DEFAULT_CHAR;
             self.add_current_char_to_message();
         }
     }
 
+    // Remove the characters of the last word after an error prosign.
+    fn remove_last_word(&mut self) {
+        let word_space = self.get_char_from_morse_char(&MORSE_DEFAULT_CHAR);
+
+        // Word space sent before the error prosign belongs to the wrong word
+        if self.last_decoded_char.is_some() && self.ends_with_word_space() {
+            self.undo_last();
+        }
+
+        loop {
+            let index = self.message.get_last_changed_index();
+            match self.message.try_char_at(index) {
+                Ok(ch) if ch != C::FILLER && Some(ch) != word_space => {
+                    self.undo_last();
+                }
+                _ => break,
+            }
+
+            if index == 0 {
+                break;
+            }
+        }
+
+        self.reset_character();
+        self.note_event(DecodeEvent::Corrected);
+    }
+
+    // Ends the transmission when low signals since the last high signal add up to the transmission gap.
+    fn track_transmission_gap(&mut self, duration_ms: MilliSeconds, is_high: bool) {
+        if is_high {
+            self.transmission_gap_ms = Some(0);
+
+            return;
+        }
+
+        let Some(gap_ms) = self.transmission_gap_ms else {
+            return;
+        };
+
+        let gap_ms = gap_ms.saturating_add(duration_ms as u32);
+        let transmission_gap_ms = self.word_space_ms() as u32 * self.transmission_gap_mult as u32;
+
+        if self.reference_short_ms == 0 || gap_ms < transmission_gap_ms {
+            self.transmission_gap_ms = Some(gap_ms);
+
+            return;
+        }
+
+        self.transmission_gap_ms = None;
+        self.signal_event_end(true);
+
+        // Word space of the last word, unless the message is edited after it
+        let word_end_index = self.word_end_index.filter(|index| *index == self.message.get_last_changed_index());
+
+        if let (Some(separator), Some(index)) = (self.transmission_separator, word_end_index) {
+            if self.get_char_from_morse_char(&MORSE_DEFAULT_CHAR) == Some(self.message.char_at(index)) {
+                let _ = self.message.put_char_at(index, separator);
+            }
+        }
+
+        self.trace(|| TraceEvent::TransmissionEnded);
+        self.note_event(DecodeEvent::TransmissionEnded);
+    }
+
+    fn ends_with_word_space(&self) -> bool {
+        match self.last_decoded_char {
+            Some(ch) => self.get_char_from_morse_char(&MORSE_DEFAULT_CHAR) == Some(ch),
+            None => true,
+        }
+    }
+
+    /// Send a batch of signal events to the decoder in one call.
+    ///
+    /// Signal events are [SignalEvent] structs or (duration in milliseconds, is_high) pairs,
+    /// same as the parameters of `signal_event`. This is handy for draining recorded sessions, test fixtures or channel
+    /// receivers into the decoder. Consecutive events with the same signal level are
+    /// merged into a single event before decoding, so a signal period that was split up
+    /// by the recording side won't be decoded as separate signals.
+    ///
+    /// ```ignore
+    /// let signals = [(100, true), (100, false), (300, true), (700, false)];
+    /// decoder.extend_signals(signals);
+    /// // Message is now "A "
+    /// ```
+    pub fn extend_signals<I>(&mut self, signals: I)
+    where
+        I: IntoIterator,
+        I::Item: Into<SignalEvent>,
+    {
+        let mut pending: Option<(MilliSeconds, bool)> = None;
+
+        for SignalEvent { duration_ms, is_high } in signals.into_iter().map(Into::into) {
+            pending = match pending {
+                Some((pending_ms, pending_high)) if pending_high == is_high => {
+                    Some((pending_ms.saturating_add(duration_ms), is_high))
+                }
+                Some((pending_ms, pending_high)) => {
+                    self.signal_event(pending_ms, pending_high);
+
+                    Some((duration_ms, is_high))
+                }
+                None => Some((duration_ms, is_high)),
+            };
+        }
+
+        if let Some((duration_ms, is_high)) = pending {
+            self.signal_event(duration_ms, is_high);
+        }
+    }
+
+    /// Send every signal event of a source to the decoder until the source runs out.
+    ///
+    /// Events are sent as a batch, see `extend_signals`, so consecutive events
+    /// with the same signal level are merged. See [source](crate::source) for the adapters of common inputs.
+    ///
+    /// ```ignore
+    /// let (sender, receiver) = std::sync::mpsc::channel();
+    /// // Key reader thread sends (duration_ms, is_high) events to the sender
+    /// decoder.drain(&mut receiver);
+    /// ```
+    pub fn drain(&mut self, source: &mut impl SignalSource) {
+        self.extend_signals(core::iter::from_fn(|| source.next_event()));
+    }
+
+    /// Send a stream of binary samples taken at a fixed sample rate to the decoder.
+    ///
+    /// Each sample is true when the signal is high (carrier or tone present) and false when it's low.
+    /// This is the output format of envelope detectors in SDR pipelines and GPIO pins polled
+    /// by a timer. Signal edges are detected from the samples, and durations of the signal
+    /// periods between them are measured and sent to the decoder as signal events.
+    ///
+    /// Samples can be pushed in chunks of any size, the current signal period carries over to the next call.
+    /// Since a period is only complete when the signal level changes, a character will be decoded
+    /// when the next high signal starts. Low periods reaching word space duration
+    /// are an exception and end the word right away, so the message is up to date while the input idles.
+    ///
+    /// ```ignore
+    /// // 1 kHz sampling, 100 ms dit and 300 ms dah
+    /// let mut samples = [false; 1400];
+    /// samples[..100].fill(true);
+    /// samples[200..500].fill(true);
+    /// decoder.push_samples(&samples, 1000);
+    /// // Message is now "A "
+    /// ```
+    pub fn push_samples(&mut self, samples: &[bool], sample_rate: u32) {
+        for &sample in samples {
+            // Sample level is the logical level. It's flipped back for signal_event which flips it again.
+            let sample = sample != self.inverted_input;
+
+            if sample == self.sample_level {
+                self.sample_run = self.sample_run.saturating_add(1);
+
+                if !sample && self.signal_pos > 0 && self.reference_short_ms > 0 {
+                    let duration_ms = Self::samples_to_ms(self.sample_run, sample_rate);
+
+                    if duration_ms >= self.word_space_ms() {
+                        self.signal_event(duration_ms, self.inverted_input);
+                        // Rest of the low period will be sent at the next edge
+                        self.sample_run = 0;
+                    }
+                }
+            } else {
+                if self.sample_run > 0 {
+                    let duration_ms = Self::samples_to_ms(self.sample_run, sample_rate);
+                    self.signal_event(duration_ms, self.sample_level != self.inverted_input);
+                }
+
+                self.sample_level = sample;
+                self.sample_run = 1;
+            }
+        }
+    }
+
     /// Send signal events to the decoder, filling signal buffer one event at a time.
     ///
     /// When a character ending long space signal or a word ending long space is sent,
@@ -528,128 +3026,261 @@ impl<const MSG_MAX: usize> MorseDecoder<MSG_MAX> {
     /// Note that if signal input itself has ended, oftentimes there's no way to send that signal.
     /// Use `signal_event_end` at that point to manually end the character.
     pub fn signal_event(&mut self, duration_ms: MilliSeconds, is_high: bool) {
-        let tolerance_range = self.signal_tolerance_range(duration_ms);
+        self.record_event(duration_ms, is_high);
+        let is_high = is_high != self.inverted_input;
+        self.last_event = DecodeEvent::Buffered;
+        self.completed_char = None;
+        self.elapsed_ms = self.elapsed_ms.saturating_add(duration_ms as u32);
+
+        if self.sos_detector.as_mut().is_some_and(|detector| detector.signal_event(duration_ms, is_high)) {
+            self.trace(|| TraceEvent::SosDetected);
+            self.note_event(DecodeEvent::SosDetected);
+        }
 
-        match self.signal_pos {
-            // Signal is the first in the series.
-            // Since this is the first signal we encounter, we'll treat it as if it's a short, when
-            // reference_short_ms == 0, otherwise try to resolve signal duration based on
-            // reference short learned from previous letters or based on
-            // initial_reference_short_ms provided to the constructor.
-            // If we have set it short preemptively, we later on check if this first short turns out to be long instead
-            // (see one of the match arms). We'll update the first buffer item with the correct value then don't worry.
-            0 => {
-                if is_high {
-                    //DBG
-                    //println!("START CHARACTER -----------------------");
+        if self.minimum_signal_ms > 0 && self.filter_short_signal(duration_ms, is_high) {
+            if duration_ms < self.minimum_signal_ms {
+                self.note_event(DecodeEvent::Rejected(RejectReason::TooShort));
+            }
 
-                    if self.reference_short_ms == 0 {
-                        self.add_to_signal_buffer(SDShort(duration_ms));
-                        self.update_reference_short_ms(duration_ms);
+            return;
+        }
 
-                        //DBG
-                        //println!("Initial ref short is set to {}", duration_ms);
-                    } else {
-                        let resolved_duration = self.resolve_signal_duration(duration_ms, &tolerance_range, is_high);
+        if self.match_preamble(duration_ms, is_high) {
+            return;
+        }
 
-                        //DBG
-                        //println!("\tINTIAL HIGH: tolerance range: {:?}, position is: {}, resolved duration: {:?}, ref short is: {}", tolerance_range, pos, resolved_duration, self.reference_short_ms);
+        let is_idle_low = !is_high && self.signal_pos == 0;
 
-                        self.add_to_signal_buffer(resolved_duration);
-                    }
-                } else {
-                    // Do nothing if we receive a low signal at the start of a series.
-                    // This happens when event engine of the client code sends low signals
-                    // inadvertently perhaps while idling or outright sends a wrong low signal at the start of a letter
-                    // which is rude.
-                }
-            }
+        self.resolve_signal_event(duration_ms, is_high);
 
-            // Signal is not high. It can be one of three things at this point:
-            // 1. It's a short duration space signal (space between two signals)
-            // 2. It's a long duration space. At this point we decode the entire signal buffer and
-            // add resulting character to the message
-            // 3. It's a very long signal (x7 or more) to divide two words in the message. So
-            // we check the signal buffer and add the character, as well as a space after it.
-            _pos if !is_high => {
-                if duration_ms < self.reference_short_ms && !tolerance_range.contains(&self.reference_short_ms) {
-                    //println!("Updating reference short to {}", duration_ms);
-                    self.update_reference_short_ms(duration_ms);
-                }
+        if is_idle_low {
+            self.note_event(DecodeEvent::Rejected(RejectReason::IdleLow));
+        }
 
-                let resolved_duration = self.resolve_signal_duration(duration_ms, &tolerance_range, is_high);
+        if self.transmission_gap_mult > 0 {
+            self.track_transmission_gap(duration_ms, is_high);
+        }
+    }
 
-                //DBG
-                //println!("LOW SIGNAL: tolerance range: {:?}, position is: {}, resolved duration: {:?}, ref short is: {}", tolerance_range, _pos, resolved_duration, self.reference_short_ms);
+    /// Same as `signal_event`, but returns what happened to the signal event as a [DecodeEvent].
+    ///
+    /// Client code can drive UIs and tests with the result deterministically
+    /// instead of comparing message states before and after the event.
+    ///
+    /// ```rust
+    /// use morse_codec::decoder::{Decoder, DecodeEvent, Precision};
+    ///
+    /// let mut decoder = Decoder::<16, u8>::new()
+    ///     .with_precision(Precision::Accurate)
+    ///     .with_reference_short_ms(100)
+    ///     .build();
+    ///
+    /// assert_eq!(decoder.signal_event_ex(100, true), DecodeEvent::Buffered);
+    /// assert_eq!(decoder.signal_event_ex(300, false), DecodeEvent::CharCompleted(b'E'));
+    /// ```
+    pub fn signal_event_ex(&mut self, duration_ms: MilliSeconds, is_high: bool) -> DecodeEvent<C> {
+        self.signal_event(duration_ms, is_high);
 
-                match resolved_duration {
-                    SDLong(_) => {
-                        //DBG
-                        //println!("END CHARACTER --------------");
+        self.last_event
+    }
 
-                        self.signal_event_end(false);
-                    }
-                    SDOther(ms) if ms >= self.word_space_ms() => {
-                        //DBG
-                        //println!("END WORD --------------");
+    /// Same as `signal_event`, but returns the character written to the message
+    /// if the signal event completed one.
+    ///
+    /// A low signal long enough to end a word returns the last character of the word,
+    /// not the word space. Characters that can't be decoded are returned as
+    /// [DECODING_ERROR_CHAR](crate::DECODING_ERROR_CHAR).
+    /// This saves client code from polling `get_last_decoded_char` after every event.
+    ///
+    /// ```rust
+    /// use morse_codec::decoder::{Decoder, Precision};
+    ///
+    /// let mut decoder = Decoder::<16, u8>::new()
+    ///     .with_precision(Precision::Accurate)
+    ///     .with_reference_short_ms(100)
+    ///     .build();
+    ///
+    /// assert_eq!(decoder.signal_event_char(300, true), None);
+    /// assert_eq!(decoder.signal_event_char(700, false), Some(b'T'));
+    /// ```
+    pub fn signal_event_char(&mut self, duration_ms: MilliSeconds, is_high: bool) -> Option<C> {
+        self.signal_event(duration_ms, is_high);
 
-                        self.signal_event_end(true);
-                    }
-                    _ => (),
-                }
-            }
+        self.completed_char.map(|(ch, _)| ch)
+    }
 
-            // Signal is not the first in a series and there are signals to be fed to the buffer.
-            // At this point signal is high and we try to resolve signal duration and save the signal to character.
-            // Also we check if the first duration in the array was wrongly saved as short but
-            // should be long instead. We fix it to long duration if it's wrong.
-            // The reason why we check at this position starting from index 2+ is that
-            // we get a better calibrated short signal from the low signal before it (index 1)
-            pos if pos < SIGNAL_BUFFER_LENGTH && is_high => {
-                let resolved_duration = self.resolve_signal_duration(duration_ms, &tolerance_range, is_high);
+    // Message index of the character written by the last signal event, word spaces excluded.
+    #[cfg(feature = "encoder")]
+    pub(crate) fn completed_char_index(&self) -> Option<usize> {
+        self.completed_char.map(|(_, index)| index)
+    }
 
-                //DBG
-                //println!("\tHIGH SIGNAL: tolerance range: {:?}, position is: {}, resolved duration: {:?}, ref short is: {}", tolerance_range, pos, resolved_duration, self.reference_short_ms);
+    /// Same as `signal_event`, but the duration is in microseconds.
+    ///
+    /// High speed CW, as used in meteor scatter, goes beyond 200 WPM where a short signal
+    /// is around 6 milliseconds. Timers of such setups usually count microseconds. Durations are rounded
+    /// to the nearest millisecond which is the resolution of the decoder, so timing jitter
+    /// below that doesn't matter. Durations longer than about 65 seconds are clamped.
+    pub fn signal_event_us(&mut self, duration_us: u32, is_high: bool) {
+        self.signal_event(Self::us_to_ms(duration_us), is_high);
+    }
 
-                self.add_to_signal_buffer(resolved_duration);
+    /// Same as `signal_event`, but the duration is a [fugit::Duration] of any tick rate.
+    ///
+    /// Timers of RTIC and HAL crates measure durations with fugit types, so they can be given
+    /// to the decoder without converting them to milliseconds first. Durations are rounded to
+    /// the nearest millisecond like `signal_event_us` does, instead of being truncated.
+    /// Durations with u32 ticks can be given with `.into()`.
+    ///
+    /// ```rust
+    /// use fugit::{MicrosDurationU64, MillisDurationU64};
+    /// use morse_codec::decoder::{Decoder, Precision};
+    ///
+    /// let mut decoder = Decoder::<16>::new()
+    ///     .with_precision(Precision::Accurate)
+    ///     .with_reference_short_ms(100)
+    ///     .build();
+    ///
+    /// decoder.signal_event_fugit(MicrosDurationU64::micros(99_600), true);
+    /// decoder.signal_event_fugit(MillisDurationU64::millis(300), false);
+    ///
+    /// assert_eq!(decoder.message.as_str(), "E");
+    /// ```
+    #[cfg(feature = "fugit")]
+    pub fn signal_event_fugit<const NOM: u32, const DENOM: u32>(
+        &mut self,
+        duration: fugit::Duration<u64, NOM, DENOM>,
+        is_high: bool,
+    ) {
+        let duration_us = duration.to_micros().min(u32::MAX as u64) as u32;
 
-                if let SDShort(first_duration) = self.signal_buffer[0] {
-                    match resolved_duration {
-                        SDLong(_) => {
-                            // If current signal is long and it's tolerance range contains the
-                            // first short signal, the first short signal should be a long
-                            if tolerance_range.contains(&first_duration) {
-                                self.signal_buffer[0] = SDLong(duration_ms);
-                            }
-                        }
-                        SDShort(_) => {
-                            // This is an edge case we need to handle where the character being
-                            // decoded, has a long high signal as the first signal in it and
-                            // has only short signals after it (including this one). If tolerance range
-                            // of the short signal we just got happens to be in the range of first
-                            // short signal divided by long signal multiplier (by default 3),
-                            // first short signal was indeed a long one, but we missed it.
-                            if tolerance_range.contains(&(first_duration / LONG_SIGNAL_MULTIPLIER)) {
-                                self.signal_buffer[0] = SDLong(duration_ms);
-                            }
-                        }
-                        _ => (),
-                    }
+        self.signal_event_us(duration_us, is_high);
+    }
+}
+
+// Methods that take or give out the message of MSG_MAX characters by value
+impl<const MSG_MAX: usize, C: MorseCharacter> MorseDecoder<MSG_MAX, C> {
+    /// Finish the current message and move it to a message log.
+    ///
+    /// If there are signals of an unfinished character, it's decoded first. Then the message
+    /// is moved to the log with the timestamp, unless it's empty, and the decoder continues with a new
+    /// empty message. Call this when the transmission is over, for example after some
+    /// idle time without any signals, to keep a history of messages instead of clearing them.
+    pub fn finish_message<const N: usize>(&mut self, log: &mut MessageLog<N, MSG_MAX, C>, timestamp: u32) {
+        if self.signal_pos > 0 {
+            self.signal_event_end(false);
+        }
+
+        let clamp_edit_pos = self.message.is_edit_clamped();
+        let letter_case = self.message.letter_case();
+        let message = core::mem::take(&mut self.message);
+        self.message.set_edit_position_clamp(clamp_edit_pos);
+        self.message.set_letter_case(letter_case);
+        self.message.follow_revision(&message);
+
+        if !message.is_empty() {
+            log.push(message, timestamp);
+        }
+
+        self.char_timestamps = core::array::from_fn(|_| None);
+        self.last_decoded_char = None;
+        self.word_end_index = None;
+        self.replay_pos = 0;
+    }
+
+    /// Decode the last received signal events again with different settings.
+    ///
+    /// Decoder keeps the last [REPLAY_LENGTH] signal events given to `signal_event` and its variants.
+    /// When the precision or tolerances turn out to be wrong for the sender mid-session, the characters
+    /// decoded from these events are removed from the message and the events are decoded again
+    /// by a decoder built with `configure`. The builder given to `configure` has the current settings
+    /// and message of this decoder, and the reference short duration as it was when the first replayed character started.
+    ///
+    /// Replay starts from the first character completely covered by the kept events, characters
+    /// before it are left alone. Quality metrics and speed history start over with the new settings.
+    ///
+    /// ```rust
+    /// use morse_codec::decoder::{Decoder, Precision};
+    ///
+    /// let mut decoder = Decoder::<16>::new()
+    ///     .with_precision(Precision::Accurate)
+    ///     .with_reference_short_ms(300)
+    ///     .build();
+    ///
+    /// // Sender is three times faster than the decoder expects
+    /// let signals = [(100, true), (100, false), (300, true), (300, false), (100, true), (700, false)];
+    /// for (duration_ms, is_high) in signals {
+    ///     decoder.signal_event(duration_ms, is_high);
+    /// }
+    /// assert_eq!(decoder.message.as_str(), "?E ");
+    ///
+    /// decoder.redecode_with(|builder| builder.with_reference_short_ms(100));
+    /// # #[cfg(not(feature = "small-footprint"))]
+    /// assert_eq!(decoder.message.as_str(), "AE ");
+    /// ```
+    pub fn redecode_with<F>(&mut self, configure: F)
+    where
+        F: FnOnce(Decoder<MSG_MAX, C>) -> Decoder<MSG_MAX, C>,
+    {
+        let replay = self.replay;
+        let events = (self.replay_pos.saturating_sub(REPLAY_LENGTH)..self.replay_pos)
+            .filter_map(|i| replay.get(i.checked_rem(REPLAY_LENGTH)?).copied())
+            .skip_while(|event| !event.starts_char);
+
+        let mut message = core::mem::take(&mut self.message);
+        let mut reference_short_ms = self.reference_short_ms;
+        let mut replayed_ms: u32 = 0;
+
+        if let Some(first) = events.clone().next() {
+            if first.edit_pos <= message.len() {
+                while message.len() > first.edit_pos {
+                    message.pop();
                 }
-            }
 
-            // This means we got the maximum amount of signals to the buffer, but still couldn't
-            // decode the character. Either because we never received a character ender low
-            // signal (3x short space) or a word ending long signal (7x short space)
-            // or outright couldn't decode them, but hey.
-            // We put a decoding error character at this point. And move on.
-            _ => {
-                //DBG
-                //println!("We reached the end of buffer and couldn't decode the character. signal_buffer so far is: {:?}", self.signal_buffer);
-                self.message.add_char(DECODING_ERROR_CHAR);
-                self.message.shift_edit_right();
-                self.reset_character();
+                message.set_edit_pos(first.edit_pos);
             }
+
+            reference_short_ms = first.reference_short_ms;
+            replayed_ms = events.clone().map(|event| event.duration_ms as u32).sum();
         }
+
+        let builder = Decoder {
+            precision: self.precision,
+            character_set: self.character_set,
+            morse_code_set: self.morse_code_set,
+            mark_tolerance: self.mark_tolerance,
+            space_tolerance: self.space_tolerance,
+            lazy_padding: self.lazy_padding,
+            word_space_tolerance: self.word_space_tolerance,
+            reference_short_ms,
+            message,
+            allowed_chars: self.allowed_chars,
+            disallowed_char_mapper: self.disallowed_char_mapper,
+            speed_tracking_step_ms: self.speed_tracking_step_ms,
+            minimum_signal_ms: self.minimum_signal_ms,
+            message_full_handler: self.message_full_handler,
+            trace_handler: self.trace_handler,
+            inverted_input: self.inverted_input,
+            timing_profile: self.timing_profile,
+            error_prosign_correction: self.error_prosign_correction,
+            transmission_gap_mult: self.transmission_gap_mult,
+            transmission_separator: self.transmission_separator,
+            #[cfg(feature = "language-model")]
+            language_model: self.language_model,
+            post_processors: self.post_processors,
+            preamble: self.preamble,
+            sos_detection: self.sos_detector.is_some(),
+            ..Decoder::new()
+        };
+
+        let mut decoder = configure(builder).build();
+        decoder.elapsed_ms = self.elapsed_ms.saturating_sub(replayed_ms);
+        decoder.char_timestamps = self.char_timestamps;
+
+        for event in events {
+            decoder.signal_event(event.duration_ms, event.is_high);
+        }
+
+        *self = decoder;
     }
 }
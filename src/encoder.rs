@@ -3,6 +3,8 @@
 //! The encoder takes [&str] literals or characters and
 //! turns them into a fixed length char array. Then client code can encode these characters
 //! to morse code either character by character, from slices, or all in one go.  
+//! Characters are encoded lazily when the output is requested, so the output always reflects
+//! the current state of the message, even after it's edited directly.  
 //! Encoded morse code can be retrieved as morse character arrays ie. ['.','-','.'] or Signal
 //! Duration Multipliers [SDMArray] to calculate individual signal durations by the client code.
 //!
@@ -35,25 +37,42 @@
 //!
 //! // This should print "... --- ..."
 
+use core::ops::Range;
+
 use crate::{
-    message::Message,
+    charsets::{get_prosign_morse_char, transliterate, validate},
+    message::{LetterCase, Message, MessageStorage},
     CharacterSet,
     MorseCodeSet,
     MorseCodeArray,
+    Transliteration,
     MorseSignal::{Long as L, Short as S},
     DEFAULT_MORSE_CODE_SET,
-    DEFAULT_CHARACTER_SET,
     MORSE_ARRAY_LENGTH,
     MORSE_DEFAULT_CHAR,
-    LONG_SIGNAL_MULTIPLIER,
-    WORD_SPACE_MULTIPLIER,
     Character,
+    Error,
+    FarnsworthTiming,
+    MorseCharacter,
+    TimingProfile,
+    sink::SignalSink,
+    text::TextFormat,
 };
 
-const DIT: Character = '.' as Character;
-const DAH: Character = '-' as Character;
-const WORD_DELIMITER: Character = '/' as Character;
+#[cfg(feature = "std")]
+use std::{string::String, vec::Vec};
+
+#[cfg(not(feature = "small-footprint"))]
+use crate::charsets::CharIndex;
+
+const DIT: u8 = b'.';
+const DAH: u8 = b'-';
+const WORD_DELIMITER: &str = "/";
 const SDM_LENGTH: usize = 12;
+const DEFAULT_WPM: u16 = 20;
+// Encoded character cache entry of characters that are looked up when they're needed
+#[cfg(not(feature = "small-footprint"))]
+const UNCACHED_CHAR: u8 = u8::MAX;
 
 /// Signal Duration Multiplier can be 1x (short), 3x (long) or 7x (word space).
 /// SDM signals are either High, or Low which corresponds to
@@ -67,7 +86,12 @@ pub enum SDM {
 
 use SDM::{Empty as SDMEmpty, High as SDMHigh, Low as SDMLow};
 
-pub type MorseCharray = [Option<Character>; MORSE_ARRAY_LENGTH];
+pub type MorseCharray<C = Character> = [Option<C>; MORSE_ARRAY_LENGTH];
+
+/// Typographically correct dit glyph, the middle dot.
+pub const UNICODE_DIT: char = '·';
+/// Typographically correct dah glyph, the minus sign.
+pub const UNICODE_DAH: char = '−';
 
 /// Signal Duration Multipliers are arrays of u8 values
 /// which can be used to multiply by a short signal duration constant
@@ -78,28 +102,168 @@ pub type MorseCharray = [Option<Character>; MORSE_ARRAY_LENGTH];
 /// representations of morse code.
 pub type SDMArray = [SDM; SDM_LENGTH];
 
-pub struct Encoder<const MSG_MAX: usize> {
+/// A single SDM signal annotated with the message character it belongs to.
+///
+/// UIs highlighting the currently played letter can use the index and the character
+/// directly instead of keeping track of a parallel cursor.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct SdmChunk<C = Character> {
+    /// Index of the character in the message.
+    pub index: usize,
+    /// The character the signal belongs to.
+    pub ch: C,
+    /// The signal itself.
+    pub sdm: SDM,
+}
+
+/// A part of the encoded message that fits into a transmit window.
+///
+/// Get them with `transmit_chunks` and their signals with `transmit_chunk_durations`.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct TransmitChunk {
+    /// Message index of the first character of the chunk.
+    pub start: usize,
+    /// Message index right after the last character of the chunk.
+    pub end: usize,
+    /// Time from the start of the first signal to the end of the last one in milliseconds.
+    pub duration_ms: u32,
+    /// True if there are more chunks after this one.
+    pub continues: bool,
+}
+
+/// What to do with characters that are not in the character set while encoding.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum UnknownCharPolicy<C = Character> {
+    /// Leave the character out.
+    Skip,
+    /// Encode another character in its place, ie. '?'.
+    SubstituteWith(C),
+    /// Return an error. This is the default.
+    Error,
+}
+
+/// Time unit of signal durations.
+///
+/// Very slow CW (QRSS) beacons use dits of 3 to 60 seconds,
+/// which are easier to schedule in deciseconds or seconds.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TimeUnit {
+    Milliseconds,
+    Deciseconds,
+    Seconds,
+}
+
+impl TimeUnit {
+    /// Returns how many milliseconds the unit is.
+    pub const fn as_ms(self) -> u32 {
+        match self {
+            TimeUnit::Milliseconds => 1,
+            TimeUnit::Deciseconds => 100,
+            TimeUnit::Seconds => 1000,
+        }
+    }
+}
+
+/// What goes between messages joined with `append_message`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Separator {
+    /// A word space.
+    Word,
+    /// BT prosign between word spaces, the CW convention for a new paragraph.
+    Paragraph,
+}
+
+impl Separator {
+    /// Returns the separator as a string slice for `encode_slice`.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Separator::Word => " ",
+            Separator::Paragraph => " <BT> ",
+        }
+    }
+}
+
+/// Calibration pattern sent before the message, see `with_preamble`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Preamble {
+    /// VVV, the classic pattern for receivers to tune in and get the speed.
+    Vvv,
+    /// Any text, ie. "CQ CQ". Prosigns can be written in brackets, see `encode_slice`.
+    Custom(&'static str),
+}
+
+impl Preamble {
+    /// Returns the text of the preamble.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Preamble::Vvv => "VVV",
+            Preamble::Custom(text) => text,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Encoder<const MSG_MAX: usize, C: MorseCharacter = Character, S: MessageStorage<C> = [C; MSG_MAX]> {
     // User defined
-    message: Message<MSG_MAX>,
-    character_set: CharacterSet,
+    message: Message<MSG_MAX, C, S>,
+    character_set: CharacterSet<C>,
     morse_code_set: MorseCodeSet,
-    // Internal stuff
-    encoded_message: [MorseCodeArray; MSG_MAX],
+    dit_glyph: C,
+    dah_glyph: C,
+    word_separator: &'static str,
+    unknown_char_policy: UnknownCharPolicy<C>,
+    transliteration: Option<Transliteration>,
+    timing_profile: TimingProfile,
+    // Speeds of the first and the last character in WPM
+    speed_ramp: (u16, u16),
+    preamble: Option<Preamble>,
 }
 
-impl<const MSG_MAX: usize> Default for Encoder<MSG_MAX> {
+impl<const MSG_MAX: usize, C: MorseCharacter> Default for Encoder<MSG_MAX, C> {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl<const MSG_MAX: usize> Encoder<MSG_MAX> {
+impl<const MSG_MAX: usize, C: MorseCharacter> Encoder<MSG_MAX, C> {
     pub fn new() -> Self {
+        Self::from_message(Message::default())
+    }
+}
+
+impl<const MSG_MAX: usize, C: MorseCharacter, S: MessageStorage<C>> Encoder<MSG_MAX, C, S> {
+    /// Get a builder of an encoder that encodes the given message.
+    ///
+    /// This is how an encoder is built over a [MessageRef](crate::message::MessageRef), a message in
+    /// a buffer sized at runtime.
+    ///
+    /// ```rust
+    /// use morse_codec::{
+    ///     encoder::Encoder,
+    ///     message::MessageRef,
+    /// };
+    ///
+    /// let mut buffer = vec![0u8; 100];
+    /// let mut encoder = Encoder::from_message(MessageRef::from_storage(&mut buffer[..]))
+    ///     .with_message("SOS", true)
+    ///     .build();
+    ///
+    /// encoder.encode_message_all();
+    /// assert_eq!(encoder.message.capacity(), 100);
+    /// ```
+    pub fn from_message(message: Message<MSG_MAX, C, S>) -> Self {
         Self {
-            message: Message::default(),
-            character_set: DEFAULT_CHARACTER_SET,
+            message,
+            character_set: C::DEFAULT_CHARACTER_SET,
             morse_code_set: DEFAULT_MORSE_CODE_SET,
-            encoded_message: [MORSE_DEFAULT_CHAR; MSG_MAX],
+            dit_glyph: C::from_ascii(DIT),
+            dah_glyph: C::from_ascii(DAH),
+            word_separator: WORD_DELIMITER,
+            unknown_char_policy: UnknownCharPolicy::Error,
+            transliteration: None,
+            timing_profile: TimingProfile::STANDARD,
+            speed_ramp: (DEFAULT_WPM, DEFAULT_WPM),
+            preamble: None,
         }
     }
 
@@ -108,7 +272,8 @@ impl<const MSG_MAX: usize> Encoder<MSG_MAX> {
     /// edit_pos_end means we'll continue encoding from the end of this string.
     /// If you pass false to it, we'll start from the beginning.
     pub fn with_message(mut self, message_str: &str, edit_pos_end: bool) -> Self {
-        self.message = Message::new(message_str, edit_pos_end, self.message.is_edit_clamped());
+        self.message.fill_from_str(message_str);
+        self.message.set_edit_pos(if edit_pos_end { self.message.len() } else { 0 });
 
         self
     }
@@ -130,7 +295,7 @@ impl<const MSG_MAX: usize> Encoder<MSG_MAX> {
     /// stronger encryption can be used. These kind of encryptions can
     /// easily be broken with powerful algorithms and AI.
     /// **DON'T** use it for secure communication.
-    pub fn with_character_set(mut self, character_set: CharacterSet) -> Self {
+    pub fn with_character_set(mut self, character_set: CharacterSet<C>) -> Self {
         self.character_set = character_set;
 
         self
@@ -163,59 +328,400 @@ impl<const MSG_MAX: usize> Encoder<MSG_MAX> {
         self
     }
 
+    /// Change the letter case of the message, see [LetterCase].
+    ///
+    /// Letters are encoded the same in any case. [LetterCase::Preserve] keeps the case of
+    /// the message as it was given, so it should be set before `with_message`.
+    pub fn with_letter_case(mut self, letter_case: LetterCase) -> Self {
+        self.message.set_letter_case(letter_case);
+
+        self
+    }
+
+    /// Use different glyphs than '.' and '-' for dits and dahs of [MorseCharray] outputs.
+    ///
+    /// ```ignore
+    /// let encoder = Encoder::<64, char>::new().with_glyphs('•', '▬').build();
+    /// ```
+    pub fn with_glyphs(mut self, dit: C, dah: C) -> Self {
+        self.dit_glyph = dit;
+        self.dah_glyph = dah;
+
+        self
+    }
+
+    /// Use a different word separator than "/" for [MorseCharray] outputs.
+    ///
+    /// Characters of the separator that can't be represented by the character type are
+    /// skipped, and only the first `MORSE_ARRAY_LENGTH` characters are used.
+    pub fn with_word_separator(mut self, word_separator: &'static str) -> Self {
+        self.word_separator = word_separator;
+
+        self
+    }
+
+    /// Decide what to do with characters that are not in the character set.
+    ///
+    /// By default encoding them returns an error. User provided strings with stray
+    /// emoji or accented letters can be encoded gracefully by skipping or substituting them instead.
+    ///
+    /// ```ignore
+    /// let encoder = Encoder::<64>::new()
+    ///     .with_unknown_char_policy(UnknownCharPolicy::SubstituteWith(b'?'))
+    ///     .build();
+    /// ```
+    pub fn with_unknown_char_policy(mut self, policy: UnknownCharPolicy<C>) -> Self {
+        self.unknown_char_policy = policy;
+
+        self
+    }
+
+    /// Replace characters with a [Transliteration] map before encoding them.
+    ///
+    /// There are maps for some languages in [charsets](crate::charsets), and they can be
+    /// combined with a character set that has accented letters for the rest.
+    ///
+    /// ```ignore
+    /// let mut encoder = Encoder::<64>::new().with_transliteration(GERMAN_TRANSLITERATION).build();
+    /// encoder.encode_slice("Grüße").unwrap();
+    /// // Message is "GRUESSE"
+    /// ```
+    #[cfg(feature = "utf8")]
+    pub fn with_transliteration(mut self, transliteration: Transliteration) -> Self {
+        self.transliteration = Some(transliteration);
+
+        self
+    }
+
+    /// Encode with other lengths of dahs and spaces than standard morse timing.
+    ///
+    /// SDM multipliers and all signal durations derived from them follow the profile.
+    /// See [TimingProfile] for the limits of the values.
+    pub fn with_timing_profile(mut self, timing_profile: TimingProfile) -> Self {
+        self.timing_profile = timing_profile.sanitized();
+
+        self
+    }
+
+    /// Speed the message up (or slow it down) gradually from the first character to the last.
+    ///
+    /// Speed changes linearly character by character from `start_wpm` to `end_wpm`,
+    /// so practice material can accelerate within one transmission. Get the signals
+    /// at these speeds with `ramped_durations`. Speeds are at least 1 WPM.
+    ///
+    /// ```ignore
+    /// let encoder = Encoder::<64>::new()
+    ///     .with_message("CQ CQ DE K1ABC", true)
+    ///     .with_speed_ramp(15, 25)
+    ///     .build();
+    /// ```
+    pub fn with_speed_ramp(mut self, start_wpm: u16, end_wpm: u16) -> Self {
+        self.speed_ramp = (start_wpm.max(1), end_wpm.max(1));
+
+        self
+    }
+
+    /// Send a calibration pattern and a word space before the message, so receivers can lock on to the speed.
+    ///
+    /// The preamble isn't stored in the message. It's only prepended to the signal outputs of the entire message:
+    /// `signal_durations`, `ramped_durations` (at the start speed), `send_to`, `farnsworth_durations`,
+    /// `sample_stream`, `timeline_runs`, `signal_schedule`, `fugit_durations` and `get_encoded_message_as_durations`.
+    /// Outputs that follow message indices, like SDM arrays, chunks and [Player](crate::playback::Player), are
+    /// message only. Characters of the preamble that are not in the character set are left out.
+    ///
+    /// ```rust
+    /// use morse_codec::encoder::{Encoder, Preamble};
+    ///
+    /// let mut encoder = Encoder::<8, u8>::new()
+    ///     .with_message("E", true)
+    ///     .with_preamble(Preamble::Custom("T"))
+    ///     .build();
+    /// encoder.encode_message_all();
+    ///
+    /// assert_eq!(encoder.message.as_str(), "E");
+    ///
+    /// let runs: Vec<(u32, bool)> = encoder.timeline_runs().collect();
+    /// assert_eq!(runs, [(3, true), (7, false), (1, true), (3, false)]);
+    /// ```
+    pub fn with_preamble(mut self, preamble: Preamble) -> Self {
+        self.preamble = Some(preamble);
+
+        self
+    }
+
+    /// Check the character set and morse code set and build a [MorseEncoder] if they match.
+    ///
+    /// Returns [Error::InvalidCharacterSet] if they don't, see [validate].
+    ///
+    /// ```ignore
+    /// let encoder = Encoder::<64, char>::new()
+    ///     .with_character_set(GREEK_CHARACTERS)
+    ///     .with_morse_code_set(GREEK_MORSE_CODES)
+    ///     .try_build()?;
+    /// ```
+    pub fn try_build(self) -> Result<MorseEncoder<MSG_MAX, C, S>, Error> {
+        validate(self.character_set, self.morse_code_set).map_err(Error::InvalidCharacterSet)?;
+
+        Ok(self.build())
+    }
+
     /// Build and get yourself a shiny new [MorseEncoder].
     ///
     /// The ring is yours now...
-    pub fn build(self) -> MorseEncoder<MSG_MAX> {
+    pub fn build(self) -> MorseEncoder<MSG_MAX, C, S> {
         let Encoder {
             message,
             character_set,
             morse_code_set,
-            encoded_message,
+            dit_glyph,
+            dah_glyph,
+            word_separator,
+            unknown_char_policy,
+            transliteration,
+            timing_profile,
+            speed_ramp,
+            preamble,
         } = self;
 
-        MorseEncoder::<MSG_MAX> {
+        let mut encoder = MorseEncoder {
             message,
             character_set,
             morse_code_set,
-            encoded_message,
-        }
+            dit_glyph,
+            dah_glyph,
+            word_separator,
+            unknown_char_policy,
+            transliteration,
+            timing_profile,
+            speed_ramp,
+            preamble,
+            #[cfg(not(feature = "small-footprint"))]
+            char_index: CharIndex::new(character_set, morse_code_set),
+            #[cfg(not(feature = "small-footprint"))]
+            encoded_chars: [UNCACHED_CHAR; MSG_MAX],
+            #[cfg(not(feature = "small-footprint"))]
+            encoded_revision: None,
+        };
+
+        encoder.update_encoded_chars(encoder.message.revision(), 0..0);
+
+        encoder
+    }
+}
+
+impl<const MSG_MAX: usize, S: MessageStorage<char>> Encoder<MSG_MAX, char, S> {
+    /// Use typographically correct [UNICODE_DIT] '·' and [UNICODE_DAH] '−' glyphs
+    /// for [MorseCharray] outputs, so UIs can display them without post-processing.
+    pub fn with_unicode_glyphs(self) -> Self {
+        self.with_glyphs(UNICODE_DIT, UNICODE_DAH)
     }
 }
 
-pub struct MorseEncoder<const MSG_MAX: usize> {
+#[derive(Clone, Debug)]
+pub struct MorseEncoder<const MSG_MAX: usize, C: MorseCharacter = Character, S: MessageStorage<C> = [C; MSG_MAX]> {
     // User defined
-    pub message: Message<MSG_MAX>,
-    character_set: CharacterSet,
+    pub message: Message<MSG_MAX, C, S>,
+    character_set: CharacterSet<C>,
     morse_code_set: MorseCodeSet,
+    dit_glyph: C,
+    dah_glyph: C,
+    word_separator: &'static str,
+    unknown_char_policy: UnknownCharPolicy<C>,
+    transliteration: Option<Transliteration>,
+    timing_profile: TimingProfile,
+    // Speeds of the first and the last character in WPM
+    speed_ramp: (u16, u16),
+    preamble: Option<Preamble>,
     // Internal stuff
-    encoded_message: [MorseCodeArray; MSG_MAX],
+    #[cfg(not(feature = "small-footprint"))]
+    char_index: CharIndex<C>,
+    // Character set indices of the message characters, valid while the message stays at the revision
+    #[cfg(not(feature = "small-footprint"))]
+    encoded_chars: [u8; MSG_MAX],
+    #[cfg(not(feature = "small-footprint"))]
+    encoded_revision: Option<u32>,
+}
+
+// Encoders are equal when their settings and messages are equal, regardless of their encoded character caches.
+impl<const MSG_MAX: usize, C: MorseCharacter, S: MessageStorage<C>> PartialEq for MorseEncoder<MSG_MAX, C, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.message == other.message
+            && self.character_set == other.character_set
+            && self.morse_code_set == other.morse_code_set
+            && self.dit_glyph == other.dit_glyph
+            && self.dah_glyph == other.dah_glyph
+            && self.word_separator == other.word_separator
+            && self.unknown_char_policy == other.unknown_char_policy
+            && self.transliteration == other.transliteration
+            && self.timing_profile == other.timing_profile
+            && self.speed_ramp == other.speed_ramp
+            && self.preamble == other.preamble
+    }
 }
 
 // Private internal methods
-impl<const MSG_MAX: usize> MorseEncoder<MSG_MAX> {
-    fn get_morse_char_from_char(&self, ch: &Character) -> Option<MorseCodeArray> {
+impl<const MSG_MAX: usize, C: MorseCharacter, S: MessageStorage<C>> MorseEncoder<MSG_MAX, C, S> {
+    #[cfg(not(feature = "small-footprint"))]
+    fn get_morse_char_from_char(&self, ch: &C) -> Option<MorseCodeArray> {
+        self.char_index.get(ch)
+    }
+
+    #[cfg(feature = "small-footprint")]
+    fn get_morse_char_from_char(&self, ch: &C) -> Option<MorseCodeArray> {
         let index = self.character_set
             .iter()
             .position(|setchar| setchar == ch);
 
-        if let Some(i) = index {
-            Some(self.morse_code_set[i].clone())
+        index.map(|i| self.morse_code_set[i])
+    }
+
+    // Characters of a string slice with bracketed prosigns converted to their characters
+    // and transliterated characters replaced. An opening bracket without a closing one is taken literally.
+    fn slice_chars(
+        character_set: CharacterSet<C>,
+        morse_code_set: MorseCodeSet,
+        transliteration: Option<Transliteration>,
+        str_slice: &str,
+    ) -> impl Iterator<Item = Result<C, &'static str>> + '_ {
+        let mut rest = str_slice;
+        let mut replacement = "".chars();
+
+        core::iter::from_fn(move || loop {
+            if let Some(ch) = replacement.next() {
+                if let Some(ch) = C::from_char(ch) {
+                    return Some(Ok(ch));
+                }
+
+                continue;
+            }
+
+            let mut chars = rest.chars();
+            let ch = chars.next()?;
+
+            if ch == '<' {
+                if let Some((letters, after)) = chars.as_str().split_once('>') {
+                    rest = after;
+
+                    return Some(
+                        get_prosign_morse_char(character_set, morse_code_set, letters)
+                            .and_then(|morse_char| morse_code_set.iter().position(|mchar| *mchar == morse_char))
+                            .map(|index| character_set[index])
+                            .ok_or("Prosign doesn't have a character in the character set."),
+                    );
+                }
+            }
+
+            rest = chars.as_str();
+
+            if let Some(to) = transliteration.and_then(|map| transliterate(map, ch)) {
+                replacement = to.chars();
+            } else if let Some(ch) = C::from_char(ch) {
+                return Some(Ok(ch));
+            }
+        })
+    }
+
+    // Number of characters a string slice adds to the message, or the error encoding it would return.
+    fn count_slice_chars(&self, str_slice: &str) -> Result<usize, &'static str> {
+        let mut char_count = 0;
+        for ch in Self::slice_chars(self.character_set, self.morse_code_set, self.transliteration, str_slice) {
+            if self.encode_with_policy(&ch?)?.is_some() {
+                char_count += 1;
+            }
+        }
+
+        Ok(char_count)
+    }
+
+    // Update cached encodings of the changed message indices after the message was changed from the revision.
+    // Everything is encoded again if the message was changed elsewhere since the cache was last updated.
+    #[cfg(not(feature = "small-footprint"))]
+    fn update_encoded_chars(&mut self, revision: u32, changed: Range<usize>) {
+        let changed = if self.encoded_revision == Some(revision) { changed } else { 0..MSG_MAX };
+
+        for index in changed.start..changed.end.min(MSG_MAX) {
+            let set_index = self.message.try_char_at(index).ok()
+                .and_then(|ch| ch.to_upper())
+                .and_then(|ch| self.character_set.iter().position(|setchar| *setchar == ch))
+                .filter(|&set_index| set_index < UNCACHED_CHAR as usize);
+
+            self.encoded_chars[index] = set_index.map_or(UNCACHED_CHAR, |set_index| set_index as u8);
+        }
+
+        self.encoded_revision = Some(self.message.revision());
+    }
+
+    #[cfg(feature = "small-footprint")]
+    fn update_encoded_chars(&mut self, _revision: u32, _changed: Range<usize>) {}
+
+    // Encode a character without transliteration and add it to the message.
+    fn add_encoded_char(&mut self, ch: &C) -> Result<(), &'static str> {
+        let revision = self.message.revision();
+        let len = self.message.len();
+        let pos = self.message.get_edit_pos();
+
+        if pos < self.message.capacity() {
+            let ch_uppercase = self.encode_with_policy(ch);
+
+            match ch_uppercase {
+                Ok(None) => Ok(()),
+                Ok(Some(ch_encoded)) => {
+                    // Letters keep their case if the message preserves it, substitutes don't
+                    let is_letter = ch.to_upper() == Some(ch_encoded);
+                    let ch = if is_letter && self.message.letter_case() == LetterCase::Preserve { *ch } else { ch_encoded };
+                    self.message.add_char(ch);
+
+                    // If message position is clamping then this should not do anything
+                    // at the end of message position.
+                    // If wrapping then it should reset the position to 0, so above condition
+                    // should pass next time.
+                    self.message.shift_edit_right();
+
+                    // Empty characters before the position are filled with spaces too
+                    self.update_encoded_chars(revision, len.min(pos)..pos + 1);
+
+                    Ok(())
+                },
+                Err(err) => Err(err)
+            }
         } else {
-            None
+            Ok(())
+        }
+    }
+
+    // Characters are taken from the cache while the message hasn't changed since it was updated,
+    // otherwise they're encoded on demand, so edits to the message can never leave a stale encoding behind.
+    fn get_encoded_char(&self, index: usize) -> Option<MorseCodeArray> {
+        if index >= self.message.len() {
+            return None;
+        }
+
+        #[cfg(not(feature = "small-footprint"))]
+        if self.encoded_revision == Some(self.message.revision()) {
+            if let Some(&set_index) = self.encoded_chars.get(index).filter(|&&set_index| set_index != UNCACHED_CHAR) {
+                return self.morse_code_set.get(set_index as usize).copied();
+            }
         }
+
+        self.message.char_at(index)
+            .to_upper()
+            .and_then(|ch| self.get_morse_char_from_char(&ch))
     }
 
-    fn get_encoded_char_as_morse_charray(&self, index: usize) -> Option<MorseCharray> {
-        if index < self.message.len() {
-            let encoded_char = self.encoded_message[index].clone();
+    fn get_encoded_char_as_morse_charray(&self, index: usize) -> Option<MorseCharray<C>> {
+        if let Some(encoded_char) = self.get_encoded_char(index) {
             if encoded_char == MORSE_DEFAULT_CHAR {
-                Some([Some(WORD_DELIMITER), None, None, None, None, None])
+                let mut charray: MorseCharray<C> = [None; MORSE_ARRAY_LENGTH];
+                charray.iter_mut()
+                    .zip(self.word_separator.chars().filter_map(C::from_char))
+                    .for_each(|(slot, ch)| *slot = Some(ch));
+
+                Some(charray)
             } else {
                 Some(encoded_char.map(|mchar| {
                     match mchar {
-                        Some(S) => Some(DIT),
-                        Some(L) => Some(DAH),
+                        Some(S) => Some(self.dit_glyph),
+                        Some(L) => Some(self.dah_glyph),
                         _ => None,
                     }
                 }))
@@ -225,124 +731,195 @@ impl<const MSG_MAX: usize> MorseEncoder<MSG_MAX> {
         }
     }
 
-    fn get_encoded_char_as_sdm(&self, index: usize) -> Option<SDMArray> {
-        if index < self.message.len() {
-            let mut sdm_array = [SDMEmpty; SDM_LENGTH];
+    pub(crate) fn get_encoded_char_as_sdm(&self, index: usize) -> Option<SDMArray> {
+        self.get_encoded_char(index).map(|encoded_char| self.sdm_array(encoded_char))
+    }
 
-            let encoded_char = self.encoded_message[index].clone();
-            if encoded_char == MORSE_DEFAULT_CHAR {
-                sdm_array[0] = SDMLow(WORD_SPACE_MULTIPLIER as u8);
-            } else {
-                let mut sdm_iter = sdm_array.iter_mut();
-                let mut encoded_iter = encoded_char.iter().filter(|mchar| mchar.is_some()).peekable();
-
-                while let Some(mchar) = encoded_iter.next() {
-                    *sdm_iter.next().unwrap() = match mchar {
-                        Some(S) => SDMHigh(1),
-                        Some(L) => SDMHigh(LONG_SIGNAL_MULTIPLIER as u8),
-                        _ => SDMEmpty,
-                    };
-
-                    // If we have a character in the future, we put a
-                    // signal space between this signal and the next.
-                    if encoded_iter.peek().is_some() {
-                        *sdm_iter.next().unwrap() = SDMLow(1);
-                    }
+    fn sdm_array(&self, encoded_char: MorseCodeArray) -> SDMArray {
+        let mut sdm_array = [SDMEmpty; SDM_LENGTH];
+
+        if encoded_char == MORSE_DEFAULT_CHAR {
+            sdm_array[0] = SDMLow(self.timing_profile.word_gap_mult);
+        } else {
+            let mut sdm_iter = sdm_array.iter_mut();
+            let mut encoded_iter = encoded_char.iter().filter(|mchar| mchar.is_some()).peekable();
+
+            // SDM array is long enough for all signals, spaces between them
+            // and the ending space, so iterator never runs out.
+            let mut push_sdm = |sdm| {
+                if let Some(slot) = sdm_iter.next() {
+                    *slot = sdm;
                 }
+            };
 
-                // Put a character ending long signal at the end.
-                *sdm_iter.next().unwrap() = SDMLow(LONG_SIGNAL_MULTIPLIER as u8);
+            while let Some(mchar) = encoded_iter.next() {
+                push_sdm(match mchar {
+                    Some(S) => SDMHigh(1),
+                    Some(L) => SDMHigh(self.timing_profile.dah_mult),
+                    _ => SDMEmpty,
+                });
+
+                // If we have a character in the future, we put a
+                // signal space between this signal and the next.
+                if encoded_iter.peek().is_some() {
+                    push_sdm(SDMLow(1));
+                }
             }
 
-            Some(sdm_array)
-        } else {
-            None
+            // Put a character ending long signal at the end.
+            push_sdm(SDMLow(self.timing_profile.char_gap_mult));
         }
+
+        sdm_array
     }
 
-    #[cfg(not(feature = "utf8"))]
-    fn encode(&mut self, ch: &Character, index: usize) -> Result<Character, &'static str> {
-        if ch.is_ascii() {
-            let ch_upper = ch.to_ascii_uppercase();
-            match self.get_morse_char_from_char(&ch_upper) {
-                Some(mchar) => {
-                    self.encoded_message[index] = mchar;
+    // SDM arrays of the characters of the preamble followed by a word space, or nothing without a preamble.
+    fn preamble_sdm_arrays(&self) -> impl Iterator<Item = SDMArray> + '_ {
+        let preamble = self.preamble.map_or("", Preamble::as_str);
+        let word_space = self.preamble.map(|_| self.sdm_array(MORSE_DEFAULT_CHAR));
 
-                    Ok(ch_upper)
-                },
-                None => Err("Encoding error: Could not find character in character set.")
+        Self::slice_chars(self.character_set, self.morse_code_set, self.transliteration, preamble)
+            .filter_map(|ch| ch.ok()?.to_upper())
+            .filter_map(|ch| self.get_morse_char_from_char(&ch))
+            .map(|encoded_char| self.sdm_array(encoded_char))
+            .chain(word_space)
+    }
+
+    // Walks SDMs of the entire encoded message as (multiplier, is_high) pairs.
+    // Consecutive low signals are merged into the longest one, so a word space
+    // following a character ending space will only be as long as a word space.
+    fn signal_multipliers(&self) -> impl Iterator<Item = (u8, bool)> + '_ {
+        let mut sdms = self.preamble_sdm_arrays()
+            .chain(self.get_encoded_message_as_sdm_arrays().flatten())
+            .flat_map(|sdm_array| sdm_array.into_iter())
+            .filter(|sdm| *sdm != SDMEmpty)
+            .peekable();
+
+        core::iter::from_fn(move || {
+            match sdms.next()? {
+                SDMHigh(mul) => Some((mul, true)),
+                SDMLow(mut mul) => {
+                    while let Some(SDMLow(next_mul)) = sdms.peek() {
+                        mul = mul.max(*next_mul);
+                        sdms.next();
+                    }
+
+                    Some((mul, false))
+                }
+                SDMEmpty => None,
             }
-        } else {
-            Err("Encoding error: Character is not ASCII")
-        }
+        })
     }
 
-    #[cfg(feature = "utf8")]
-    fn encode(&mut self, ch: &Character, index: usize) -> Result<Character, &'static str> {
-        let mut ch_upper = ch.to_uppercase();
+    // Keyed duration of a character from its first signal to the end of its last one, and the duration of
+    // the space after it. Word spaces are all space. Characters that can't be encoded take no time.
+    fn char_keying_ms(&self, index: usize, short_ms: u32) -> (u32, u32) {
+        let sdms = self.get_encoded_char_as_sdm(index).unwrap_or([SDMEmpty; SDM_LENGTH]);
+        let mut sdms = sdms.iter().filter(|sdm| **sdm != SDMEmpty).peekable();
+        let mut keyed_ms = 0;
 
-        if let Some(ch) = ch_upper.next() {
-            match self.get_morse_char_from_char(&ch) {
-                Some(mchar) => {
-                    self.encoded_message[index] = mchar;
+        while let Some(sdm) = sdms.next() {
+            match sdm {
+                SDMLow(mul) if sdms.peek().is_none() => return (keyed_ms, *mul as u32 * short_ms),
+                SDMHigh(mul) | SDMLow(mul) => keyed_ms += *mul as u32 * short_ms,
+                SDMEmpty => (),
+            }
+        }
 
-                    Ok(ch)
-                },
+        (keyed_ms, 0)
+    }
+
+    // Check if the character can be encoded and return its uppercase version.
+    fn encode(&self, ch: &C) -> Result<C, &'static str> {
+        if let Some(ch) = ch.to_upper() {
+            match self.get_morse_char_from_char(&ch) {
+                Some(_) => Ok(ch),
                 None => Err("Encoding error: Could not find character in character set.")
             }
         } else {
-            Err("Encoding error: Could not convert character to uppercase.")
+            Err("Encoding error: Could not convert character to uppercase. Maybe it's not ASCII?")
+        }
+    }
+
+    // Encode a character applying the unknown character policy.
+    // Returns None if the character should be skipped.
+    fn encode_with_policy(&self, ch: &C) -> Result<Option<C>, &'static str> {
+        match self.encode(ch) {
+            Ok(ch) => Ok(Some(ch)),
+            Err(err) => match self.unknown_char_policy {
+                UnknownCharPolicy::Skip => Ok(None),
+                UnknownCharPolicy::SubstituteWith(substitute) => self.encode(&substitute).map(Some),
+                UnknownCharPolicy::Error => Err(err),
+            },
         }
     }
 }
 
 // Public API
-impl<const MSG_MAX: usize> MorseEncoder<MSG_MAX> {
+impl<const MSG_MAX: usize, C: MorseCharacter, S: MessageStorage<C>> MorseEncoder<MSG_MAX, C, S> {
     // INPUTS
 
     /// Encode a single character at the edit position
-    /// and add it both to the message and encoded_message.
-    pub fn encode_character(&mut self, ch: &Character) -> Result<(), &str> {
-        let pos = self.message.get_edit_pos();
-
-        if pos < MSG_MAX {
-            let ch_uppercase = self.encode(ch, pos);
+    /// and add it to the message.
+    ///
+    /// If the encoder has a transliteration for the character, its replacement
+    /// is added instead, which can be more than one character.
+    pub fn encode_character(&mut self, ch: &C) -> Result<(), &'static str> {
+        if let Some(to) = self.transliteration.and_then(|map| transliterate(map, ch.to_char())) {
+            for ch in to.chars().filter_map(C::from_char) {
+                self.add_encoded_char(&ch)?;
+            }
 
-            match ch_uppercase {
-                Ok(ch) => {
-                    self.message.add_char(ch);
+            return Ok(());
+        }
 
-                    // If message position is clamping then this should not do anything
-                    // at the end of message position.
-                    // If wrapping then it should reset the position to 0, so above condition
-                    // should pass next time.
-                    self.message.shift_edit_right();
+        self.add_encoded_char(ch)
+    }
 
-                    Ok(())
-                },
-                Err(err) => Err(err)
-            }
-        } else {
-            Ok(())
+    /// Encode a character and put it at an index of the message.
+    ///
+    /// Unlike changing the message directly with `message.put_char_at`, this checks
+    /// if the character can be encoded and converts it to uppercase.
+    /// Edit position doesn't change.
+    pub fn set_char_at(&mut self, index: usize, ch: &C) -> Result<(), &'static str> {
+        if index >= self.message.capacity() {
+            return Err("Set char index doesn't fit into message length");
         }
+
+        let Some(ch) = self.encode_with_policy(ch)? else {
+            return Ok(());
+        };
+
+        let revision = self.message.revision();
+        let len = self.message.len();
+        self.message.put_char_at(index, ch).map_err(|_| "Set char index doesn't fit into message length")?;
+        self.update_encoded_chars(revision, len.min(index)..index + 1);
+
+        Ok(())
     }
 
     /// Encode a &str slice at the edit position
-    /// and add it both to the message and encoded message.
+    /// and add it to the message.
     ///
     /// Note if the slice exceeds maximum message length it will return an error.
-    /// Non-ASCII characters will be ignored.
-    #[cfg(not(feature = "utf8"))]
+    /// Characters that can't be represented by the character type
+    /// ie. non-ASCII characters for u8 will be ignored.
+    ///
+    /// Prosigns can be written in brackets as the letters they're made of, ie. `"HELLO <AR>"`.
+    /// Their signals are run together without inter-letter gaps and stored as one message position,
+    /// the character with the same code. So `<AR>` is stored as '+' and `<BT>` as '='.
+    /// Prosigns that don't have a character in the character set, like `<SOS>`
+    /// which is longer than any character, return an error.
     pub fn encode_slice(&mut self, str_slice: &str) -> Result<(), &str> {
-        let ascii_count = str_slice.chars().filter(|ch| ch.is_ascii()).count();
-
-        if self.message.len() + ascii_count < MSG_MAX {
-            str_slice.chars()
-                .filter(|ch| ch.is_ascii())
-                .for_each(|ch| {
-                    let byte = ch as u8;
-                    self.encode_character(&byte).unwrap();
-                });
+        let (character_set, morse_code_set, transliteration) =
+            (self.character_set, self.morse_code_set, self.transliteration);
+
+        let char_count = self.count_slice_chars(str_slice)?;
+
+        if self.message.len() + char_count < self.message.capacity() {
+            for ch in Self::slice_chars(character_set, morse_code_set, transliteration, str_slice).flatten() {
+                self.add_encoded_char(&ch)?;
+            }
 
             Ok(())
         } else {
@@ -350,36 +927,153 @@ impl<const MSG_MAX: usize> MorseEncoder<MSG_MAX> {
         }
     }
 
-    #[cfg(feature = "utf8")]
-    pub fn encode_slice(&mut self, str_slice: &str) -> Result<(), &str> {
-        if self.message.len() + str_slice.len() < MSG_MAX {
-            str_slice.chars()
-                .for_each(|ch| {
-                    self.encode_character(&ch).unwrap();
-                });
+    /// Encode characters of a [heapless::Vec] at the edit position
+    /// and add them to the message.
+    ///
+    /// Note if the characters exceed maximum message length it will return an error.
+    #[cfg(feature = "heapless")]
+    pub fn encode_heapless_vec<const N: usize>(&mut self, chars: &heapless::Vec<C, N>) -> Result<(), &str> {
+        if self.message.len() + chars.len() < self.message.capacity() {
+            for ch in chars.iter() {
+                self.encode_character(ch)?;
+            }
 
             Ok(())
         } else {
-            Err("String slice length exceeds maximum message length.")
+            Err("Vector length exceeds maximum message length.")
+        }
+    }
+
+    /// Encode characters of another [Message] at the edit position
+    /// and add them to the message.
+    ///
+    /// The other message can have a different maximum length and storage, ie. the message of a decoder
+    /// to be sent back. Clear the message of the encoder first to send only the other message.
+    /// Returns [Error::MessageTooLong] if the characters exceed maximum message length,
+    /// or [Error::UnknownCharacter] for a character the encoder can't encode.
+    pub fn encode_from_message<const N: usize, T: MessageStorage<C>>(&mut self, message: &Message<N, C, T>) -> Result<(), Error> {
+        if self.message.len() + message.len() < self.message.capacity() {
+            for ch in message.iter() {
+                self.encode_character(ch).map_err(|_| Error::UnknownCharacter)?;
+            }
+
+            Ok(())
+        } else {
+            Err(Error::MessageTooLong)
         }
     }
 
-    /// Encode the entire message from start to finish
-    /// and save it to encoded_message.
+    /// Encode a &str slice and add it to the end of the message after a [Separator].
+    ///
+    /// Beacons cycling through several stored strings can join them into one message
+    /// with the right gaps between them. The separator is left out if the message is empty,
+    /// and a message ending with a word space isn't given another one. Edit position moves to the end of the message.
+    /// If the separator and the slice together exceed maximum message length,
+    /// it returns an error and the message is left as it was.
+    ///
+    /// ```rust
+    /// use morse_codec::encoder::{Encoder, Separator};
+    ///
+    /// let mut encoder = Encoder::<32>::new().build();
+    /// encoder.append_message("VVV", Separator::Word).unwrap();
+    /// encoder.append_message("DE BEACON", Separator::Word).unwrap();
+    /// encoder.append_message("73", Separator::Paragraph).unwrap();
+    ///
+    /// assert_eq!(encoder.message.as_str(), "VVV DE BEACON = 73");
+    /// ```
+    pub fn append_message(&mut self, str_slice: &str, separator: Separator) -> Result<(), &str> {
+        let last_index = self.message.len().checked_sub(1);
+        let separator = match last_index {
+            None => "",
+            Some(index) if self.get_encoded_char(index) == Some(MORSE_DEFAULT_CHAR) => separator.as_str().trim_start(),
+            Some(_) => separator.as_str(),
+        };
+
+        let char_count = self.count_slice_chars(separator)? + self.count_slice_chars(str_slice)?;
+        if self.message.len() + char_count >= self.message.capacity() {
+            return Err("String slice length exceeds maximum message length.");
+        }
+
+        let (character_set, morse_code_set, transliteration) =
+            (self.character_set, self.morse_code_set, self.transliteration);
+
+        self.message.set_edit_pos(self.message.len());
+        for slice in [separator, str_slice] {
+            for ch in Self::slice_chars(character_set, morse_code_set, transliteration, slice).flatten() {
+                self.add_encoded_char(&ch)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Encode the entire message from start to finish.
+    ///
+    /// Encoding is done lazily when outputs are requested, so this is not necessary
+    /// anymore. Characters that are not in the character set are handled with the
+    /// [UnknownCharPolicy] of the encoder. Skipped characters are left out of the outputs,
+    /// substituted characters are replaced in the message. With the default policy it panics.
+    /// See `try_encode_message_all` for a non-panicking variant.
     pub fn encode_message_all(&mut self) {
+        let revision = self.message.revision();
+
         for index in 0..self.message.len() {
-            let ch = &self.message.char_at(index).clone();
+            let ch = self.message.char_at(index);
+            if self.encode(&ch).is_ok() {
+                continue;
+            }
 
-            self.encode(ch, index).unwrap();
+            if let Some(substitute) = self.encode_with_policy(&ch).unwrap() {
+                let _ = self.message.put_char_at(index, substitute);
+            }
         }
+
+        self.update_encoded_chars(revision, 0..self.message.len());
+    }
+
+    /// Check that every character of the message can be encoded.
+    ///
+    /// Returns an error for the first character that's not in the character set,
+    /// unless the [UnknownCharPolicy] of the encoder skips or substitutes it.
+    pub fn try_encode_message_all(&self) -> Result<(), Error> {
+        self.message
+            .iter()
+            .try_for_each(|ch| self.encode_with_policy(ch).map(|_| ()).map_err(|_| Error::UnknownCharacter))
     }
 
     // OUTPUTS
+    /// Write the encoded message as morse text in a [TextFormat] to a [core::fmt::Write] implementor.
+    ///
+    /// This doesn't need an allocator, so a fixed capacity string or a display driver
+    /// can be written to directly. Characters that can't be encoded are skipped.
+    pub fn write_text<W: core::fmt::Write>(&self, format: &TextFormat, out: &mut W) -> core::fmt::Result {
+        let encoded_chars = (0..self.message.len()).filter_map(|index| self.get_encoded_char(index));
+
+        for (index, encoded_char) in encoded_chars.enumerate() {
+            if index > 0 {
+                out.write_char(format.char_separator)?;
+            }
+
+            if encoded_char == MORSE_DEFAULT_CHAR {
+                out.write_str(format.word_separator)?;
+            } else {
+                for signal in encoded_char.iter().flatten() {
+                    out.write_char(match signal {
+                        S => format.dit,
+                        L => format.dah,
+                    })?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     /// Get last encoded message character as `Option<Character>` arrays of morse code.
     ///
     /// Arrays will have a fixed length of `MORSE_ARRAY_LENGTH` and if there's no
     /// signal the option will be None.
-    pub fn get_last_char_as_morse_charray(&self) -> Option<MorseCharray> {
+    pub fn get_last_char_as_morse_charray(&self) -> Option<MorseCharray<C>> {
         let pos = self.message.get_last_changed_index();
         self.get_encoded_char_as_morse_charray(pos)
     }
@@ -397,7 +1091,7 @@ impl<const MSG_MAX: usize> MorseEncoder<MSG_MAX> {
     /// Get an iterator to encoded message as `Option<Character>` arrays of morse code.
     /// Arrays will have a fixed length of `MORSE_ARRAY_LENGTH` and if there's no
     /// signal the option will be `None`. So it will be good to filter them out.
-    pub fn get_encoded_message_as_morse_charrays(&self) -> impl Iterator<Item = Option<MorseCharray>> + '_ {
+    pub fn get_encoded_message_as_morse_charrays(&self) -> impl Iterator<Item = Option<MorseCharray<C>>> + '_ {
         (0..self.message.len()).map(|index| {
             self.get_encoded_char_as_morse_charray(index)
         })
@@ -412,4 +1106,416 @@ impl<const MSG_MAX: usize> MorseEncoder<MSG_MAX> {
             self.get_encoded_char_as_sdm(index)
         })
     }
+
+    /// Get the entire encoded message as SDM signals annotated with their characters.
+    ///
+    /// Empty SDM values are skipped, so every chunk is an actual high or low signal.
+    ///
+    /// ```ignore
+    /// for chunk in encoder.get_encoded_message_as_sdm_chunks() {
+    ///     ui.highlight_char(chunk.index);
+    ///     play_signal(chunk.sdm);
+    /// }
+    /// ```
+    pub fn get_encoded_message_as_sdm_chunks(&self) -> impl Iterator<Item = SdmChunk<C>> + '_ {
+        (0..self.message.len()).flat_map(move |index| {
+            let ch = self.message.char_at(index);
+
+            self.get_encoded_char_as_sdm(index)
+                .into_iter()
+                .flatten()
+                .filter(|sdm| *sdm != SDMEmpty)
+                .map(move |sdm| SdmChunk { index, ch, sdm })
+        })
+    }
+
+    /// Get the entire encoded message as a stream of samples at a fixed sample rate.
+    ///
+    /// Each sample is a bit of keyed carrier: true when the signal is high (on) and
+    /// false when it's low (off). Software defined transmitters and LED strip animations can
+    /// consume this uniform time base instead of variable length durations.
+    /// Signal durations are calculated from `wpm` (words per minute) with
+    /// the PARIS standard, so a short signal is 1200 / wpm milliseconds long.
+    /// Sample boundaries are calculated from the start of the message so rounding errors
+    /// don't accumulate over long messages.
+    pub fn sample_stream(&self, sample_rate_hz: u32, wpm: u16) -> impl Iterator<Item = bool> + '_ {
+        let short_ms = 1200 / wpm.max(1) as u64;
+        let samples_at = move |elapsed_ms: u64| elapsed_ms * sample_rate_hz as u64 / 1000;
+
+        self.signal_multipliers()
+            .scan(0u64, move |elapsed_ms, (mul, is_high)| {
+                let start = samples_at(*elapsed_ms);
+                *elapsed_ms += mul as u64 * short_ms;
+                let end = samples_at(*elapsed_ms);
+
+                Some(core::iter::repeat_n(is_high, (end - start) as usize))
+            })
+            .flatten()
+    }
+
+    /// Get the entire encoded message as signal durations paired with signal highs (true) and lows (false).
+    ///
+    /// Durations are calculated by multiplying SDM values with `short_ms` and converted to the unit,
+    /// rounding to the nearest. Calculations are done in 64 bits and the results saturate
+    /// at `u32::MAX`, so a QRSS60 dit of 60 seconds or longer is no problem.
+    /// Consecutive low signals are merged the same way as `get_encoded_message_as_durations` does.
+    ///
+    /// ```rust
+    /// use morse_codec::encoder::{Encoder, TimeUnit};
+    ///
+    /// let mut encoder = Encoder::<8>::new().with_message("T", true).build();
+    /// encoder.encode_message_all();
+    ///
+    /// // QRSS10, a dit is 10 seconds
+    /// let mut durations = encoder.signal_durations(10_000, TimeUnit::Seconds);
+    /// assert_eq!(durations.next(), Some((30, true)));
+    /// assert_eq!(durations.next(), Some((30, false)));
+    /// ```
+    pub fn signal_durations(&self, short_ms: u32, unit: TimeUnit) -> impl Iterator<Item = (u32, bool)> + '_ {
+        let unit_ms = unit.as_ms() as u64;
+
+        self.signal_multipliers().map(move |(mul, is_high)| {
+            let duration = (mul as u64 * short_ms as u64 + unit_ms / 2) / unit_ms;
+
+            (duration.min(u32::MAX as u64) as u32, is_high)
+        })
+    }
+
+    /// Get the entire encoded message as signal durations in milliseconds at the speeds of the speed ramp,
+    /// paired with signal highs (true) and lows (false).
+    ///
+    /// Signals of each character are as long as the speed of the character says with the PARIS standard,
+    /// see `with_speed_ramp`. Without a speed ramp the whole message is at 20 WPM.
+    /// Consecutive lows are merged into the longest one like `signal_durations` does.
+    ///
+    /// ```rust
+    /// use morse_codec::encoder::Encoder;
+    ///
+    /// let mut encoder = Encoder::<8>::new()
+    ///     .with_message("EEE", true)
+    ///     .with_speed_ramp(10, 20)
+    ///     .build();
+    /// encoder.encode_message_all();
+    ///
+    /// let durations: Vec<(u32, bool)> = encoder.ramped_durations().collect();
+    /// assert_eq!(durations, [(120, true), (360, false), (80, true), (240, false), (60, true), (180, false)]);
+    /// ```
+    pub fn ramped_durations(&self) -> impl Iterator<Item = (u32, bool)> + '_ {
+        let (start_wpm, end_wpm) = (self.speed_ramp.0 as i32, self.speed_ramp.1 as i32);
+        let last_index = self.message.len().saturating_sub(1).max(1) as i32;
+
+        let preamble_short_ms = 1200 / start_wpm.max(1) as u32;
+        let preamble = self.preamble_sdm_arrays()
+            .flatten()
+            .map(move |sdm| (sdm, preamble_short_ms));
+
+        let signals = self.get_encoded_message_as_sdm_chunks().map(move |chunk| {
+            let wpm = start_wpm + (end_wpm - start_wpm) * chunk.index as i32 / last_index;
+
+            (chunk.sdm, 1200 / wpm.max(1) as u32)
+        });
+
+        let duration_ms = |(sdm, short_ms): (SDM, u32)| match sdm {
+            SDMHigh(mul) | SDMLow(mul) => mul as u32 * short_ms,
+            SDMEmpty => 0,
+        };
+
+        let mut signals = preamble
+            .chain(signals)
+            .filter(|(sdm, _)| *sdm != SDMEmpty)
+            .peekable();
+
+        core::iter::from_fn(move || {
+            let signal = signals.next()?;
+            let mut duration = duration_ms(signal);
+
+            match signal.0 {
+                SDMHigh(_) => Some((duration, true)),
+                _ => {
+                    while let Some(next) = signals.next_if(|(sdm, _)| matches!(sdm, SDMLow(_))) {
+                        duration = duration.max(duration_ms(next));
+                    }
+
+                    Some((duration, false))
+                }
+            }
+        })
+    }
+
+    /// Send the entire encoded message to a sink as signal durations in milliseconds.
+    ///
+    /// Signals are at the speeds of `ramped_durations`, so 20 WPM without a speed ramp.
+    /// See [sink](crate::sink) for the adapters of common outputs.
+    ///
+    /// ```ignore
+    /// let mut sink = PinSink::new(|is_high| key_pin.set_state(is_high.into()).unwrap(), |ms| delay.delay_ms(ms));
+    /// encoder.send_to(&mut sink);
+    /// ```
+    pub fn send_to(&self, sink: &mut impl SignalSink) {
+        for (duration_ms, is_high) in self.ramped_durations() {
+            sink.emit(duration_ms, is_high);
+        }
+    }
+
+    /// Split the encoded message into chunks no longer than `max_ms` on air at `wpm` words per minute.
+    ///
+    /// Transmitters with duty cycle limits or shared time slots can send the chunks one window at a time.
+    /// Chunks are split at word spaces when possible and at character spaces otherwise, and the spaces between chunks
+    /// are left out. A single character longer than `max_ms` makes a chunk of its own.
+    /// `continues` of a chunk marks that the message goes on in the next one.
+    ///
+    /// ```rust
+    /// use morse_codec::encoder::Encoder;
+    ///
+    /// let mut encoder = Encoder::<16>::new().with_message("EE EE", true).build();
+    /// encoder.encode_message_all();
+    ///
+    /// // At 20 WPM a dit is 60 ms, so "EE" takes 60 + 180 + 60 = 300 ms
+    /// let chunks: Vec<(usize, usize, u32, bool)> = encoder.transmit_chunks(400, 20)
+    ///     .map(|chunk| (chunk.start, chunk.end, chunk.duration_ms, chunk.continues))
+    ///     .collect();
+    /// assert_eq!(chunks, [(0, 2, 300, true), (3, 5, 300, false)]);
+    /// ```
+    pub fn transmit_chunks(&self, max_ms: u32, wpm: u16) -> impl Iterator<Item = TransmitChunk> + '_ {
+        let short_ms = 1200 / wpm.max(1) as u32;
+        let length = self.message.len();
+        let is_word_space = move |index: usize| self.get_encoded_char(index) == Some(MORSE_DEFAULT_CHAR);
+        let mut position = 0;
+
+        core::iter::from_fn(move || {
+            while position < length && is_word_space(position) {
+                position += 1;
+            }
+
+            if position >= length {
+                return None;
+            }
+
+            let start = position;
+            let mut end = start;
+            let mut duration_ms = 0u32;
+            let mut space_ms = 0;
+            // End and duration of the chunk at the last word space
+            let mut word_end = None;
+            let mut is_full = false;
+
+            for index in start..length {
+                let (keyed_ms, char_space_ms) = self.char_keying_ms(index, short_ms);
+
+                if is_word_space(index) {
+                    space_ms = space_ms.max(char_space_ms);
+                    word_end = Some((end, duration_ms));
+                    continue;
+                }
+
+                let total_ms = if end == start { keyed_ms } else { duration_ms + space_ms + keyed_ms };
+                if end > start && total_ms > max_ms {
+                    is_full = true;
+                    break;
+                }
+
+                duration_ms = total_ms;
+                space_ms = char_space_ms;
+                end = index + 1;
+            }
+
+            if let (true, Some((word_end, word_duration_ms))) = (is_full, word_end) {
+                end = word_end;
+                duration_ms = word_duration_ms;
+            }
+
+            position = end;
+
+            Some(TransmitChunk {
+                start,
+                end,
+                duration_ms,
+                continues: (end..length).any(|index| !is_word_space(index)),
+            })
+        })
+    }
+
+    /// Get the signals of a chunk from `transmit_chunks` as durations in milliseconds at `wpm` words per minute,
+    /// paired with signal highs (true) and lows (false).
+    ///
+    /// Lows are merged like `signal_durations` does, and the space after the last signal is left out.
+    pub fn transmit_chunk_durations(&self, chunk: &TransmitChunk, wpm: u16) -> impl Iterator<Item = (u32, bool)> + '_ {
+        let short_ms = 1200 / wpm.max(1) as u32;
+        let range = chunk.start..chunk.end;
+        let mut sdms = self.get_encoded_message_as_sdm_chunks()
+            .filter(move |sdm_chunk| range.contains(&sdm_chunk.index))
+            .map(|sdm_chunk| sdm_chunk.sdm)
+            .peekable();
+
+        core::iter::from_fn(move || {
+            match sdms.next()? {
+                SDMHigh(mul) => Some((mul as u32 * short_ms, true)),
+                SDMLow(mut mul) => {
+                    while let Some(SDMLow(next_mul)) = sdms.peek() {
+                        mul = mul.max(*next_mul);
+                        sdms.next();
+                    }
+
+                    // Nothing to space out at the end of the chunk
+                    sdms.peek()?;
+
+                    Some((mul as u32 * short_ms, false))
+                }
+                SDMEmpty => None,
+            }
+        })
+    }
+
+    /// Get the entire encoded message as [timeline](crate::timeline) runs of (length in dit units, is_high).
+    ///
+    /// Consecutive lows are merged like `signal_durations` does. Runs can be expanded
+    /// into a bit pattern with [expand](crate::timeline::expand).
+    ///
+    /// ```rust
+    /// use morse_codec::encoder::Encoder;
+    ///
+    /// let mut encoder = Encoder::<8>::new().with_message("N", true).build();
+    /// encoder.encode_message_all();
+    ///
+    /// let runs: Vec<(u32, bool)> = encoder.timeline_runs().collect();
+    /// assert_eq!(runs, [(3, true), (1, false), (1, true), (3, false)]);
+    /// ```
+    pub fn timeline_runs(&self) -> impl Iterator<Item = (u32, bool)> + '_ {
+        self.signal_multipliers().map(|(mul, is_high)| (mul as u32, is_high))
+    }
+
+    /// Get the entire encoded message as a schedule of signals with absolute start times.
+    ///
+    /// Items are (start offset in milliseconds, duration in milliseconds, is_high), offsets are counted from the start
+    /// of the message. Hosts scheduling output against an absolute clock, like an audio timeline or
+    /// animation keyframes, can use the offsets directly instead of adding up durations.
+    /// Durations are SDM values multiplied with `short_ms`, lows are merged like `signal_durations` does.
+    /// Offsets and durations saturate at `u32::MAX`.
+    ///
+    /// ```rust
+    /// use morse_codec::encoder::Encoder;
+    ///
+    /// let mut encoder = Encoder::<8>::new().with_message("A", true).build();
+    /// encoder.encode_message_all();
+    ///
+    /// let schedule: Vec<(u32, u32, bool)> = encoder.signal_schedule(100).collect();
+    /// assert_eq!(schedule, [(0, 100, true), (100, 100, false), (200, 300, true), (500, 300, false)]);
+    /// ```
+    pub fn signal_schedule(&self, short_ms: u32) -> impl Iterator<Item = (u32, u32, bool)> + '_ {
+        self.signal_multipliers().scan(0u32, move |offset_ms, (mul, is_high)| {
+            let start_ms = *offset_ms;
+            let duration_ms = short_ms.saturating_mul(mul as u32);
+            *offset_ms = offset_ms.saturating_add(duration_ms);
+
+            Some((start_ms, duration_ms, is_high))
+        })
+    }
+
+    /// Get the entire encoded message as signal durations in milliseconds with [FarnsworthTiming],
+    /// paired with signal highs (true) and lows (false).
+    ///
+    /// Signals and the spaces between them are as long as they are at the character speed,
+    /// spaces between characters and words are stretched to the effective speed.
+    ///
+    /// ```rust
+    /// use morse_codec::{encoder::Encoder, FarnsworthTiming};
+    ///
+    /// let mut encoder = Encoder::<8>::new().with_message("E E", true).build();
+    /// encoder.encode_message_all();
+    ///
+    /// let timing = FarnsworthTiming::new(18, 5);
+    /// let durations: Vec<(u32, bool)> = encoder.farnsworth_durations(timing).collect();
+    /// assert_eq!(durations, [(66, true), (3659, false), (66, true), (1568, false)]);
+    /// ```
+    pub fn farnsworth_durations(&self, timing: FarnsworthTiming) -> impl Iterator<Item = (u32, bool)> + '_ {
+        let TimingProfile { char_gap_mult, word_gap_mult, .. } = self.timing_profile;
+
+        self.signal_multipliers().map(move |(mul, is_high)| {
+            let duration = match (mul, is_high) {
+                (mul, true) => mul as u32 * timing.short_ms,
+                (mul, false) if mul == word_gap_mult => timing.word_space_ms,
+                (mul, false) if mul == char_gap_mult => timing.char_space_ms,
+                (mul, false) => mul as u32 * timing.short_ms,
+            };
+
+            (duration, is_high)
+        })
+    }
+
+    /// Get the entire encoded message as [fugit::Duration]s of signals paired with signal highs (true) and lows (false).
+    ///
+    /// Durations are multiples of the `short` duration and have its tick rate, so they can be
+    /// scheduled with the timers of RTIC and HAL crates directly. Tick counts saturate at `u32::MAX`.
+    ///
+    /// ```rust
+    /// use fugit::MillisDurationU32;
+    /// use morse_codec::encoder::Encoder;
+    ///
+    /// let mut encoder = Encoder::<8>::new().with_message("A", true).build();
+    /// encoder.encode_message_all();
+    ///
+    /// let mut durations = encoder.fugit_durations(MillisDurationU32::millis(60));
+    /// assert_eq!(durations.next(), Some((MillisDurationU32::millis(60), true)));
+    /// assert_eq!(durations.next(), Some((MillisDurationU32::millis(60), false)));
+    /// assert_eq!(durations.next(), Some((MillisDurationU32::millis(180), true)));
+    /// ```
+    #[cfg(feature = "fugit")]
+    pub fn fugit_durations<const NOM: u32, const DENOM: u32>(
+        &self,
+        short: fugit::Duration<u32, NOM, DENOM>,
+    ) -> impl Iterator<Item = (fugit::Duration<u32, NOM, DENOM>, bool)> + '_ {
+        self.signal_multipliers().map(move |(mul, is_high)| {
+            (fugit::Duration::<u32, NOM, DENOM>::from_ticks(short.ticks().saturating_mul(mul as u32)), is_high)
+        })
+    }
+}
+
+// Convenience API with std types
+#[cfg(feature = "std")]
+impl<const MSG_MAX: usize, C: MorseCharacter, S: MessageStorage<C>> MorseEncoder<MSG_MAX, C, S> {
+    /// Encode the entire message and return it as a morse code string.
+    ///
+    /// Characters are separated with a space and words with the word separator, '/' by default
+    /// ie. "... --- ... / ... --- ...". Dits and dahs use the glyphs of the encoder.
+    pub fn encode_to_string(&mut self) -> String {
+        self.encode_message_all();
+
+        let mut morse_string = String::new();
+
+        for (index, charray) in self.get_encoded_message_as_morse_charrays().flatten().enumerate() {
+            if index > 0 {
+                morse_string.push(' ');
+            }
+
+            charray.iter()
+                .flatten()
+                .for_each(|ch| morse_string.push(ch.to_char()));
+        }
+
+        morse_string
+    }
+
+    /// Get the encoded message as morse text in a [TextFormat].
+    pub fn encode_to_text(&self, format: &TextFormat) -> String {
+        let mut text = String::new();
+        // Writing to a String never fails
+        let _ = self.write_text(format, &mut text);
+
+        text
+    }
+
+    /// Get the entire encoded message as signal durations in milliseconds
+    /// paired with signal highs (true) and lows (false).
+    ///
+    /// Durations are calculated by multiplying SDM values with `short_ms`.
+    /// They saturate at `u16::MAX`, use `signal_durations` for longer signals.
+    /// Consecutive low signals are merged, so a word space following
+    /// a character ending space will only be as long as a word space.
+    /// This is the same format decoder `signal_event` calls take, so the result
+    /// can be fed to a decoder directly.
+    pub fn get_encoded_message_as_durations(&self, short_ms: u16) -> Vec<(u16, bool)> {
+        self.signal_multipliers()
+            .map(|(mul, is_high)| ((mul as u16).saturating_mul(short_ms), is_high))
+            .collect()
+    }
 }
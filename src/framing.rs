@@ -0,0 +1,202 @@
+//! Byte framing to forward morse traffic over UART or other serial links.
+//!
+//! A common setup is an MCU front-end reading a key or a receiver and
+//! a host decoding or displaying the traffic. Both ends can use this module to serialize
+//! decoded characters or raw signal events into compact frames and parse them back.
+//!
+//! Every frame has the layout below. CRC is CRC-8 (polynomial 0x07) of kind, length and payload bytes.
+//!
+//! | Start | Kind | Length | Payload | CRC |
+//! |-------|------|--------|---------|-----|
+//! | 0x7E  | 1 byte | 1 byte | 0 to 4 bytes | 1 byte |
+//!
+//! ```rust
+//! use morse_codec::{
+//!     framing::{Frame, FrameParser, MAX_FRAME_LENGTH},
+//!     SignalEvent,
+//! };
+//!
+//! // Sending side
+//! let mut buffer = [0u8; MAX_FRAME_LENGTH];
+//! let frame = Frame::<u8>::Signal(SignalEvent::new(300, true));
+//! let length = frame.to_bytes(&mut buffer).unwrap();
+//!
+//! // Receiving side, bytes arrive one at a time from the UART
+//! let mut parser = FrameParser::<u8>::new();
+//! let mut received = None;
+//! for byte in &buffer[..length] {
+//!     if let Ok(Some(frame)) = parser.push_byte(*byte) {
+//!         received = Some(frame);
+//!     }
+//! }
+//!
+//! assert_eq!(received, Some(frame));
+//! ```
+
+use crate::{
+    Character,
+    MorseCharacter,
+    SignalEvent,
+};
+
+/// First byte of every frame.
+pub const FRAME_START: u8 = 0x7E;
+
+/// Maximum payload length of a frame. A UTF-8 encoded char takes up to 4 bytes.
+pub const MAX_PAYLOAD_LENGTH: usize = 4;
+
+/// Maximum length of a frame in bytes including the start, kind, length and CRC bytes.
+pub const MAX_FRAME_LENGTH: usize = MAX_PAYLOAD_LENGTH + 4;
+
+const KIND_CHARACTER: u8 = 0x01;
+const KIND_SIGNAL: u8 = 0x02;
+
+/// Morse traffic carried by a frame.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Frame<C: MorseCharacter = Character> {
+    /// A decoded character. Serialized as UTF-8 bytes.
+    Character(C),
+    /// A raw signal event as it would be sent to `signal_event` of the decoder.
+    Signal(SignalEvent),
+}
+
+impl<C: MorseCharacter> Frame<C> {
+    /// Serialize the frame into the buffer.
+    ///
+    /// Returns the number of bytes written or an error if the buffer is too small.
+    /// A buffer of [MAX_FRAME_LENGTH] bytes always fits a frame.
+    pub fn to_bytes(&self, buffer: &mut [u8]) -> Result<usize, &'static str> {
+        let mut payload = [0u8; MAX_PAYLOAD_LENGTH];
+
+        let (kind, payload_length) = match *self {
+            Frame::Character(ch) => {
+                (KIND_CHARACTER, ch.to_char().encode_utf8(&mut payload).len())
+            }
+            Frame::Signal(SignalEvent { duration_ms, is_high }) => {
+                payload[..2].copy_from_slice(&duration_ms.to_le_bytes());
+                payload[2] = is_high as u8;
+
+                (KIND_SIGNAL, 3)
+            }
+        };
+
+        let frame_length = payload_length + 4;
+        if buffer.len() < frame_length {
+            return Err("Buffer is too small for the frame.");
+        }
+
+        buffer[0] = FRAME_START;
+        buffer[1] = kind;
+        buffer[2] = payload_length as u8;
+        buffer[3..3 + payload_length].copy_from_slice(&payload[..payload_length]);
+        buffer[3 + payload_length] = crc8(&buffer[1..3 + payload_length]);
+
+        Ok(frame_length)
+    }
+
+    fn from_parts(kind: u8, payload: &[u8]) -> Result<Self, &'static str> {
+        match kind {
+            KIND_CHARACTER => {
+                let ch = core::str::from_utf8(payload)
+                    .ok()
+                    .and_then(|s| s.chars().next())
+                    .ok_or("Character frame payload is not valid UTF-8.")?;
+
+                C::from_char(ch)
+                    .map(Frame::Character)
+                    .ok_or("Character frame payload can't be represented by the character type.")
+            }
+            KIND_SIGNAL if payload.len() == 3 => Ok(Frame::Signal(SignalEvent {
+                duration_ms: u16::from_le_bytes([payload[0], payload[1]]),
+                is_high: payload[2] != 0,
+            })),
+            KIND_SIGNAL => Err("Signal frame payload has a wrong length."),
+            _ => Err("Unknown frame kind."),
+        }
+    }
+}
+
+/// Incremental parser of frames from a byte stream.
+///
+/// Bytes can be pushed one at a time as they arrive, for example from a UART
+/// receive interrupt. Bytes before a start byte are skipped, so the parser
+/// syncs to the stream even when it's started in the middle of a frame.
+#[derive(Clone, Debug)]
+pub struct FrameParser<C: MorseCharacter = Character> {
+    buffer: [u8; MAX_FRAME_LENGTH],
+    pos: usize,
+    _character: core::marker::PhantomData<C>,
+}
+
+impl<C: MorseCharacter> Default for FrameParser<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C: MorseCharacter> FrameParser<C> {
+    pub fn new() -> Self {
+        Self {
+            buffer: [0; MAX_FRAME_LENGTH],
+            pos: 0,
+            _character: core::marker::PhantomData,
+        }
+    }
+
+    /// Discard a partially received frame.
+    pub fn reset(&mut self) {
+        self.pos = 0;
+    }
+
+    /// Push a received byte to the parser.
+    ///
+    /// Returns Ok(Some(frame)) when the byte completes a valid frame, Ok(None) when
+    /// more bytes are needed and an error when a complete frame fails the CRC check or
+    /// has invalid contents. Parser starts looking for the next frame after a frame or an error.
+    pub fn push_byte(&mut self, byte: u8) -> Result<Option<Frame<C>>, &'static str> {
+        if self.pos == 0 && byte != FRAME_START {
+            return Ok(None);
+        }
+
+        self.buffer[self.pos] = byte;
+        self.pos += 1;
+
+        // Start, kind and length are needed to know the frame length
+        if self.pos < 3 {
+            return Ok(None);
+        }
+
+        let payload_length = self.buffer[2] as usize;
+        if payload_length > MAX_PAYLOAD_LENGTH {
+            self.reset();
+            return Err("Frame payload is too long.");
+        }
+
+        let frame_length = payload_length + 4;
+        if self.pos < frame_length {
+            return Ok(None);
+        }
+
+        self.reset();
+
+        let payload = &self.buffer[3..3 + payload_length];
+        if crc8(&self.buffer[1..3 + payload_length]) != self.buffer[3 + payload_length] {
+            return Err("Frame CRC check failed.");
+        }
+
+        Frame::from_parts(self.buffer[1], payload).map(Some)
+    }
+}
+
+// CRC-8 with polynomial 0x07 and initial value 0
+fn crc8(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0u8, |crc, &byte| {
+        (0..8).fold(crc ^ byte, |crc, _| {
+            if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x07
+            } else {
+                crc << 1
+            }
+        })
+    })
+}
@@ -0,0 +1,80 @@
+//! Letter frequency tables to break ties between ambiguous signals while decoding.
+//!
+//! Human keying is often sloppy enough that a high signal falls between a dit and a dah.
+//! Give a [LanguageModel] to the decoder with `with_language_model` and it tries both readings of such signals,
+//! picking the character that's more probable after the character decoded before it.
+//!
+//! Tables are plain static slices, so models for other languages and character sets
+//! can be given the same way as [ENGLISH].
+//!
+//! ```rust
+//! use morse_codec::{
+//!     decoder::{Decoder, Precision},
+//!     language_model::ENGLISH,
+//! };
+//!
+//! let mut decoder = Decoder::<16>::new()
+//!     .with_precision(Precision::Accurate)
+//!     .with_reference_short_ms(100)
+//!     .with_language_model(&ENGLISH)
+//!     .build();
+//!
+//! // T and a 220 ms signal, which can be a dit or a dah. H is more probable after T than B.
+//! decoder.decode_text("-", &Default::default());
+//! let signals = [(220, true), (100, false), (100, true), (100, false), (100, true), (100, false), (100, true), (300, false)];
+//! for (duration_ms, is_high) in signals {
+//!     decoder.signal_event(duration_ms, is_high);
+//! }
+//!
+//! assert_eq!(decoder.message.as_str(), "TH");
+//! ```
+
+/// Relative frequencies of characters and character pairs of a language.
+///
+/// Weights are only compared with each other, so they can be in any scale that fits into a u8.
+/// Characters that aren't in the tables have a weight of 0.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LanguageModel {
+    /// Weights of single characters.
+    pub letters: &'static [(char, u8)],
+    /// Weights of characters following another character, ie. ('T', 'H', weight) for TH.
+    pub bigrams: &'static [(char, char, u8)],
+}
+
+impl LanguageModel {
+    /// Returns how probable the character is after the previous one.
+    pub fn score(&self, previous: char, ch: char) -> u16 {
+        let letter = self.letters
+            .iter()
+            .find(|(letter, _)| *letter == ch)
+            .map_or(0, |(_, weight)| *weight);
+
+        let bigram = self.bigrams
+            .iter()
+            .find(|(first, second, _)| *first == previous && *second == ch)
+            .map_or(0, |(_, _, weight)| *weight);
+
+        letter as u16 + bigram as u16
+    }
+}
+
+/// Letter and bigram frequencies of English text.
+pub const ENGLISH: LanguageModel = LanguageModel {
+    letters: &[
+        ('E', 127), ('T', 91), ('A', 82), ('O', 75), ('I', 70), ('N', 67), ('S', 63),
+        ('H', 61), ('R', 60), ('D', 43), ('L', 40), ('C', 28), ('U', 28), ('M', 24),
+        ('W', 24), ('F', 22), ('G', 20), ('Y', 20), ('P', 19), ('B', 15), ('V', 10),
+        ('K', 8), ('J', 2), ('X', 2), ('Q', 1), ('Z', 1),
+    ],
+    bigrams: &[
+        ('T', 'H', 178), ('H', 'E', 154), ('I', 'N', 122), ('E', 'R', 103), ('A', 'N', 100),
+        ('R', 'E', 93), ('O', 'N', 88), ('A', 'T', 75), ('E', 'N', 73), ('N', 'D', 68),
+        ('T', 'I', 67), ('E', 'S', 67), ('O', 'R', 64), ('T', 'E', 60), ('O', 'F', 59),
+        ('E', 'D', 59), ('I', 'S', 57), ('I', 'T', 56), ('A', 'L', 55), ('A', 'R', 54),
+        ('S', 'T', 53), ('T', 'O', 52), ('N', 'T', 52), ('N', 'G', 48), ('S', 'E', 47),
+        ('H', 'A', 47), ('A', 'S', 44), ('O', 'U', 44), ('I', 'O', 42), ('L', 'E', 42),
+        ('V', 'E', 42), ('C', 'O', 40), ('M', 'E', 40), ('D', 'E', 38), ('H', 'I', 38),
+        ('R', 'I', 37), ('R', 'O', 37), ('I', 'C', 35), ('N', 'E', 35), ('E', 'A', 35),
+        ('R', 'A', 35), ('C', 'E', 33),
+    ],
+};
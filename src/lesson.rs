@@ -0,0 +1,222 @@
+//! Practice content for morse code lessons, ie. with the Koch method.
+//!
+//! Koch lessons start with two characters sent at full character speed and add a character
+//! each time the student copies the ones so far well enough. [KOCH_ORDER] is the usual order
+//! the characters are added in. A [Lesson] writes random character groups, pronounceable
+//! pseudo words or common words made of its characters right into an encoder, so practice
+//! firmware can make new content every time without a host computer.
+//! Characters can be sent at full speed with longer spaces using `farnsworth_durations` of the encoder.
+//!
+//! ```rust
+//! use morse_codec::{
+//!     encoder::Encoder,
+//!     lesson::{Drill, Lesson},
+//! };
+//!
+//! let mut encoder = Encoder::<64, u8>::new().build();
+//!
+//! // Fourth Koch lesson, K M U R and E
+//! let mut lesson = Lesson::koch(4, 1234);
+//! assert_eq!(lesson.characters(), "KMURE");
+//!
+//! assert_eq!(lesson.write_to(&mut encoder, Drill::Groups, 5, 3), 3);
+//!
+//! let text = encoder.message.as_str();
+//! assert_eq!(text.len(), 17);
+//! assert!(text.chars().all(|ch| "KMURE ".contains(ch)));
+//! ```
+
+use crate::{
+    encoder::{MorseEncoder, Separator},
+    message::MessageStorage,
+    MorseCharacter,
+};
+
+/// Order of the characters added to Koch lessons, as taught by most courses.
+pub const KOCH_ORDER: &str = "KMURESNAPTLWI.JZ=FOY,VG5/Q92H38B?47C1D60X";
+
+/// Maximum length of a group written by a [Lesson]. Longer group lengths are cut to this.
+pub const MAX_GROUP_LENGTH: usize = 16;
+
+/// Some of the most common English words and amateur radio abbreviations, for [Drill::Words].
+pub const COMMON_WORDS: &[&str] = &[
+    "THE", "AND", "TO", "OF", "A", "IN", "IS", "IT", "YOU", "THAT", "HE", "WAS", "FOR", "ON",
+    "ARE", "AS", "WITH", "THEY", "AT", "BE", "THIS", "HAVE", "FROM", "OR", "ONE", "HAD", "BY",
+    "BUT", "NOT", "ALL", "WE", "CAN", "AN", "SHE", "DO", "IF", "WILL", "UP", "OUT", "SO",
+    "SOME", "HER", "MAKE", "TIME", "TWO", "MORE", "GO", "SEE", "NO", "MY", "NOW", "DAY", "MAN",
+    "CQ", "DE", "RST", "QTH", "NAME", "RIG", "ANT", "WX", "TNX", "FB", "OM", "UR", "ES", "HR",
+    "PSE", "AGN", "73", "599", "5NN", "TU", "GM", "GA", "GE",
+];
+
+/// What a [Lesson] makes groups of.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Drill<'a> {
+    /// Random characters of the lesson, ie. "KMRUE".
+    Groups,
+    /// Letters of the lesson alternating between consonants and vowels, ie. "MURE".
+    /// Lessons without vowels or consonants make them of any letters.
+    PseudoWords,
+    /// Words of a word list made only of the characters of the lesson, ie. [COMMON_WORDS].
+    Words(&'a [&'a str]),
+}
+
+/// Small xorshift pseudo random number generator, good enough for practice content.
+///
+/// Same seed gives the same numbers, so lessons can be repeated.
+/// Seeding from a free running timer or ADC noise gives new lessons every time.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rng {
+    state: u32,
+}
+
+impl Rng {
+    pub fn new(seed: u32) -> Self {
+        // Xorshift never leaves the zero state
+        Self { state: if seed == 0 { 0x9E37_79B9 } else { seed } }
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.state = x;
+
+        x
+    }
+
+    /// Returns a number less than bound, or 0 if bound is 0.
+    pub fn below(&mut self, bound: u32) -> u32 {
+        ((self.next_u32() as u64 * bound as u64) >> 32) as u32
+    }
+}
+
+/// Random practice content made of a set of characters.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Lesson<'a> {
+    characters: &'a str,
+    rng: Rng,
+}
+
+impl<'a> Lesson<'a> {
+    /// Create a lesson of the characters with a random number generator seed.
+    pub fn new(characters: &'a str, seed: u32) -> Self {
+        Self { characters, rng: Rng::new(seed) }
+    }
+
+    /// Returns the characters of the lesson.
+    pub fn characters(&self) -> &'a str {
+        self.characters
+    }
+
+    /// Make the next group of the drill in a buffer and return it.
+    ///
+    /// Groups and pseudo words are `group_length` characters long, up to [MAX_GROUP_LENGTH]
+    /// and as many as fit into the buffer. Words are picked whole regardless of the group length,
+    /// and left out if they don't fit. Returns an empty string if the lesson can't make a group of the drill,
+    /// ie. none of the words are made of its characters.
+    pub fn next_group<'b>(&mut self, drill: Drill<'_>, group_length: usize, buffer: &'b mut [u8]) -> &'b str {
+        let group_length = group_length.min(MAX_GROUP_LENGTH);
+        let mut length = 0;
+
+        match drill {
+            Drill::Groups => {
+                for _ in 0..group_length {
+                    if let Some(ch) = self.pick(|_| true) {
+                        push_char(buffer, &mut length, ch);
+                    }
+                }
+            }
+            Drill::PseudoWords => {
+                let has_vowels = self.characters.chars().any(is_vowel);
+                let has_consonants = self.characters.chars().any(|ch| ch.is_alphabetic() && !is_vowel(ch));
+                let mut vowel_next = self.rng.below(2) == 0;
+
+                for _ in 0..group_length {
+                    let ch = if has_vowels && has_consonants {
+                        self.pick(|ch| ch.is_alphabetic() && is_vowel(ch) == vowel_next)
+                    } else {
+                        self.pick(char::is_alphabetic)
+                    };
+
+                    if let Some(ch) = ch {
+                        push_char(buffer, &mut length, ch);
+                    }
+
+                    vowel_next = !vowel_next;
+                }
+            }
+            Drill::Words(words) => {
+                let characters = self.characters;
+                let fits = |word: &&&str| {
+                    word.chars().all(|ch| characters.chars().any(|lesson_ch| lesson_ch.eq_ignore_ascii_case(&ch)))
+                };
+
+                let count = words.iter().filter(fits).count();
+                let index = self.rng.below(count as u32) as usize;
+
+                if let Some(word) = words.iter().filter(fits).nth(index) {
+                    if word.len() <= buffer.len() {
+                        buffer[..word.len()].copy_from_slice(word.as_bytes());
+                        length = word.len();
+                    }
+                }
+            }
+        }
+
+        // Only whole UTF-8 encoded chars are written, so this can't fail.
+        core::str::from_utf8(&buffer[..length]).unwrap_or("")
+    }
+
+    /// Write groups of the drill to the end of the encoder message, separated by word spaces.
+    ///
+    /// Returns the number of groups written, which is less than count if the message is full,
+    /// or the encoder can't encode the characters of the lesson. See `next_group` for the group length.
+    pub fn write_to<const MSG_MAX: usize, C: MorseCharacter, S: MessageStorage<C>>(
+        &mut self,
+        encoder: &mut MorseEncoder<MSG_MAX, C, S>,
+        drill: Drill<'_>,
+        group_length: usize,
+        count: usize,
+    ) -> usize {
+        let mut buffer = [0u8; MAX_GROUP_LENGTH * 4];
+
+        for written in 0..count {
+            let group = self.next_group(drill, group_length, &mut buffer);
+
+            if group.is_empty() || encoder.append_message(group, Separator::Word).is_err() {
+                return written;
+            }
+        }
+
+        count
+    }
+
+    // Pick a random character of the lesson matching the filter
+    fn pick(&mut self, filter: impl Fn(char) -> bool) -> Option<char> {
+        let count = self.characters.chars().filter(|ch| filter(*ch)).count();
+        let index = self.rng.below(count as u32) as usize;
+
+        self.characters.chars().filter(|ch| filter(*ch)).nth(index)
+    }
+}
+
+impl Lesson<'static> {
+    /// Create the Koch lesson of a number, starting from 1.
+    ///
+    /// First lesson has the first two characters of [KOCH_ORDER] and every lesson after it adds the next one.
+    pub fn koch(lesson: usize, seed: u32) -> Self {
+        Self::new(&KOCH_ORDER[..(lesson + 1).clamp(2, KOCH_ORDER.len())], seed)
+    }
+}
+
+// Add a char to the end of a UTF-8 buffer if it fits
+fn push_char(buffer: &mut [u8], length: &mut usize, ch: char) {
+    if *length + ch.len_utf8() <= buffer.len() {
+        *length += ch.encode_utf8(&mut buffer[*length..]).len();
+    }
+}
+
+fn is_vowel(ch: char) -> bool {
+    matches!(ch.to_ascii_uppercase(), 'A' | 'E' | 'I' | 'O' | 'U')
+}
@@ -12,25 +12,190 @@
 //! # Features
 //! * Decoder
 //! * Encoder
+//! * Dichotomic morse tree to look up characters and completions of partial codes
+//! * Callsign and RST report recognizer
+//! * Detection of the SOS prosign regardless of the speed, for distress beacons and safety gadgets
+//! * Contest exchange templates and cut number decoding
+//! * Telemetry frames of fixed width sensor readings with a checksum, for LED and laser links between MCUs
+//! * Log of completed messages
+//! * Byte framing of characters and signal events for serial links
+//! * Parsing and emitting textual morse code conventions
+//! * Timelines of keying at dit resolution for plotting
+//! * Dispatching of commands keyed in morse code
+//! * Banks of decoders for multi-channel input, and detecting the codebook of unknown traffic
+//! * Pluggable signal event sources for the decoder, ie. slices, channels and sampled GPIO pins
+//! * Pluggable signal event sinks for the encoder, ie. buffers, GPIO pins and tone generators
+//! * Conformance test vectors of signal timings and their expected decoding
+//! * Transcoding with a decoder and an encoder sharing one message, and relaying
+//! * Practice QSO sessions taking turns between an encoder and a decoder with break-in detection
+//! * Koch lesson content of random groups, pseudo words and common words for practice
+//! * Random pileup stations with their own callsigns, exchanges, speeds and jitter for contest practice
 //!
 //! UTF-8 is supported behind a feature flag.
 //! When not used it should not interfere with embedded device applications.
+//! The feature only changes the default [Character] type though. Both ASCII and UTF-8
+//! character types can be picked explicitly with the [MorseCharacter] generic parameter.
+//! Character and morse code sets of Cyrillic, Greek and accented Latin letters are packaged
+//! as codebooks with the feature, which can be looked up by name with `charsets::by_name`.
+//!
+//! Interop with [heapless](https://docs.rs/heapless) String and Vec types is available
+//! behind the "heapless" feature flag.
+//!
+//! [SignalEvent] can be serialized with serde behind the "serde" feature flag.
+//!
+//! Tiny MCUs can enable the "small-footprint" feature to shrink the decoder. Decode times of
+//! characters and raw signal events for re-decoding are not kept, speed history covers only the last 4 characters
+//! and characters are looked up by a linear search of the character set instead of a morse tree.
+//! Encoders search the character set the same way instead of keeping a 168 byte index of it
+//! and a cache of the encoded message characters, one byte per MSG_MAX.
+//! On a 64-bit target a [MorseDecoder](decoder::MorseDecoder) with MSG_MAX of 64 takes 536 bytes
+//! instead of 1744 bytes, and the decoder size doesn't grow with MSG_MAX
+//! more than the message itself.
+//!
+//! Message length is a const generic MSG_MAX by default. When it's only known at runtime,
+//! a [MessageRef](message::MessageRef) over a buffer of the client code can be given to
+//! `Decoder::from_message` or `Encoder::from_message` instead. Messages can also be backed by
+//! heapless or std vectors, or any other buffer implementing [MessageStorage](message::MessageStorage).
+//!
+//! MCUs without an FPU can enable the "fixed-point" feature to decode with integer math only.
+//! Signal tolerance and Farnsworth factors are then given in thousandths,
+//! see [Factor](decoder::Factor). Note that this changes a public type: Factor goes from f32 to u16,
+//! so code passing float literals such as `Precision::Farnsworth(0.5)` stops compiling.
+//! Code that must build with and without the feature can use
+//! [factor_from_thousandths](decoder::factor_from_thousandths) instead.
+//!
+//! Firmware timing signals with [fugit](https://docs.rs/fugit) durations, as RTIC and HAL timers do,
+//! can enable the "fugit" feature to send them to the decoder and get them from the encoder
+//! without converting to milliseconds.
+//!
+//! Decoders of noisy human keying can enable the "language-model" feature to break ties
+//! between dits and dahs with letter frequencies, see [language_model].
+//!
+//! Desktop applications can enable the "std" feature for convenience functions
+//! returning String and Vec types, as well as WAV audio encoding and decoding.
+//!
+//! Messages, encoders, decoders and the types built on them are Send and Sync. They keep no interior
+//! mutability and their handlers are plain function pointers, so they can be moved into worker threads
+//! or async tasks, or shared between threads behind a lock.
 //!
 //! The lib is no_std outside testing to make sure it will work on embedded devices
 //! as well as operating systems.
 
-// There're debug println!() statements in various parts of
-// the code marked by a "// DBG" sign on top. In order to use them on a development environment
-// with a proper OS and std, comment out the below attribute and uncomment the debug lines you want.
-
 #![cfg_attr(not(test), no_std)]
 
+#[cfg(feature = "std")]
+extern crate std;
+
+/// Default character type of messages, encoders and decoders.
+///
+/// It's u8 by default and char when "utf8" feature is enabled.
+/// Any other [MorseCharacter] type can be used explicitly regardless of the feature.
 #[cfg(not(feature = "utf8"))]
 pub type Character = u8;
 
 #[cfg(feature = "utf8")]
 pub type Character = char;
 
+/// Character types that messages, encoders and decoders can work with.
+///
+/// Implemented for u8 (ASCII) and char (UTF-8). [Character] is the default type used
+/// when no explicit type is given, but a specific type can also be passed
+/// as the second generic parameter. So a single binary can host an ASCII decoder
+/// for the radio path and a UTF-8 encoder for the UI path:
+///
+/// ```rust
+/// use morse_codec::{decoder::Decoder, encoder::Encoder};
+///
+/// let decoder = Decoder::<64, u8>::new().build();
+/// let encoder = Encoder::<64, char>::new().with_message("ÇAY", true).build();
+///
+/// assert_eq!(decoder.message.as_str(), "");
+/// assert_eq!(encoder.message.as_str(), "ÇAY");
+/// ```
+///
+/// Character types are Send and Sync, so messages, encoders and decoders of any character type
+/// can be moved to other threads.
+pub trait MorseCharacter: Copy + PartialEq + core::fmt::Debug + Send + Sync + 'static {
+    /// Character to fill empty message positions. See [FILLER].
+    const FILLER: Self;
+
+    /// Placeholder character for decoding errors. See [DECODING_ERROR_CHAR].
+    const DECODING_ERROR_CHAR: Self;
+
+    /// Character set used when no custom set is provided.
+    const DEFAULT_CHARACTER_SET: &'static [Self];
+
+    /// Convert an ASCII byte to this character type.
+    fn from_ascii(byte: u8) -> Self;
+
+    /// Convert a char to this character type.
+    /// Returns None if the char can't be represented by the type.
+    fn from_char(ch: char) -> Option<Self>;
+
+    /// Convert this character to char, mainly for printing and comparisons.
+    fn to_char(self) -> char;
+
+    /// Returns the uppercase version of the character.
+    /// Returns None if it can't be converted.
+    fn to_upper(self) -> Option<Self>;
+
+    /// Returns the lowercase version of the character.
+    /// Returns None if it can't be converted.
+    fn to_lower(self) -> Option<Self>;
+}
+
+impl MorseCharacter for u8 {
+    const FILLER: Self = b'#';
+    const DECODING_ERROR_CHAR: Self = b'?';
+    const DEFAULT_CHARACTER_SET: &'static [Self] = DEFAULT_ASCII_CHARACTER_SET;
+
+    fn from_ascii(byte: u8) -> Self {
+        byte
+    }
+
+    fn from_char(ch: char) -> Option<Self> {
+        ch.is_ascii().then_some(ch as u8)
+    }
+
+    fn to_char(self) -> char {
+        self as char
+    }
+
+    fn to_upper(self) -> Option<Self> {
+        self.is_ascii().then(|| self.to_ascii_uppercase())
+    }
+
+    fn to_lower(self) -> Option<Self> {
+        self.is_ascii().then(|| self.to_ascii_lowercase())
+    }
+}
+
+impl MorseCharacter for char {
+    const FILLER: Self = '#';
+    const DECODING_ERROR_CHAR: Self = '?';
+    const DEFAULT_CHARACTER_SET: &'static [Self] = DEFAULT_UTF8_CHARACTER_SET;
+
+    fn from_ascii(byte: u8) -> Self {
+        byte as char
+    }
+
+    fn from_char(ch: char) -> Option<Self> {
+        Some(ch)
+    }
+
+    fn to_char(self) -> char {
+        self
+    }
+
+    fn to_upper(self) -> Option<Self> {
+        self.to_uppercase().next()
+    }
+
+    fn to_lower(self) -> Option<Self> {
+        self.to_lowercase().next()
+    }
+}
+
 // This is the array length for a sequence of morse signals or
 // character representation of those signals while encoding
 const MORSE_ARRAY_LENGTH: usize = 6;
@@ -41,7 +206,7 @@ const WORD_SPACE_MULTIPLIER: u16 = 7;
 /// it actually means there's no character there.
 ///
 /// The character # is not a part of international morse code, so it's a good candidate.
-pub const FILLER: Character = '#' as Character;
+pub const FILLER: Character = <Character as MorseCharacter>::FILLER;
 
 /// Char version of the [FILLER] coz why not? It's mainly used while generating bytes from
 /// &str slices. A [char] which is utf-8 by default in Rust, can be more than one byte.
@@ -49,19 +214,331 @@ pub const FILLER: Character = '#' as Character;
 pub const FILLER_CHAR: char = '#';
 
 /// If a decoding error happens, we put this character as a placeholder.
-pub const DECODING_ERROR_CHAR: Character = '?' as Character;
+pub const DECODING_ERROR_CHAR: Character = <Character as MorseCharacter>::DECODING_ERROR_CHAR;
 
 /// Building block of morse characters.
 ///
 /// This enum can be used with the decoder to directly add signals to characters.
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum MorseSignal {
     Short,
     Long,
 }
 
+/// A high or low signal period with its duration.
+///
+/// This is the same information `signal_event` of the decoder takes, packed in a struct.
+/// With the "serde" feature it can be serialized, so distributed setups
+/// (a sensor node reading the key and a base station decoding) can share
+/// a stable representation of signals, for example with postcard.
+/// It can also be created from and converted to (duration_ms, is_high) tuples.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SignalEvent {
+    pub duration_ms: u16,
+    pub is_high: bool,
+}
+
+impl SignalEvent {
+    pub fn new(duration_ms: u16, is_high: bool) -> Self {
+        Self { duration_ms, is_high }
+    }
+}
+
+impl From<(u16, bool)> for SignalEvent {
+    fn from((duration_ms, is_high): (u16, bool)) -> Self {
+        Self { duration_ms, is_high }
+    }
+}
+
+impl From<SignalEvent> for (u16, bool) {
+    fn from(event: SignalEvent) -> Self {
+        (event.duration_ms, event.is_high)
+    }
+}
+
+/// Signal durations of Farnsworth timing as in the
+/// [ARRL standard](https://www.arrl.org/files/file/Technology/x9004008.pdf).
+///
+/// Characters are sent at the character speed and the spaces between characters and words
+/// are stretched, so the overall speed drops to the effective speed. Speeds are in words per minute
+/// with the PARIS standard, so values line up with other training software.
+///
+/// ```rust
+/// use morse_codec::FarnsworthTiming;
+///
+/// // Characters at 18 WPM, overall speed 5 WPM
+/// let timing = FarnsworthTiming::new(18, 5);
+/// assert_eq!(timing.short_ms, 66);
+/// assert_eq!(timing.char_space_ms, 1568);
+/// assert_eq!(timing.word_space_ms, 3659);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FarnsworthTiming {
+    /// Duration of a short signal and spaces between signals at the character speed.
+    pub short_ms: u32,
+    /// Duration of a space between characters.
+    pub char_space_ms: u32,
+    /// Duration of a space between words.
+    pub word_space_ms: u32,
+}
+
+impl FarnsworthTiming {
+    /// Calculate Farnsworth timing of speeds in words per minute.
+    ///
+    /// Character speed is at least 1 and effective speed is clamped between 1 and the character speed.
+    /// When they're equal, the timing is standard.
+    pub const fn new(character_wpm: u16, effective_wpm: u16) -> Self {
+        let character_wpm = if character_wpm == 0 { 1 } else { character_wpm as u32 };
+        let effective_wpm = if effective_wpm == 0 {
+            1
+        } else if effective_wpm as u32 > character_wpm {
+            character_wpm
+        } else {
+            effective_wpm as u32
+        };
+
+        // Total delay of the 19 units of spaces in PARIS is
+        // (60 * c - 37.2 * s) / (c * s) seconds.
+        let delay_ms = (60_000 * character_wpm - 37_200 * effective_wpm) / (character_wpm * effective_wpm);
+
+        Self {
+            short_ms: 1200 / character_wpm,
+            char_space_ms: 3 * delay_ms / 19,
+            word_space_ms: 7 * delay_ms / 19,
+        }
+    }
+}
+
+/// Lengths of long signals and spaces as multiples of the short signal duration.
+///
+/// Standard morse timing has dahs and spaces between characters 3 shorts long and spaces between words
+/// 7 shorts long. Some training methods stretch them, ie. wider gaps between letters so beginners have time
+/// to recognize them, or shrink the word space to 4 shorts. Give a profile to the builders of the decoder
+/// and the encoder with `with_timing_profile` to decode and encode with such timing.
+/// Spaces between the signals of a character are always 1 short long.
+///
+/// Builders keep the profile sensible: dahs and character spaces are at least 2 shorts long,
+/// and word spaces are longer than character spaces.
+///
+/// ```rust
+/// use morse_codec::{
+///     decoder::{Decoder, Precision},
+///     encoder::{Encoder, TimeUnit},
+///     TimingProfile,
+/// };
+///
+/// // Word spaces 4 shorts long
+/// let profile = TimingProfile { word_gap_mult: 4, ..TimingProfile::STANDARD };
+///
+/// let mut encoder = Encoder::<8>::new()
+///     .with_message("T T", true)
+///     .with_timing_profile(profile)
+///     .build();
+/// encoder.encode_message_all();
+///
+/// let durations: Vec<(u32, bool)> = encoder.signal_durations(100, TimeUnit::Milliseconds).collect();
+/// assert_eq!(durations, [(300, true), (400, false), (300, true), (300, false)]);
+///
+/// let mut decoder = Decoder::<8>::new()
+///     .with_precision(Precision::Accurate)
+///     .with_reference_short_ms(100)
+///     .with_timing_profile(profile)
+///     .build();
+///
+/// for (duration_ms, is_high) in durations {
+///     decoder.signal_event(duration_ms as u16, is_high);
+/// }
+/// assert_eq!(decoder.message.as_str(), "T T");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimingProfile {
+    /// Length of a dah.
+    pub dah_mult: u8,
+    /// Length of a space between characters.
+    pub char_gap_mult: u8,
+    /// Length of a space between words.
+    pub word_gap_mult: u8,
+}
+
+impl TimingProfile {
+    /// Standard timing of international morse code.
+    pub const STANDARD: Self = Self {
+        dah_mult: LONG_SIGNAL_MULTIPLIER as u8,
+        char_gap_mult: LONG_SIGNAL_MULTIPLIER as u8,
+        word_gap_mult: WORD_SPACE_MULTIPLIER as u8,
+    };
+
+    // Clamp multipliers so the decoder can tell signals and spaces apart.
+    #[cfg(any(feature = "decoder", feature = "encoder"))]
+    pub(crate) fn sanitized(self) -> Self {
+        let char_gap_mult = self.char_gap_mult.clamp(2, u8::MAX - 1);
+
+        Self {
+            dah_mult: self.dah_mult.max(2),
+            char_gap_mult,
+            word_gap_mult: self.word_gap_mult.max(char_gap_mult + 1),
+        }
+    }
+}
+
+impl Default for TimingProfile {
+    fn default() -> Self {
+        Self::STANDARD
+    }
+}
+
+/// Rescale recorded signal events to another speed in words per minute.
+///
+/// Speed of the recording is estimated from its short signals, then every duration is scaled
+/// by the same factor. So the weighting and the irregularities of the sender, ie. long dahs or
+/// rushed character spaces, stay as they are, only slower or faster. Handy to slow down
+/// received practice material for students. Signals are (duration in milliseconds, is_high) pairs.
+/// Scaled durations are at least 1 millisecond. If there are no high signals, durations are not changed.
+///
+/// ```rust
+/// use morse_codec::retime;
+///
+/// // A at 20 WPM with a heavy dah
+/// let signals = [(60, true), (60, false), (200, true), (180, false)];
+///
+/// // Slowed down to 10 WPM
+/// let slow: Vec<(u16, bool)> = retime(&signals, 10).collect();
+/// assert_eq!(slow, [(120, true), (120, false), (400, true), (360, false)]);
+/// ```
+pub fn retime(signals: &[(u16, bool)], target_wpm: u16) -> impl Iterator<Item = (u16, bool)> + '_ {
+    let target_short_ms = 1200 / target_wpm.max(1) as u32;
+    let source_short_ms = estimate_short_ms(signals).unwrap_or(target_short_ms);
+
+    signals.iter().map(move |&(duration_ms, is_high)| {
+        let scaled = (duration_ms as u32 * target_short_ms + source_short_ms / 2) / source_short_ms;
+
+        (scaled.clamp(1, u16::MAX as u32) as u16, is_high)
+    })
+}
+
+// Average duration of the short high signals. High signals shorter than twice the shortest one count as short.
+// Zero length signals are left out.
+fn estimate_short_ms(signals: &[(u16, bool)]) -> Option<u32> {
+    let shortest = signals
+        .iter()
+        .filter(|&&(duration_ms, is_high)| is_high && duration_ms > 0)
+        .map(|&(duration_ms, _)| duration_ms)
+        .min()?;
+
+    let (sum, count) = signals
+        .iter()
+        .filter(|&&(duration_ms, is_high)| is_high && duration_ms > 0 && (duration_ms as u32) < shortest as u32 * 2)
+        .fold((0u32, 0u32), |(sum, count), &(duration_ms, _)| (sum.saturating_add(duration_ms as u32), count + 1));
+
+    Some((sum / count).max(1))
+}
+
+/// Errors returned by the fallible variants of functions that would otherwise panic
+/// or silently clamp bad values.
+///
+/// Firmware that must not panic can stick to these variants.
+/// An error can be turned into a `&'static str` message for APIs that return string errors.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// Index is out of the range of the message.
+    IndexOutOfRange,
+    /// Character is not in the character set, so it can't be encoded.
+    UnknownCharacter,
+    /// Message bytes are not valid UTF-8.
+    InvalidUtf8,
+    /// Edit position given to a builder is out of the range of the message.
+    EditPositionOutOfRange,
+    /// Signal tolerance or Farnsworth factor given to a builder is not a number.
+    InvalidFactor,
+    /// Accurate and Training decoding precisions need a reference short duration to start with.
+    MissingReferenceShort,
+    /// Word space of the reference short duration given to a decoder builder doesn't fit into u16 milliseconds.
+    ReferenceShortTooLong,
+    /// Text doesn't fit into the message.
+    MessageTooLong,
+    /// Known text given for calibration has no characters to encode.
+    EmptyKnownText,
+    /// Received signals don't line up with the known text given for calibration.
+    KnownTextMismatch,
+    /// Received signals given for calibration are all zero milliseconds long.
+    ZeroSignalDurations,
+    /// Character set and morse code set given to a builder don't make a sound pair.
+    InvalidCharacterSet(charsets::CharsetError),
+}
+
+impl Error {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Error::IndexOutOfRange => "Index doesn't fit into message length.",
+            Error::UnknownCharacter => "Character is not in the character set.",
+            Error::InvalidUtf8 => "Message is not valid UTF-8.",
+            Error::EditPositionOutOfRange => "Edit position doesn't fit into message length.",
+            Error::InvalidFactor => "Factor is not a number.",
+            Error::MissingReferenceShort => "Accurate and Training precisions need a reference short duration.",
+            Error::ReferenceShortTooLong => "Word space of the reference short duration is longer than u16 milliseconds.",
+            Error::MessageTooLong => "Text is longer than message length.",
+            Error::EmptyKnownText => "Known text has nothing to encode.",
+            Error::KnownTextMismatch => "Received signals don't match the known text.",
+            Error::ZeroSignalDurations => "Signal durations are zero.",
+            Error::InvalidCharacterSet(error) => error.as_str(),
+        }
+    }
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<Error> for &'static str {
+    fn from(error: Error) -> Self {
+        error.as_str()
+    }
+}
+
 type MorseCodeArray = [Option<MorseSignal>; MORSE_ARRAY_LENGTH];
 
+// Fail the build if a type loses Send or Sync.
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+
+    assert_send_sync::<message::Message<1>>();
+    assert_send_sync::<charsets::MorseTree>();
+    assert_send_sync::<message_log::MessageLog<1, 1>>();
+    assert_send_sync::<framing::FrameParser>();
+    assert_send_sync::<text::TextFormat>();
+    assert_send_sync::<sos::SosDetector>();
+    assert_send_sync::<telemetry::TelemetryFormat>();
+
+    #[cfg(feature = "decoder")]
+    {
+        assert_send_sync::<decoder::Decoder<1>>();
+        assert_send_sync::<decoder::MorseDecoder<1>>();
+        assert_send_sync::<bank::DecoderBank<1, 1>>();
+        assert_send_sync::<source::SliceSource<'static>>();
+        assert_send_sync::<command::CommandSet<1>>();
+    }
+
+    #[cfg(feature = "encoder")]
+    {
+        assert_send_sync::<encoder::Encoder<1>>();
+        assert_send_sync::<encoder::MorseEncoder<1>>();
+        assert_send_sync::<playback::Player>();
+        assert_send_sync::<playback::IdTimer>();
+        assert_send_sync::<buzzer::Buzzer>();
+        assert_send_sync::<sink::BufferSink<'static>>();
+        assert_send_sync::<pileup::Pileup>();
+    }
+
+    #[cfg(all(feature = "encoder", feature = "decoder"))]
+    {
+        assert_send_sync::<transcoder::Relay<1>>();
+        assert_send_sync::<session::Session<1, 1>>();
+    }
+};
+
 /// This corresponds to empty character ' ' which is the default character
 pub const MORSE_DEFAULT_CHAR: MorseCodeArray = [None, None, None, None, None, None];
 
@@ -71,7 +548,10 @@ pub use charsets::{
     MorseCodeSet,
     DEFAULT_CHARACTER_SET_LENGTH,
     DEFAULT_CHARACTER_SET,
+    DEFAULT_ASCII_CHARACTER_SET,
+    DEFAULT_UTF8_CHARACTER_SET,
     DEFAULT_MORSE_CODE_SET,
+    Transliteration,
 };
 
 #[cfg(feature = "decoder")]
@@ -80,4 +560,58 @@ pub mod decoder;
 #[cfg(feature = "encoder")]
 pub mod encoder;
 
+#[cfg(feature = "encoder")]
+pub mod buzzer;
+
+#[cfg(feature = "encoder")]
+pub mod playback;
+
+#[cfg(feature = "encoder")]
+pub mod sink;
+
+#[cfg(feature = "encoder")]
+pub mod lesson;
+
+#[cfg(feature = "encoder")]
+pub mod pileup;
+
 pub mod message;
+
+pub mod message_log;
+
+pub mod recognizer;
+
+pub mod sos;
+
+pub mod contest;
+
+pub mod telemetry;
+
+pub mod framing;
+
+pub mod text;
+
+pub mod timeline;
+
+pub mod conformance;
+
+#[cfg(feature = "decoder")]
+pub mod command;
+
+#[cfg(feature = "decoder")]
+pub mod bank;
+
+#[cfg(feature = "decoder")]
+pub mod source;
+
+#[cfg(feature = "language-model")]
+pub mod language_model;
+
+#[cfg(all(feature = "encoder", feature = "decoder"))]
+pub mod transcoder;
+
+#[cfg(all(feature = "encoder", feature = "decoder"))]
+pub mod session;
+
+#[cfg(all(feature = "std", feature = "encoder", feature = "decoder"))]
+pub mod wav;
@@ -26,17 +26,32 @@
 //! ```
 
 use crate::{
-    FILLER,
-    FILLER_CHAR,
     Character,
+    Error,
+    MorseCharacter,
 };
 
-#[cfg(feature = "utf8")]
-use core::fmt::Display;
+#[cfg(feature = "std")]
+use std::{string::String, vec::Vec};
+
+use core::{
+    fmt::Display,
+    iter::FusedIterator,
+    marker::PhantomData,
+    ops::{
+        Index,
+        IndexMut,
+        Range,
+        RangeFrom,
+        RangeFull,
+        RangeInclusive,
+        RangeTo,
+        RangeToInclusive,
+    },
+};
 
-#[cfg(feature = "utf8")]
 #[derive(Debug)]
-/// When "utf8" feature is enabled, instead of &str
+/// When working with char messages ie. "utf8" feature is enabled, instead of &str
 /// we return this new type struct as a placeholder for &str,
 /// because it's still hard to use arithmetic operations in
 /// const expressions. In the future if this PR gets merged:
@@ -45,7 +60,6 @@ use core::fmt::Display;
 /// let chars: [0; MSG_MAX * 4] = ...
 pub struct Utf8Charray<'a>(&'a [char]);
 
-#[cfg(feature = "utf8")]
 impl Display for Utf8Charray<'_> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         for ch in self.0 {
@@ -56,7 +70,6 @@ impl Display for Utf8Charray<'_> {
     }
 }
 
-#[cfg(feature = "utf8")]
 impl PartialEq<&str> for Utf8Charray<'_> {
     fn eq(&self, other: &&str) -> bool {
         let mut other_chars = other.chars();
@@ -71,37 +84,165 @@ impl PartialEq<&str> for Utf8Charray<'_> {
     }
 }
 
-#[cfg(feature = "utf8")]
 impl Utf8Charray<'_> {
     pub fn iter(&self) -> impl Iterator<Item = &char> {
         self.0.iter()
     }
 }
 
+/// Backing buffer of a [Message].
+///
+/// Messages are backed by a fixed array of MSG_MAX characters by default.
+/// A [MessageRef] is backed by a slice of characters given by the client code instead.
+/// Vectors of [heapless](https://docs.rs/heapless) and std can back a message too, behind their feature flags.
+///
+/// Capacity of a message is the length of its buffer, so vectors should be filled
+/// up to the capacity wanted before they're given to `Message::from_storage`.
+/// Storages of other buffers can be implemented by the client code.
+///
+/// ```rust
+/// use morse_codec::message::{Message, MessageStorage};
+///
+/// // A line of a character display
+/// struct LcdLine {
+///     chars: [u8; 16],
+/// }
+///
+/// impl MessageStorage<u8> for LcdLine {
+///     fn as_slice(&self) -> &[u8] {
+///         &self.chars
+///     }
+///
+///     fn as_mut_slice(&mut self) -> &mut [u8] {
+///         &mut self.chars
+///     }
+/// }
+///
+/// let mut message = Message::<0, u8, LcdLine>::from_storage(LcdLine { chars: [b'#'; 16] });
+/// message.set_message("Hello world", true).unwrap();
+///
+/// assert_eq!(message.as_str(), "HELLO WORLD");
+/// assert_eq!(message.remaining(), 5);
+/// ```
+pub trait MessageStorage<C> {
+    /// Returns all characters of the buffer, including the FILLERs at the end of the message.
+    fn as_slice(&self) -> &[C];
+    /// Returns all characters of the buffer for changing them.
+    fn as_mut_slice(&mut self) -> &mut [C];
+}
+
+impl<C, const N: usize> MessageStorage<C> for [C; N] {
+    fn as_slice(&self) -> &[C] {
+        self
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [C] {
+        self
+    }
+}
+
+impl<C> MessageStorage<C> for &mut [C] {
+    fn as_slice(&self) -> &[C] {
+        self
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [C] {
+        self
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<C, const N: usize> MessageStorage<C> for heapless::Vec<C, N> {
+    fn as_slice(&self) -> &[C] {
+        self
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [C] {
+        self
+    }
+}
+
+#[cfg(feature = "std")]
+impl<C> MessageStorage<C> for Vec<C> {
+    fn as_slice(&self) -> &[C] {
+        self
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [C] {
+        self
+    }
+}
+
+/// Letter case of the characters of a [Message].
+///
+/// Morse code has no letter case, so messages are uppercase by default.
+/// UI text can keep its case or be shown in lowercase instead.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum LetterCase {
+    /// Letters of strings set to the message are turned to uppercase. This is the default.
+    #[default]
+    Upper,
+    /// Letters of strings set to the message keep their case.
+    Preserve,
+    /// All letters written to the message are turned to lowercase, including decoded characters.
+    Lower,
+}
+
 /// This struct holds the message in human readable format.
 ///
 /// It also provides functions to do edit position manipulation,
 /// getting or setting characters at index positions.
-pub struct Message<const MSG_MAX: usize> {
-    chars: [Character; MSG_MAX],
+///
+/// Characters are kept in a fixed array of MSG_MAX characters by default.
+/// See [MessageRef] for a message in a buffer sized at runtime.
+#[derive(Clone, Debug)]
+pub struct Message<const MSG_MAX: usize, C: MorseCharacter = Character, S: MessageStorage<C> = [C; MSG_MAX]> {
+    chars: S,
     edit_pos: usize,
     last_change_index: usize,
     clamp_edit_pos: bool,
+    letter_case: LetterCase,
+    revision: u32,
+    character: PhantomData<C>,
+}
+
+/// Messages are equal when their characters and editing state are equal, regardless of their revisions.
+impl<const MSG_MAX: usize, C: MorseCharacter, S: MessageStorage<C>> PartialEq for Message<MSG_MAX, C, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.chars.as_slice() == other.chars.as_slice()
+            && self.edit_pos == other.edit_pos
+            && self.last_change_index == other.last_change_index
+            && self.clamp_edit_pos == other.clamp_edit_pos
+            && self.letter_case == other.letter_case
+    }
 }
 
-impl<const MSG_MAX: usize> Default for Message<MSG_MAX> {
+/// A message in a slice of characters given by the client code.
+///
+/// Size of the buffer can be chosen at runtime, ie. allocated on the heap on std or taken from a static on no_std,
+/// instead of making a [Message] type for every size with MSG_MAX. Capacity of the message is the length of the slice.
+/// Decoders and encoders can be built over it with `from_message` of their builders.
+///
+/// ```rust
+/// use morse_codec::message::MessageRef;
+///
+/// let mut buffer = vec![0u8; 100];
+/// let mut message = MessageRef::from_storage(&mut buffer[..]);
+/// message.set_message("Hello", true).unwrap();
+///
+/// assert_eq!(message.as_str(), "HELLO");
+/// assert_eq!(message.capacity(), 100);
+/// ```
+pub type MessageRef<'a, C = Character> = Message<0, C, &'a mut [C]>;
+
+impl<const MSG_MAX: usize, C: MorseCharacter> Default for Message<MSG_MAX, C> {
     fn default() -> Self {
-        Self {
-            chars: [FILLER; MSG_MAX],
-            edit_pos: 0,
-            last_change_index: 0,
-            clamp_edit_pos: false,
-        }
+        Self::from_storage([C::FILLER; MSG_MAX])
     }
 }
 
 // Constructor with a starter string
-impl<const MSG_MAX: usize> Message<MSG_MAX> {
+impl<const MSG_MAX: usize, C: MorseCharacter> Message<MSG_MAX, C> {
     /// Maximum index editing position can be at
     pub const POS_MAX: usize = MSG_MAX - 1;
 
@@ -111,62 +252,108 @@ impl<const MSG_MAX: usize> Message<MSG_MAX> {
     /// text at the end.
     pub fn new(message_str: &str, edit_pos_end: bool, clamp_edit_pos: bool) -> Self {
         let mut new_self = Self {
-            chars: Self::str_to_chars(message_str),
             clamp_edit_pos,
             ..Self::default()
         };
 
+        new_self.fill_from_str(message_str);
+
         if edit_pos_end {
             new_self.edit_pos = new_self.len().clamp(0, Self::POS_MAX);
         }
 
         new_self
     }
+}
 
-    #[cfg(not(feature = "utf8"))]
-    // Static member utility function to convert an &str to character array internal format.
-    fn str_to_chars(str: &str) -> [u8; MSG_MAX] {
-        let mut str_iter = str.chars()
-            .take(MSG_MAX)
-            .filter(|ch| ch.is_ascii());
-
-        core::array::from_fn(|_|
-            str_iter.next()
-                .unwrap_or(FILLER_CHAR)
-                .to_ascii_uppercase() as u8
-        )
-    }
-
-    #[cfg(feature = "utf8")]
-    // Static member utility function to convert an &str to charray internal format.
-    fn str_to_chars(str: &str) -> [Character; MSG_MAX] {
-        let mut str_iter = str.chars()
-            .take(MSG_MAX);
+impl<const MSG_MAX: usize, C: MorseCharacter, S: MessageStorage<C>> Message<MSG_MAX, C, S> {
+    /// Get an empty message backed by a buffer. All characters of the buffer are cleared.
+    pub fn from_storage(mut storage: S) -> Self {
+        storage.as_mut_slice().fill(C::FILLER);
 
-        core::array::from_fn(|_|
-            str_iter.next()
-                .unwrap_or(FILLER_CHAR)
-                .to_uppercase()
-                .next()
-                .unwrap()
-        )
+        Self {
+            chars: storage,
+            edit_pos: 0,
+            last_change_index: 0,
+            clamp_edit_pos: false,
+            letter_case: LetterCase::Upper,
+            revision: 0,
+            character: PhantomData,
+        }
     }
 }
 
 // Private stuff
-impl<const MSG_MAX: usize> Message<MSG_MAX> {
+impl<const MSG_MAX: usize, C: MorseCharacter, S: MessageStorage<C>> Message<MSG_MAX, C, S> {
     // Index of last character before the last FILLERs
     fn last_char_index(&self) -> Option<usize> {
-        self.chars.iter().rposition(|ch| *ch != FILLER)
+        self.chars.as_slice().iter().rposition(|ch| *ch != C::FILLER)
+    }
+
+    // Maximum index editing position can be at
+    fn pos_max(&self) -> usize {
+        self.capacity().saturating_sub(1)
+    }
+
+    // Characters of the message discarding FILLERs at the end
+    pub(crate) fn as_chars(&self) -> &[C] {
+        &self.chars.as_slice()[..self.len()]
+    }
+
+    pub(crate) fn as_chars_mut(&mut self) -> &mut [C] {
+        let len = self.len();
+        self.touch();
+
+        &mut self.chars.as_mut_slice()[..len]
+    }
+
+    // Replace the characters with the ones of an &str.
+    // Characters that can't be represented by the character type (ie. non-ASCII for u8) are skipped.
+    pub(crate) fn fill_from_str(&mut self, str: &str) {
+        self.touch();
+        let letter_case = self.letter_case;
+        let mut str_iter = str.chars()
+            .filter_map(C::from_char);
+
+        self.chars.as_mut_slice().iter_mut().for_each(|ch| {
+            *ch = str_iter.next()
+                .and_then(|ch| match letter_case {
+                    LetterCase::Upper => ch.to_upper(),
+                    LetterCase::Preserve => Some(ch),
+                    LetterCase::Lower => ch.to_lower(),
+                })
+                .unwrap_or(C::FILLER)
+        });
+    }
+
+    // Characters written one by one are only changed in lowercase mode,
+    // decoded characters are already uppercase.
+    fn with_letter_case(&self, ch: C) -> C {
+        match self.letter_case {
+            LetterCase::Lower => ch.to_lower().unwrap_or(ch),
+            _ => ch,
+        }
+    }
+
+    // Count a change of the message
+    fn touch(&mut self) {
+        self.revision = self.revision.wrapping_add(1);
+    }
+
+    // Continue counting revisions of a message this one replaces
+    #[cfg(feature = "decoder")]
+    pub(crate) fn follow_revision(&mut self, previous: &Self) {
+        self.revision = previous.revision;
+        self.touch();
     }
 
     // Check if any FILLER characters are between normal chars
     // and convert them to ' ' space characters.
     fn update_empty_chars(&mut self) {
         if let Some(last_index) = self.last_char_index() {
-            self.chars.iter_mut().enumerate().for_each(|(index, ch)| {
-                if *ch == FILLER && index < last_index {
-                    *ch = ' ' as Character;
+            self.chars.as_mut_slice().iter_mut().enumerate().for_each(|(index, ch)| {
+                if *ch == C::FILLER && index < last_index {
+                    *ch = C::from_ascii(b' ');
                 }
             });
         }
@@ -174,18 +361,20 @@ impl<const MSG_MAX: usize> Message<MSG_MAX> {
 }
 
 // Public API
-impl<const MSG_MAX: usize> Message<MSG_MAX> {
+impl<const MSG_MAX: usize, C: MorseCharacter, S: MessageStorage<C>> Message<MSG_MAX, C, S> {
     /// Get an iterator to the message chars contained within.
-    pub fn iter(&self) -> MessageIterator<MSG_MAX> {
+    pub fn iter(&self) -> MessageIterator<'_, MSG_MAX, C> {
         MessageIterator {
-            message: self,
+            chars: self.as_chars(),
             index: 0,
+            end: self.len(),
         }
     }
 
     /// Sets current editing position to given value.
     pub fn set_edit_pos(&mut self, pos: usize) {
-        self.edit_pos = pos.clamp(0, Self::POS_MAX);
+        self.edit_pos = pos.clamp(0, self.pos_max());
+        self.touch();
     }
 
     /// Change the clamping behaviour of the edit position to
@@ -203,6 +392,64 @@ impl<const MSG_MAX: usize> Message<MSG_MAX> {
         self.clamp_edit_pos
     }
 
+    /// Change the letter case of the message, see [LetterCase].
+    ///
+    /// Characters already in the message are turned to uppercase or lowercase
+    /// with [LetterCase::Upper] and [LetterCase::Lower]. Case of the characters can't be
+    /// recovered with [LetterCase::Preserve], so it should be set before the message is set from a string.
+    ///
+    /// ```rust
+    /// use morse_codec::message::{LetterCase, Message};
+    ///
+    /// let mut message = Message::<16>::default();
+    /// message.set_letter_case(LetterCase::Preserve);
+    /// message.set_message("Hello", true).unwrap();
+    /// assert_eq!(message.as_str(), "Hello");
+    ///
+    /// message.set_letter_case(LetterCase::Lower);
+    /// message.add_char(b'S');
+    /// assert_eq!(message.as_str(), "hellos");
+    /// ```
+    pub fn set_letter_case(&mut self, letter_case: LetterCase) {
+        self.letter_case = letter_case;
+        self.touch();
+
+        let len = self.len();
+        for ch in self.chars.as_mut_slice()[..len].iter_mut() {
+            *ch = match letter_case {
+                LetterCase::Upper => ch.to_upper(),
+                LetterCase::Preserve => Some(*ch),
+                LetterCase::Lower => ch.to_lower(),
+            }
+            .unwrap_or(*ch);
+        }
+    }
+
+    /// Returns the letter case of the message.
+    pub fn letter_case(&self) -> LetterCase {
+        self.letter_case
+    }
+
+    /// Returns the revision of the message, which changes with every change
+    /// of its characters or editing position.
+    ///
+    /// UIs can keep the revision they last rendered and render again only when it's different,
+    /// instead of comparing the characters every frame. Revisions wrap around at u32::MAX.
+    ///
+    /// ```rust
+    /// use morse_codec::message::Message;
+    ///
+    /// let mut message = Message::<16, u8>::default();
+    /// let rendered = message.revision();
+    /// assert_eq!(message.revision(), rendered);
+    ///
+    /// message.add_char(b'E');
+    /// assert_ne!(message.revision(), rendered);
+    /// ```
+    pub fn revision(&self) -> u32 {
+        self.revision
+    }
+
     /// Returns current editing position.
     pub fn get_edit_pos(&self) -> usize {
         self.edit_pos
@@ -214,35 +461,43 @@ impl<const MSG_MAX: usize> Message<MSG_MAX> {
     }
 
     /// Returns the character at the index of last change
-    pub fn get_last_changed_char(&self) -> Character {
-        self.chars[self.last_change_index]
+    pub fn get_last_changed_char(&self) -> C {
+        self.chars.as_slice().get(self.last_change_index).copied().unwrap_or(C::FILLER)
     }
 
     /// Move editing position to the left.
     /// By default it will wrap to the end if position is 0
     pub fn shift_edit_left(&mut self) {
         self.edit_pos = match self.edit_pos {
-            0 => if self.clamp_edit_pos { 0 } else { Self::POS_MAX },
+            0 => if self.clamp_edit_pos { 0 } else { self.pos_max() },
             p => p - 1,
-        }
+        };
+        self.touch();
     }
 
     /// Move editing position to the right.
     /// By default it will wrap to the beginning if position is POS_MAX
     pub fn shift_edit_right(&mut self) {
         self.edit_pos = match self.edit_pos {
-            p if p == Self::POS_MAX => if self.clamp_edit_pos { Self::POS_MAX } else { 0 },
+            p if p == self.pos_max() => if self.clamp_edit_pos { p } else { 0 },
             p => p + 1,
-        }
+        };
+        self.touch();
     }
 
     /// Insert character at the editing position.
     ///
-    /// If any characters before the character are [FILLER]s
+    /// If any characters before the character are [FILLER](crate::FILLER)s
     /// They'll automatically be converted to empty characters ' '
     /// which means the user wants some space between words.
-    pub fn add_char(&mut self, ch: Character) {
-        self.chars[self.edit_pos] = ch;
+    pub fn add_char(&mut self, ch: C) {
+        let ch = self.with_letter_case(ch);
+        let Some(slot) = self.chars.as_mut_slice().get_mut(self.edit_pos) else {
+            return;
+        };
+
+        *slot = ch;
+        self.touch();
         // This is only necessary if client code sets edit position
         // manually and adds a character after it, but hey.
         self.update_empty_chars();
@@ -251,12 +506,13 @@ impl<const MSG_MAX: usize> Message<MSG_MAX> {
 
     /// Insert character at index.
     ///
-    /// If any characters before the character are [FILLER]s
+    /// If any characters before the character are [FILLER](crate::FILLER)s
     /// They'll automatically be converted to empty characters ' '
     /// which means the user wants some space between words.
-    pub fn put_char_at(&mut self, index: usize, ch: Character) -> Result<(), &str> {
-        if index < MSG_MAX {
-            self.chars[index] = ch;
+    pub fn put_char_at(&mut self, index: usize, ch: C) -> Result<(), &str> {
+        if index < self.capacity() {
+            self.chars.as_mut_slice()[index] = self.with_letter_case(ch);
+            self.touch();
             self.update_empty_chars();
             self.last_change_index = index;
 
@@ -266,21 +522,74 @@ impl<const MSG_MAX: usize> Message<MSG_MAX> {
         }
     }
 
+    /// Remove the character at index and return it.
+    ///
+    /// If it's the last character of the message, it's replaced by a [FILLER](crate::FILLER),
+    /// otherwise by an empty character ' ' to keep the characters after it in place.
+    /// Edit position moves to the index, so the next character added will take its place.
+    /// Returns None if the index is out of range or there's no character at the index.
+    pub fn remove_char_at(&mut self, index: usize) -> Option<C> {
+        let ch = *self.chars.as_slice().get(index)?;
+        if ch == C::FILLER {
+            return None;
+        }
+
+        let len = self.len();
+        self.chars.as_mut_slice()[index] = if index + 1 == len { C::FILLER } else { C::from_ascii(b' ') };
+        self.edit_pos = index;
+        self.last_change_index = index.saturating_sub(1);
+        self.touch();
+
+        Some(ch)
+    }
+
+    /// Remove the last character of the message and return it.
+    ///
+    /// Edit position moves to the removed character's position.
+    /// Returns None if the message is empty.
+    pub fn pop(&mut self) -> Option<C> {
+        let index = self.last_char_index()?;
+
+        self.remove_char_at(index)
+    }
+
     /// Returns character at an index
-    pub fn char_at(&self, index: usize) -> Character {
-        self.chars[index]
+    ///
+    /// Panics if the index is not less than the capacity. See `try_char_at` for a non-panicking variant.
+    pub fn char_at(&self, index: usize) -> C {
+        self.chars.as_slice()[index]
+    }
+
+    /// Returns character at an index or an error if the index is out of range.
+    pub fn try_char_at(&self, index: usize) -> Result<C, Error> {
+        self.chars.as_slice().get(index).copied().ok_or(Error::IndexOutOfRange)
     }
 
     /// Returns current length of the message discarding empty FILLER characters at the end.
     ///
     /// This is useful for creating ranged loops of actual characters decoded or can be encoded.
     pub fn len(&self) -> usize {
-        let index = self.last_char_index();
-        match index {
-            Some(i) if i < MSG_MAX => i + 1,
-            Some(i) if i == MSG_MAX => MSG_MAX,
-            _ => 0,
-        }
+        self.last_char_index().map_or(0, |index| index + 1)
+    }
+
+    /// Returns maximum number of characters the message can hold, which is MSG_MAX
+    /// or the length of the buffer of a [MessageRef] or another [MessageStorage].
+    pub fn capacity(&self) -> usize {
+        self.chars.as_slice().len()
+    }
+
+    /// Returns how many more characters can be added to the end of the message.
+    pub fn remaining(&self) -> usize {
+        self.capacity() - self.len()
+    }
+
+    /// Returns true if there's no room left at the end of the message.
+    ///
+    /// When the message is full, adding characters at the edit position will overwrite existing ones.
+    /// With wrapping edit position (default) that starts from the beginning of the message,
+    /// with clamping edit position it's the last character that gets overwritten again and again.
+    pub fn is_full(&self) -> bool {
+        self.len() == self.capacity()
     }
 
     /// Returns true if the message is empty, false otherwise.
@@ -296,13 +605,13 @@ impl<const MSG_MAX: usize> Message<MSG_MAX> {
     /// edit_pos_end flag means we'll continue from the end of this string when
     /// we continue decoding or encoding.
     pub fn set_message(&mut self, message_str: &str, edit_pos_end: bool) -> Result<(), &str> {
-        if message_str.len() > MSG_MAX {
+        if message_str.len() > self.capacity() {
             Err("Message string can't be longer than MSG_MAX.")
         } else {
-            self.chars = Self::str_to_chars(message_str);
+            self.fill_from_str(message_str);
 
             if edit_pos_end {
-                self.edit_pos = self.len().clamp(0, Self::POS_MAX);
+                self.edit_pos = self.len().clamp(0, self.pos_max());
             } else {
                 self.edit_pos = 0;
             }
@@ -313,26 +622,90 @@ impl<const MSG_MAX: usize> Message<MSG_MAX> {
         }
     }
 
+    /// Clear the message and start over.
+    pub fn clear(&mut self) {
+        self.chars.as_mut_slice().fill(C::FILLER);
+        self.edit_pos = 0;
+        self.touch();
+    }
+}
+
+impl<const MSG_MAX: usize, C: MorseCharacter> Message<MSG_MAX, C> {
     /// Returns the message as it is now in a character array format.
     ///
-    /// Note that this also includes 'empty' [FILLER] characters.
+    /// Note that this also includes 'empty' [FILLER](crate::FILLER) characters.
     /// Client code can use return value of len() which is the actual length
     /// to loop through it or filter the fillers manually in a loop or iterator.
-    pub fn as_charray(&self) -> [Character; MSG_MAX] {
+    /// See `as_charray_trimmed` and `slots` to get the message without FILLERs.
+    pub fn as_charray(&self) -> [C; MSG_MAX] {
         self.chars
     }
 
+    /// Returns the message in a character array like `as_charray`, but with
+    /// [FILLER](crate::FILLER) characters replaced by empty characters ' '.
+    ///
+    /// The array can be printed as it is, ie. on a fixed width display,
+    /// without showing the FILLER characters to users.
+    ///
+    /// ```rust
+    /// use morse_codec::message::Message;
+    ///
+    /// let message = Message::<6, u8>::new("SOS", true, false);
+    ///
+    /// assert_eq!(message.as_charray(), *b"SOS###");
+    /// assert_eq!(message.as_charray_trimmed(), *b"SOS   ");
+    /// ```
+    pub fn as_charray_trimmed(&self) -> [C; MSG_MAX] {
+        self.chars.map(|ch| if ch == C::FILLER { C::from_ascii(b' ') } else { ch })
+    }
+}
+
+impl<const MSG_MAX: usize, C: MorseCharacter, S: MessageStorage<C>> Message<MSG_MAX, C, S> {
+    /// Get an iterator of all character positions of the message up to its capacity.
+    ///
+    /// Positions with a character give Some(character), empty positions give None
+    /// instead of [FILLER](crate::FILLER) characters.
+    ///
+    /// ```rust
+    /// use morse_codec::message::Message;
+    ///
+    /// let message = Message::<4, u8>::new("HI", true, false);
+    /// let slots: Vec<Option<u8>> = message.slots().collect();
+    ///
+    /// assert_eq!(slots, [Some(b'H'), Some(b'I'), None, None]);
+    /// ```
+    pub fn slots(&self) -> impl Iterator<Item = Option<C>> + '_ {
+        self.chars.as_slice().iter().map(|&ch| (ch != C::FILLER).then_some(ch))
+    }
+}
+
+impl<const MSG_MAX: usize, S: MessageStorage<u8>> Message<MSG_MAX, u8, S> {
     /// Returns the message as it is now as &str slice.
-    /// Or as a [Utf8Charray] if "utf8" feature is enabled.
+    /// Or as a [Utf8Charray] for char messages ie. "utf8" feature is enabled.
     ///
-    /// Note that this *does not* include empty [FILLER] characters.
-    #[cfg(not(feature = "utf8"))]
+    /// Note that this *does not* include empty [FILLER](crate::FILLER) characters.
+    /// If the message has bytes that are not valid UTF-8, only the part before them
+    /// is returned. Use `try_as_str` to find out about it.
     pub fn as_str(&self) -> &str {
-        core::str::from_utf8(self.chars[0..self.len()].as_ref()).unwrap()
+        let bytes = self.as_chars();
+
+        match core::str::from_utf8(bytes) {
+            Ok(str) => str,
+            Err(err) => core::str::from_utf8(&bytes[..err.valid_up_to()]).unwrap_or_default(),
+        }
     }
 
-    #[cfg(feature = "utf8")]
-    pub fn as_str(&self) -> Utf8Charray {
+    /// Returns the message as &str slice or an error if it has bytes that are not valid UTF-8.
+    pub fn try_as_str(&self) -> Result<&str, Error> {
+        core::str::from_utf8(self.as_chars()).map_err(|_| Error::InvalidUtf8)
+    }
+}
+
+impl<const MSG_MAX: usize, S: MessageStorage<char>> Message<MSG_MAX, char, S> {
+    /// Returns the message as it is now as a [Utf8Charray].
+    ///
+    /// Note that this *does not* include empty [FILLER](crate::FILLER) characters.
+    pub fn as_str(&self) -> Utf8Charray<'_> {
         // Fixme: Update the code to use buffer copy,
         // after const generic expressions become stable in Rust.
         // https://github.com/rust-lang/rust/issues/76560
@@ -345,29 +718,203 @@ impl<const MSG_MAX: usize> Message<MSG_MAX> {
         //
         // core::str::from_utf8(buffer[0..pos].asref()).unwrap()
 
-        Utf8Charray(self.chars[..self.len()].as_ref())
+        Utf8Charray(self.as_chars())
     }
+}
 
-    /// Clear the message and start over.
-    pub fn clear(&mut self) {
-        self.chars = [FILLER; MSG_MAX];
-        self.edit_pos = 0;
+/// Message can be displayed directly without the FILLER characters at the end.
+///
+/// This also means `message.to_string()` is available with "std" feature.
+impl<const MSG_MAX: usize, C: MorseCharacter, S: MessageStorage<C>> Display for Message<MSG_MAX, C, S> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for ch in self.iter() {
+            write!(f, "{}", ch.to_char())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<const MSG_MAX: usize, C: MorseCharacter> Message<MSG_MAX, C> {
+    /// Get an instance of Message from a [heapless::String].
+    ///
+    /// Works the same as `new` with an &str.
+    pub fn from_heapless<const N: usize>(
+        message_string: &heapless::String<N>,
+        edit_pos_end: bool,
+        clamp_edit_pos: bool,
+    ) -> Self {
+        Self::new(message_string.as_str(), edit_pos_end, clamp_edit_pos)
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<const MSG_MAX: usize, C: MorseCharacter, S: MessageStorage<C>> Message<MSG_MAX, C, S> {
+    /// Returns the message copied into a [heapless::String] with a capacity of N bytes.
+    ///
+    /// Empty [FILLER](crate::FILLER) characters at the end are not included.
+    /// Returns [Error::MessageTooLong] if the message doesn't fit into the string.
+    pub fn to_heapless_string<const N: usize>(&self) -> Result<heapless::String<N>, Error> {
+        let mut string = heapless::String::new();
+
+        for ch in self.iter() {
+            string.push(ch.to_char())
+                .map_err(|_| Error::MessageTooLong)?;
+        }
+
+        Ok(string)
+    }
+}
+
+/// Get a message from an &str with the editing position at the end of it.
+///
+/// Returns [Error::MessageTooLong] if the text has more characters than MSG_MAX.
+/// Characters that can't be represented by the character type are skipped like `new` does.
+impl<const MSG_MAX: usize, C: MorseCharacter> TryFrom<&str> for Message<MSG_MAX, C> {
+    type Error = Error;
+
+    fn try_from(message_str: &str) -> Result<Self, Self::Error> {
+        if message_str.chars().filter_map(C::from_char).count() > MSG_MAX {
+            return Err(Error::MessageTooLong);
+        }
+
+        Ok(Self::new(message_str, true, false))
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<const N: usize, const MSG_MAX: usize, C: MorseCharacter, S: MessageStorage<C>> TryFrom<&Message<MSG_MAX, C, S>> for heapless::String<N> {
+    type Error = Error;
+
+    /// Same as `to_heapless_string`.
+    fn try_from(message: &Message<MSG_MAX, C, S>) -> Result<Self, Self::Error> {
+        message.to_heapless_string()
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<const N: usize, const MSG_MAX: usize, C: MorseCharacter> TryFrom<Message<MSG_MAX, C>> for heapless::String<N> {
+    type Error = Error;
+
+    fn try_from(message: Message<MSG_MAX, C>) -> Result<Self, Self::Error> {
+        message.to_heapless_string()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<const MSG_MAX: usize, C: MorseCharacter, S: MessageStorage<C>> From<&Message<MSG_MAX, C, S>> for String {
+    /// Message without the FILLER characters at the end.
+    fn from(message: &Message<MSG_MAX, C, S>) -> Self {
+        message.iter().map(|ch| ch.to_char()).collect()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<const MSG_MAX: usize, C: MorseCharacter> From<Message<MSG_MAX, C>> for String {
+    fn from(message: Message<MSG_MAX, C>) -> Self {
+        Self::from(&message)
+    }
+}
+
+/// Index the message like a slice, ie. `message[0]` or `message[1..3]`.
+///
+/// Single characters can be read up to the capacity like `char_at` does, but reading the FILLERs past the end of
+/// the message panics with debug assertions enabled. Ranges index the characters of the message
+/// without the FILLERs at the end, so `&message[..]` is all of the message.
+impl<const MSG_MAX: usize, C: MorseCharacter, S: MessageStorage<C>> Index<usize> for Message<MSG_MAX, C, S> {
+    type Output = C;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        debug_assert!(index < self.len(), "Message index {} is past the end of the message", index);
+
+        &self.chars.as_slice()[index]
+    }
+}
+
+/// Change a character of the message with `message[index] = ch`.
+///
+/// Index can be anywhere in the message or right after its end to append a character.
+/// Indexes further than that would leave FILLERs in the message, so they panic with debug
+/// assertions enabled. Use `put_char_at` to have the FILLERs before the index converted to spaces.
+impl<const MSG_MAX: usize, C: MorseCharacter, S: MessageStorage<C>> IndexMut<usize> for Message<MSG_MAX, C, S> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        debug_assert!(index <= self.len(), "Message index {} leaves a gap after the end of the message", index);
+
+        self.last_change_index = index;
+        self.touch();
+
+        &mut self.chars.as_mut_slice()[index]
+    }
+}
+
+impl<const MSG_MAX: usize, C: MorseCharacter, S: MessageStorage<C>> Index<Range<usize>> for Message<MSG_MAX, C, S> {
+    type Output = [C];
+
+    fn index(&self, range: Range<usize>) -> &Self::Output {
+        &self.as_chars()[range]
+    }
+}
+
+impl<const MSG_MAX: usize, C: MorseCharacter, S: MessageStorage<C>> Index<RangeFrom<usize>> for Message<MSG_MAX, C, S> {
+    type Output = [C];
+
+    fn index(&self, range: RangeFrom<usize>) -> &Self::Output {
+        &self.as_chars()[range]
+    }
+}
+
+impl<const MSG_MAX: usize, C: MorseCharacter, S: MessageStorage<C>> Index<RangeTo<usize>> for Message<MSG_MAX, C, S> {
+    type Output = [C];
+
+    fn index(&self, range: RangeTo<usize>) -> &Self::Output {
+        &self.as_chars()[range]
+    }
+}
+
+impl<const MSG_MAX: usize, C: MorseCharacter, S: MessageStorage<C>> Index<RangeInclusive<usize>> for Message<MSG_MAX, C, S> {
+    type Output = [C];
+
+    fn index(&self, range: RangeInclusive<usize>) -> &Self::Output {
+        &self.as_chars()[range]
+    }
+}
+
+impl<const MSG_MAX: usize, C: MorseCharacter, S: MessageStorage<C>> Index<RangeToInclusive<usize>> for Message<MSG_MAX, C, S> {
+    type Output = [C];
+
+    fn index(&self, range: RangeToInclusive<usize>) -> &Self::Output {
+        &self.as_chars()[range]
+    }
+}
+
+impl<const MSG_MAX: usize, C: MorseCharacter, S: MessageStorage<C>> Index<RangeFull> for Message<MSG_MAX, C, S> {
+    type Output = [C];
+
+    fn index(&self, _range: RangeFull) -> &Self::Output {
+        self.as_chars()
     }
 }
 
 /// Message iterator provides a convenient way to iterate over
 /// message characters. This doesn't include empty FILLER chars.
-pub struct MessageIterator<'a, const MSG_MAX: usize> {
-    message: &'a Message<MSG_MAX>,
+///
+/// It can be iterated from both ends and knows its length, so adapters like
+/// `rev` and `len` work as they do with slice iterators.
+#[derive(Clone, Debug)]
+pub struct MessageIterator<'a, const MSG_MAX: usize, C: MorseCharacter = Character> {
+    chars: &'a [C],
     index: usize,
+    // Index right after the last character not iterated from the back yet
+    end: usize,
 }
 
-impl<'a, const MSG_MAX: usize> Iterator for MessageIterator<'a, MSG_MAX> {
-    type Item = &'a Character;
+impl<'a, const MSG_MAX: usize, C: MorseCharacter> Iterator for MessageIterator<'a, MSG_MAX, C> {
+    type Item = &'a C;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.index < self.message.len() {
-            let result = Some(&self.message.chars[self.index]);
+        if self.index < self.end {
+            let result = Some(&self.chars[self.index]);
             self.index += 1;
 
             result
@@ -375,4 +922,35 @@ impl<'a, const MSG_MAX: usize> Iterator for MessageIterator<'a, MSG_MAX> {
             None
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end - self.index;
+
+        (remaining, Some(remaining))
+    }
+}
+
+impl<const MSG_MAX: usize, C: MorseCharacter> DoubleEndedIterator for MessageIterator<'_, MSG_MAX, C> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.index < self.end {
+            self.end -= 1;
+
+            Some(&self.chars[self.end])
+        } else {
+            None
+        }
+    }
+}
+
+impl<const MSG_MAX: usize, C: MorseCharacter> ExactSizeIterator for MessageIterator<'_, MSG_MAX, C> {}
+
+impl<const MSG_MAX: usize, C: MorseCharacter> FusedIterator for MessageIterator<'_, MSG_MAX, C> {}
+
+impl<'a, const MSG_MAX: usize, C: MorseCharacter, S: MessageStorage<C>> IntoIterator for &'a Message<MSG_MAX, C, S> {
+    type Item = &'a C;
+    type IntoIter = MessageIterator<'a, MSG_MAX, C>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
 }
@@ -0,0 +1,116 @@
+//! Log of completed messages for monitoring applications.
+//!
+//! A [Message] has a constant maximum length, so a monitoring application decoding
+//! traffic for hours has to clear it at some point, destroying what was decoded so far.
+//! [MessageLog] keeps the last N completed messages along with the time they were completed.
+//! When the log is full, the oldest message is dropped to make room for the new one.
+//!
+//! There's no clock in no_std environments, so timestamps are provided by the client code.
+//! They can be milliseconds since boot, a unix timestamp or whatever makes sense for the application.
+//!
+//! ```rust
+//! use morse_codec::{
+//!     decoder::Decoder,
+//!     message_log::MessageLog,
+//! };
+//!
+//! let mut decoder = Decoder::<16>::new().with_reference_short_ms(100).build();
+//! let mut log = MessageLog::<4, 16>::new();
+//!
+//! // "E" followed by a long silence
+//! decoder.signal_event(100, true);
+//! decoder.signal_event(300, false);
+//!
+//! // Client code decides the transmission is over after some idle time
+//! decoder.finish_message(&mut log, 1000);
+//!
+//! assert!(decoder.message.is_empty());
+//! assert_eq!(log.latest().unwrap().1.as_str(), "E");
+//! ```
+
+use crate::{
+    message::Message,
+    Character,
+    MorseCharacter,
+};
+
+/// A fixed capacity ring buffer of completed messages with timestamps.
+///
+/// N is the number of messages the log can hold, MSG_MAX is the maximum length of each message.
+#[derive(Clone, Debug)]
+pub struct MessageLog<const N: usize, const MSG_MAX: usize, C: MorseCharacter = Character> {
+    messages: [Message<MSG_MAX, C>; N],
+    timestamps: [u32; N],
+    // Index of the next message to be written
+    pos: usize,
+    count: usize,
+}
+
+impl<const N: usize, const MSG_MAX: usize, C: MorseCharacter> Default for MessageLog<N, MSG_MAX, C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize, const MSG_MAX: usize, C: MorseCharacter> MessageLog<N, MSG_MAX, C> {
+    pub fn new() -> Self {
+        Self {
+            messages: core::array::from_fn(|_| Message::default()),
+            timestamps: [0; N],
+            pos: 0,
+            count: 0,
+        }
+    }
+
+    /// Add a completed message to the log with the time it was completed.
+    ///
+    /// If the log is full, the oldest message will be dropped.
+    pub fn push(&mut self, message: Message<MSG_MAX, C>, timestamp: u32) {
+        if N == 0 {
+            return;
+        }
+
+        self.messages[self.pos] = message;
+        self.timestamps[self.pos] = timestamp;
+
+        self.pos = (self.pos + 1) % N;
+        self.count = (self.count + 1).min(N);
+    }
+
+    /// Returns the number of messages in the log.
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    /// Returns true if there are no messages in the log.
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Returns maximum number of messages the log can hold, which is N.
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Get an iterator of (timestamp, message) pairs from the oldest to the latest message.
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &Message<MSG_MAX, C>)> + '_ {
+        let start = (self.pos + N - self.count) % N.max(1);
+
+        (0..self.count).map(move |i| {
+            let index = (start + i) % N;
+
+            (self.timestamps[index], &self.messages[index])
+        })
+    }
+
+    /// Returns the latest message with its timestamp if there's any.
+    pub fn latest(&self) -> Option<(u32, &Message<MSG_MAX, C>)> {
+        self.iter().last()
+    }
+
+    /// Remove all messages from the log.
+    pub fn clear(&mut self) {
+        self.pos = 0;
+        self.count = 0;
+    }
+}
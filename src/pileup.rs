@@ -0,0 +1,236 @@
+//! Random stations calling in a pileup, for contest and DX simulation.
+//!
+//! Pileup trainers play many stations calling at once, each with its own callsign,
+//! speed and fist. A [Pileup] makes random [Station]s with plausible callsigns
+//! (prefix, digit and suffix, ie. DL3XYZ), serial numbers, speeds and timing jitter.
+//! A station writes its callsign or exchange into an encoder and sends it out
+//! at its own speed with the jitter of a hand sent signal.
+//!
+//! ```rust
+//! use morse_codec::{
+//!     contest::CutNumbers,
+//!     encoder::Encoder,
+//!     pileup::Pileup,
+//!     recognizer::is_callsign,
+//! };
+//!
+//! let mut pileup = Pileup::new(2024).with_speed_range(22, 30).with_max_jitter(10);
+//! let station = pileup.next_station();
+//! assert!(is_callsign(station.callsign().as_bytes()));
+//! assert!((22..=30).contains(&station.wpm()));
+//!
+//! let mut encoder = Encoder::<32, u8>::new().build();
+//! station.write_to(&mut encoder, "{callsign} {rst} {serial}", CutNumbers::ZeroAndNine).unwrap();
+//! assert!(encoder.message.as_str().starts_with(station.callsign()));
+//!
+//! let durations: Vec<(u32, bool)> = station.durations(&encoder).collect();
+//! assert!(!durations.is_empty());
+//! ```
+
+use crate::{
+    contest::{format_exchange, CutNumbers, Field},
+    encoder::{MorseEncoder, Separator, TimeUnit},
+    lesson::Rng,
+    message::MessageStorage,
+    sink::SignalSink,
+    MorseCharacter,
+};
+
+/// Maximum length of a callsign made by a [Pileup].
+pub const MAX_CALLSIGN_LENGTH: usize = 6;
+
+// Common prefixes around the world, weighted towards busy ones by repeating them
+const PREFIXES: &[&str] = &[
+    "K", "K", "W", "W", "N", "AA", "KA", "VE", "XE", "PY", "LU", "CE", "G", "M", "F", "DL", "DL",
+    "DK", "I", "EA", "CT", "ON", "PA", "OZ", "SM", "LA", "OH", "SP", "OK", "HA", "YO", "LZ", "9A",
+    "UA", "UR", "JA", "JA", "BY", "HL", "VK", "ZL", "ZS", "4X", "OE", "HB", "EI", "YU",
+];
+
+const LETTERS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+// Largest serial number of a station
+const MAX_SERIAL: u32 = 999;
+
+/// A station calling in a pileup.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Station {
+    callsign: [u8; MAX_CALLSIGN_LENGTH],
+    callsign_length: u8,
+    serial: u16,
+    wpm: u16,
+    jitter_percent: u8,
+    jitter_seed: u32,
+}
+
+impl Station {
+    /// Returns the callsign of the station.
+    pub fn callsign(&self) -> &str {
+        // Callsigns are made of ASCII characters, so this can't fail.
+        core::str::from_utf8(&self.callsign[..self.callsign_length as usize]).unwrap_or("")
+    }
+
+    /// Returns the serial number the station sends in its exchange.
+    pub fn serial(&self) -> u16 {
+        self.serial
+    }
+
+    /// Returns the speed the station sends at in words per minute.
+    pub fn wpm(&self) -> u16 {
+        self.wpm
+    }
+
+    /// Returns how many percent longer or shorter the signals of the station can be.
+    pub fn jitter_percent(&self) -> u8 {
+        self.jitter_percent
+    }
+
+    /// Fill a contest exchange template of the station and write it into the buffer.
+    ///
+    /// Placeholders of the template are `{callsign}`, `{rst}` (always 599) and `{serial}`
+    /// (at least three digits). See [format_exchange] for the template and errors.
+    pub fn exchange<'b>(&self, template: &str, cut_numbers: CutNumbers, buffer: &'b mut [u8]) -> Result<&'b str, &'static str> {
+        let fields = [
+            ("callsign", Field::Text(self.callsign())),
+            ("rst", Field::Number { value: 599, width: 3 }),
+            ("serial", Field::Number { value: self.serial as u32, width: 3 }),
+        ];
+
+        format_exchange(template, &fields, cut_numbers, buffer)
+    }
+
+    /// Write the exchange of the station to the end of the encoder message, after a word space.
+    ///
+    /// See `exchange` for the template. Returns an error if the exchange can't be made
+    /// or it doesn't fit into the message.
+    pub fn write_to<const MSG_MAX: usize, C: MorseCharacter, S: MessageStorage<C>>(
+        &self,
+        encoder: &mut MorseEncoder<MSG_MAX, C, S>,
+        template: &str,
+        cut_numbers: CutNumbers,
+    ) -> Result<(), &'static str> {
+        let mut buffer = [0u8; 64];
+        let exchange = self.exchange(template, cut_numbers, &mut buffer)?;
+
+        encoder
+            .append_message(exchange, Separator::Word)
+            .map_err(|_| "Exchange doesn't fit into the message.")
+    }
+
+    /// Get the entire encoded message of the encoder as signal durations in milliseconds
+    /// at the speed of the station, paired with signal highs (true) and lows (false).
+    ///
+    /// Every signal is made longer or shorter by a random amount up to the jitter of the station.
+    /// Durations are the same every time for the same station and message.
+    pub fn durations<'e, const MSG_MAX: usize, C: MorseCharacter, S: MessageStorage<C>>(
+        &self,
+        encoder: &'e MorseEncoder<MSG_MAX, C, S>,
+    ) -> impl Iterator<Item = (u32, bool)> + 'e {
+        let short_ms = 1200 / self.wpm.max(1) as u32;
+        let jitter = self.jitter_percent as u32;
+        let mut rng = Rng::new(self.jitter_seed);
+
+        encoder
+            .signal_durations(short_ms, TimeUnit::Milliseconds)
+            .map(move |(duration_ms, is_high)| {
+                let percent = 100 + rng.below(jitter * 2 + 1) as u64 - jitter as u64;
+
+                ((duration_ms as u64 * percent / 100) as u32, is_high)
+            })
+    }
+
+    /// Send the entire encoded message of the encoder to a sink at the speed and jitter of the station.
+    ///
+    /// See `durations` for the signals.
+    pub fn send_to<const MSG_MAX: usize, C: MorseCharacter, S: MessageStorage<C>>(
+        &self,
+        encoder: &MorseEncoder<MSG_MAX, C, S>,
+        sink: &mut impl SignalSink,
+    ) {
+        for (duration_ms, is_high) in self.durations(encoder) {
+            sink.emit(duration_ms, is_high);
+        }
+    }
+}
+
+/// Maker of random stations.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pileup {
+    rng: Rng,
+    speed_range: (u16, u16),
+    max_jitter_percent: u8,
+}
+
+impl Pileup {
+    /// Create a pileup with a random number generator seed.
+    ///
+    /// Stations send between 18 and 30 WPM with up to 10 percent jitter by default.
+    pub fn new(seed: u32) -> Self {
+        Self {
+            rng: Rng::new(seed),
+            speed_range: (18, 30),
+            max_jitter_percent: 10,
+        }
+    }
+
+    /// Set the slowest and fastest speeds of the stations in words per minute.
+    pub fn with_speed_range(mut self, min_wpm: u16, max_wpm: u16) -> Self {
+        let min_wpm = min_wpm.max(1);
+        self.speed_range = (min_wpm, max_wpm.max(min_wpm));
+
+        self
+    }
+
+    /// Set how many percent longer or shorter the signals of the stations can be, up to 50.
+    ///
+    /// Every station gets a random jitter up to this, so some stations have a neater fist than others.
+    /// 0 makes all stations send perfectly timed signals.
+    pub fn with_max_jitter(mut self, max_jitter_percent: u8) -> Self {
+        self.max_jitter_percent = max_jitter_percent.min(50);
+
+        self
+    }
+
+    /// Make the next random callsign in the buffer and return it.
+    ///
+    /// Callsigns are a common prefix, a digit and a suffix of one to three letters.
+    /// The callsign is cut if the buffer is shorter than [MAX_CALLSIGN_LENGTH].
+    pub fn next_callsign<'b>(&mut self, buffer: &'b mut [u8]) -> &'b str {
+        let prefix = PREFIXES[self.rng.below(PREFIXES.len() as u32) as usize].as_bytes();
+        let digit = b'0' + self.rng.below(10) as u8;
+        // Mostly two and three letter suffixes
+        let suffix_length = [1, 2, 2, 3, 3, 3][self.rng.below(6) as usize];
+
+        let mut callsign = [0u8; MAX_CALLSIGN_LENGTH];
+        callsign[..prefix.len()].copy_from_slice(prefix);
+        callsign[prefix.len()] = digit;
+
+        let mut length = prefix.len() + 1;
+        for _ in 0..suffix_length {
+            callsign[length] = LETTERS[self.rng.below(LETTERS.len() as u32) as usize];
+            length += 1;
+        }
+
+        let length = length.min(buffer.len());
+        buffer[..length].copy_from_slice(&callsign[..length]);
+
+        // Callsigns are made of ASCII characters, so this can't fail.
+        core::str::from_utf8(&buffer[..length]).unwrap_or("")
+    }
+
+    /// Make the next random station.
+    pub fn next_station(&mut self) -> Station {
+        let mut callsign = [0u8; MAX_CALLSIGN_LENGTH];
+        let callsign_length = self.next_callsign(&mut callsign).len() as u8;
+
+        let (min_wpm, max_wpm) = self.speed_range;
+
+        Station {
+            callsign,
+            callsign_length,
+            serial: (self.rng.below(MAX_SERIAL) + 1) as u16,
+            wpm: min_wpm + self.rng.below((max_wpm - min_wpm) as u32 + 1) as u16,
+            jitter_percent: self.rng.below(self.max_jitter_percent as u32 + 1) as u8,
+            jitter_seed: self.rng.next_u32(),
+        }
+    }
+}
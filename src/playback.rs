@@ -0,0 +1,332 @@
+//! Playback cursor for the signals of an encoded message.
+//!
+//! [Player] walks through the SDM signals of an encoder character by character, one signal at a time.
+//! It doesn't deal with time itself, the client code asks for the next signal when
+//! the current one has been played on a buzzer, LED or speaker. Playback can be paused and resumed,
+//! and moved to the start of any character, so a training application can replay a group the user missed
+//! without rebuilding the encoder.
+//!
+//! ```rust
+//! use morse_codec::{
+//!     encoder::{Encoder, SDM},
+//!     playback::Player,
+//! };
+//!
+//! let mut encoder = Encoder::<16>::new().with_message("EE", true).build();
+//! encoder.encode_message_all();
+//!
+//! let mut player = Player::new();
+//! assert_eq!(player.next_signal(&encoder).unwrap().sdm, SDM::High(1));
+//!
+//! player.pause();
+//! assert!(player.next_signal(&encoder).is_none());
+//!
+//! // Replay the first character
+//! player.seek_to_char(0);
+//! player.resume();
+//! assert_eq!(player.position(), 0);
+//! assert_eq!(player.next_signal(&encoder).unwrap().sdm, SDM::High(1));
+//! ```
+//!
+//! [Beacon] plays a message over and over with a gap between repetitions,
+//! as fox hunting and propagation beacons do.
+//!
+//! ```rust
+//! use morse_codec::{
+//!     encoder::{Encoder, SDM},
+//!     playback::{Beacon, BeaconStep},
+//! };
+//!
+//! let mut encoder = Encoder::<16>::new().with_message("E", true).build();
+//! encoder.encode_message_all();
+//!
+//! // Wait 10 seconds between repetitions
+//! let mut beacon = Beacon::new(10_000);
+//!
+//! assert!(matches!(beacon.next_step(&encoder), Some(BeaconStep::Signal(chunk)) if chunk.sdm == SDM::High(1)));
+//! assert!(matches!(beacon.next_step(&encoder), Some(BeaconStep::Signal(chunk)) if chunk.sdm == SDM::Low(3)));
+//! assert_eq!(beacon.next_step(&encoder), Some(BeaconStep::Gap(10_000)));
+//! assert!(matches!(beacon.next_step(&encoder), Some(BeaconStep::Signal(chunk)) if chunk.sdm == SDM::High(1)));
+//! assert_eq!(beacon.repetitions(), 1);
+//! ```
+//!
+//! Repeaters and beacons are required to identify themselves periodically. [IdTimer] interleaves
+//! the signals of a station ID message into any other transmission when the ID is due,
+//! tracking elapsed time from the ticks supplied by the client code.
+//!
+//! ```rust
+//! use morse_codec::{
+//!     encoder::Encoder,
+//!     playback::{IdTimer, Player},
+//! };
+//!
+//! let mut traffic = Encoder::<16>::new().with_message("TEST", true).build();
+//! traffic.encode_message_all();
+//! let mut id = Encoder::<16>::new().with_message("DE K1ABC", true).build();
+//! id.encode_message_all();
+//!
+//! // ID every 10 minutes
+//! let mut timer = IdTimer::new(10 * 60 * 1000);
+//! let mut player = Player::new();
+//!
+//! assert!(timer.next_signal(&mut player, &traffic, &id).is_some());
+//! assert!(!timer.is_sending_id());
+//!
+//! // Client code reports the time passed since the last call
+//! timer.tick(10 * 60 * 1000);
+//! assert!(timer.is_due());
+//!
+//! // ID is sent at the next character boundary of the traffic
+//! while !timer.is_sending_id() {
+//!     timer.next_signal(&mut player, &traffic, &id).unwrap();
+//! }
+//! ```
+
+use crate::{
+    encoder::{MorseEncoder, SdmChunk, SDM},
+    message::MessageStorage,
+    Character,
+    MorseCharacter,
+};
+
+/// Playback position and state over the signals of an encoded message.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Player {
+    char_index: usize,
+    signal_index: usize,
+    paused: bool,
+}
+
+impl Player {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop giving out signals until `resume` is called.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Continue playback from where it was paused.
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    /// Returns true if playback is paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Move playback to the first signal of the character at the message index.
+    pub fn seek_to_char(&mut self, index: usize) {
+        self.char_index = index;
+        self.signal_index = 0;
+    }
+
+    /// Returns the message index of the character being played.
+    pub fn position(&self) -> usize {
+        self.char_index
+    }
+
+    /// Returns true if all signals of the encoded message have been played.
+    pub fn is_finished<const MSG_MAX: usize, C: MorseCharacter, S: MessageStorage<C>>(&self, encoder: &MorseEncoder<MSG_MAX, C, S>) -> bool {
+        self.char_index >= encoder.message.len()
+    }
+
+    // Returns true if there are no signals left to play in the current character.
+    fn at_char_boundary<const MSG_MAX: usize, C: MorseCharacter, S: MessageStorage<C>>(&self, encoder: &MorseEncoder<MSG_MAX, C, S>) -> bool {
+        self.signal_index == 0
+            || encoder
+                .get_encoded_char_as_sdm(self.char_index)
+                .and_then(|sdm_array| sdm_array.get(self.signal_index).copied())
+                .is_none_or(|sdm| sdm == SDM::Empty)
+    }
+
+    /// Get the next signal to play and move playback forward.
+    ///
+    /// Returns None if playback is paused or finished.
+    pub fn next_signal<const MSG_MAX: usize, C: MorseCharacter, S: MessageStorage<C>>(
+        &mut self,
+        encoder: &MorseEncoder<MSG_MAX, C, S>,
+    ) -> Option<SdmChunk<C>> {
+        if self.paused {
+            return None;
+        }
+
+        while let Some(sdm_array) = encoder.get_encoded_char_as_sdm(self.char_index) {
+            match sdm_array.get(self.signal_index) {
+                Some(sdm) if *sdm != SDM::Empty => {
+                    self.signal_index += 1;
+
+                    return Some(SdmChunk {
+                        index: self.char_index,
+                        ch: encoder.message.char_at(self.char_index),
+                        sdm: *sdm,
+                    });
+                }
+                _ => self.seek_to_char(self.char_index + 1),
+            }
+        }
+
+        None
+    }
+}
+
+/// A step of beacon playback.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BeaconStep<C = Character> {
+    /// A signal of the message to play.
+    Signal(SdmChunk<C>),
+    /// Silence between repetitions of the message in milliseconds.
+    /// It comes after the character space at the end of the message.
+    Gap(u32),
+}
+
+/// Plays the encoded message of an encoder repeatedly with a gap between repetitions.
+///
+/// Like [Player], it doesn't deal with time itself. Client code asks for the next step
+/// when the current one has been played.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Beacon {
+    player: Player,
+    gap_ms: u32,
+    repetitions: u32,
+}
+
+impl Beacon {
+    /// Create a beacon waiting `gap_ms` milliseconds between repetitions of the message.
+    pub fn new(gap_ms: u32) -> Self {
+        Self {
+            gap_ms,
+            ..Self::default()
+        }
+    }
+
+    /// Change the gap between repetitions. It takes effect at the next gap.
+    pub fn set_gap_ms(&mut self, gap_ms: u32) {
+        self.gap_ms = gap_ms;
+    }
+
+    /// Returns the gap between repetitions in milliseconds.
+    pub fn gap_ms(&self) -> u32 {
+        self.gap_ms
+    }
+
+    /// Returns how many times the message has been played completely.
+    pub fn repetitions(&self) -> u32 {
+        self.repetitions
+    }
+
+    /// Stop giving out steps until `resume` is called.
+    pub fn pause(&mut self) {
+        self.player.pause();
+    }
+
+    /// Continue playback from where it was paused.
+    pub fn resume(&mut self) {
+        self.player.resume();
+    }
+
+    /// Returns true if playback is paused.
+    pub fn is_paused(&self) -> bool {
+        self.player.is_paused()
+    }
+
+    /// Start over from the beginning of the message and reset the repetition count.
+    pub fn restart(&mut self) {
+        self.player.seek_to_char(0);
+        self.repetitions = 0;
+    }
+
+    /// Get the next step to play.
+    ///
+    /// Returns None if playback is paused or the encoder has no encoded signals to play.
+    pub fn next_step<const MSG_MAX: usize, C: MorseCharacter, S: MessageStorage<C>>(
+        &mut self,
+        encoder: &MorseEncoder<MSG_MAX, C, S>,
+    ) -> Option<BeaconStep<C>> {
+        if let Some(chunk) = self.player.next_signal(encoder) {
+            return Some(BeaconStep::Signal(chunk));
+        }
+
+        if self.player.is_paused() || self.player.position() == 0 {
+            return None;
+        }
+
+        self.player.seek_to_char(0);
+        self.repetitions = self.repetitions.saturating_add(1);
+
+        Some(BeaconStep::Gap(self.gap_ms))
+    }
+}
+
+/// Interleaves a station ID message into a transmission every time an interval passes.
+///
+/// The ID is sent at the first character boundary of the transmission after it's due,
+/// so characters are never cut in half. The interval starts over when the ID is finished.
+/// Put spaces at the start and the end of the ID message to separate it from the
+/// transmission with word spaces.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct IdTimer {
+    interval_ms: u32,
+    elapsed_ms: u32,
+    id_player: Player,
+    sending_id: bool,
+}
+
+impl IdTimer {
+    /// Create an ID timer sending the ID every `interval_ms` milliseconds.
+    pub fn new(interval_ms: u32) -> Self {
+        Self {
+            interval_ms,
+            ..Self::default()
+        }
+    }
+
+    /// Report the time passed since the last call in milliseconds.
+    pub fn tick(&mut self, elapsed_ms: u32) {
+        self.elapsed_ms = self.elapsed_ms.saturating_add(elapsed_ms);
+    }
+
+    /// Returns true if the interval has passed and the ID should be sent.
+    pub fn is_due(&self) -> bool {
+        self.elapsed_ms >= self.interval_ms
+    }
+
+    /// Returns true while the signals of the ID are given out.
+    pub fn is_sending_id(&self) -> bool {
+        self.sending_id
+    }
+
+    /// Get the next signal to play, either from the transmission or from the ID.
+    ///
+    /// `player` is the playback position of the `transmission` encoder. ID is sent when it's due
+    /// and the player is at a character boundary, including the end of the transmission.
+    /// Returns None when there's nothing to play or the player is paused.
+    pub fn next_signal<const MSG_MAX: usize, const ID_MAX: usize, C: MorseCharacter, S: MessageStorage<C>>(
+        &mut self,
+        player: &mut Player,
+        transmission: &MorseEncoder<MSG_MAX, C, S>,
+        id: &MorseEncoder<ID_MAX, C>,
+    ) -> Option<SdmChunk<C>> {
+        if player.is_paused() {
+            return None;
+        }
+
+        if !self.sending_id && self.is_due() && player.at_char_boundary(transmission) {
+            self.sending_id = true;
+            self.id_player.seek_to_char(0);
+        }
+
+        if self.sending_id {
+            if let Some(chunk) = self.id_player.next_signal(id) {
+                return Some(chunk);
+            }
+
+            self.sending_id = false;
+            self.elapsed_ms = 0;
+        }
+
+        player.next_signal(transmission)
+    }
+}
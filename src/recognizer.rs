@@ -0,0 +1,178 @@
+//! Recognizer for common amateur radio patterns in a message.
+//!
+//! Scans a [Message] word by word and reports ham radio callsigns
+//! (ie. K1ABC, 9A1A, DL/K1ABC/P) and RST signal reports (ie. 599, 5NN)
+//! as index spans into the message. There's no regex engine or heap involved,
+//! so embedded loggers can use it as well as CW skimmer style applications.
+//!
+//! ```rust
+//! use morse_codec::{
+//!     message::Message,
+//!     recognizer::{find_patterns, PatternKind},
+//! };
+//!
+//! let message = Message::<32>::new("CQ DE K1ABC UR 5NN", false, false);
+//! let mut patterns = find_patterns(&message);
+//!
+//! let callsign = patterns.next().unwrap();
+//! assert_eq!(callsign.kind, PatternKind::Callsign);
+//! assert_eq!((callsign.start, callsign.end), (6, 11));
+//!
+//! let report = patterns.next().unwrap();
+//! assert_eq!(report.kind, PatternKind::Rst);
+//! assert_eq!((report.start, report.end), (15, 18));
+//!
+//! assert!(patterns.next().is_none());
+//! ```
+
+use crate::{
+    message::Message,
+    MorseCharacter,
+};
+
+const SLASH: char = '/';
+// In CW signal reports N is commonly sent in place of a 9, as in 5NN
+const CUT_NINE: char = 'N';
+
+/// Kind of the pattern found in a message.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PatternKind {
+    Callsign,
+    Rst,
+}
+
+/// Position of a recognized pattern in the message.
+///
+/// `start` is the index of the first character of the pattern and `end` is
+/// the index right after the last one, so `start..end` can be used as a range.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PatternSpan {
+    pub kind: PatternKind,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Iterator over the patterns found in a message. Get one with [find_patterns].
+pub struct PatternIter<'a, C: MorseCharacter> {
+    chars: &'a [C],
+    index: usize,
+}
+
+impl<C: MorseCharacter> Iterator for PatternIter<'_, C> {
+    type Item = PatternSpan;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.index < self.chars.len() {
+            // Skip spaces to the start of the next word
+            if is_separator(&self.chars[self.index]) {
+                self.index += 1;
+                continue;
+            }
+
+            let start = self.index;
+            while self.index < self.chars.len() && !is_separator(&self.chars[self.index]) {
+                self.index += 1;
+            }
+
+            let word = &self.chars[start..self.index];
+            let kind = if is_rst(word) {
+                Some(PatternKind::Rst)
+            } else if is_callsign(word) {
+                Some(PatternKind::Callsign)
+            } else {
+                None
+            };
+
+            if let Some(kind) = kind {
+                return Some(PatternSpan { kind, start, end: self.index });
+            }
+        }
+
+        None
+    }
+}
+
+/// Get an iterator of callsigns and RST reports found in the message
+/// in the order they appear.
+pub fn find_patterns<const MSG_MAX: usize, C: MorseCharacter>(message: &Message<MSG_MAX, C>) -> PatternIter<'_, C> {
+    PatternIter {
+        chars: message.as_chars(),
+        index: 0,
+    }
+}
+
+/// Returns true if the word looks like a ham radio callsign.
+///
+/// A callsign has a prefix of one letter, two letters or a letter and digit combination
+/// (K, DL, 9A, A7) followed by a single digit and a suffix of one to four letters.
+/// Portable designators separated by slashes are also accepted, ie. DL/K1ABC/P.
+/// Characters are expected to be uppercase, as they are in a [Message].
+pub fn is_callsign<C: MorseCharacter>(word: &[C]) -> bool {
+    let mut base_found = false;
+
+    for (index, part) in word.split(|ch| ch.to_char() == SLASH).enumerate() {
+        if index > 2 || part.is_empty() {
+            return false;
+        }
+
+        if !base_found && is_base_callsign(part) {
+            base_found = true;
+        } else if part.len() > 4 || !part.iter().all(|ch| ch.to_char().is_ascii_alphanumeric()) {
+            return false;
+        }
+    }
+
+    base_found
+}
+
+/// Returns true if the word is a three digit RST signal report.
+///
+/// Readability is between 1 and 5, strength and tone are between 1 and 9.
+/// Cut number N is accepted in place of 9, so 5NN is a valid report.
+pub fn is_rst<C: MorseCharacter>(word: &[C]) -> bool {
+    let is_report_digit = |ch: &C| {
+        ch.to_char() == CUT_NINE || ('1'..='9').contains(&ch.to_char())
+    };
+
+    match word {
+        [readability, strength, tone] => {
+            ('1'..='5').contains(&readability.to_char())
+                && is_report_digit(strength)
+                && is_report_digit(tone)
+        }
+        _ => false,
+    }
+}
+
+fn is_separator<C: MorseCharacter>(ch: &C) -> bool {
+    *ch == C::from_ascii(b' ') || *ch == C::FILLER
+}
+
+fn is_base_callsign<C: MorseCharacter>(part: &[C]) -> bool {
+    [1, 2].into_iter().any(|prefix_len| {
+        // We need at least a digit and a single letter suffix after the prefix
+        if part.len() < prefix_len + 2 {
+            return false;
+        }
+
+        let (prefix, rest) = part.split_at(prefix_len);
+        let prefix_ok = match prefix {
+            [p] => p.to_char().is_ascii_uppercase(),
+            [p1, p2] => {
+                let (p1, p2) = (p1.to_char(), p2.to_char());
+
+                p1.is_ascii_alphanumeric()
+                    && p2.is_ascii_alphanumeric()
+                    && (p1.is_ascii_uppercase() || p2.is_ascii_uppercase())
+            }
+            _ => false,
+        };
+
+        let suffix = &rest[1..];
+
+        prefix_ok
+            && rest[0].to_char().is_ascii_digit()
+            && suffix.len() <= 4
+            && suffix.iter().all(|ch| ch.to_char().is_ascii_uppercase())
+    })
+}
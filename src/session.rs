@@ -0,0 +1,268 @@
+//! Two operators taking turns on the same channel.
+//!
+//! A QSO goes back and forth: one side sends an over while the other listens, then they swap.
+//! Operators with full break-in (QSK) hear the channel between their own signals,
+//! so the other side can interrupt an over by keying. [Session] pairs an encoder for the local
+//! operator with a decoder for the remote one, keeps track of whose turn it is, notices break-ins
+//! and writes both sides of the QSO into a transcript. That's all a practice QSO simulator needs
+//! besides the audio.
+//!
+//! Like [Player], it doesn't deal with time itself. Client code plays the signals of the local over
+//! one at a time with `next_duration` and sends the signals heard from the remote side with `signal_event`.
+//!
+//! ```rust
+//! use morse_codec::{
+//!     decoder::{Decoder, Precision},
+//!     encoder::Encoder,
+//!     session::{Session, Turn},
+//! };
+//!
+//! let decoder = Decoder::<16, u8>::new()
+//!     .with_precision(Precision::Accurate)
+//!     .with_reference_short_ms(60)
+//!     .build();
+//! let encoder = Encoder::<16, u8>::new().build();
+//!
+//! // Send at 20 WPM, a dit is 60 ms
+//! let mut session = Session::<16, 64, u8>::new(encoder, decoder, 60);
+//!
+//! session.send("EE").unwrap();
+//! assert_eq!(session.turn(), Turn::Sending);
+//! assert_eq!(session.next_duration(), Some((60, true)));
+//!
+//! // Remote operator breaks in with a T
+//! session.signal_event(180, true);
+//! assert_eq!(session.turn(), Turn::Receiving);
+//! assert!(session.is_broken_in());
+//!
+//! session.signal_event(180, false);
+//! session.end_over();
+//! assert_eq!(session.turn(), Turn::Idle);
+//!
+//! // Only the first E was sent before the break-in
+//! assert_eq!(session.transcript().as_str(), "E T");
+//! ```
+
+use crate::{
+    decoder::{DecodeEvent, MorseDecoder, RejectReason},
+    encoder::{MorseEncoder, SDM},
+    message::Message,
+    playback::Player,
+    Character,
+    MorseCharacter,
+};
+
+/// Whose turn it is in a [Session].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Turn {
+    /// Nobody is keying.
+    #[default]
+    Idle,
+    /// Local operator is sending an over with the encoder.
+    Sending,
+    /// Remote operator is sending an over to the decoder.
+    Receiving,
+}
+
+/// An encoder for the local operator and a decoder for the remote one, taking turns.
+///
+/// MSG_MAX is the maximum length of an over, TRANSCRIPT_MAX is the maximum length of the transcript.
+#[derive(Clone, Debug)]
+pub struct Session<const MSG_MAX: usize, const TRANSCRIPT_MAX: usize, C: MorseCharacter = Character> {
+    encoder: MorseEncoder<MSG_MAX, C>,
+    decoder: MorseDecoder<MSG_MAX, C>,
+    transcript: Message<TRANSCRIPT_MAX, C>,
+    player: Player,
+    // Number of characters with signals given out of the local over
+    sent: usize,
+    short_ms: u32,
+    turn: Turn,
+    is_broken_in: bool,
+}
+
+impl<const MSG_MAX: usize, const TRANSCRIPT_MAX: usize, C: MorseCharacter> Session<MSG_MAX, TRANSCRIPT_MAX, C> {
+    /// Create a session from a built encoder and decoder, sending with a short signal duration
+    /// of `short_ms` milliseconds.
+    ///
+    /// Messages of the encoder and the decoder are cleared, they hold the current overs.
+    pub fn new(mut encoder: MorseEncoder<MSG_MAX, C>, mut decoder: MorseDecoder<MSG_MAX, C>, short_ms: u32) -> Self {
+        encoder.message.clear();
+        decoder.message.clear();
+
+        Self {
+            encoder,
+            decoder,
+            transcript: Message::default(),
+            player: Player::new(),
+            sent: 0,
+            short_ms,
+            turn: Turn::Idle,
+            is_broken_in: false,
+        }
+    }
+
+    /// Returns whose turn it is.
+    pub fn turn(&self) -> Turn {
+        self.turn
+    }
+
+    /// Returns true if the last over of the local operator was interrupted by the remote operator.
+    pub fn is_broken_in(&self) -> bool {
+        self.is_broken_in
+    }
+
+    /// Returns both sides of the QSO so far, overs separated by word spaces.
+    ///
+    /// Overs that don't fit into the transcript are cut.
+    pub fn transcript(&self) -> &Message<TRANSCRIPT_MAX, C> {
+        &self.transcript
+    }
+
+    /// Returns the transcript for editing, ie. to clear it.
+    pub fn transcript_mut(&mut self) -> &mut Message<TRANSCRIPT_MAX, C> {
+        &mut self.transcript
+    }
+
+    /// Returns the encoder of the local operator. Its message is the over being sent.
+    pub fn encoder(&mut self) -> &mut MorseEncoder<MSG_MAX, C> {
+        &mut self.encoder
+    }
+
+    /// Returns the decoder of the remote operator. Its message is the over being received.
+    pub fn decoder(&mut self) -> &mut MorseDecoder<MSG_MAX, C> {
+        &mut self.decoder
+    }
+
+    /// Change the sending speed. It takes effect at the next signal.
+    pub fn set_short_ms(&mut self, short_ms: u32) {
+        self.short_ms = short_ms;
+    }
+
+    /// Start sending an over of the local operator.
+    ///
+    /// An over being received is ended first, see `end_over`.
+    /// Returns an error if the local operator is sending already,
+    /// or the text can't be encoded. See `encode_slice` of the encoder for the latter.
+    pub fn send(&mut self, text: &str) -> Result<(), &'static str> {
+        match self.turn {
+            Turn::Sending => return Err("Local operator is sending already."),
+            Turn::Receiving => self.end_over(),
+            Turn::Idle => (),
+        }
+
+        self.encoder.message.clear();
+        if self.encoder.encode_slice(text).is_err() {
+            self.encoder.message.clear();
+
+            return Err("Over can't be encoded.");
+        }
+
+        self.player = Player::new();
+        self.sent = 0;
+        self.turn = Turn::Sending;
+        self.is_broken_in = false;
+
+        Ok(())
+    }
+
+    /// Get the duration in milliseconds and the level of the next signal of the local over.
+    ///
+    /// Returns None if the local operator isn't sending. After the last signal
+    /// the over is written to the transcript and the turn goes idle.
+    pub fn next_duration(&mut self) -> Option<(u32, bool)> {
+        if self.turn != Turn::Sending {
+            return None;
+        }
+
+        while let Some(chunk) = self.player.next_signal(&self.encoder) {
+            match chunk.sdm {
+                SDM::High(multiplier) => {
+                    self.sent = chunk.index + 1;
+
+                    return Some((multiplier as u32 * self.short_ms, true));
+                }
+                SDM::Low(multiplier) => return Some((multiplier as u32 * self.short_ms, false)),
+                SDM::Empty => (),
+            }
+        }
+
+        self.end_sending();
+
+        None
+    }
+
+    /// Send a signal event heard from the remote operator to the decoder.
+    ///
+    /// A high signal while the local operator is sending is a break-in. The local over is cut
+    /// after the characters started so far and written to the transcript, and it's the remote operator's turn.
+    /// Low signals while the local operator is sending are the gaps of the local signals, so they're rejected.
+    /// When the decoder ends the transmission, the over is ended, see `with_transmission_gap` of the decoder.
+    ///
+    /// Returns what the decoder did with the signal event, see `signal_event_ex` of the decoder.
+    pub fn signal_event(&mut self, duration_ms: u16, is_high: bool) -> DecodeEvent<C> {
+        match (self.turn, is_high) {
+            (Turn::Sending, false) => return DecodeEvent::Rejected(RejectReason::IdleLow),
+            (Turn::Sending, true) => {
+                self.end_sending();
+                self.is_broken_in = true;
+                self.turn = Turn::Receiving;
+            }
+            (Turn::Idle, true) => self.turn = Turn::Receiving,
+            _ => (),
+        }
+
+        let event = self.decoder.signal_event_ex(duration_ms, is_high);
+        if event == DecodeEvent::TransmissionEnded {
+            self.end_over();
+        }
+
+        event
+    }
+
+    /// End the over of the remote operator, ie. when the channel has been quiet long enough.
+    ///
+    /// The character being decoded is ended, the over is written to the transcript
+    /// and the turn goes idle. Does nothing if the remote operator isn't sending.
+    pub fn end_over(&mut self) {
+        if self.turn != Turn::Receiving {
+            return;
+        }
+
+        self.decoder.signal_event_end(false);
+
+        let over = &self.decoder.message;
+        Self::write_transcript(&mut self.transcript, (0..over.len()).map(|index| over.char_at(index)));
+
+        self.decoder.message.clear();
+        self.turn = Turn::Idle;
+    }
+
+    // Write the sent characters of the local over to the transcript and go idle
+    fn end_sending(&mut self) {
+        let over = &self.encoder.message;
+        Self::write_transcript(&mut self.transcript, (0..self.sent).map(|index| over.char_at(index)));
+
+        self.turn = Turn::Idle;
+    }
+
+    // Add an over to the end of the transcript after a word space, without its spaces at the ends
+    fn write_transcript(transcript: &mut Message<TRANSCRIPT_MAX, C>, over: impl Iterator<Item = C>) {
+        let space = C::from_ascii(b' ');
+        let mut spaces = usize::from(!transcript.is_empty());
+        let mut is_started = false;
+
+        for ch in over {
+            if ch == space {
+                spaces += usize::from(is_started);
+                continue;
+            }
+
+            for ch in core::iter::repeat_n(space, spaces).chain([ch]) {
+                let _ = transcript.put_char_at(transcript.len(), ch);
+            }
+
+            spaces = 0;
+            is_started = true;
+        }
+    }
+}
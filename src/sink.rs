@@ -0,0 +1,138 @@
+//! Sinks of signal events for the encoder.
+//!
+//! Outputs such as keyed GPIO pins, tone generators and recording buffers all take
+//! a sequence of (duration in milliseconds, is_high) signal events. [SignalSink] is the common
+//! interface of them, so output backends can be swapped without touching the encoding side
+//! and an encoder sends its message to any of them with `send_to`.
+//!
+//! ```rust
+//! use morse_codec::{
+//!     encoder::Encoder,
+//!     sink::BufferSink,
+//! };
+//!
+//! let mut encoder = Encoder::<16>::new().with_message("A", true).build();
+//! encoder.encode_message_all();
+//!
+//! let mut buffer = [(0, false); 8];
+//! let mut sink = BufferSink::new(&mut buffer);
+//! encoder.send_to(&mut sink);
+//!
+//! // At 20 WPM a dit is 60 ms
+//! assert_eq!(sink.as_slice(), [(60, true), (60, false), (180, true), (180, false)]);
+//! ```
+
+/// Anything that takes signal events one at a time.
+pub trait SignalSink {
+    /// Output a signal of `duration_ms` milliseconds, high (key down) or low (key up).
+    fn emit(&mut self, duration_ms: u32, is_high: bool);
+}
+
+/// Signal events written into a slice, ie. for playing back later or comparing in tests.
+///
+/// Events that don't fit into the slice are dropped and counted.
+#[derive(Debug)]
+pub struct BufferSink<'a> {
+    buffer: &'a mut [(u32, bool)],
+    length: usize,
+    dropped: usize,
+}
+
+impl<'a> BufferSink<'a> {
+    pub fn new(buffer: &'a mut [(u32, bool)]) -> Self {
+        Self { buffer, length: 0, dropped: 0 }
+    }
+
+    /// Returns the signal events written so far.
+    pub fn as_slice(&self) -> &[(u32, bool)] {
+        &self.buffer[..self.length]
+    }
+
+    /// Returns the number of signal events that didn't fit into the buffer.
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    /// Start writing from the beginning of the buffer again.
+    pub fn clear(&mut self) {
+        self.length = 0;
+        self.dropped = 0;
+    }
+}
+
+impl SignalSink for BufferSink<'_> {
+    fn emit(&mut self, duration_ms: u32, is_high: bool) {
+        match self.buffer.get_mut(self.length) {
+            Some(slot) => {
+                *slot = (duration_ms, is_high);
+                self.length += 1;
+            }
+            None => self.dropped += 1,
+        }
+    }
+}
+
+/// Signal events pushed to the end of a vector.
+#[cfg(feature = "std")]
+impl SignalSink for std::vec::Vec<(u32, bool)> {
+    fn emit(&mut self, duration_ms: u32, is_high: bool) {
+        self.push((duration_ms, is_high));
+    }
+}
+
+/// Signal events keyed on an output pin, ie. an LED or the key line of a transmitter.
+///
+/// `set_level` sets the pin high or low and `delay_ms` blocks for the duration of the signal.
+///
+/// ```ignore
+/// let mut sink = PinSink::new(|is_high| led_pin.set_state(is_high.into()).unwrap(), |ms| delay.delay_ms(ms));
+/// encoder.send_to(&mut sink);
+/// ```
+#[derive(Clone, Debug)]
+pub struct PinSink<F: FnMut(bool), D: FnMut(u32)> {
+    set_level: F,
+    delay_ms: D,
+}
+
+impl<F: FnMut(bool), D: FnMut(u32)> PinSink<F, D> {
+    pub fn new(set_level: F, delay_ms: D) -> Self {
+        Self { set_level, delay_ms }
+    }
+}
+
+impl<F: FnMut(bool), D: FnMut(u32)> SignalSink for PinSink<F, D> {
+    fn emit(&mut self, duration_ms: u32, is_high: bool) {
+        (self.set_level)(is_high);
+        (self.delay_ms)(duration_ms);
+    }
+}
+
+/// Signal events played as a tone, ie. on a synthesizer or a PWM driven buzzer.
+///
+/// `set_tone` starts a tone of the given frequency in Hz during high signals and is given 0
+/// to silence it during low signals. `delay_ms` blocks for the duration of the signal.
+/// See [Buzzer](crate::buzzer::Buzzer) for the PWM settings of a tone frequency.
+///
+/// ```ignore
+/// let mut sink = ToneSink::new(600, |hz| synth.set_frequency(hz), |ms| delay.delay_ms(ms));
+/// encoder.send_to(&mut sink);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ToneSink<F: FnMut(u32), D: FnMut(u32)> {
+    tone_hz: u32,
+    set_tone: F,
+    delay_ms: D,
+}
+
+impl<F: FnMut(u32), D: FnMut(u32)> ToneSink<F, D> {
+    pub fn new(tone_hz: u32, set_tone: F, delay_ms: D) -> Self {
+        Self { tone_hz, set_tone, delay_ms }
+    }
+}
+
+impl<F: FnMut(u32), D: FnMut(u32)> SignalSink for ToneSink<F, D> {
+    fn emit(&mut self, duration_ms: u32, is_high: bool) {
+        (self.set_tone)(if is_high { self.tone_hz } else { 0 });
+        (self.delay_ms)(duration_ms);
+    }
+}
@@ -0,0 +1,114 @@
+//! Detection of the SOS distress prosign straight from raw signals.
+//!
+//! Distress beacons and safety gadgets must not miss an SOS. It's sent as a continuous prosign,
+//! ...---... without character spaces, so the regular decoding path sees one long character.
+//! If the reference short duration is far off, it might not decode it at all.
+//! [SosDetector] doesn't use a reference short duration. It keeps the last nine
+//! high signals and the low signals between them, and compares them with each other:
+//! three short, three long and three short high signals, with every low signal between them
+//! shorter than a character space.
+//!
+//! It can be used on its own, or in the decoder with `with_sos_detection`,
+//! which reports the SOS as a high priority event.
+//!
+//! ```rust
+//! use morse_codec::sos::SosDetector;
+//!
+//! let mut detector = SosDetector::new();
+//!
+//! // SOS at any speed, here a dit is around 40 ms
+//! let dits = [(40, true), (40, false), (45, true), (35, false), (40, true), (40, false)];
+//! let dahs = [(120, true), (40, false), (130, true), (40, false), (125, true), (40, false)];
+//!
+//! let detections = dits
+//!     .iter()
+//!     .chain(dahs.iter())
+//!     .chain(dits.iter())
+//!     .filter(|&&(duration_ms, is_high)| detector.signal_event(duration_ms, is_high))
+//!     .count();
+//!
+//! assert_eq!(detections, 1);
+//! ```
+
+/// Number of high signals of the SOS prosign.
+const SOS_LENGTH: usize = 9;
+
+// Long signals are at least this many times longer than short ones
+const MIN_LONG_RATIO: u32 = 2;
+
+/// Watches signal events for the continuous SOS prosign, regardless of the speed.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SosDetector {
+    // Last high signals, oldest first, each with the low signals after it
+    highs: [u16; SOS_LENGTH],
+    lows: [u16; SOS_LENGTH],
+    count: usize,
+}
+
+impl SosDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Send a signal event to the detector, same as `signal_event` of the decoder.
+    ///
+    /// Returns true if the signal completes an SOS. It's the last dit of it, so the SOS is detected
+    /// without waiting for the space after it. Signals of a detected SOS don't count towards the next one.
+    pub fn signal_event(&mut self, duration_ms: u16, is_high: bool) -> bool {
+        if !is_high {
+            // Split low signals are added up, lows before the first high don't matter
+            if self.count > 0 {
+                let low = &mut self.lows[self.count - 1];
+                *low = low.saturating_add(duration_ms);
+            }
+
+            return false;
+        }
+
+        if self.count == SOS_LENGTH {
+            self.highs.rotate_left(1);
+            self.lows.rotate_left(1);
+            self.count -= 1;
+        }
+
+        self.highs[self.count] = duration_ms;
+        self.lows[self.count] = 0;
+        self.count += 1;
+
+        if self.count == SOS_LENGTH && self.is_sos() {
+            self.reset();
+
+            return true;
+        }
+
+        false
+    }
+
+    /// Forget the signals received so far.
+    pub fn reset(&mut self) {
+        self.count = 0;
+    }
+
+    fn is_sos(&self) -> bool {
+        let shortest = self.highs.iter().copied().min().unwrap_or(0) as u32;
+        let longest = self.highs.iter().copied().max().unwrap_or(0) as u32;
+
+        if shortest == 0 || longest < shortest * MIN_LONG_RATIO {
+            return false;
+        }
+
+        // Halfway between a dit and a dah is about two shorts, character spaces are three
+        let split_ms = (shortest + longest) / 2;
+
+        let signals_match = self.highs
+            .iter()
+            .enumerate()
+            .all(|(index, &high)| (high as u32 > split_ms) == (3..6).contains(&index));
+
+        let spaces_match = self.lows[..SOS_LENGTH - 1]
+            .iter()
+            .all(|&low| (low as u32) < split_ms);
+
+        signals_match && spaces_match
+    }
+}
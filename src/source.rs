@@ -0,0 +1,130 @@
+//! Sources of signal events for the decoder.
+//!
+//! Inputs such as recorded sessions, channels of another thread and GPIO pins all come down
+//! to a sequence of (duration in milliseconds, is_high) signal events. [SignalSource] is the common
+//! interface of them, so a front-end written once can feed any decoder with `drain`.
+//!
+//! ```rust
+//! use morse_codec::{
+//!     decoder::{Decoder, Precision},
+//!     source::SliceSource,
+//! };
+//!
+//! let mut decoder = Decoder::<16>::new()
+//!     .with_precision(Precision::Accurate)
+//!     .with_reference_short_ms(100)
+//!     .build();
+//!
+//! let mut source = SliceSource::new(&[(100, true), (100, false), (300, true), (300, false)]);
+//! decoder.drain(&mut source);
+//!
+//! assert_eq!(decoder.message.as_str(), "A");
+//! ```
+
+/// Anything that gives out signal events one at a time.
+pub trait SignalSource {
+    /// Returns the next signal event as (duration in milliseconds, is_high),
+    /// or None if there are no more events for now.
+    fn next_event(&mut self) -> Option<(u16, bool)>;
+}
+
+/// Signal events from a slice, ie. a recording or a test fixture.
+#[derive(Clone, Debug)]
+pub struct SliceSource<'a> {
+    signals: &'a [(u16, bool)],
+    position: usize,
+}
+
+impl<'a> SliceSource<'a> {
+    pub fn new(signals: &'a [(u16, bool)]) -> Self {
+        Self { signals, position: 0 }
+    }
+}
+
+impl SignalSource for SliceSource<'_> {
+    fn next_event(&mut self) -> Option<(u16, bool)> {
+        let event = self.signals.get(self.position).copied()?;
+        self.position += 1;
+
+        Some(event)
+    }
+}
+
+/// Signal events sent from another thread. Waits for each event and runs out when all senders are dropped.
+#[cfg(feature = "std")]
+impl SignalSource for std::sync::mpsc::Receiver<(u16, bool)> {
+    fn next_event(&mut self) -> Option<(u16, bool)> {
+        self.recv().ok()
+    }
+}
+
+/// Signal events measured by sampling an input pin at a fixed period.
+///
+/// `read` is called once every sample period and returns the level of the pin, true when the key is down.
+/// It's up to the function to wait for the period, ie. for a timer tick, before reading the pin.
+/// A signal event is given out when the level changes. When the pin stays low for `idle_ms`,
+/// the low signal so far is given out and the source runs out, so `drain` returns while the key is idle.
+/// The next call starts sampling again.
+///
+/// ```ignore
+/// let mut source = PinSampler::new(|| { timer.wait(); key_pin.is_low() }, 5, 2000);
+/// loop {
+///     decoder.drain(&mut source);
+///     display.show(decoder.message.as_str());
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct PinSampler<F: FnMut() -> bool> {
+    read: F,
+    sample_period_ms: u16,
+    idle_ms: u16,
+    level: bool,
+    run_ms: u16,
+    is_idle: bool,
+}
+
+impl<F: FnMut() -> bool> PinSampler<F> {
+    pub fn new(read: F, sample_period_ms: u16, idle_ms: u16) -> Self {
+        Self {
+            read,
+            sample_period_ms: sample_period_ms.max(1),
+            idle_ms,
+            level: false,
+            run_ms: 0,
+            is_idle: false,
+        }
+    }
+}
+
+impl<F: FnMut() -> bool> SignalSource for PinSampler<F> {
+    fn next_event(&mut self) -> Option<(u16, bool)> {
+        if self.is_idle {
+            self.is_idle = false;
+
+            return None;
+        }
+
+        loop {
+            let level = (self.read)();
+
+            if level == self.level {
+                self.run_ms = self.run_ms.saturating_add(self.sample_period_ms);
+
+                if !level && self.run_ms >= self.idle_ms {
+                    self.is_idle = true;
+
+                    return Some((core::mem::take(&mut self.run_ms), false));
+                }
+            } else {
+                let event = (self.run_ms, self.level);
+                self.level = level;
+                self.run_ms = self.sample_period_ms;
+
+                // Low periods cut by idling are already given out
+                if event.0 > 0 {
+                    return Some(event);
+                }
+            }
+        }
+    }
+}
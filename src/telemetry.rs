@@ -0,0 +1,188 @@
+//! Sensor readings sent as short morse frames, for LED and laser links between MCUs.
+//!
+//! A morse code link between two MCUs only needs an LED and a photodiode, but the receiving side
+//! has to know where a number ends and if it was received right. [TelemetryFormat] writes readings
+//! as fixed width numbers padded with leading zeros, one after another without spaces, and adds
+//! a checksum letter at the end. So a frame of two readings 215 and 7 with a width of 3 is "215007" and its checksum.
+//! The receiving side decodes the frame back to readings, and rejects it if its length or checksum is wrong.
+//!
+//! Digits can be sent as cut numbers to make frames quicker to send, see [CutNumbers].
+//!
+//! ```rust
+//! use morse_codec::{contest::CutNumbers, telemetry::TelemetryFormat};
+//!
+//! let format = TelemetryFormat::new(3).with_cut_numbers(CutNumbers::ZeroAndNine);
+//!
+//! let mut buffer = [0u8; 16];
+//! let frame = format.encode(&[215, 7], &mut buffer).unwrap();
+//! assert_eq!(frame, "215TT7P");
+//!
+//! let mut readings = [0u32; 4];
+//! assert_eq!(format.decode(frame, &mut readings), Ok(2));
+//! assert_eq!(readings[..2], [215, 7]);
+//!
+//! // A dit lost on the way turns 5 into 4
+//! assert!(format.decode("214TT7P", &mut readings).is_err());
+//! ```
+
+use crate::{
+    contest::CutNumbers,
+    message::{Message, MessageStorage},
+    MorseCharacter,
+};
+
+/// Most digits a reading can have, the digits of the largest u32.
+pub const MAX_WIDTH: u8 = 10;
+
+// Checksum is modulo a prime, so a wrong digit always changes it. It's a letter from A to W.
+const CHECKSUM_LETTERS: u32 = 23;
+
+/// Width and cut numbers of the readings in a telemetry frame.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TelemetryFormat {
+    width: u8,
+    cut_numbers: CutNumbers,
+}
+
+impl TelemetryFormat {
+    /// Create a format of readings with `width` digits each. The width is clamped between 1 and [MAX_WIDTH].
+    pub fn new(width: u8) -> Self {
+        Self {
+            width: width.clamp(1, MAX_WIDTH),
+            cut_numbers: CutNumbers::Off,
+        }
+    }
+
+    /// Send digits as cut numbers. Both sides of the link must use the same option.
+    pub fn with_cut_numbers(mut self, cut_numbers: CutNumbers) -> Self {
+        self.cut_numbers = cut_numbers;
+
+        self
+    }
+
+    /// Returns the number of digits of a reading.
+    pub fn width(&self) -> u8 {
+        self.width
+    }
+
+    /// Write the readings and their checksum letter into the buffer as a frame.
+    ///
+    /// Returns the written part of the buffer as a string, or an error if a reading has more
+    /// digits than the width or the buffer is too small.
+    pub fn encode<'b>(&self, readings: &[u32], buffer: &'b mut [u8]) -> Result<&'b str, &'static str> {
+        let width = self.width as usize;
+        if readings.len() * width + 1 > buffer.len() {
+            return Err("Buffer is too small for the frame.");
+        }
+
+        let mut checksum = Checksum::default();
+        for (reading_index, &reading) in readings.iter().enumerate() {
+            // Any u32 fits into the maximum width
+            if width < MAX_WIDTH as usize && reading >= 10u32.pow(width as u32) {
+                return Err("Reading has more digits than the width.");
+            }
+
+            for index in 0..width {
+                let digit = (reading / 10u32.pow((width - index - 1) as u32) % 10) as u8;
+
+                buffer[reading_index * width + index] = self.cut_numbers.cut((b'0' + digit) as char) as u8;
+                checksum.add(digit);
+            }
+        }
+
+        let length = readings.len() * width;
+        buffer[length] = checksum.letter() as u8;
+
+        // Digits, cut numbers and checksum letters are ASCII, so this can't fail.
+        Ok(core::str::from_utf8(&buffer[..=length]).unwrap_or(""))
+    }
+
+    /// Read the readings of a frame into the buffer and return how many there were.
+    ///
+    /// Spaces are left out, so a decoder message ending with a word space can be given as it is.
+    /// Letters can be lowercase. Returns an error if the frame is too short, its length doesn't fit
+    /// the width, it has a character other than a digit or a cut number, the checksum doesn't match
+    /// or there are more readings than the buffer holds.
+    pub fn decode(&self, frame: &str, readings: &mut [u32]) -> Result<usize, &'static str> {
+        self.decode_chars(frame.chars(), readings)
+    }
+
+    /// Read the readings of a frame in a message into the buffer and return how many there were.
+    ///
+    /// See `decode`.
+    pub fn decode_message<const MSG_MAX: usize, C: MorseCharacter, S: MessageStorage<C>>(
+        &self,
+        message: &Message<MSG_MAX, C, S>,
+        readings: &mut [u32],
+    ) -> Result<usize, &'static str> {
+        self.decode_chars((0..message.len()).map(|index| message.char_at(index).to_char()), readings)
+    }
+
+    fn decode_chars(&self, chars: impl Iterator<Item = char>, readings: &mut [u32]) -> Result<usize, &'static str> {
+        let width = self.width as usize;
+        let mut chars = chars
+            .filter(|ch| !ch.is_whitespace())
+            .map(|ch| ch.to_ascii_uppercase())
+            .peekable();
+
+        let mut checksum = Checksum::default();
+        let mut count = 0;
+        let mut received = None;
+
+        while let Some(ch) = chars.next() {
+            // Last character is the checksum, the ones before it are digits
+            if chars.peek().is_none() {
+                received = Some(ch);
+                break;
+            }
+
+            let digit = self.cut_numbers
+                .uncut(ch)
+                .to_digit(10)
+                .ok_or("Frame has a character that isn't a digit.")?;
+
+            if count % width == 0 {
+                let reading = readings
+                    .get_mut(count / width)
+                    .ok_or("Buffer is too small for the readings.")?;
+                *reading = 0;
+            }
+
+            let reading = &mut readings[count / width];
+            *reading = reading.saturating_mul(10).saturating_add(digit);
+
+            checksum.add(digit as u8);
+            count += 1;
+        }
+
+        if count == 0 || count % width != 0 {
+            return Err("Frame length doesn't fit the width.");
+        }
+
+        if received != Some(checksum.letter()) {
+            return Err("Frame checksum doesn't match.");
+        }
+
+        Ok(count / width)
+    }
+}
+
+// Weighted sum of the digits in the frame. Digits are weighted by their position from 1 to 22 over and over,
+// so swapped neighbour digits change the checksum as well as wrong digits.
+#[derive(Default)]
+struct Checksum {
+    sum: u32,
+    position: u32,
+}
+
+impl Checksum {
+    fn add(&mut self, digit: u8) {
+        let weight = self.position % (CHECKSUM_LETTERS - 1) + 1;
+        self.position += 1;
+        self.sum = (self.sum + weight * digit as u32) % CHECKSUM_LETTERS;
+    }
+
+    fn letter(&self) -> char {
+        (b'A' + self.sum as u8) as char
+    }
+}
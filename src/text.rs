@@ -0,0 +1,143 @@
+//! Textual morse code conventions for interop with other tools.
+//!
+//! Morse code is written down in many different ways. Some tools use '_' for dahs,
+//! some separate words with '|' instead of '/', some separate characters with tabs.
+//! Prosigns are often written as the letters they're made of, in brackets like `<BT>`.
+//! [TextFormat] describes one of these conventions. Encoders can emit morse text
+//! with it, and decoders can decode morse text written with it using `decode_text`.
+//!
+//! ```rust
+//! use morse_codec::{
+//!     decoder::Decoder,
+//!     text::TextFormat,
+//! };
+//!
+//! let format = TextFormat {
+//!     dah: '_',
+//!     word_separator: "|",
+//!     ..TextFormat::default()
+//! };
+//!
+//! let mut decoder = Decoder::<16>::new().build();
+//! decoder.decode_text("... ___ ... | <BT>", &format);
+//!
+//! assert_eq!(decoder.message.as_str(), "SOS =");
+//! ```
+
+#[cfg(feature = "decoder")]
+use crate::{
+    MorseCodeArray,
+    MorseSignal::{Long as L, Short as S},
+    MORSE_ARRAY_LENGTH,
+    MORSE_DEFAULT_CHAR,
+};
+
+/// A textual morse code convention.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TextFormat {
+    /// Dit glyph, '.' by default.
+    pub dit: char,
+    /// Dah glyph, '-' by default.
+    pub dah: char,
+    /// Separator between characters, ' ' by default. Any whitespace separates
+    /// characters while decoding text regardless of this.
+    pub char_separator: char,
+    /// Separator between words, "/" by default. It shouldn't be whitespace.
+    pub word_separator: &'static str,
+    /// Opening and closing brackets of prosigns, '<' and '>' by default.
+    /// None disables prosign parsing.
+    pub prosign_brackets: Option<(char, char)>,
+}
+
+impl TextFormat {
+    /// The convention used by the encoder: "... --- ... / <BT>".
+    pub const DEFAULT: TextFormat = TextFormat {
+        dit: '.',
+        dah: '-',
+        char_separator: ' ',
+        word_separator: "/",
+        prosign_brackets: Some(('<', '>')),
+    };
+}
+
+impl Default for TextFormat {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+// A piece of morse text between separators.
+#[cfg(feature = "decoder")]
+pub(crate) enum TextToken<'a> {
+    WordSpace,
+    // None if the token has characters other than dits and dahs or it's too long.
+    Signals(Option<MorseCodeArray>),
+    // Letters a prosign is made of, without the brackets.
+    Prosign(&'a str),
+}
+
+#[cfg(feature = "decoder")]
+impl TextFormat {
+    fn is_separator(&self, ch: char) -> bool {
+        ch == self.char_separator || ch.is_whitespace()
+    }
+
+    // Split text into tokens. Word separators are recognized even when they're not
+    // surrounded by character separators, ie. "...|---".
+    pub(crate) fn tokens<'a>(&'a self, text: &'a str) -> impl Iterator<Item = TextToken<'a>> + 'a {
+        let word_separator = self.word_separator;
+        let starts_word_separator = move |rest: &str| !word_separator.is_empty() && rest.starts_with(word_separator);
+        let mut rest = text;
+
+        core::iter::from_fn(move || {
+            rest = rest.trim_start_matches(|ch| self.is_separator(ch));
+            if rest.is_empty() {
+                return None;
+            }
+
+            if starts_word_separator(rest) {
+                rest = &rest[word_separator.len()..];
+
+                return Some(TextToken::WordSpace);
+            }
+
+            let end = rest
+                .char_indices()
+                .find(|&(i, ch)| self.is_separator(ch) || starts_word_separator(&rest[i..]))
+                .map_or(rest.len(), |(i, _)| i);
+
+            let (part, after) = rest.split_at(end);
+            rest = after;
+
+            Some(self.parse_part(part))
+        })
+    }
+
+    fn parse_part<'a>(&self, part: &'a str) -> TextToken<'a> {
+        if let Some((open, close)) = self.prosign_brackets {
+            if let Some(letters) = part.strip_prefix(open).and_then(|rest| rest.strip_suffix(close)) {
+                return TextToken::Prosign(letters);
+            }
+        }
+
+        let mut morse_array = MORSE_DEFAULT_CHAR;
+
+        for (index, ch) in part.chars().enumerate() {
+            let signal = if ch == self.dit {
+                S
+            } else if ch == self.dah {
+                L
+            } else {
+                return TextToken::Signals(None);
+            };
+
+            if index == MORSE_ARRAY_LENGTH {
+                return TextToken::Signals(None);
+            }
+
+            morse_array[index] = Some(signal);
+        }
+
+        TextToken::Signals(Some(morse_array))
+    }
+}
@@ -0,0 +1,113 @@
+//! Timeline of morse keying at dit resolution.
+//!
+//! A timeline is a run-length encoded bit pattern: each run is (length in dit units, is_high).
+//! It's a compact representation of the keying envelope that doesn't depend on the speed,
+//! so it's handy for plotting waveforms in user interfaces and documentation.
+//! Encoders give the timeline of their message with `timeline_runs`, recorded signal events
+//! of a decoding session can be turned into one with [quantize].
+//!
+//! ```rust
+//! use morse_codec::timeline::{expand, quantize};
+//!
+//! // A, keyed a bit unevenly with a 100 ms short signal
+//! let signals = [(95, true), (110, false), (290, true), (320, false)];
+//!
+//! let runs: Vec<(u32, bool)> = quantize(signals, 100).collect();
+//! assert_eq!(runs, [(1, true), (1, false), (3, true), (3, false)]);
+//!
+//! let bits: Vec<bool> = expand(runs).collect();
+//! assert_eq!(bits, [true, false, true, true, true, false, false, false]);
+//! ```
+//!
+//! With the "std" feature timelines can be drawn as ASCII art or SVG timing diagrams
+//! for teaching material, or to see what a user's keying looks like when debugging decoding issues.
+//!
+//! ```rust
+//! # #[cfg(feature = "std")]
+//! # {
+//! use morse_codec::timeline::{ascii_diagram, quantize};
+//!
+//! let signals = [(95, true), (110, false), (290, true), (320, false)];
+//! assert_eq!(ascii_diagram(quantize(signals, 100)), "#_###___");
+//! # }
+//! ```
+
+#[cfg(feature = "std")]
+use std::{format, string::String};
+
+/// Turn recorded signal events into timeline runs.
+///
+/// Signals are (duration in milliseconds, is_high) pairs. Durations are rounded to the nearest
+/// multiple of `short_ms`, a run is at least one unit long. Consecutive runs with the same level are merged.
+pub fn quantize<I>(signals: I, short_ms: u16) -> impl Iterator<Item = (u32, bool)>
+where
+    I: IntoIterator<Item = (u16, bool)>,
+{
+    let short_ms = short_ms.max(1) as u32;
+    let mut signals = signals
+        .into_iter()
+        .map(move |(duration_ms, is_high)| (((duration_ms as u32 + short_ms / 2) / short_ms).max(1), is_high))
+        .peekable();
+
+    core::iter::from_fn(move || {
+        let (mut units, is_high) = signals.next()?;
+
+        while let Some(&(next_units, next_is_high)) = signals.peek() {
+            if next_is_high != is_high {
+                break;
+            }
+
+            units = units.saturating_add(next_units);
+            signals.next();
+        }
+
+        Some((units, is_high))
+    })
+}
+
+/// Expand timeline runs into a bit pattern with one bit per dit unit.
+pub fn expand<I>(runs: I) -> impl Iterator<Item = bool>
+where
+    I: IntoIterator<Item = (u32, bool)>,
+{
+    runs.into_iter()
+        .flat_map(|(units, is_high)| core::iter::repeat_n(is_high, units as usize))
+}
+
+/// Draw timeline runs as a line of ASCII art, '#' for every high unit and '_' for every low unit.
+#[cfg(feature = "std")]
+pub fn ascii_diagram<I>(runs: I) -> String
+where
+    I: IntoIterator<Item = (u32, bool)>,
+{
+    expand(runs).map(|is_high| if is_high { '#' } else { '_' }).collect()
+}
+
+/// Draw timeline runs as an SVG timing diagram of the keying envelope.
+///
+/// Every dit unit is `unit_px` pixels wide and the diagram is `height_px` pixels high.
+/// The envelope is a single black path, so it can be restyled with CSS.
+#[cfg(feature = "std")]
+pub fn svg_diagram<I>(runs: I, unit_px: u32, height_px: u32) -> String
+where
+    I: IntoIterator<Item = (u32, bool)>,
+{
+    use core::fmt::Write;
+
+    // Keep the 2 pixel stroke inside the diagram
+    let (high_y, low_y) = (1, height_px.saturating_sub(1));
+
+    let mut path = format!("M0,{}", low_y);
+    let mut x: u32 = 0;
+
+    for (units, is_high) in runs {
+        x = x.saturating_add(units.saturating_mul(unit_px));
+
+        let _ = write!(path, "V{}H{}", if is_high { high_y } else { low_y }, x);
+    }
+
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{x}\" height=\"{height_px}\" viewBox=\"0 0 {x} {height_px}\">\
+        <path d=\"{path}\" fill=\"none\" stroke=\"black\" stroke-width=\"2\"/></svg>"
+    )
+}
@@ -0,0 +1,220 @@
+//! A decoder and an encoder working on the same message.
+//!
+//! Echo and repeater applications hear morse code and send it back. Keeping a decoder and an encoder
+//! side by side means two message buffers that have to be copied over and kept in sync after every edit.
+//! [Transcoder] owns a decoder and an encoder with a single message between them.
+//! The message lives in the decoder and is lent to the encoder while it's used with `with_encoder`,
+//! so whatever one of them writes is what the other one sees.
+//!
+//! ```rust
+//! use morse_codec::{
+//!     decoder::{Decoder, Precision},
+//!     encoder::{Encoder, SDM},
+//!     transcoder::Transcoder,
+//! };
+//!
+//! let decoder = Decoder::<16>::new()
+//!     .with_precision(Precision::Accurate)
+//!     .with_reference_short_ms(100)
+//!     .build();
+//! let encoder = Encoder::<16>::new().build();
+//!
+//! let mut transcoder = Transcoder::new(decoder, encoder);
+//!
+//! // Hear an E
+//! transcoder.decoder().signal_event(100, true);
+//! transcoder.decoder().signal_event(300, false);
+//! assert_eq!(transcoder.message().as_str(), "E");
+//!
+//! // Send it back
+//! let sdm = transcoder.with_encoder(|encoder| encoder.get_last_char_as_sdm());
+//! assert_eq!(sdm.unwrap()[0], SDM::High(1));
+//!
+//! // Answer to it on the same message
+//! assert!(transcoder.with_encoder(|encoder| encoder.encode_slice(" R").is_ok()));
+//! assert_eq!(transcoder.message().as_str(), "E R");
+//! ```
+//!
+//! [Relay] builds a repeater on top of a transcoder. Signal events that come in are decoded,
+//! each decoded character can be transformed and it's given out as signals to retransmit
+//! at another speed as soon as it's complete. So the latency is a character and the space after it.
+//!
+//! ```rust
+//! use morse_codec::{
+//!     decoder::{Decoder, Precision},
+//!     encoder::Encoder,
+//!     transcoder::{Relay, Transcoder},
+//! };
+//!
+//! let decoder = Decoder::<16>::new()
+//!     .with_precision(Precision::Accurate)
+//!     .with_reference_short_ms(60)
+//!     .build();
+//! let encoder = Encoder::<16>::new().build();
+//!
+//! // Hear at 20 WPM, retransmit at 10 WPM
+//! let mut relay = Relay::new(Transcoder::new(decoder, encoder), 120);
+//!
+//! // T
+//! relay.signal_event(180, true);
+//! assert_eq!(relay.next_duration(), None);
+//! relay.signal_event(180, false);
+//!
+//! assert_eq!(relay.next_duration(), Some((360, true)));
+//! assert_eq!(relay.next_duration(), Some((360, false)));
+//! assert_eq!(relay.next_duration(), None);
+//! ```
+
+use crate::{
+    decoder::{CharacterMapper, MorseDecoder},
+    encoder::{MorseEncoder, SdmChunk, SDM},
+    message::Message,
+    playback::Player,
+    Character,
+    MorseCharacter,
+};
+
+/// Owns a decoder and an encoder sharing one message.
+#[derive(Clone, Debug)]
+pub struct Transcoder<const MSG_MAX: usize, C: MorseCharacter = Character> {
+    decoder: MorseDecoder<MSG_MAX, C>,
+    encoder: MorseEncoder<MSG_MAX, C>,
+}
+
+impl<const MSG_MAX: usize, C: MorseCharacter> Transcoder<MSG_MAX, C> {
+    /// Create a transcoder from a built decoder and encoder.
+    ///
+    /// Message of the decoder is the shared message, message of the encoder is discarded.
+    pub fn new(decoder: MorseDecoder<MSG_MAX, C>, mut encoder: MorseEncoder<MSG_MAX, C>) -> Self {
+        encoder.message = Message::default();
+
+        Self { decoder, encoder }
+    }
+
+    /// Returns the shared message.
+    pub fn message(&self) -> &Message<MSG_MAX, C> {
+        &self.decoder.message
+    }
+
+    /// Returns the shared message for editing.
+    pub fn message_mut(&mut self) -> &mut Message<MSG_MAX, C> {
+        &mut self.decoder.message
+    }
+
+    /// Returns the decoder to send signal events to. Decoded characters go to the shared message.
+    pub fn decoder(&mut self) -> &mut MorseDecoder<MSG_MAX, C> {
+        &mut self.decoder
+    }
+
+    /// Use the encoder on the shared message.
+    ///
+    /// Message is moved into the encoder for the duration of the closure
+    /// and moved back afterwards, along with any changes the encoder made to it.
+    pub fn with_encoder<F, R>(&mut self, use_encoder: F) -> R
+    where
+        F: FnOnce(&mut MorseEncoder<MSG_MAX, C>) -> R,
+    {
+        core::mem::swap(&mut self.decoder.message, &mut self.encoder.message);
+        let result = use_encoder(&mut self.encoder);
+        core::mem::swap(&mut self.decoder.message, &mut self.encoder.message);
+
+        result
+    }
+
+    /// Take the decoder and the encoder apart. The shared message stays with the decoder.
+    pub fn into_parts(self) -> (MorseDecoder<MSG_MAX, C>, MorseEncoder<MSG_MAX, C>) {
+        (self.decoder, self.encoder)
+    }
+}
+
+/// Decodes incoming signal events and gives the decoded characters out as signals to retransmit.
+///
+/// Like [Player], it doesn't deal with time itself. Client code sends signal events as they're received
+/// and asks for the next signal to retransmit when the current one has been played.
+/// When the message is full and everything in it has been retransmitted, it's cleared to make room.
+#[derive(Clone, Debug)]
+pub struct Relay<const MSG_MAX: usize, C: MorseCharacter = Character> {
+    transcoder: Transcoder<MSG_MAX, C>,
+    player: Player,
+    short_ms: u32,
+    transform: Option<CharacterMapper<C>>,
+}
+
+impl<const MSG_MAX: usize, C: MorseCharacter> Relay<MSG_MAX, C> {
+    /// Create a relay retransmitting with a short signal duration of `short_ms` milliseconds.
+    pub fn new(transcoder: Transcoder<MSG_MAX, C>, short_ms: u32) -> Self {
+        Self {
+            transcoder,
+            player: Player::new(),
+            short_ms,
+            transform: None,
+        }
+    }
+
+    /// Change the retransmission speed. It takes effect at the next signal.
+    pub fn set_short_ms(&mut self, short_ms: u32) {
+        self.short_ms = short_ms;
+    }
+
+    /// Transform decoded characters before they're retransmitted, ie. to translate between
+    /// character sets. Returning None drops the character. Word spaces are not transformed.
+    /// Characters should be in the character set of the encoder to be retransmitted.
+    pub fn set_transform(&mut self, transform: Option<CharacterMapper<C>>) {
+        self.transform = transform;
+    }
+
+    /// Returns the transcoder, ie. to read the relayed message or change decoder settings.
+    pub fn transcoder(&mut self) -> &mut Transcoder<MSG_MAX, C> {
+        &mut self.transcoder
+    }
+
+    /// Send a received signal event to the decoder. Same as `signal_event` of the decoder.
+    pub fn signal_event(&mut self, duration_ms: u16, is_high: bool) {
+        let is_relayed = self.transcoder.with_encoder(|encoder| self.player.is_finished(encoder));
+        if is_relayed && self.transcoder.message().is_full() {
+            self.transcoder.message_mut().clear();
+            self.player.seek_to_char(0);
+        }
+
+        let decoder = self.transcoder.decoder();
+        let Some(ch) = decoder.signal_event_char(duration_ms, is_high) else {
+            return;
+        };
+
+        if let (Some(transform), Some(index)) = (self.transform, decoder.completed_char_index()) {
+            let message = self.transcoder.message_mut();
+
+            match transform(ch) {
+                Some(to) => {
+                    let _ = message.put_char_at(index, to);
+                }
+                // Word space after the character can be decoded with it, so the rest is shifted over
+                None => {
+                    for index in index..message.len().saturating_sub(1) {
+                        let _ = message.put_char_at(index, message.char_at(index + 1));
+                    }
+
+                    message.pop();
+                }
+            }
+        }
+    }
+
+    /// Get the next signal to retransmit along with the character it belongs to.
+    ///
+    /// Returns None if the signals of all decoded characters have been given out.
+    pub fn next_signal(&mut self) -> Option<SdmChunk<C>> {
+        let player = &mut self.player;
+
+        self.transcoder.with_encoder(|encoder| player.next_signal(encoder))
+    }
+
+    /// Get the duration in milliseconds and the level of the next signal to retransmit.
+    pub fn next_duration(&mut self) -> Option<(u32, bool)> {
+        self.next_signal().and_then(|chunk| match chunk.sdm {
+            SDM::High(multiplier) => Some((multiplier as u32 * self.short_ms, true)),
+            SDM::Low(multiplier) => Some((multiplier as u32 * self.short_ms, false)),
+            SDM::Empty => None,
+        })
+    }
+}
@@ -0,0 +1,244 @@
+//! WAV file encoding and decoding of morse code messages.
+//!
+//! Available with the "std" feature along with default "encoder" and "decoder" features.
+//! Encoded messages can be written as 16 bit mono PCM WAV audio with a sine tone
+//! playing during high signals, and WAV audio can be decoded back by detecting the tone with the Goertzel algorithm.
+//! So practice files can be produced and graded end-to-end by this crate alone.
+//!
+//! ```rust
+//! use std::io::Cursor;
+//! use morse_codec::{
+//!     decoder::{Decoder, Precision},
+//!     encoder::Encoder,
+//!     wav::{encode_to_wav, decode_from_wav, WavParams},
+//! };
+//!
+//! let mut encoder = Encoder::<16>::new().with_message("PARIS", true).build();
+//! encoder.encode_message_all();
+//!
+//! let params = WavParams::default();
+//! let mut wav_bytes: Vec<u8> = Vec::new();
+//! encode_to_wav(&encoder, &mut wav_bytes, &params).unwrap();
+//!
+//! let mut decoder = Decoder::<16>::new()
+//!     .with_precision(Precision::Accurate)
+//!     .with_reference_short_ms(params.short_ms)
+//!     .build();
+//! decode_from_wav(Cursor::new(wav_bytes), &mut decoder, &params).unwrap();
+//!
+//! assert_eq!(decoder.message.as_str(), "PARIS");
+//! ```
+
+use std::{
+    f32::consts::PI,
+    io::{self, Read, Write},
+    vec::Vec,
+};
+
+use crate::{
+    decoder::MorseDecoder,
+    encoder::MorseEncoder,
+    MorseCharacter,
+};
+
+const BITS_PER_SAMPLE: u16 = 16;
+// Length of the fade in and fade out of tones to prevent clicks in the audio
+const RAMP_MS: u32 = 4;
+// Tone detection works on blocks of this length while decoding
+const DETECTION_BLOCK_MS: u32 = 5;
+
+/// Parameters of the audio in the WAV file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WavParams {
+    /// Samples per second of the audio.
+    pub sample_rate: u32,
+    /// Frequency of the tone in Hz.
+    pub tone_hz: f32,
+    /// Duration of a short signal (dit) in milliseconds.
+    pub short_ms: u16,
+    /// Volume of the tone between 0.0 and 1.0.
+    pub amplitude: f32,
+}
+
+impl Default for WavParams {
+    /// 8 kHz audio with a 600 Hz tone at 12 WPM (100 ms dits).
+    fn default() -> Self {
+        Self {
+            sample_rate: 8000,
+            tone_hz: 600.0,
+            short_ms: 100,
+            amplitude: 0.8,
+        }
+    }
+}
+
+/// Write the encoded message of the encoder as WAV audio to a writer.
+///
+/// Writer can be a file or any other [Write] implementation. Message should be
+/// encoded beforehand with one of the encoding functions of the encoder.
+///
+/// ```ignore
+/// let file = std::fs::File::create("practice.wav")?;
+/// encode_to_wav(&encoder, file, &WavParams::default())?;
+/// ```
+pub fn encode_to_wav<const MSG_MAX: usize, C: MorseCharacter, W: Write>(
+    encoder: &MorseEncoder<MSG_MAX, C>,
+    mut writer: W,
+    params: &WavParams,
+) -> io::Result<()> {
+    let samples_per_ms = params.sample_rate as f32 / 1000.0;
+    let durations = encoder.get_encoded_message_as_durations(params.short_ms);
+
+    let sample_count: u32 = durations.iter()
+        .map(|&(duration_ms, _)| (duration_ms as f32 * samples_per_ms) as u32)
+        .sum();
+
+    write_header(&mut writer, params.sample_rate, sample_count)?;
+
+    let amplitude = params.amplitude.clamp(0.0, 1.0) * i16::MAX as f32;
+    let ramp_samples = (RAMP_MS as f32 * samples_per_ms) as u32;
+    let mut phase_index: u32 = 0;
+
+    for (duration_ms, is_high) in durations {
+        let signal_samples = (duration_ms as f32 * samples_per_ms) as u32;
+
+        for i in 0..signal_samples {
+            let sample = if is_high {
+                let ramp = i.min(signal_samples - 1 - i).min(ramp_samples) as f32 / ramp_samples.max(1) as f32;
+                let t = phase_index as f32 / params.sample_rate as f32;
+
+                (amplitude * ramp * (2.0 * PI * params.tone_hz * t).sin()) as i16
+            } else {
+                0
+            };
+
+            phase_index = phase_index.wrapping_add(1);
+            writer.write_all(&sample.to_le_bytes())?;
+        }
+    }
+
+    writer.flush()
+}
+
+/// Read WAV audio from a reader and decode it into the message of the decoder.
+///
+/// Supports 16 bit PCM audio. If there are multiple channels, only the first one is used.
+/// The tone is detected at the `tone_hz` frequency of the parameters. Other parameters
+/// are read from the WAV file itself.
+pub fn decode_from_wav<const MSG_MAX: usize, C: MorseCharacter, R: Read>(
+    mut reader: R,
+    decoder: &mut MorseDecoder<MSG_MAX, C>,
+    params: &WavParams,
+) -> io::Result<()> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+
+    let (sample_rate, samples) = read_samples(&bytes)?;
+
+    let block_len = ((sample_rate * DETECTION_BLOCK_MS / 1000) as usize).max(1);
+    let magnitudes: Vec<f32> = samples
+        .chunks(block_len)
+        .map(|block| goertzel_magnitude(block, sample_rate, params.tone_hz))
+        .collect();
+
+    let max_magnitude = magnitudes.iter().cloned().fold(0.0, f32::max);
+    if max_magnitude <= 0.0 {
+        return Ok(());
+    }
+
+    let threshold = max_magnitude / 2.0;
+    let signals = magnitudes
+        .iter()
+        .map(|&magnitude| (DETECTION_BLOCK_MS as u16, magnitude >= threshold));
+
+    // Consecutive blocks with the same level are merged by the decoder
+    decoder.extend_signals(signals);
+
+    Ok(())
+}
+
+fn write_header<W: Write>(writer: &mut W, sample_rate: u32, sample_count: u32) -> io::Result<()> {
+    let block_align = BITS_PER_SAMPLE / 8;
+    let data_size = sample_count * block_align as u32;
+
+    writer.write_all(b"RIFF")?;
+    writer.write_all(&(36 + data_size).to_le_bytes())?;
+    writer.write_all(b"WAVE")?;
+
+    writer.write_all(b"fmt ")?;
+    writer.write_all(&16u32.to_le_bytes())?;
+    writer.write_all(&1u16.to_le_bytes())?; // PCM format
+    writer.write_all(&1u16.to_le_bytes())?; // Mono
+    writer.write_all(&sample_rate.to_le_bytes())?;
+    writer.write_all(&(sample_rate * block_align as u32).to_le_bytes())?;
+    writer.write_all(&block_align.to_le_bytes())?;
+    writer.write_all(&BITS_PER_SAMPLE.to_le_bytes())?;
+
+    writer.write_all(b"data")?;
+    writer.write_all(&data_size.to_le_bytes())
+}
+
+// Parse WAV bytes and return the sample rate along with samples of the first channel
+fn read_samples(bytes: &[u8]) -> io::Result<(u32, Vec<i16>)> {
+    let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidData, msg);
+    let read_u16 = |pos: usize| u16::from_le_bytes([bytes[pos], bytes[pos + 1]]);
+    let read_u32 = |pos: usize| u32::from_le_bytes([bytes[pos], bytes[pos + 1], bytes[pos + 2], bytes[pos + 3]]);
+
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err(invalid("Not a RIFF WAVE file."));
+    }
+
+    let mut format: Option<(u16, u32)> = None;
+    let mut pos = 12;
+
+    while pos + 8 <= bytes.len() {
+        let chunk_id = &bytes[pos..pos + 4];
+        let chunk_size = read_u32(pos + 4) as usize;
+        let chunk_start = pos + 8;
+        let chunk_end = (chunk_start + chunk_size).min(bytes.len());
+
+        match chunk_id {
+            b"fmt " if chunk_size >= 16 && chunk_end - chunk_start >= 16 => {
+                if read_u16(chunk_start) != 1 || read_u16(chunk_start + 14) != BITS_PER_SAMPLE {
+                    return Err(invalid("Only 16 bit PCM WAV files are supported."));
+                }
+
+                format = Some((read_u16(chunk_start + 2).max(1), read_u32(chunk_start + 4)));
+            }
+            b"data" => {
+                let (channels, sample_rate) = format.ok_or_else(|| invalid("WAV data chunk before fmt chunk."))?;
+                let frame_size = channels as usize * 2;
+
+                let samples = bytes[chunk_start..chunk_end]
+                    .chunks_exact(frame_size)
+                    .map(|frame| i16::from_le_bytes([frame[0], frame[1]]))
+                    .collect();
+
+                return Ok((sample_rate, samples));
+            }
+            _ => (),
+        }
+
+        // Chunks are padded to even sizes
+        pos = chunk_start + chunk_size + (chunk_size & 1);
+    }
+
+    Err(invalid("WAV file has no data chunk."))
+}
+
+// Goertzel algorithm to find the magnitude of a single frequency in a block of samples.
+// It's cheaper than a full FFT when we're only interested in the tone frequency.
+fn goertzel_magnitude(block: &[i16], sample_rate: u32, tone_hz: f32) -> f32 {
+    let coefficient = 2.0 * (2.0 * PI * tone_hz / sample_rate as f32).cos();
+    let (mut s_prev, mut s_prev2) = (0.0f32, 0.0f32);
+
+    for &sample in block {
+        let s = sample as f32 + coefficient * s_prev - s_prev2;
+        s_prev2 = s_prev;
+        s_prev = s;
+    }
+
+    let power = s_prev2 * s_prev2 + s_prev * s_prev - coefficient * s_prev * s_prev2;
+
+    power.max(0.0).sqrt() / block.len() as f32
+}
@@ -0,0 +1,86 @@
+use morse_codec::{
+    bank::DecoderBank,
+    decoder::{Decoder, DecodeEvent, Precision},
+};
+
+#[test]
+fn bank_dispatch() {
+    let builder = Decoder::<16, u8>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(100);
+    let mut bank = DecoderBank::<3, 16, u8>::new([
+        builder.clone().build(),
+        builder.clone().with_reference_short_ms(50).build(),
+        builder.build(),
+    ]);
+
+    // A on channel 0 and channel 1 at double speed, interleaved
+    let events = [
+        (0, 100, true), (1, 50, true),
+        (0, 100, false), (1, 50, false),
+        (0, 300, true), (1, 150, true),
+        (0, 300, false), (1, 150, false),
+    ];
+    for (channel, duration_ms, is_high) in events {
+        assert!(bank.signal_event(channel, duration_ms, is_high).is_some());
+    }
+
+    assert_eq!(bank.signal_event(2, 300, true), Some(DecodeEvent::Buffered));
+    assert_eq!(bank.signal_event(3, 300, true), None);
+    assert!(bank.decoder(3).is_none());
+
+    bank.signal_event_end_all(true);
+    let messages: Vec<&str> = bank.messages().map(|(_, message)| message.as_str()).collect();
+    assert_eq!(messages, ["A ", "A ", "T "]);
+
+    for (_, decoder) in bank.iter_mut() {
+        decoder.message.clear();
+    }
+    assert!(bank.iter().all(|(_, decoder)| decoder.message.is_empty()));
+
+    bank.decoder_mut(1).unwrap().decode_text("...", &Default::default());
+    assert_eq!(bank.decoder(1).unwrap().message.as_str(), "S");
+}
+
+#[cfg(feature = "utf8")]
+#[test]
+fn bank_codebook_detection() {
+    use morse_codec::{bank::CodebookDetector, charsets::by_name};
+
+    let builder = Decoder::<32>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(100);
+    let codebooks = [by_name("international").unwrap(), by_name("cyrillic").unwrap()];
+    let mut detector = CodebookDetector::new(builder, codebooks);
+    assert!(detector.best().is_none());
+
+    let send = |detector: &mut CodebookDetector<2, 32>, text: &str| {
+        for code in text.split(' ') {
+            for (index, signal) in code.chars().enumerate() {
+                detector.signal_event(if signal == '-' { 300 } else { 100 }, true);
+                detector.signal_event(if index + 1 == code.len() { 300 } else { 100 }, false);
+            }
+        }
+        detector.signal_event_end(true);
+    };
+
+    // Codes both codebooks have, international wins the tie
+    send(&mut detector, ".- -... -.-.");
+    assert_eq!(detector.best().unwrap().0.name, "international");
+    let errors: Vec<(&str, u32)> = detector.errors().map(|(codebook, errors)| (codebook.name, errors)).collect();
+    assert_eq!(errors, [("international", 0), ("cyrillic", 0)]);
+
+    // ..-- is Ю and .-.- is Я in cyrillic
+    send(&mut detector, "..-- .-.-");
+    let (codebook, decoder) = detector.best().unwrap();
+    assert_eq!(codebook.name, "cyrillic");
+    assert_eq!(decoder.message.as_str(), "АБЦ ЮЯ ");
+    let errors: Vec<u32> = detector.errors().map(|(_, errors)| errors).collect();
+    assert_eq!(errors, [2, 0]);
+
+    assert_eq!(detector.bank().decoder(0).unwrap().message.as_str(), "ABC ?? ");
+
+    detector.reset();
+    assert!(detector.best().is_none());
+    assert!(detector.bank().messages().all(|(_, message)| message.is_empty()));
+}
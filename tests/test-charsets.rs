@@ -0,0 +1,128 @@
+use morse_codec::{
+    charsets::MorseTree,
+    MorseSignal::{Long as L, Short as S},
+    DEFAULT_ASCII_CHARACTER_SET,
+    DEFAULT_MORSE_CODE_SET,
+    MORSE_DEFAULT_CHAR,
+};
+
+#[test]
+fn charsets_morse_tree() {
+    let tree = MorseTree::<u8>::new(DEFAULT_ASCII_CHARACTER_SET, DEFAULT_MORSE_CODE_SET);
+
+    // Every code of the set is found. X and multiplication sign share a code.
+    for (ch, morse_char) in DEFAULT_ASCII_CHARACTER_SET.iter().zip(DEFAULT_MORSE_CODE_SET) {
+        assert_eq!(tree.get(morse_char), Some(*ch));
+    }
+
+    assert_eq!(tree.get(&[Some(S), Some(S), Some(L), Some(L), None, None]), None);
+    assert_eq!(tree.get(&[Some(S), None, Some(L), None, None, None]), None);
+
+    let completions: Vec<u8> = tree.completions(&[Some(L), Some(L), Some(L), None, None, None]).collect();
+    assert_eq!(completions, b"O890:");
+
+    let completions: Vec<u8> = tree.completions(&[Some(S), Some(S), Some(S), Some(S), Some(S), None]).collect();
+    assert_eq!(completions, b"5");
+
+    assert_eq!(tree.completions(&[Some(S), None, Some(L), None, None, None]).count(), 0);
+
+    // Empty code is the root, so everything is under it
+    let completions: Vec<u8> = tree.completions(&MORSE_DEFAULT_CHAR).take(7).collect();
+    assert_eq!(completions, b" ETIANM");
+    assert_eq!(tree.completions(&MORSE_DEFAULT_CHAR).count(), DEFAULT_ASCII_CHARACTER_SET.len() - 1);
+}
+
+#[cfg(feature = "encoder")]
+#[test]
+fn charsets_validation() {
+    use morse_codec::{
+        charsets::{validate, CharsetError, PUNCTUATION_EXTENDED_ASCII_CHARACTER_SET, PUNCTUATION_EXTENDED_MORSE_CODE_SET},
+        decoder::Decoder,
+        encoder::Encoder,
+        CharacterSet,
+        Error,
+        MorseCodeSet,
+        DEFAULT_UTF8_CHARACTER_SET,
+    };
+
+    assert_eq!(validate(DEFAULT_ASCII_CHARACTER_SET, DEFAULT_MORSE_CODE_SET), Ok(()));
+    assert_eq!(validate(DEFAULT_UTF8_CHARACTER_SET, DEFAULT_MORSE_CODE_SET), Ok(()));
+    assert_eq!(validate(PUNCTUATION_EXTENDED_ASCII_CHARACTER_SET, PUNCTUATION_EXTENDED_MORSE_CODE_SET), Ok(()));
+
+    assert_eq!(
+        validate(&DEFAULT_ASCII_CHARACTER_SET[..10], DEFAULT_MORSE_CODE_SET),
+        Err(CharsetError::LengthMismatch { characters: 10, codes: DEFAULT_MORSE_CODE_SET.len() }),
+    );
+
+    const CHARACTERS: CharacterSet<u8> = b" ABA";
+    const CODES: MorseCodeSet = &[
+        MORSE_DEFAULT_CHAR,
+        [Some(S), Some(L), None, None, None, None],
+        [Some(L), Some(S), Some(S), Some(S), None, None],
+        [Some(L), Some(L), None, None, None, None],
+    ];
+    assert_eq!(validate(CHARACTERS, CODES), Err(CharsetError::DuplicateCharacter { index: 1, duplicate: 3 }));
+
+    const MALFORMED_CODES: MorseCodeSet = &[
+        MORSE_DEFAULT_CHAR,
+        [Some(S), Some(L), None, None, None, None],
+        [Some(L), None, Some(S), Some(S), None, None],
+        [Some(L), Some(L), None, None, None, None],
+    ];
+    assert_eq!(validate(b" ABM", MALFORMED_CODES), Err(CharsetError::MalformedCode { index: 2 }));
+
+    let result = Decoder::<16, u8>::new()
+        .with_character_set(CHARACTERS)
+        .with_morse_code_set(CODES)
+        .try_build();
+    assert_eq!(result.err(), Some(Error::InvalidCharacterSet(CharsetError::DuplicateCharacter { index: 1, duplicate: 3 })));
+
+    let result = Encoder::<16, u8>::new()
+        .with_morse_code_set(CODES)
+        .try_build();
+    assert!(matches!(result, Err(Error::InvalidCharacterSet(CharsetError::LengthMismatch { .. }))));
+
+    assert!(Encoder::<16>::new().try_build().is_ok());
+}
+
+#[cfg(all(feature = "utf8", feature = "encoder"))]
+#[test]
+fn charsets_codebooks() {
+    use morse_codec::{
+        charsets::{by_name, validate, CODEBOOKS},
+        decoder::Decoder,
+        encoder::Encoder,
+        text::TextFormat,
+    };
+
+    for codebook in CODEBOOKS {
+        assert_eq!(validate(codebook.character_set, codebook.morse_code_set), Ok(()), "{}", codebook.name);
+        assert_eq!(by_name(codebook.name), Some(*codebook));
+    }
+    assert_eq!(by_name("GREEK").map(|codebook| codebook.name), Some("greek"));
+    assert_eq!(by_name("klingon"), None);
+
+    for (name, text, expected) in [
+        ("cyrillic", "ПРИВЕТ МИР", ".--. .-. .. .-- . - / -- .. .-."),
+        ("greek", "ΓΕΙΑ 73", "--. . .. .- / --... ...--"),
+    ] {
+        let codebook = by_name(name).unwrap();
+
+        let encoder = Encoder::<16>::new()
+            .with_character_set(codebook.character_set)
+            .with_morse_code_set(codebook.morse_code_set)
+            .with_message(text, true)
+            .build();
+
+        let mut morse = String::new();
+        encoder.write_text(&TextFormat::DEFAULT, &mut morse).unwrap();
+        assert_eq!(morse, expected);
+
+        let mut decoder = Decoder::<16>::new()
+            .with_character_set(codebook.character_set)
+            .with_morse_code_set(codebook.morse_code_set)
+            .build();
+        decoder.decode_text(&morse, &TextFormat::DEFAULT);
+        assert_eq!(decoder.message.as_str(), text);
+    }
+}
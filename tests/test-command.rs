@@ -0,0 +1,63 @@
+use morse_codec::{
+    command::CommandSet,
+    decoder::{Decoder, Precision},
+};
+
+const LIGHT_ON: u16 = 1;
+const LIGHT_OFF: u16 = 2;
+const ON: u16 = 3;
+
+#[test]
+fn command_registering() {
+    let mut commands = CommandSet::<2>::new();
+    assert!(commands.is_empty());
+
+    assert!(commands.register("  ", ON).is_err());
+    assert!(commands.register("LIGHT ON", LIGHT_ON).is_ok());
+    assert!(commands.register("light off", LIGHT_OFF).is_ok());
+    assert!(commands.register("ON", ON).is_err());
+    assert_eq!(commands.len(), 2);
+
+    commands.clear();
+    assert!(commands.is_empty());
+}
+
+#[test]
+fn command_dispatching() {
+    let mut commands = CommandSet::<4>::new();
+    commands.register("light on", LIGHT_ON).unwrap();
+    commands.register("LIGHT OFF", LIGHT_OFF).unwrap();
+    commands.register("ON", ON).unwrap();
+
+    let mut decoder = Decoder::<64>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(100)
+        .build();
+
+    // "E" keyed with a character space doesn't complete a word
+    assert_eq!(commands.feed(&mut decoder, 100, true), None);
+    assert_eq!(commands.feed(&mut decoder, 300, false), None);
+
+    // "E ON" is not "LIGHT ON" but it ends with "ON"
+    decoder.decode_text("/ ---", &Default::default());
+    commands.feed(&mut decoder, 300, true);
+    commands.feed(&mut decoder, 100, false);
+    commands.feed(&mut decoder, 100, true);
+    assert_eq!(commands.feed(&mut decoder, 700, false), Some(ON));
+
+    // Longest phrase wins
+    decoder.decode_text(".-.. .. --. .... - / ---", &Default::default());
+    commands.feed(&mut decoder, 300, true);
+    commands.feed(&mut decoder, 100, false);
+    commands.feed(&mut decoder, 100, true);
+    assert_eq!(commands.feed(&mut decoder, 700, false), Some(LIGHT_ON));
+
+    // Phrase must start at a word boundary, "DON" doesn't match "ON"
+    decoder.decode_text("-.. ---", &Default::default());
+    commands.feed(&mut decoder, 300, true);
+    commands.feed(&mut decoder, 100, false);
+    commands.feed(&mut decoder, 100, true);
+    assert_eq!(commands.feed(&mut decoder, 700, false), None);
+
+    assert_eq!(decoder.message.as_str(), "E ON LIGHT ON DON ");
+}
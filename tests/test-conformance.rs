@@ -0,0 +1,46 @@
+use morse_codec::conformance::{FARNSWORTH, JITTER, VECTORS};
+
+#[test]
+fn conformance_decoding() {
+    for vector in VECTORS {
+        let mut decoder = vector.decoder::<32>().build();
+        decoder.extend_signals(vector.signals.iter().copied());
+
+        assert_eq!(decoder.message.as_str(), vector.expected, "{}", vector.name);
+    }
+
+    assert!(FARNSWORTH.is_farnsworth());
+    assert!(!JITTER.is_farnsworth());
+}
+
+#[cfg(feature = "encoder")]
+#[test]
+fn conformance_encoding() {
+    use morse_codec::{
+        encoder::{Encoder, TimeUnit},
+        FarnsworthTiming,
+    };
+
+    // Jitter can't be reproduced by the encoder
+    for vector in VECTORS.iter().filter(|vector| vector.name != JITTER.name) {
+        // Vectors end with a word space
+        let text = format!("{} ", vector.text);
+        let mut encoder = Encoder::<32>::new().build();
+        encoder.encode_slice(&text).unwrap();
+
+        let signals: Vec<(u16, bool)> = if vector.is_farnsworth() {
+            let timing = FarnsworthTiming::new(vector.character_wpm, vector.effective_wpm);
+            encoder.farnsworth_durations(timing)
+                .map(|(duration_ms, is_high)| (duration_ms as u16, is_high))
+                .collect()
+        } else {
+            let short_ms = 1200 / vector.character_wpm as u32;
+            encoder.signal_durations(short_ms, TimeUnit::Milliseconds)
+                .map(|(duration_ms, is_high)| (duration_ms as u16, is_high))
+                .collect()
+        };
+
+        assert_eq!(signals, vector.signals, "{}", vector.name);
+    }
+}
+
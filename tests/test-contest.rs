@@ -0,0 +1,81 @@
+use morse_codec::{
+    contest::{
+        format_exchange,
+        uncut_numbers,
+        CutNumbers,
+        Field,
+    },
+    message::Message,
+};
+
+#[test]
+fn contest_exchange_templates() {
+    let fields = [
+        ("callsign", Field::Text("9A1A")),
+        ("serial", Field::Number { value: 105, width: 3 }),
+        ("power", Field::Number { value: 5, width: 0 }),
+    ];
+    let template = "TU {callsign} 5NN {serial}";
+    let mut buffer = [0u8; 32];
+
+    let exchange = format_exchange(template, &fields, CutNumbers::Off, &mut buffer).unwrap();
+    assert_eq!(exchange, "TU 9A1A 5NN 105");
+
+    // Callsign digits are never cut
+    let exchange = format_exchange(template, &fields, CutNumbers::ZeroAndNine, &mut buffer).unwrap();
+    assert_eq!(exchange, "TU 9A1A 5NN 1T5");
+
+    let exchange = format_exchange(template, &fields, CutNumbers::All, &mut buffer).unwrap();
+    assert_eq!(exchange, "TU 9A1A 5NN ATE");
+
+    let exchange = format_exchange("{power}W {serial}{serial}", &fields, CutNumbers::Off, &mut buffer).unwrap();
+    assert_eq!(exchange, "5W 105105");
+
+    let fields = [("serial", Field::Number { value: 0, width: 0 })];
+    let exchange = format_exchange("NR {serial}", &fields, CutNumbers::ZeroAndNine, &mut buffer).unwrap();
+    assert_eq!(exchange, "NR T");
+
+    assert!(format_exchange("NR {serial", &fields, CutNumbers::Off, &mut buffer).is_err());
+    assert!(format_exchange("TU {callsign}", &fields, CutNumbers::Off, &mut buffer).is_err());
+    assert!(format_exchange("NR {serial}", &fields, CutNumbers::Off, &mut buffer[..3]).is_err());
+}
+
+#[test]
+fn contest_uncut_numbers() {
+    for ch in ['T', 'N', 'A', 'E', 'X', '4'] {
+        assert_eq!(CutNumbers::Off.uncut(ch), ch);
+    }
+    assert_eq!(CutNumbers::ZeroAndNine.uncut('T'), '0');
+    assert_eq!(CutNumbers::ZeroAndNine.uncut('A'), 'A');
+    assert_eq!(CutNumbers::All.uncut('A'), '1');
+    assert_eq!(CutNumbers::All.uncut('4'), '4');
+
+    let mut message = Message::<32>::new("K1ABC 5NN 1T5 NR TN9", false, false);
+    assert_eq!(uncut_numbers(&mut message, CutNumbers::ZeroAndNine), 5);
+    assert_eq!(message.as_str(), "K1ABC 599 105 NR 099");
+
+    // Callsigns and words without digits are kept
+    let mut message = Message::<32>::new("A1A ATE 5NN UV2", false, false);
+    assert_eq!(uncut_numbers(&mut message, CutNumbers::All), 4);
+    assert_eq!(message.as_str(), "A1A ATE 599 232");
+
+    let mut message = Message::<32>::new("5NN TT1", false, false);
+    assert_eq!(uncut_numbers(&mut message, CutNumbers::Off), 0);
+    assert_eq!(message.as_str(), "5NN TT1");
+}
+
+#[cfg(feature = "encoder")]
+#[test]
+fn contest_exchange_encoding() {
+    use morse_codec::{encoder::Encoder, text::TextFormat};
+
+    let fields = [("serial", Field::Number { value: 9, width: 3 })];
+    let mut buffer = [0u8; 16];
+    let exchange = format_exchange("5NN {serial}", &fields, CutNumbers::ZeroAndNine, &mut buffer).unwrap();
+
+    let encoder = Encoder::<16>::new().with_message(exchange, true).build();
+
+    let mut text = String::new();
+    encoder.write_text(&TextFormat::DEFAULT, &mut text).unwrap();
+    assert_eq!(text, "..... -. -. / - - -.");
+}
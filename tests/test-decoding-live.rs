@@ -1,6 +1,6 @@
 
 use morse_codec::decoder::{
-    Decoder, Precision
+    factor_from_thousandths, Decoder, Precision
 };
 use std::{
     thread::sleep,
@@ -101,10 +101,10 @@ fn decoding_live_100_ms() {
 
 #[test]
 fn decoding_live_farnsworth_half() {
-    decoding_live(Precision::Farnsworth(0.5), 100);
+    decoding_live(Precision::Farnsworth(factor_from_thousandths(500)), 100);
 }
 
 #[test]
 fn decoding_live_farnsworth_quarter() {
-    decoding_live(Precision::Farnsworth(0.25), 100);
+    decoding_live(Precision::Farnsworth(factor_from_thousandths(250)), 100);
 }
@@ -2,11 +2,13 @@ use morse_codec::{
     decoder::{
         Decoder,
         Precision,
+        factor_from_thousandths,
     },
     CharacterSet,
     MorseSignal::{ Long as L, Short as S },
     FILLER,
     Character,
+    SignalEvent,
 };
 
 #[test]
@@ -640,7 +642,7 @@ fn decode_random_positions() {
     decoder.signal_event(100, true);
     decoder.signal_event(300, false);
 
-    print!("{}", decoder.get_last_decoded_char());
+    print!("{}", decoder.get_last_decoded_char().unwrap());
 
     decoder.signal_event(300, true);
     decoder.signal_event(100, false);
@@ -649,7 +651,7 @@ fn decode_random_positions() {
     decoder.signal_event(300, true);
     decoder.signal_event(300, false);
 
-    print!("{}", decoder.get_last_decoded_char());
+    print!("{}", decoder.get_last_decoded_char().unwrap());
 
     decoder.signal_event(100, true);
     decoder.signal_event(100, false);
@@ -658,7 +660,7 @@ fn decode_random_positions() {
     decoder.signal_event(100, true);
     decoder.signal_event(300, false);
 
-    print!("{}", decoder.get_last_decoded_char());
+    print!("{}", decoder.get_last_decoded_char().unwrap());
 
     decoder.message.set_edit_pos(3);
 
@@ -669,7 +671,7 @@ fn decode_random_positions() {
     decoder.signal_event(300, true);
     decoder.signal_event(300, false);
 
-    print!("{}", decoder.get_last_decoded_char());
+    print!("{}", decoder.get_last_decoded_char().unwrap());
 
     decoder.signal_event(100, true);
     decoder.signal_event(100, false);
@@ -678,7 +680,7 @@ fn decode_random_positions() {
     decoder.signal_event(100, true);
     decoder.signal_event(300, false);
 
-    print!("{}", decoder.get_last_decoded_char());
+    print!("{}", decoder.get_last_decoded_char().unwrap());
 
     decoder.signal_event(300, true);
     decoder.signal_event(100, false);
@@ -687,7 +689,7 @@ fn decode_random_positions() {
     decoder.signal_event(300, true);
     decoder.signal_event(300, false);
 
-    print!("{}", decoder.get_last_decoded_char());
+    print!("{}", decoder.get_last_decoded_char().unwrap());
 
     println!();
 
@@ -695,3 +697,1561 @@ fn decode_random_positions() {
 
     println!();
 }
+
+#[test]
+fn decoding_with_allowed_chars() {
+    const MESSAGE_MAX_LENGTH: usize = 8;
+
+    #[cfg(not(feature = "utf8"))]
+    let allowed_chars: &[Character] = b"0123456789";
+
+    #[cfg(feature = "utf8")]
+    let allowed_chars: &[Character] = &['0', '1', '2', '3', '4', '5', '6', '7', '8', '9'];
+
+    let mut decoder = Decoder::<MESSAGE_MAX_LENGTH>::new()
+        .with_allowed_chars(allowed_chars)
+        .with_disallowed_char_mapper(|ch| {
+            // Map cut number T to 0 and reject everything else
+            if ch == b'T' as Character {
+                Some(b'0' as Character)
+            } else {
+                None
+            }
+        })
+        .build();
+
+    // 1 is allowed
+    decoder.add_signal_to_character(Some(S));
+    decoder.add_signal_to_character(Some(L));
+    decoder.add_signal_to_character(Some(L));
+    decoder.add_signal_to_character(Some(L));
+    decoder.add_signal_to_character(Some(L));
+    decoder.add_current_char_to_message();
+
+    // E is rejected
+    decoder.add_signal_to_character(Some(S));
+    decoder.add_current_char_to_message();
+
+    // T is mapped to 0
+    decoder.add_signal_to_character(Some(L));
+    decoder.add_current_char_to_message();
+
+    // Word space is rejected as well
+    decoder.signal_event_end(true);
+
+    println!("Message with allowed chars: {}", decoder.message.as_str());
+
+    assert_eq!(decoder.message.as_str(), "10");
+}
+
+#[test]
+fn decoding_from_signal_iterator() {
+    const MESSAGE_MAX_LENGTH: usize = 8;
+
+    let mut decoder = Decoder::<MESSAGE_MAX_LENGTH>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(100)
+        .build();
+
+    // SOS with the word space split into two low signals
+    let signals = [
+        (100, true), (100, false), (100, true), (100, false), (100, true), (300, false),
+        (300, true), (100, false), (300, true), (100, false), (300, true), (300, false),
+        (100, true), (100, false), (100, true), (100, false), (100, true), (400, false), (300, false),
+    ];
+
+    decoder.extend_signals(signals);
+
+    // A recorded session from a channel or whatever
+    let recorded: Vec<(u16, bool)> = vec![(100, true), (300, false)];
+    decoder.extend_signals(recorded);
+
+    println!("Message decoded from iterator: {}", decoder.message.as_str());
+
+    assert_eq!(decoder.message.as_str(), "SOS E");
+}
+
+#[test]
+fn decoding_from_binary_samples() {
+    const MESSAGE_MAX_LENGTH: usize = 8;
+    const SAMPLE_RATE: u32 = 2000;
+
+    let mut decoder = Decoder::<MESSAGE_MAX_LENGTH>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(50)
+        .build();
+
+    // "TE" at 50 ms dits, followed by a long idle period
+    let periods = [(150, true), (150, false), (50, true), (600, false)];
+    let samples: Vec<bool> = periods
+        .iter()
+        .flat_map(|&(ms, level)| std::iter::repeat_n(level, (ms * SAMPLE_RATE / 1000) as usize))
+        .collect();
+
+    // Push samples in odd sized chunks like an SDR pipeline would
+    for chunk in samples.chunks(37) {
+        decoder.push_samples(chunk, SAMPLE_RATE);
+    }
+
+    println!("Message decoded from samples: '{}'", decoder.message.as_str());
+
+    // The last character is decoded while idling, without another high signal
+    assert_eq!(decoder.message.as_str(), "TE ");
+}
+
+#[test]
+fn decoding_from_signal_events() {
+    const MESSAGE_MAX_LENGTH: usize = 8;
+
+    let mut decoder = Decoder::<MESSAGE_MAX_LENGTH>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(100)
+        .build();
+
+    // "N" as sent by a sensor node
+    let events = [
+        SignalEvent::new(300, true),
+        SignalEvent::new(100, false),
+        SignalEvent::new(100, true),
+        SignalEvent::new(700, false),
+    ];
+
+    // Base station receives them over the wire
+    #[cfg(feature = "serde")]
+    let events: Vec<SignalEvent> = events
+        .iter()
+        .map(|event| {
+            let bytes = postcard::to_allocvec(event).unwrap();
+            println!("Signal event {:?} as postcard bytes: {:02X?}", event, bytes);
+
+            postcard::from_bytes(&bytes).unwrap()
+        })
+        .collect();
+
+    decoder.extend_signals(events);
+
+    assert_eq!(decoder.message.as_str(), "N ");
+    assert_eq!(<(u16, bool)>::from(SignalEvent::from((300, true))), (300, true));
+}
+
+#[test]
+fn decoding_error_rate() {
+    const MESSAGE_MAX_LENGTH: usize = 16;
+
+    let mut decoder = Decoder::<MESSAGE_MAX_LENGTH>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(100)
+        .build();
+
+    assert_eq!(decoder.error_rate(), 0.0);
+
+    // E, T and a word space
+    decoder.extend_signals([(100, true), (300, false), (300, true), (700, false)]);
+
+    // Six dahs is not a character in the set
+    for _ in 0..6 {
+        decoder.add_signal_to_character(Some(L));
+    }
+    decoder.add_current_char_to_message();
+
+    // A question mark decoded properly is not an error
+    decoder.extend_signals([
+        (100, true), (100, false), (100, true), (100, false), (300, true), (100, false),
+        (300, true), (100, false), (100, true), (100, false), (100, true), (300, false),
+    ]);
+
+    println!("Message: {}, error rate: {}", decoder.message.as_str(), decoder.error_rate());
+
+    assert_eq!(decoder.message.as_str(), "ET ??");
+    assert_eq!(decoder.get_decoded_char_count(), 3);
+    assert_eq!(decoder.get_error_count(), 1);
+    assert_eq!(decoder.error_rate(), 0.25);
+
+    decoder.reset_metrics();
+    assert_eq!(decoder.get_error_count(), 0);
+    assert_eq!(decoder.error_rate(), 0.0);
+}
+
+#[test]
+fn decoding_with_speed_tracking() {
+    const MESSAGE_MAX_LENGTH: usize = 8;
+
+    // Sender is faster than the initial reference short of 100 ms
+    let letter_i = [(60, true), (60, false), (60, true), (300, false)];
+
+    let mut decoder = Decoder::<MESSAGE_MAX_LENGTH>::new()
+        .with_reference_short_ms(100)
+        .build();
+
+    decoder.extend_signals(letter_i);
+
+    // Without speed tracking reference short jumps to the shorter signal right away
+    assert_eq!(decoder.get_reference_short(), 60);
+    assert_eq!(decoder.get_speed_history().collect::<Vec<_>>(), vec![60]);
+
+    let mut decoder = Decoder::<MESSAGE_MAX_LENGTH>::new()
+        .with_reference_short_ms(100)
+        .with_speed_tracking(5)
+        .build();
+
+    for _ in 0..4 {
+        decoder.extend_signals(letter_i);
+    }
+
+    let history: Vec<_> = decoder.get_speed_history().collect();
+    println!("Speed history: {:?}", history);
+
+    assert_eq!(decoder.message.as_str(), "IIII");
+    assert_eq!(history, vec![95, 90, 85, 80]);
+    assert_eq!(decoder.wpm_history().collect::<Vec<_>>(), vec![12, 13, 14, 15]);
+
+    // History only keeps the latest characters
+    for _ in 0..20 {
+        decoder.extend_signals(letter_i);
+    }
+
+    let history: Vec<_> = decoder.get_speed_history().collect();
+    assert_eq!(history.len(), morse_codec::decoder::SPEED_HISTORY_LENGTH);
+    assert_eq!(history.last(), Some(&60));
+    assert_eq!(decoder.wpm_history().count(), history.len());
+    assert_eq!(decoder.wpm_history().last(), Some(20));
+}
+
+#[test]
+fn decoding_with_minimum_signal_duration() {
+    const MESSAGE_MAX_LENGTH: usize = 8;
+
+    // "A" and "E" sent with a bouncy key and some noise bursts
+    let signals = [
+        (60, true), (4, false), (36, true), (100, false), (300, true),
+        (150, false), (5, true), (145, false),
+        (100, true), (300, false), (4, true), (400, false),
+    ];
+
+    let mut decoder = Decoder::<MESSAGE_MAX_LENGTH>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(100)
+        .build();
+
+    signals.iter().for_each(|&(duration_ms, is_high)| decoder.signal_event(duration_ms, is_high));
+    println!("Decoded without minimum signal duration: '{}'", decoder.message.as_str());
+    assert_ne!(decoder.message.as_str(), "AE ");
+
+    let mut decoder = Decoder::<MESSAGE_MAX_LENGTH>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(100)
+        .with_minimum_signal_ms(10)
+        .build();
+
+    signals.iter().for_each(|&(duration_ms, is_high)| decoder.signal_event(duration_ms, is_high));
+    println!("Decoded with minimum signal duration: '{}'", decoder.message.as_str());
+    assert_eq!(decoder.message.as_str(), "AE ");
+}
+
+#[test]
+fn decoding_farnsworth_factor() {
+    const MESSAGE_MAX_LENGTH: usize = 8;
+
+    // Same factor of 0.5 in both floating point and fixed-point modes
+    let mut decoder = Decoder::<MESSAGE_MAX_LENGTH>::new()
+        .with_precision(Precision::Farnsworth(factor_from_thousandths(500)))
+        .with_reference_short_ms(100)
+        .build();
+
+    assert_eq!(decoder.get_wpm(), 12);
+
+    // At 12 WPM halved, Farnsworth short is 363 ms, so spaces between
+    // characters are 1089 ms and word spaces are 2541 ms.
+    decoder.extend_signals([
+        (100, true), (1089, false),
+        (300, true), (2541, false),
+        (100, true), (1089, false),
+    ]);
+
+    assert_eq!(decoder.message.as_str(), "ET E");
+}
+
+#[test]
+#[cfg(not(feature = "small-footprint"))]
+fn decoding_with_char_timestamps() {
+    const MESSAGE_MAX_LENGTH: usize = 8;
+
+    let mut decoder = Decoder::<MESSAGE_MAX_LENGTH>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(100)
+        .with_message("K", true)
+        .build();
+
+    // "E", a word space, a long pause and "T"
+    decoder.extend_signals([(100, true), (700, false), (5000, false), (300, true), (300, false)]);
+
+    let timed: Vec<_> = decoder.get_timed_chars().collect();
+    println!("Timed chars: {:?}", timed);
+
+    assert_eq!(decoder.message.as_str(), "KE T");
+    assert_eq!(decoder.get_elapsed_ms(), 6400);
+    // Starter message is not decoded from signals
+    assert_eq!(decoder.get_char_timestamp(0), None);
+    // Word space and the pause are merged into a single low signal by extend_signals
+    assert_eq!(decoder.get_char_timestamp(1), Some(5800));
+    assert_eq!(decoder.get_char_timestamp(2), Some(5800));
+    assert_eq!(decoder.get_char_timestamp(3), Some(6400));
+    assert_eq!(decoder.get_char_timestamp(MESSAGE_MAX_LENGTH), None);
+}
+
+#[test]
+#[cfg(feature = "small-footprint")]
+fn decoding_with_small_footprint() {
+    const MESSAGE_MAX_LENGTH: usize = 8;
+
+    let mut decoder = Decoder::<MESSAGE_MAX_LENGTH>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(100)
+        .build();
+
+    decoder.extend_signals([(100, true), (300, false), (300, true), (300, false)]);
+
+    // Decoding works the same but decode times are not kept
+    assert_eq!(decoder.message.as_str(), "ET");
+    assert_eq!(decoder.get_elapsed_ms(), 1000);
+    assert_eq!(decoder.get_char_timestamp(0), None);
+    assert!(decoder.get_timed_chars().all(|(_, timestamp)| timestamp.is_none()));
+}
+
+#[test]
+fn decoding_undo_last() {
+    const MESSAGE_MAX_LENGTH: usize = 4;
+
+    let letter_e = [(100, true), (300, false)];
+    let letter_t = [(300, true), (300, false)];
+
+    for clamping in [false, true] {
+        let mut decoder = Decoder::<MESSAGE_MAX_LENGTH>::new()
+            .with_precision(Precision::Accurate)
+            .with_reference_short_ms(100)
+            .build();
+        decoder.message.set_edit_position_clamp(clamping);
+
+        assert_eq!(decoder.undo_last(), None);
+
+        // Fill the message, edit position wraps or clamps at the end
+        for _ in 0..4 {
+            decoder.extend_signals(letter_e);
+        }
+
+        assert_eq!(decoder.undo_last(), Some(b'E' as Character));
+        assert_eq!(decoder.message.get_edit_pos(), 3);
+
+        assert_eq!(decoder.undo_last(), Some(b'E' as Character));
+        assert_eq!(decoder.message.as_str(), "EE");
+        assert_eq!(decoder.message.get_edit_pos(), 2);
+
+        // Decoding continues from the undone position
+        decoder.extend_signals(letter_t);
+        println!("Message after undo with clamping {}: {}", clamping, decoder.message);
+        assert_eq!(decoder.message.as_str(), "EET");
+    }
+
+    // Last decoded character follows the undo
+    let mut decoder = Decoder::<MESSAGE_MAX_LENGTH, u8>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(100)
+        .build();
+    decoder.extend_signals([(100, true), (100, false), (300, true), (700, false)]);
+    decoder.extend_signals([(300, true), (100, false), (100, true), (100, false), (100, true), (100, false), (100, true), (300, false)]);
+    assert_eq!(decoder.message.as_str(), "A B");
+    assert_eq!(decoder.undo_last(), Some(b'B'));
+    assert_eq!(decoder.get_last_decoded_char(), Some(b' '));
+    assert!(!decoder.is_word_boundary_pending());
+    assert_eq!(decoder.undo_last(), Some(b' '));
+    assert_eq!(decoder.get_last_decoded_char(), Some(b'A'));
+    assert!(decoder.is_word_boundary_pending());
+    assert_eq!(decoder.undo_last(), Some(b'A'));
+    assert_eq!(decoder.get_last_decoded_char(), None);
+
+    let mut decoder = Decoder::<MESSAGE_MAX_LENGTH, u8>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(100)
+        .build();
+    decoder.extend_signals([(100, true), (100, false), (300, true), (300, false)]);
+    decoder.extend_signals([(300, true), (100, false), (100, true), (100, false), (100, true), (100, false), (100, true), (300, false)]);
+    assert_eq!(decoder.message.as_str(), "AB");
+    decoder.undo_last();
+    assert_eq!(decoder.get_last_decoded_char(), Some(b'A'));
+
+    let mut decoder = Decoder::<MESSAGE_MAX_LENGTH>::new().with_message("SOS", true).build();
+    assert_eq!(decoder.message.pop(), Some(b'S' as Character));
+    assert_eq!(decoder.message.as_str(), "SO");
+    assert_eq!(decoder.message.get_edit_pos(), 2);
+}
+
+#[test]
+fn decoding_try_build() {
+    use morse_codec::{decoder::factor_from_thousandths, Error};
+
+    const MESSAGE_MAX_LENGTH: usize = 8;
+
+    assert!(Decoder::<MESSAGE_MAX_LENGTH>::new().try_build().is_ok());
+    assert!(Decoder::<MESSAGE_MAX_LENGTH>::new()
+        .with_edit_position(MESSAGE_MAX_LENGTH - 1)
+        .try_build()
+        .is_ok());
+
+    let result = Decoder::<MESSAGE_MAX_LENGTH>::new()
+        .with_edit_position(MESSAGE_MAX_LENGTH)
+        .try_build();
+    assert_eq!(result.err(), Some(Error::EditPositionOutOfRange));
+
+    let result = Decoder::<MESSAGE_MAX_LENGTH>::new()
+        .with_precision(Precision::Accurate)
+        .try_build();
+    assert_eq!(result.err(), Some(Error::MissingReferenceShort));
+
+    let result = Decoder::<MESSAGE_MAX_LENGTH>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(100)
+        .with_signal_tolerance(factor_from_thousandths(250))
+        .try_build();
+    assert!(result.is_ok());
+
+    #[cfg(not(feature = "fixed-point"))]
+    {
+        let result = Decoder::<MESSAGE_MAX_LENGTH>::new()
+            .with_signal_tolerance(f32::NAN)
+            .try_build();
+        assert_eq!(result.err(), Some(Error::InvalidFactor));
+
+        let result = Decoder::<MESSAGE_MAX_LENGTH>::new()
+            .with_precision(Precision::Farnsworth(f32::NAN))
+            .try_build();
+        assert_eq!(result.err(), Some(Error::InvalidFactor));
+    }
+}
+
+#[test]
+fn decoding_try_build_never_panics() {
+    use morse_codec::decoder::factor_from_thousandths;
+
+    const MESSAGE_MAX_LENGTH: usize = 4;
+
+    let precisions = [
+        Precision::Lazy,
+        Precision::Accurate,
+        Precision::Training,
+        Precision::Farnsworth(factor_from_thousandths(500)),
+        Precision::FarnsworthWpm { character_wpm: 20, effective_wpm: 5 },
+    ];
+
+    // Boundaries where a word space of 7 dits, or 8 with Lazy padding, overflows u16 milliseconds,
+    // and a stride over the rest
+    let word_space_edges = [7, 8].map(|units| u16::MAX / units);
+    let reference_shorts = [0, 1, u16::MAX - 1, u16::MAX]
+        .into_iter()
+        .chain(word_space_edges.into_iter().flat_map(|edge| [edge - 1, edge, edge + 1]))
+        .chain((0..=u16::MAX).step_by(97));
+
+    // Decoders that try_build accepts can decode extreme signals without panicking
+    for precision in precisions {
+        for reference_short_ms in reference_shorts.clone() {
+            let result = Decoder::<MESSAGE_MAX_LENGTH, u8>::new()
+                .with_precision(precision)
+                .with_reference_short_ms(reference_short_ms)
+                .try_build();
+
+            if let Ok(mut decoder) = result {
+                decoder.extend_signals([(u16::MAX, true), (u16::MAX, false), (1, true), (reference_short_ms, false)]);
+                decoder.signal_event_end(true);
+            }
+        }
+    }
+}
+
+#[test]
+fn decoding_qrss_reference_short() {
+    use morse_codec::Error;
+
+    const MESSAGE_MAX_LENGTH: usize = 8;
+
+    // QRSS10, word space of a 10 second dit doesn't fit into u16 milliseconds
+    let result = Decoder::<MESSAGE_MAX_LENGTH, u8>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(10000)
+        .try_build();
+    assert_eq!(result.err(), Some(Error::ReferenceShortTooLong));
+
+    let result = Decoder::<MESSAGE_MAX_LENGTH, u8>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(9362)
+        .try_build();
+    assert!(result.is_ok());
+
+    // Built anyway, the word space saturates instead of overflowing
+    let mut decoder = Decoder::<MESSAGE_MAX_LENGTH, u8>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(10000)
+        .build();
+    decoder.extend_signals([(10000, true), (10000, false), (30000, true), (30000, false), (10000, true)]);
+    decoder.signal_event_end(false);
+    assert_eq!(decoder.message.as_str(), "AE");
+
+    let mut decoder = Decoder::<MESSAGE_MAX_LENGTH, u8>::new().build();
+    decoder.extend_signals([(9000, true), (9000, false), (27000, true), (27000, false), (9000, true)]);
+    decoder.signal_event_end(false);
+    assert_eq!(decoder.message.as_str(), "AE");
+}
+
+#[test]
+fn decoding_with_events() {
+    use morse_codec::decoder::{DecodeEvent, RejectReason};
+
+    const MESSAGE_MAX_LENGTH: usize = 16;
+
+    let mut decoder = Decoder::<MESSAGE_MAX_LENGTH>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(100)
+        .with_minimum_signal_ms(10)
+        .build();
+
+    let e = b'E' as Character;
+
+    assert_eq!(decoder.signal_event_ex(300, false), DecodeEvent::Rejected(RejectReason::IdleLow));
+    assert_eq!(decoder.signal_event_ex(100, true), DecodeEvent::Buffered);
+    assert_eq!(decoder.signal_event_ex(5, false), DecodeEvent::Rejected(RejectReason::TooShort));
+    assert_eq!(decoder.signal_event_ex(300, false), DecodeEvent::CharCompleted(e));
+    assert_eq!(decoder.signal_event_ex(100, true), DecodeEvent::Buffered);
+    assert_eq!(decoder.signal_event_ex(700, false), DecodeEvent::WordCompleted);
+
+    // Six dahs are not a character
+    for _ in 0..5 {
+        assert_eq!(decoder.signal_event_ex(300, true), DecodeEvent::Buffered);
+        assert_eq!(decoder.signal_event_ex(100, false), DecodeEvent::Buffered);
+    }
+    assert_eq!(decoder.signal_event_ex(300, true), DecodeEvent::Buffered);
+    assert_eq!(decoder.signal_event_ex(300, false), DecodeEvent::Error);
+
+    assert_eq!(decoder.message.as_str(), "EE ?");
+
+    const ALLOWED: &[Character] = &[b'E' as Character];
+
+    let mut decoder = Decoder::<MESSAGE_MAX_LENGTH>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(100)
+        .with_allowed_chars(ALLOWED)
+        .build();
+
+    decoder.signal_event(300, true);
+    assert_eq!(decoder.signal_event_ex(300, false), DecodeEvent::Rejected(RejectReason::Filtered));
+}
+
+#[test]
+fn decoding_with_completed_chars() {
+    const MESSAGE_MAX_LENGTH: usize = 16;
+
+    let mut decoder = Decoder::<MESSAGE_MAX_LENGTH>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(100)
+        .build();
+
+    // "SOS " with an undecodable character at the end
+    let signals: &[(u16, bool)] = &[
+        (100, true), (100, false), (100, true), (100, false), (100, true), (300, false),
+        (300, true), (100, false), (300, true), (100, false), (300, true), (300, false),
+        (100, true), (100, false), (100, true), (100, false), (100, true), (700, false),
+        (300, true), (100, false), (300, true), (100, false), (300, true), (100, false),
+        (300, true), (100, false), (300, true), (100, false), (300, true), (300, false),
+    ];
+
+    let completed: Vec<Character> = signals
+        .iter()
+        .filter_map(|&(duration_ms, is_high)| decoder.signal_event_char(duration_ms, is_high))
+        .collect();
+
+    let expected: Vec<Character> = "SOS?".chars().map(|ch| ch as u8 as Character).collect();
+    assert_eq!(completed, expected);
+    assert_eq!(decoder.message.as_str(), "SOS ?");
+}
+
+#[test]
+fn decoding_last_decoded_char() {
+    let mut decoder = Decoder::<2>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(100)
+        .build();
+
+    assert_eq!(decoder.get_last_decoded_char(), None);
+
+    // "ETE" wraps the edit position back to the start of the message
+    for &(duration_ms, is_high) in &[(100, true), (300, false), (300, true), (300, false)] {
+        decoder.signal_event(duration_ms, is_high);
+    }
+    assert_eq!(decoder.get_last_decoded_char(), Some(b'T' as Character));
+    assert_eq!(decoder.message.get_edit_pos(), 0);
+
+    decoder.signal_event(100, true);
+    decoder.signal_event(300, false);
+    assert_eq!(decoder.get_last_decoded_char(), Some(b'E' as Character));
+
+    // Moving the edit position doesn't change it
+    decoder.message.set_edit_pos(1);
+    assert_eq!(decoder.get_last_decoded_char(), Some(b'E' as Character));
+}
+
+#[test]
+fn decoding_end_with_nothing_pending() {
+    let mut decoder = Decoder::<16>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(100)
+        .build();
+
+    // Nothing decoded yet
+    decoder.signal_event_end(false);
+    decoder.signal_event_end(true);
+    assert!(decoder.message.is_empty());
+
+    decoder.signal_event(100, true);
+    decoder.signal_event_end(false);
+    decoder.signal_event_end(false);
+    assert_eq!(decoder.message.as_str(), "E");
+
+    // Only one word space is added
+    decoder.signal_event_end(true);
+    decoder.signal_event_end(true);
+    assert_eq!(decoder.message.as_str(), "E ");
+
+    // A long low ending a word followed by a timeout flush
+    decoder.signal_event(300, true);
+    decoder.signal_event(700, false);
+    decoder.signal_event_end(true);
+    assert_eq!(decoder.message.as_str(), "E T ");
+}
+
+#[test]
+fn decoding_completed_words() {
+    let mut decoder = Decoder::<16>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(100)
+        .build();
+
+    let mut buffer = [0u8; 16];
+
+    assert!(!decoder.is_word_boundary_pending());
+    assert_eq!(decoder.last_completed_word(&mut buffer), "");
+
+    // "ON" and a word space
+    for &(duration_ms, is_high) in &[
+        (300, true), (100, false), (300, true), (100, false), (300, true), (300, false),
+        (300, true), (100, false),
+    ] {
+        decoder.signal_event(duration_ms, is_high);
+    }
+
+    assert!(decoder.is_word_boundary_pending());
+
+    decoder.signal_event(100, true);
+    decoder.signal_event(700, false);
+
+    assert!(!decoder.is_word_boundary_pending());
+    assert_eq!(decoder.last_completed_word(&mut buffer), "ON");
+
+    // "E" keyed, word not completed yet
+    decoder.signal_event(100, true);
+    decoder.signal_event(300, false);
+
+    assert!(decoder.is_word_boundary_pending());
+    assert_eq!(decoder.last_completed_word(&mut buffer), "ON");
+
+    decoder.signal_event_end(true);
+    assert_eq!(decoder.last_completed_word(&mut buffer), "E");
+
+    // Word is cut to fit the buffer
+    decoder.decode_text("... --- ... /", &Default::default());
+    let mut small_buffer = [0u8; 2];
+    assert_eq!(decoder.last_completed_word(&mut small_buffer), "SO");
+}
+
+#[test]
+fn decoding_high_speed() {
+    // 240 WPM, a short signal is 5 milliseconds
+    const SHORT_US: u32 = 5_000;
+
+    // "PARIS " with timing jitter of a few hundred microseconds
+    let signals: &[(u32, bool)] = &[
+        (SHORT_US + 300, true), (SHORT_US - 400, false), (3 * SHORT_US - 200, true), (SHORT_US + 100, false),
+        (3 * SHORT_US + 400, true), (SHORT_US, false), (SHORT_US - 300, true), (3 * SHORT_US + 200, false),
+        (SHORT_US, true), (SHORT_US + 200, false), (3 * SHORT_US - 300, true), (3 * SHORT_US, false),
+        (SHORT_US - 200, true), (SHORT_US + 300, false), (3 * SHORT_US + 100, true), (SHORT_US - 100, false),
+        (SHORT_US + 400, true), (3 * SHORT_US - 400, false),
+        (SHORT_US, true), (SHORT_US - 300, false), (SHORT_US + 200, true), (3 * SHORT_US + 300, false),
+        (SHORT_US - 100, true), (SHORT_US, false), (SHORT_US + 300, true), (SHORT_US - 200, false),
+        (SHORT_US, true), (8 * SHORT_US, false),
+    ];
+
+    for precision in [Precision::Accurate, Precision::Lazy] {
+        let mut decoder = Decoder::<16>::new()
+            .with_precision(precision)
+            .with_reference_short_ms(5)
+            .build();
+
+        for &(duration_us, is_high) in signals {
+            decoder.signal_event_us(duration_us, is_high);
+        }
+
+        println!("High speed decoded message: {}", decoder.message.as_str());
+
+        assert_eq!(decoder.message.as_str(), "PARIS ");
+        assert_eq!(decoder.get_wpm(), 240);
+    }
+}
+
+#[test]
+fn decoding_calibration() {
+    use morse_codec::{Error, TimingProfile};
+
+    const MESSAGE_MAX_LENGTH: usize = 16;
+
+    // "V V" sent at 60 ms shorts with heavy dahs and long word space
+    let signals: &[(u16, bool)] = &[
+        (60, true), (60, false), (60, true), (60, false), (60, true), (60, false), (240, true), (840, false),
+        (60, true), (60, false), (60, true), (60, false), (60, true), (60, false), (240, true),
+    ];
+
+    let builder = Decoder::<MESSAGE_MAX_LENGTH>::new();
+    let calibration = builder.calibrate_from_known("v v", signals).unwrap();
+
+    println!("Calibration: {:?}", calibration);
+
+    // Dahs pull the reference short up a bit
+    assert_eq!(calibration.reference_short_ms, 67);
+    assert_eq!(calibration.dah_weight, Some(factor_from_thousandths(1194)));
+    assert_eq!(calibration.char_space_weight, None);
+    assert_eq!(calibration.word_space_weight, Some(factor_from_thousandths(1791)));
+    assert_eq!(calibration.signal_tolerance, factor_from_thousandths(294));
+
+    // Signals don't match the text
+    assert_eq!(builder.calibrate_from_known("VE", signals).err(), Some(Error::KnownTextMismatch));
+    assert_eq!(builder.calibrate_from_known("V", signals).err(), Some(Error::KnownTextMismatch));
+    assert_eq!(builder.calibrate_from_known("V V", &signals[..10]).err(), Some(Error::KnownTextMismatch));
+    assert_eq!(builder.calibrate_from_known("  ", signals).err(), Some(Error::EmptyKnownText));
+    assert_eq!(builder.calibrate_from_known("V~V", signals).err(), Some(Error::UnknownCharacter));
+    assert_eq!(builder.calibrate_from_known("E", &[(0, true)]).err(), Some(Error::ZeroSignalDurations));
+
+    let mut decoder = Decoder::<MESSAGE_MAX_LENGTH>::new()
+        .with_precision(Precision::Accurate)
+        .with_calibration(&calibration)
+        .build();
+
+    // Weights are applied to the timing profile, dahs are 4 shorts and word spaces 12
+    let weighted = Decoder::<MESSAGE_MAX_LENGTH>::new()
+        .with_precision(Precision::Accurate)
+        .with_timing_profile(TimingProfile { dah_mult: 4, char_gap_mult: 3, word_gap_mult: 12 })
+        .with_reference_short_ms(calibration.reference_short_ms)
+        .with_signal_tolerance(calibration.signal_tolerance)
+        .build();
+    assert_eq!(decoder.current_thresholds(), weighted.current_thresholds());
+
+    for &(duration_ms, is_high) in signals {
+        decoder.signal_event(duration_ms, is_high);
+    }
+    decoder.signal_event_end(false);
+
+    assert_eq!(decoder.message.as_str(), "V V");
+}
+
+#[test]
+fn decoding_thresholds() {
+    let decoder = Decoder::<16>::new().build();
+    assert!(decoder.current_thresholds().is_none());
+
+    for precision in [Precision::Lazy, Precision::Accurate, Precision::Farnsworth(factor_from_thousandths(500))] {
+        let decoder = Decoder::<16>::new()
+            .with_precision(precision)
+            .with_reference_short_ms(60)
+            .build();
+
+        let thresholds = decoder.current_thresholds().unwrap();
+        println!("Thresholds: {:?}", thresholds);
+
+        let dit = thresholds.dit.unwrap();
+        let dah = thresholds.dah.unwrap();
+        let char_space = thresholds.char_space.unwrap();
+        let word_space_min = thresholds.word_space_min.unwrap();
+
+        assert!(dit.contains(&60));
+        assert!(dah.contains(&180));
+        assert!(dit.end() < dah.start());
+        assert!(char_space.end() < &word_space_min);
+    }
+
+    // Farnsworth spaces are longer than the ones of Accurate
+    let thresholds = |precision| {
+        Decoder::<16>::new()
+            .with_precision(precision)
+            .with_reference_short_ms(60)
+            .build()
+            .current_thresholds()
+            .unwrap()
+    };
+
+    let accurate = thresholds(Precision::Accurate);
+    let farnsworth = thresholds(Precision::Farnsworth(factor_from_thousandths(500)));
+
+    assert_eq!(accurate.dit, farnsworth.dit);
+    assert!(farnsworth.char_space.unwrap().start() > accurate.char_space.unwrap().start());
+    assert!(farnsworth.word_space_min.unwrap() > accurate.word_space_min.unwrap());
+}
+
+#[test]
+fn decoding_farnsworth_wpm() {
+    use morse_codec::FarnsworthTiming;
+
+    // Characters at 20 WPM, overall speed 10 WPM
+    let timing = FarnsworthTiming::new(20, 10);
+    assert_eq!(timing, FarnsworthTiming { short_ms: 60, char_space_ms: 653, word_space_ms: 1525 });
+
+    // Equal speeds are standard timing
+    assert_eq!(FarnsworthTiming::new(20, 20), FarnsworthTiming { short_ms: 60, char_space_ms: 180, word_space_ms: 420 });
+    // Effective speed can't be faster than character speed
+    assert_eq!(FarnsworthTiming::new(20, 30), FarnsworthTiming::new(20, 20));
+
+    let mut decoder = Decoder::<16>::new()
+        .with_precision(Precision::FarnsworthWpm { character_wpm: 20, effective_wpm: 10 })
+        .build();
+
+    // Reference short comes from the character speed
+    assert_eq!(decoder.get_reference_short(), 60);
+
+    // "EE T": standard character space isn't enough to end a character
+    let signals: &[(u16, bool)] = &[
+        (60, true), (653, false), (60, true), (1525, false), (180, true), (653, false),
+    ];
+
+    for &(duration_ms, is_high) in signals {
+        decoder.signal_event(duration_ms, is_high);
+    }
+
+    assert_eq!(decoder.message.as_str(), "EE T");
+}
+
+#[test]
+fn decoding_lazy_padding() {
+    let thresholds = |lazy_padding| {
+        Decoder::<16>::new()
+            .with_reference_short_ms(100)
+            .with_lazy_padding(lazy_padding)
+            .build()
+            .current_thresholds()
+            .unwrap()
+    };
+
+    // Default padding is half a short, 50 ms at 100 ms shorts
+    let default_thresholds = Decoder::<16>::new()
+        .with_reference_short_ms(100)
+        .build()
+        .current_thresholds()
+        .unwrap();
+    assert_eq!(default_thresholds.dit, Some(1..=199));
+
+    assert_eq!(thresholds(factor_from_thousandths(500)), default_thresholds);
+    assert_eq!(thresholds(factor_from_thousandths(0)).dit, Some(1..=149));
+    assert_eq!(thresholds(factor_from_thousandths(2000)).dit, Some(1..=249));
+
+    // Padding scales with speed
+    let high_speed_thresholds = Decoder::<16>::new()
+        .with_reference_short_ms(6)
+        .build()
+        .current_thresholds()
+        .unwrap();
+    assert_eq!(high_speed_thresholds.dit, Some(1..=11));
+
+    #[cfg(not(feature = "fixed-point"))]
+    assert!(Decoder::<16>::new().with_lazy_padding(f32::NAN).try_build().is_err());
+}
+
+#[test]
+fn decoding_mark_and_space_tolerance() {
+    let decoder = |mark_tolerance, space_tolerance| {
+        Decoder::<16>::new()
+            .with_precision(Precision::Accurate)
+            .with_reference_short_ms(100)
+            .with_mark_tolerance(factor_from_thousandths(mark_tolerance))
+            .with_space_tolerance(factor_from_thousandths(space_tolerance))
+            .build()
+    };
+
+    // Tight marks and loose spaces
+    let thresholds = decoder(200, 500).current_thresholds().unwrap();
+    assert_eq!(thresholds.dit, Some(84..=125));
+    assert_eq!(thresholds.char_space, Some(201..=600));
+
+    // "EE" with a sloppy short character space, then "A" with a malformed dit
+    let mut decoder = decoder(200, 500);
+    for &(duration_ms, is_high) in &[
+        (100, true), (220, false), (100, true), (300, false),
+        (160, true), (100, false), (300, true), (300, false),
+    ] {
+        decoder.signal_event(duration_ms, is_high);
+    }
+
+    assert_eq!(decoder.message.as_str(), "EE?");
+}
+
+#[test]
+fn decoding_word_space_tolerance() {
+    let decoder = |precision, word_space_tolerance| {
+        Decoder::<16>::new()
+            .with_precision(precision)
+            .with_reference_short_ms(100)
+            .with_word_space_tolerance(factor_from_thousandths(word_space_tolerance))
+            .build()
+    };
+
+    let decode = |precision, word_space_tolerance, space_ms| {
+        let mut decoder = decoder(precision, word_space_tolerance);
+        for &(duration_ms, is_high) in &[(100, true), (space_ms, false), (300, true)] {
+            decoder.signal_event(duration_ms, is_high);
+        }
+        decoder.signal_event_end(false);
+
+        assert_eq!(decoder.get_error_count(), 0);
+        decoder.message.as_str().to_string()
+    };
+
+    // Long thinking pauses are word spaces in all precisions
+    for precision in [Precision::Lazy, Precision::Accurate, Precision::Farnsworth(factor_from_thousandths(500))] {
+        assert_eq!(decode(precision, 0, 3000), "E T");
+        assert_eq!(decode(precision, 0, 60000), "E T");
+    }
+
+    // A short word space of 6 dits
+    for precision in [Precision::Lazy, Precision::Accurate] {
+        assert_eq!(decode(precision, 0, 600), "ET");
+        assert_eq!(decode(precision, 300, 600), "E T");
+    }
+
+    let thresholds = decoder(Precision::Accurate, 0).current_thresholds().unwrap();
+    assert_eq!(thresholds.char_space, Some(201..=600));
+    assert_eq!(thresholds.word_space_min, Some(700));
+
+    let thresholds = decoder(Precision::Accurate, 300).current_thresholds().unwrap();
+    assert_eq!(thresholds.char_space, Some(201..=489));
+    assert_eq!(thresholds.word_space_min, Some(490));
+
+    // Never before the nominal character space
+    let thresholds = decoder(Precision::Accurate, 1000).current_thresholds().unwrap();
+    assert_eq!(thresholds.char_space, Some(201..=300));
+    assert_eq!(thresholds.word_space_min, Some(301));
+}
+
+#[cfg(not(feature = "small-footprint"))]
+#[test]
+fn decoding_redecode_with() {
+    const SHORT: u16 = 100;
+    let signals = [
+        (SHORT, true), (SHORT, false), (SHORT * 3, true), (SHORT * 3, false),
+        (SHORT, true), (SHORT * 7, false),
+    ];
+
+    let mut decoder = Decoder::<32>::new()
+        .with_message("HI ", true)
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(SHORT * 3)
+        .build();
+
+    for (duration_ms, is_high) in signals {
+        decoder.signal_event(duration_ms, is_high);
+    }
+    assert_eq!(decoder.message.as_str(), "HI ?E ");
+
+    // Characters before the replayed events are kept
+    decoder.redecode_with(|builder| builder.with_reference_short_ms(SHORT));
+    assert_eq!(decoder.message.as_str(), "HI AE ");
+    assert_eq!(decoder.get_elapsed_ms(), SHORT as u32 * 16);
+
+    // Replaying again with the same settings doesn't change anything
+    decoder.redecode_with(|builder| builder);
+    assert_eq!(decoder.message.as_str(), "HI AE ");
+
+    // Nothing is replayed for a new message
+    let mut log = morse_codec::message_log::MessageLog::<2, 32>::new();
+    decoder.finish_message(&mut log, 0);
+    decoder.redecode_with(|builder| builder.with_precision(Precision::Lazy));
+    assert!(decoder.message.is_empty());
+}
+
+#[test]
+fn decoding_snapshot_clone() {
+    let mut decoder = Decoder::<16>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(100)
+        .build();
+
+    decoder.decode_text("... ---", &Default::default());
+
+    // Snapshot keeps decoding independently of the original
+    let mut snapshot = decoder.clone();
+    decoder.decode_text("...", &Default::default());
+    assert_eq!(decoder.message.as_str(), "SOS");
+    assert_eq!(snapshot.message.as_str(), "SO");
+
+    snapshot.decode_text("..", &Default::default());
+    assert_eq!(snapshot.message.as_str(), "SOI");
+    assert_ne!(snapshot.message, decoder.message);
+
+    println!("{:?}", snapshot.message);
+}
+
+#[test]
+fn decoding_retimed_signals() {
+    use morse_codec::retime;
+
+    // "PARIS" at about 20 WPM with uneven keying
+    let signals = [
+        (58, true), (62, false), (190, true), (60, false), (175, true), (55, false), (64, true), (190, false),
+        (61, true), (59, false), (182, true), (185, false),
+        (60, true), (60, false), (185, true), (62, false), (57, true), (178, false),
+        (66, true), (58, false), (60, true), (190, false),
+        (58, true), (61, false), (60, true), (55, false), (62, true), (430, false),
+    ];
+
+    // Slowed down to 5 WPM, a short signal is 240 ms
+    let slow: Vec<(u16, bool)> = retime(&signals, 5).collect();
+    assert_eq!(slow.len(), signals.len());
+    assert_eq!(slow[0], (232, true));
+    assert_eq!(slow[2], (760, true));
+
+    let mut decoder = Decoder::<16>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(240)
+        .build();
+
+    for (duration_ms, is_high) in slow {
+        decoder.signal_event(duration_ms, is_high);
+    }
+
+    assert_eq!(decoder.message.as_str(), "PARIS ");
+
+    // Nothing to measure the speed with
+    assert!(retime(&[(100, false)], 5).eq([(100, false)]));
+}
+
+#[test]
+fn decoding_inverted_input() {
+    let mut decoder = Decoder::<16>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(100)
+        .with_inverted_input()
+        .build();
+
+    // Key down pulls the input low
+    for (duration_ms, is_high) in [(100, false), (100, true), (300, false), (700, true)] {
+        decoder.signal_event(duration_ms, is_high);
+    }
+    assert_eq!(decoder.message.as_str(), "A ");
+
+    // Samples of an input idling high
+    let mut samples = [true; 1300];
+    samples[..300].fill(false);
+    samples[400..500].fill(false);
+    decoder.push_samples(&samples, 1000);
+
+    assert_eq!(decoder.message.as_str(), "A N ");
+}
+
+#[test]
+fn decoding_timing_profile() {
+    use morse_codec::TimingProfile;
+
+    // Long dahs and character spaces, short word spaces
+    let profile = TimingProfile { dah_mult: 4, char_gap_mult: 5, word_gap_mult: 6 };
+    let signals = [
+        (100, true), (100, false), (400, true), (500, false),
+        (400, true), (100, false), (100, true), (600, false),
+        (400, true), (500, false),
+    ];
+
+    let mut decoder = Decoder::<16>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(100)
+        .with_timing_profile(profile)
+        .build();
+    decoder.extend_signals(signals);
+    assert_eq!(decoder.message.as_str(), "AN T");
+
+    let thresholds = decoder.current_thresholds().unwrap();
+    assert_eq!(thresholds.word_space_min, Some(600));
+
+    // Word space is taken as a character space with standard timing
+    let mut decoder = Decoder::<16>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(100)
+        .build();
+    decoder.extend_signals(signals);
+    assert_eq!(decoder.message.as_str(), "ANT");
+}
+
+#[test]
+fn decoding_error_prosign_correction() {
+    use morse_codec::decoder::DecodeEvent;
+
+    fn send_error_prosign(decoder: &mut morse_codec::decoder::MorseDecoder<16>, end_ms: u16) -> DecodeEvent {
+        for _ in 0..7 {
+            decoder.signal_event(100, true);
+            decoder.signal_event(100, false);
+        }
+        decoder.signal_event(100, true);
+
+        decoder.signal_event_ex(end_ms, false)
+    }
+
+    let mut decoder = Decoder::<16>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(100)
+        .with_error_prosign_correction()
+        .build();
+
+    decoder.decode_text("- . ... - / .-- --- .-.", &Default::default());
+    assert_eq!(decoder.message.as_str(), "TEST WOR");
+
+    // Word being keyed is removed
+    assert_eq!(send_error_prosign(&mut decoder, 300), DecodeEvent::Corrected);
+    assert_eq!(decoder.message.as_str(), "TEST ");
+    assert_eq!(decoder.message.get_edit_pos(), 5);
+    assert!(!decoder.is_word_boundary_pending());
+
+    // Word space before the prosign goes with the word
+    assert_eq!(send_error_prosign(&mut decoder, 700), DecodeEvent::Corrected);
+    assert_eq!(decoder.message.as_str(), "");
+
+    decoder.signal_event(300, true);
+    decoder.signal_event(700, false);
+    assert_eq!(decoder.message.as_str(), "T ");
+
+    // Error prosign is a decoding error without correction
+    let mut decoder = Decoder::<16>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(100)
+        .with_message("TEST", true)
+        .build();
+
+    send_error_prosign(&mut decoder, 300);
+    assert_eq!(decoder.message.as_str(), "TEST?");
+}
+
+#[test]
+fn decoding_post_processors() {
+    use morse_codec::decoder::PostProcessor;
+
+    let capitalize = PostProcessor::Char(|ch: u8, previous| match previous {
+        None | Some(b' ') => Some(ch),
+        _ => Some(ch.to_ascii_lowercase()),
+    });
+    let drop_errors = PostProcessor::Char(|ch: u8, _| (ch != b'?').then_some(ch));
+    let expand = PostProcessor::Word(|word: &[u8], buffer| {
+        (word == b"Tnx").then(|| {
+            buffer[..6].copy_from_slice(b"Thanks");
+            6
+        })
+    });
+
+    let mut decoder = Decoder::<16, u8>::new()
+        .with_post_processor(capitalize)
+        .with_post_processor(drop_errors)
+        .with_post_processor(expand)
+        .build();
+    decoder.decode_text("- -. -..- / ..-. ...... . .-. / - -. -..-", &Default::default());
+    assert_eq!(decoder.message.as_str(), "Thanks Fer Tnx");
+
+    let mut buffer = [0u8; 16];
+    assert_eq!(decoder.last_completed_word(&mut buffer), "Fer");
+
+    // Expanded word doesn't fit
+    let mut decoder = Decoder::<6, u8>::new()
+        .with_post_processor(capitalize)
+        .with_post_processor(expand)
+        .build();
+    decoder.decode_text("- -. -..- /", &Default::default());
+    assert_eq!(decoder.message.as_str(), "Tnx ");
+}
+
+#[cfg(feature = "language-model")]
+#[test]
+fn decoding_with_language_model() {
+    use morse_codec::language_model::ENGLISH;
+
+    // THE with a sloppy first dit of H and a long dit of E
+    let signals = [
+        (300, true), (300, false),
+        (220, true), (100, false), (100, true), (100, false), (100, true), (100, false), (100, true), (300, false),
+        (180, true), (700, false),
+    ];
+
+    let mut decoder = Decoder::<16>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(100)
+        .build();
+    decoder.extend_signals(signals);
+    assert_eq!(decoder.message.as_str(), "TBE ");
+
+    let mut decoder = Decoder::<16>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(100)
+        .with_language_model(&ENGLISH)
+        .build();
+    decoder.extend_signals(signals);
+    assert_eq!(decoder.message.as_str(), "THE ");
+}
+
+#[cfg(all(feature = "fugit", feature = "encoder"))]
+#[test]
+fn decoding_fugit_durations() {
+    use fugit::{Duration, MillisDurationU32};
+    use morse_codec::encoder::Encoder;
+
+    let mut encoder = Encoder::<16>::new().with_message("SOS", true).build();
+    encoder.encode_message_all();
+
+    // Timer ticking at 32768 Hz
+    let short = MillisDurationU32::millis(80).convert::<1, 32_768>();
+
+    let mut decoder = Decoder::<16>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(80)
+        .build();
+
+    for (duration, is_high) in encoder.fugit_durations(short) {
+        let duration: Duration<u64, 1, 32_768> = duration.into();
+        decoder.signal_event_fugit(duration, is_high);
+    }
+
+    assert_eq!(decoder.message.as_str(), "SOS");
+}
+
+#[test]
+fn decoding_trace_handler() {
+    use std::sync::Mutex;
+    use morse_codec::decoder::{SignalClass, TraceEvent};
+
+    static EVENTS: Mutex<Vec<TraceEvent>> = Mutex::new(Vec::new());
+
+    let mut decoder = Decoder::<16>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(100)
+        .with_trace_handler(|event| EVENTS.lock().unwrap().push(event.clone()))
+        .build();
+
+    // A
+    for (duration_ms, is_high) in [(100, true), (100, false), (300, true), (300, false)] {
+        decoder.signal_event(duration_ms, is_high);
+    }
+    assert_eq!(decoder.message.as_str(), "A");
+
+    let resolved = |duration_ms, is_high, position, tolerance_range, class| TraceEvent::SignalResolved {
+        duration_ms,
+        is_high,
+        position,
+        tolerance_range,
+        reference_short_ms: 100,
+        class,
+    };
+
+    assert_eq!(*EVENTS.lock().unwrap(), [
+        TraceEvent::CharacterStarted,
+        resolved(100, true, 0, 50..=150, SignalClass::Short),
+        resolved(100, false, 1, 50..=150, SignalClass::Short),
+        resolved(300, true, 1, 150..=450, SignalClass::Long),
+        resolved(300, false, 2, 150..=450, SignalClass::Long),
+        TraceEvent::CharacterEnded,
+        TraceEvent::BufferDecoded([Some(S), Some(L), None, None, None, None]),
+    ]);
+}
+
+#[test]
+fn decoding_trace_farnsworth_short() {
+    use std::sync::Mutex;
+    use morse_codec::decoder::{factor_from_thousandths, TraceEvent};
+
+    static EVENTS: Mutex<Vec<TraceEvent>> = Mutex::new(Vec::new());
+
+    let mut decoder = Decoder::<16>::new()
+        .with_precision(Precision::Farnsworth(factor_from_thousandths(500)))
+        .with_reference_short_ms(100)
+        .with_trace_handler(|event| EVENTS.lock().unwrap().push(event.clone()))
+        .build();
+
+    decoder.signal_event(100, true);
+    decoder.signal_event(400, false);
+
+    let farnsworth_short = EVENTS
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|event| matches!(event, TraceEvent::FarnsworthShort { .. }))
+        .cloned();
+
+    assert_eq!(farnsworth_short, Some(TraceEvent::FarnsworthShort { current_wpm: 12, reduced_wpm: 6, short_ms: 363 }));
+}
+
+#[test]
+fn decoding_duplicate_morse_codes() {
+    use morse_codec::{MorseCodeSet, MORSE_DEFAULT_CHAR};
+
+    // A and B share the same code, first one in the set wins
+    const CHARACTER_SET: CharacterSet<u8> = b" ABT";
+    const MORSE_CODE_SET: MorseCodeSet = &[
+        MORSE_DEFAULT_CHAR,
+        [Some(S), Some(L), None, None, None, None],
+        [Some(S), Some(L), None, None, None, None],
+        [Some(L), None, None, None, None, None],
+    ];
+
+    let mut decoder = Decoder::<8, u8>::new()
+        .with_character_set(CHARACTER_SET)
+        .with_morse_code_set(MORSE_CODE_SET)
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(100)
+        .build();
+
+    decoder.extend_signals([(100, true), (100, false), (300, true), (300, false), (300, true), (700, false)]);
+    assert_eq!(decoder.message.as_str(), "AT ");
+
+    // Codes that aren't in the set
+    decoder.extend_signals([(100, true), (700, false)]);
+    assert_eq!(decoder.message.as_str(), "AT ? ");
+}
+
+#[test]
+fn decoding_possible_completions() {
+    let mut decoder = Decoder::<16, u8>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(100)
+        .build();
+
+    // Every character but the word space is possible before keying
+    assert_eq!(decoder.possible_completions().count(), 51);
+    assert_eq!(decoder.possible_completions().take(4).collect::<Vec<u8>>(), b"ETIA");
+
+    decoder.signal_event(100, true);
+    assert_eq!(decoder.current_pattern(), [Some(S), None, None, None, None, None]);
+    decoder.signal_event(100, false);
+    decoder.signal_event(300, true);
+    assert_eq!(decoder.current_pattern(), [Some(S), Some(L), None, None, None, None]);
+
+    let completions: Vec<u8> = decoder.possible_completions().collect();
+    assert_eq!(completions, b"ARWLPJ+1\".@'");
+
+    // Character is decoded, anything is possible again
+    decoder.signal_event(300, false);
+    assert_eq!(decoder.message.as_str(), "A");
+    assert_eq!(decoder.possible_completions().count(), 51);
+
+    // Unresolved signal
+    decoder.signal_event(100, true);
+    decoder.signal_event(100, false);
+    decoder.signal_event(900, true);
+    assert_eq!(decoder.current_pattern(), [Some(S), None, None, None, None, None]);
+    assert_eq!(decoder.possible_completions().count(), 0);
+
+    // Manual entry
+    let mut decoder = Decoder::<16, u8>::new().build();
+    decoder.add_signal_to_character(Some(L));
+    decoder.add_signal_to_character(Some(L));
+    decoder.add_signal_to_character(Some(S));
+    let completions: Vec<u8> = decoder.possible_completions().collect();
+    assert_eq!(completions, b"GZQ7,");
+}
+
+#[test]
+fn decoding_transmission_gap() {
+    use morse_codec::decoder::{DecodeEvent, RejectReason};
+
+    let build = |separator| {
+        Decoder::<16, u8>::new()
+            .with_precision(Precision::Accurate)
+            .with_reference_short_ms(100)
+            .with_transmission_gap(5, separator)
+            .build()
+    };
+
+    // Idle before the first transmission
+    let mut decoder = build(Some(b'|'));
+    assert_eq!(decoder.signal_event_ex(10000, false), DecodeEvent::Rejected(RejectReason::IdleLow));
+
+    // "E", a long gap at once, then "T"
+    decoder.signal_event(100, true);
+    assert_eq!(decoder.signal_event_ex(5000, false), DecodeEvent::TransmissionEnded);
+    assert_eq!(decoder.message.as_str(), "E|");
+    decoder.signal_event(300, true);
+    decoder.signal_event(300, false);
+    assert_eq!(decoder.message.as_str(), "E|T");
+
+    // Gap split into many low signals ends the transmission only once
+    let mut decoder = build(None);
+    decoder.signal_event(100, true);
+    assert_eq!(decoder.signal_event_ex(700, false), DecodeEvent::WordCompleted);
+    assert_eq!(decoder.signal_event_ex(2000, false), DecodeEvent::Rejected(RejectReason::IdleLow));
+    assert_eq!(decoder.signal_event_ex(1000, false), DecodeEvent::TransmissionEnded);
+    assert_eq!(decoder.signal_event_ex(5000, false), DecodeEvent::Rejected(RejectReason::IdleLow));
+    assert_eq!(decoder.message.as_str(), "E ");
+
+    // Off by default
+    let mut decoder = Decoder::<16, u8>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(100)
+        .build();
+    decoder.signal_event(100, true);
+    assert_eq!(decoder.signal_event_ex(60000, false), DecodeEvent::WordCompleted);
+}
+
+#[test]
+fn decoding_training_faults() {
+    use morse_codec::{
+        decoder::{DecodeEvent, TimingElement, TimingFault},
+        Error,
+    };
+
+    let build = || {
+        Decoder::<16, u8>::new()
+            .with_precision(Precision::Training)
+            .with_reference_short_ms(100)
+            .with_signal_tolerance(factor_from_thousandths(200))
+            .build()
+    };
+
+    let send = |decoder: &mut morse_codec::decoder::MorseDecoder<16, u8>, signals: &[(u16, bool)]| {
+        signals
+            .iter()
+            .map(|&(duration_ms, is_high)| decoder.signal_event_ex(duration_ms, is_high))
+            .last()
+    };
+
+    // Well keyed "A" decodes as usual
+    let mut decoder = build();
+    let event = send(&mut decoder, &[(100, true), (100, false), (300, true), (300, false)]);
+    assert_eq!(event, Some(DecodeEvent::CharCompleted(b'A')));
+
+    // Third dit of "S" too long
+    let event = send(&mut decoder, &[(100, true), (100, false), (100, true), (100, false), (150, true), (300, false)]);
+    let Some(DecodeEvent::Faulted(fault)) = event else {
+        panic!("Expected a fault, got {:?}", event);
+    };
+    assert_eq!(fault, TimingFault { element: TimingElement::Dit, ordinal: 3, expected_ms: 100, actual_ms: 150 });
+    assert_eq!(fault.deviation_percent(), 50);
+    assert_eq!(decoder.message.as_str(), "A?");
+    assert_eq!(decoder.get_error_count(), 1);
+
+    // Space between the signals of "N" too short. Only the first fault of a character is reported.
+    let event = send(&mut decoder, &[(300, true), (60, false), (220, true), (300, false)]);
+    let Some(DecodeEvent::Faulted(fault)) = event else {
+        panic!("Expected a fault, got {:?}", event);
+    };
+    assert_eq!((fault.element, fault.ordinal, fault.deviation_percent()), (TimingElement::SignalSpace, 1, -40));
+    assert_eq!(decoder.get_reference_short(), 100);
+
+    // Space after "E" too short to end it, so it's taken as a character space
+    let event = send(&mut decoder, &[(100, true), (210, false), (100, true), (300, false)]);
+    let Some(DecodeEvent::Faulted(fault)) = event else {
+        panic!("Expected a fault, got {:?}", event);
+    };
+    assert_eq!((fault.element, fault.ordinal, fault.deviation_percent()), (TimingElement::CharSpace, 1, -30));
+    assert_eq!(decoder.message.as_str(), "A???");
+
+    // Accurate precision decodes the same signals without faults
+    let mut decoder = Decoder::<16, u8>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(100)
+        .with_signal_tolerance(factor_from_thousandths(200))
+        .build();
+    let event = send(&mut decoder, &[(100, true), (100, false), (100, true), (100, false), (150, true), (300, false)]);
+    assert_eq!(event, Some(DecodeEvent::CharCompleted(b'I')));
+
+    // Training needs a reference short to compare signals with
+    let result = Decoder::<16, u8>::new().with_precision(Precision::Training).try_build();
+    assert_eq!(result.err(), Some(Error::MissingReferenceShort));
+}
+
+#[test]
+fn decoding_with_preamble_lock() {
+    use morse_codec::decoder::{DecodeEvent, RejectReason};
+
+    // Signals of a text at a short duration, every other high signal a bit longer or shorter
+    let signals = |text: &str, short_ms: u16| {
+        let mut encoder = morse_codec::encoder::Encoder::<32, u8>::new().with_message(text, true).build();
+        encoder.encode_message_all();
+
+        encoder
+            .signal_durations(short_ms as u32, morse_codec::encoder::TimeUnit::Milliseconds)
+            .enumerate()
+            .map(|(index, (duration_ms, is_high))| match index % 4 {
+                0 => (duration_ms as u16 + short_ms / 10, is_high),
+                2 => (duration_ms as u16 - short_ms / 10, is_high),
+                _ => (duration_ms as u16, is_high),
+            })
+            .collect::<Vec<_>>()
+    };
+
+    // Decoder expects a very different speed, preamble fixes it
+    let mut decoder = Decoder::<32, u8>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(200)
+        .with_preamble_lock("CQ CQ")
+        .build();
+
+    let events: Vec<_> = signals("CQ CQ DE K1ABC", 60)
+        .into_iter()
+        .map(|(duration_ms, is_high)| decoder.signal_event_ex(duration_ms, is_high))
+        .collect();
+    decoder.signal_event_end(false);
+
+    assert!(decoder.is_preamble_locked());
+    assert_eq!(decoder.get_reference_short(), 60);
+    assert!(events.contains(&DecodeEvent::Rejected(RejectReason::Preamble)));
+    assert_eq!(decoder.message.as_str(), "DE K1ABC");
+
+    // Repeated V characters are all left out
+    let mut decoder = Decoder::<32, u8>::new().with_preamble_lock("VVV").build();
+    decoder.extend_signals(signals("VVV VVV TEST", 80));
+    decoder.signal_event_end(false);
+    assert!(decoder.is_preamble_locked());
+    assert_eq!(decoder.message.as_str(), "TEST");
+
+    // No preamble sent, decoding goes on as usual
+    let mut decoder = Decoder::<32, u8>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(80)
+        .with_preamble_lock("VVV")
+        .build();
+    decoder.extend_signals(signals("SOS VVV", 80));
+    decoder.signal_event_end(false);
+    assert!(!decoder.is_preamble_locked());
+    assert_eq!(decoder.get_reference_short(), 80);
+    assert_eq!(decoder.message.as_str(), "SOS VVV");
+
+    // Dahs too short for the dits are not a V
+    let mut decoder = Decoder::<32, u8>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(80)
+        .with_preamble_lock("V")
+        .build();
+    decoder.extend_signals([(80, true), (80, false), (80, true), (80, false), (80, true), (80, false), (120, true), (240, false)]);
+    assert!(!decoder.is_preamble_locked());
+}
+
+#[cfg(not(feature = "small-footprint"))]
+#[test]
+fn decoding_redecode_with_preamble_lock() {
+    let mut encoder = morse_codec::encoder::Encoder::<16, u8>::new().with_message("VV TEST", true).build();
+    encoder.encode_message_all();
+
+    let mut decoder = Decoder::<16, u8>::new().with_preamble_lock("VV").build();
+    decoder.extend_signals(
+        encoder
+            .signal_durations(60, morse_codec::encoder::TimeUnit::Milliseconds)
+            .map(|(duration_ms, is_high)| (duration_ms as u16, is_high)),
+    );
+    assert_eq!(decoder.message.as_str(), "TEST");
+
+    // Preamble is locked on again while replaying, so it's still left out
+    decoder.redecode_with(|builder| builder);
+    assert!(decoder.is_preamble_locked());
+    assert_eq!(decoder.message.as_str(), "TEST");
+}
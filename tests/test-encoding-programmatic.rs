@@ -7,6 +7,8 @@ use morse_codec::{
     encoder::{
         Encoder,
         MorseCharray,
+        SDMArray,
+        SdmChunk,
         SDM,
     },
     Character,
@@ -301,3 +303,859 @@ fn message_position_clamping() {
     println!("Message in wrapping encoder as morse code:");
     encoded_charrays.for_each(|charray| print_morse_charray(charray.unwrap()));
 }
+
+#[test]
+fn encoding_with_explicit_character_types() {
+    const MESSAGE_MAX_LENGTH: usize = 16;
+
+    println!("TEST ENCODING WITH ASCII AND UTF8 ENCODERS SIDE BY SIDE");
+
+    let mut ascii_encoder = Encoder::<MESSAGE_MAX_LENGTH, u8>::new().build();
+    let mut utf8_encoder = Encoder::<MESSAGE_MAX_LENGTH, char>::new().build();
+
+    ascii_encoder.encode_slice("sos").unwrap();
+    utf8_encoder.encode_slice("sos").unwrap();
+
+    println!("ASCII encoder message: {}", ascii_encoder.message.as_str());
+    println!("UTF8 encoder message: {}", utf8_encoder.message.as_str());
+
+    assert_eq!(ascii_encoder.message.as_str(), "SOS");
+    assert_eq!(utf8_encoder.message.as_str(), "SOS");
+
+    let ascii_morse: Vec<_> = ascii_encoder.get_encoded_message_as_morse_charrays().collect();
+    let utf8_morse: Vec<_> = utf8_encoder.get_encoded_message_as_morse_charrays().collect();
+
+    assert_eq!(ascii_morse[1].unwrap()[0], Some(b'-'));
+    assert_eq!(utf8_morse[1].unwrap()[0], Some('-'));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn encoding_to_std_types() {
+    use morse_codec::decoder::{Decoder, Precision};
+
+    const MESSAGE_MAX_LENGTH: usize = 16;
+
+    let mut encoder = Encoder::<MESSAGE_MAX_LENGTH>::new()
+        .with_message("SOS SOS", true).build();
+
+    let morse_string = encoder.encode_to_string();
+    println!("Morse string: {}", morse_string);
+    println!("Message string: {}", encoder.message);
+
+    assert_eq!(morse_string, "... --- ... / ... --- ...");
+
+    let durations = encoder.get_encoded_message_as_durations(100);
+    println!("Durations: {:?}", durations);
+
+    let mut decoder = Decoder::<MESSAGE_MAX_LENGTH>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(100)
+        .build();
+
+    durations.iter().for_each(|&(duration, is_high)| decoder.signal_event(duration, is_high));
+
+    assert_eq!(decoder.message.to_string(), "SOS SOS");
+}
+
+#[test]
+fn encoding_buzzer_schedule() {
+    use morse_codec::buzzer::{Buzzer, BuzzerStep, PwmSettings};
+
+    const MESSAGE_MAX_LENGTH: usize = 8;
+
+    // 16 MHz AVR timer without prescaler and a 800 Hz sidetone
+    let buzzer = Buzzer::new(16_000_000, 800);
+
+    assert_eq!(buzzer.pwm_settings(50), PwmSettings { period_ticks: 20_000, duty_ticks: 10_000 });
+    assert_eq!(buzzer.pwm_settings(150).duty_ticks, 20_000);
+
+    let mut encoder = Encoder::<MESSAGE_MAX_LENGTH>::new().build();
+    encoder.encode_character(&(b'A' as Character)).unwrap();
+
+    let sdm = encoder.get_last_char_as_sdm().unwrap();
+    let steps: Vec<BuzzerStep> = buzzer.gpio_schedule(&sdm, 60).collect();
+
+    println!("Buzzer schedule for A: {:?}", steps);
+
+    assert_eq!(steps, vec![
+        BuzzerStep::Tone { toggles: 96, half_period_ticks: 10_000 },
+        BuzzerStep::Silence { ticks: 960_000 },
+        BuzzerStep::Tone { toggles: 288, half_period_ticks: 10_000 },
+        BuzzerStep::Silence { ticks: 2_880_000 },
+    ]);
+}
+
+#[test]
+fn encoding_sample_stream() {
+    const MESSAGE_MAX_LENGTH: usize = 8;
+
+    let mut encoder = Encoder::<MESSAGE_MAX_LENGTH>::new()
+        .with_message("E E", true).build();
+    encoder.encode_message_all();
+
+    // 20 WPM means 60 ms short signals, at 1 kHz that's 60 samples
+    let samples: Vec<bool> = encoder.sample_stream(1000, 20).collect();
+
+    let transitions: Vec<(bool, usize)> = samples
+        .chunk_by(|a, b| a == b)
+        .map(|run| (run[0], run.len()))
+        .collect();
+
+    println!("Sample stream runs: {:?}", transitions);
+
+    // Dit, word space (merged with character space), dit, character space
+    assert_eq!(transitions, vec![(true, 60), (false, 420), (true, 60), (false, 180)]);
+}
+
+#[test]
+fn encoding_sdm_chunks() {
+    const MESSAGE_MAX_LENGTH: usize = 8;
+
+    let mut encoder = Encoder::<MESSAGE_MAX_LENGTH>::new()
+        .with_message("AT", true).build();
+    encoder.encode_message_all();
+
+    let chunks: Vec<SdmChunk> = encoder.get_encoded_message_as_sdm_chunks().collect();
+
+    for chunk in chunks.iter() {
+        println!("{:?} at {}: {:?}", chunk.ch, chunk.index, chunk.sdm);
+    }
+
+    let a = b'A' as Character;
+    let t = b'T' as Character;
+
+    assert_eq!(chunks, vec![
+        SdmChunk { index: 0, ch: a, sdm: SDM::High(1) },
+        SdmChunk { index: 0, ch: a, sdm: SDM::Low(1) },
+        SdmChunk { index: 0, ch: a, sdm: SDM::High(3) },
+        SdmChunk { index: 0, ch: a, sdm: SDM::Low(3) },
+        SdmChunk { index: 1, ch: t, sdm: SDM::High(3) },
+        SdmChunk { index: 1, ch: t, sdm: SDM::Low(3) },
+    ]);
+}
+
+#[test]
+fn encoding_playback_pause_seek() {
+    const MESSAGE_MAX_LENGTH: usize = 8;
+
+    let mut encoder = Encoder::<MESSAGE_MAX_LENGTH>::new()
+        .with_message("ET", true).build();
+    encoder.encode_message_all();
+
+    let mut player = morse_codec::playback::Player::new();
+    let mut played = Vec::new();
+
+    // Play E, pause in the middle of T
+    for _ in 0..3 {
+        played.push(player.next_signal(&encoder).unwrap().sdm);
+    }
+
+    player.pause();
+    assert!(player.is_paused());
+    assert!(player.next_signal(&encoder).is_none());
+    assert_eq!(player.position(), 1);
+
+    // User missed the E, go back to it
+    player.seek_to_char(0);
+    player.resume();
+
+    while let Some(chunk) = player.next_signal(&encoder) {
+        played.push(chunk.sdm);
+    }
+
+    println!("Played signals: {:?}", played);
+
+    assert!(player.is_finished(&encoder));
+    assert_eq!(played, vec![
+        SDM::High(1), SDM::Low(3), SDM::High(3),
+        SDM::High(1), SDM::Low(3), SDM::High(3), SDM::Low(3),
+    ]);
+}
+
+#[test]
+fn encoding_beacon() {
+    use morse_codec::playback::{Beacon, BeaconStep};
+
+    let mut encoder = Encoder::<8>::new()
+        .with_message("ET", true).build();
+    encoder.encode_message_all();
+
+    let mut beacon = Beacon::new(5000);
+    let mut steps = Vec::new();
+
+    while beacon.repetitions() < 2 {
+        match beacon.next_step(&encoder).unwrap() {
+            BeaconStep::Signal(chunk) => steps.push(Some(chunk.sdm)),
+            BeaconStep::Gap(ms) => {
+                assert_eq!(ms, 5000);
+                steps.push(None);
+            }
+        }
+    }
+
+    let message = [
+        Some(SDM::High(1)), Some(SDM::Low(3)), Some(SDM::High(3)), Some(SDM::Low(3)),
+    ];
+    let expected: Vec<Option<SDM>> = message.iter().chain(&[None]).chain(&message).chain(&[None]).copied().collect();
+    assert_eq!(steps, expected);
+
+    beacon.pause();
+    assert!(beacon.next_step(&encoder).is_none());
+    beacon.resume();
+
+    // Nothing to play without encoded signals
+    let empty_encoder = Encoder::<8>::new().build();
+    let mut beacon = Beacon::new(5000);
+    assert!(beacon.next_step(&empty_encoder).is_none());
+}
+
+#[test]
+fn encoding_id_timer() {
+    use morse_codec::playback::{IdTimer, Player};
+
+    let mut transmission = Encoder::<8>::new()
+        .with_message("TT", true).build();
+    transmission.encode_message_all();
+
+    let mut id = Encoder::<8>::new()
+        .with_message("E", true).build();
+    id.encode_message_all();
+
+    let mut timer = IdTimer::new(1000);
+    let mut player = Player::new();
+    let mut played = Vec::new();
+
+    // First signal of T, then the ID gets due in the middle of T
+    played.push(timer.next_signal(&mut player, &transmission, &id).unwrap().ch);
+    timer.tick(1000);
+    assert!(timer.is_due());
+
+    while let Some(chunk) = timer.next_signal(&mut player, &transmission, &id) {
+        played.push(chunk.ch);
+    }
+
+    let (t, e) = (b'T' as Character, b'E' as Character);
+    assert_eq!(played, vec![t, t, e, e, t, t]);
+    assert!(!timer.is_due());
+    assert!(!timer.is_sending_id());
+
+    // ID is also sent after the transmission is finished
+    timer.tick(1500);
+    assert_eq!(timer.next_signal(&mut player, &transmission, &id).unwrap().ch, e);
+}
+
+#[test]
+fn encoding_qrss_durations() {
+    use morse_codec::encoder::TimeUnit;
+
+    let mut encoder = Encoder::<8>::new()
+        .with_message("E E", true).build();
+    encoder.encode_message_all();
+
+    // QRSS60, a dit is a minute long and a word space 7 minutes
+    let durations: Vec<(u32, bool)> = encoder.signal_durations(60_000, TimeUnit::Seconds).collect();
+    assert_eq!(durations, vec![(60, true), (420, false), (60, true), (180, false)]);
+
+    let durations: Vec<(u32, bool)> = encoder.signal_durations(3_000, TimeUnit::Deciseconds).collect();
+    assert_eq!(durations, vec![(30, true), (210, false), (30, true), (90, false)]);
+
+    let durations: Vec<(u32, bool)> = encoder.signal_durations(60_000, TimeUnit::Milliseconds).collect();
+    assert_eq!(durations[1], (420_000, false));
+
+    // Millisecond durations for the decoder saturate instead of overflowing
+    #[cfg(feature = "std")]
+    {
+        let durations = encoder.get_encoded_message_as_durations(60_000);
+        assert_eq!(durations[1], (u16::MAX, false));
+    }
+}
+
+#[test]
+fn encoding_farnsworth_durations() {
+    use morse_codec::FarnsworthTiming;
+
+    let mut encoder = Encoder::<8>::new()
+        .with_message("TE T", true).build();
+    encoder.encode_message_all();
+
+    let durations: Vec<(u32, bool)> = encoder.farnsworth_durations(FarnsworthTiming::new(20, 10)).collect();
+    assert_eq!(durations, vec![
+        (180, true), (653, false), (60, true), (1525, false), (180, true), (653, false),
+    ]);
+}
+
+#[test]
+fn encoding_signal_schedule() {
+    let mut encoder = Encoder::<8>::new()
+        .with_message("TE T", true).build();
+    encoder.encode_message_all();
+
+    let schedule: Vec<(u32, u32, bool)> = encoder.signal_schedule(100).collect();
+    assert_eq!(schedule, vec![
+        (0, 300, true), (300, 300, false), (600, 100, true), (700, 700, false), (1400, 300, true), (1700, 300, false),
+    ]);
+
+    // Same signals as the durations
+    assert!(schedule.iter().map(|&(_, duration, is_high)| (duration, is_high))
+        .eq(encoder.signal_durations(100, morse_codec::encoder::TimeUnit::Milliseconds)));
+}
+
+#[test]
+fn encoding_timing_profile() {
+    use morse_codec::{encoder::TimeUnit, TimingProfile};
+
+    let mut encoder = Encoder::<8>::new()
+        .with_message("E T", true)
+        .with_timing_profile(TimingProfile { dah_mult: 4, char_gap_mult: 5, word_gap_mult: 6 })
+        .build();
+    encoder.encode_message_all();
+
+    let durations: Vec<(u32, bool)> = encoder.signal_durations(100, TimeUnit::Milliseconds).collect();
+    assert_eq!(durations, vec![(100, true), (600, false), (400, true), (500, false)]);
+
+    // Multipliers too small to tell signals apart are raised
+    let mut encoder = Encoder::<8>::new()
+        .with_message("T T", true)
+        .with_timing_profile(TimingProfile { dah_mult: 0, char_gap_mult: 0, word_gap_mult: 0 })
+        .build();
+    encoder.encode_message_all();
+
+    let sdm_arrays: Vec<SDMArray> = encoder.get_encoded_message_as_sdm_arrays().flatten().collect();
+    assert_eq!(sdm_arrays[0][..2], [SDM::High(2), SDM::Low(2)]);
+    assert_eq!(sdm_arrays[1][0], SDM::Low(3));
+}
+
+#[test]
+fn encoding_speed_ramp() {
+    let mut encoder = Encoder::<8>::new()
+        .with_message("E E", true)
+        .with_speed_ramp(20, 10)
+        .build();
+    encoder.encode_message_all();
+
+    // Word space is at 15 WPM
+    let durations: Vec<(u32, bool)> = encoder.ramped_durations().collect();
+    assert_eq!(durations, vec![(60, true), (560, false), (120, true), (360, false)]);
+
+    let mut encoder = Encoder::<8>::new().with_message("E", true).build();
+    encoder.encode_message_all();
+
+    let durations: Vec<(u32, bool)> = encoder.ramped_durations().collect();
+    assert_eq!(durations, vec![(60, true), (180, false)]);
+
+    // Speeds are at least 1 WPM
+    let mut encoder = Encoder::<8>::new()
+        .with_message("E", true)
+        .with_speed_ramp(0, 0)
+        .build();
+    encoder.encode_message_all();
+
+    assert!(encoder.ramped_durations().eq([(1200, true), (3600, false)]));
+}
+
+#[test]
+fn encoding_transmit_chunks() {
+    use morse_codec::encoder::TransmitChunk;
+
+    let mut encoder = Encoder::<16>::new().with_message("TEST  OK", true).build();
+    encoder.encode_message_all();
+
+    // At 12 WPM a dit is 100 ms. TEST is 300 + 300 + 100 + 300 + 500 + 300 + 300 = 2100 ms
+    let chunks: Vec<TransmitChunk> = encoder.transmit_chunks(2400, 12).collect();
+    assert_eq!(chunks, vec![
+        TransmitChunk { start: 0, end: 4, duration_ms: 2100, continues: true },
+        TransmitChunk { start: 6, end: 8, duration_ms: 2300, continues: false },
+    ]);
+
+    // Durations of a chunk add up to its duration
+    for chunk in &chunks {
+        let total: u32 = encoder.transmit_chunk_durations(chunk, 12).map(|(duration_ms, _)| duration_ms).sum();
+        assert_eq!(total, chunk.duration_ms);
+    }
+
+    let durations: Vec<(u32, bool)> = encoder.transmit_chunk_durations(&chunks[0], 12).collect();
+    assert_eq!(durations, vec![
+        (300, true), (300, false), (100, true), (300, false),
+        (100, true), (100, false), (100, true), (100, false), (100, true), (300, false), (300, true),
+    ]);
+
+    // Words longer than the window are split between characters
+    let ranges: Vec<(usize, usize)> = encoder.transmit_chunks(1000, 12)
+        .map(|chunk| (chunk.start, chunk.end))
+        .collect();
+    assert_eq!(ranges, vec![(0, 2), (2, 3), (3, 4), (6, 7), (7, 8)]);
+
+    // A character longer than the window is a chunk of its own
+    let chunk = encoder.transmit_chunks(100, 12).nth(5).unwrap();
+    assert_eq!(chunk, TransmitChunk { start: 7, end: 8, duration_ms: 900, continues: false });
+
+    let encoder = Encoder::<16>::new().with_message("  ", true).build();
+    assert_eq!(encoder.transmit_chunks(1000, 12).next(), None);
+}
+
+#[test]
+fn encoding_set_char_at() {
+    const MESSAGE_MAX_LENGTH: usize = 8;
+
+    let mut encoder = Encoder::<MESSAGE_MAX_LENGTH>::new()
+        .with_message("SOS", true).build();
+    encoder.encode_message_all();
+
+    encoder.set_char_at(1, &(b'e' as Character)).unwrap();
+    // Setting a character after the end puts a word space before it
+    encoder.set_char_at(4, &(b'T' as Character)).unwrap();
+
+    let sdms: Vec<SDMArray> = encoder.get_encoded_message_as_sdm_arrays().flatten().collect();
+
+    println!("Message after set_char_at: {}", encoder.message);
+
+    assert_eq!(encoder.message.as_str(), "SES T");
+    assert_eq!(&sdms[1][..2], &[SDM::High(1), SDM::Low(3)]);
+    assert_eq!(sdms[3][0], SDM::Low(7));
+    assert_eq!(&sdms[4][..2], &[SDM::High(3), SDM::Low(3)]);
+    assert_eq!(encoder.message.get_edit_pos(), 3);
+
+    assert!(encoder.set_char_at(MESSAGE_MAX_LENGTH, &(b'E' as Character)).is_err());
+    assert!(encoder.set_char_at(0, &(b'~' as Character)).is_err());
+}
+
+#[test]
+fn encoding_from_message() {
+    use morse_codec::{message::{Message, MessageRef}, Error};
+
+    let heard = Message::<32>::new("CQ DE K1ABC", true, false);
+
+    let mut encoder = Encoder::<16>::new().with_message("R ", true).build();
+    encoder.encode_from_message(&heard).unwrap();
+
+    assert_eq!(encoder.message.as_str(), "R CQ DE K1ABC");
+    assert_eq!(encoder.get_encoded_message_as_sdm_arrays().count(), 13);
+
+    // Doesn't fit
+    assert_eq!(encoder.encode_from_message(&heard), Err(Error::MessageTooLong));
+    assert_eq!(encoder.message.as_str(), "R CQ DE K1ABC");
+
+    // Messages over a buffer of the client code
+    let mut buffer = [b' ' as Character; 8];
+    let mut heard = MessageRef::from_storage(&mut buffer[..]);
+    heard.set_message("73", false).unwrap();
+
+    let mut encoder = Encoder::<16>::new().build();
+    encoder.encode_from_message(&heard).unwrap();
+    assert_eq!(encoder.message.as_str(), "73");
+
+    heard.put_char_at(0, b'~' as Character).unwrap();
+    assert_eq!(encoder.encode_from_message(&heard), Err(Error::UnknownCharacter));
+}
+
+#[test]
+fn encoding_lazily_after_message_edits() {
+    const MESSAGE_MAX_LENGTH: usize = 8;
+
+    // No explicit encoding calls
+    let mut encoder = Encoder::<MESSAGE_MAX_LENGTH>::new()
+        .with_message("SOS", true).build();
+
+    encoder.message.put_char_at(1, b'T' as Character).unwrap();
+
+    let charrays: Vec<MorseCharray> = encoder.get_encoded_message_as_morse_charrays().flatten().collect();
+    print_morse_charray(charrays[1]);
+    println!();
+
+    assert_eq!(charrays.len(), 3);
+    assert_eq!(charrays[1][0], Some(b'-' as Character));
+    assert_eq!(charrays[1][1], None);
+
+    // A character that's not in the character set has no encoding
+    encoder.message.put_char_at(2, b'~' as Character).unwrap();
+    assert!(encoder.get_encoded_message_as_sdm_arrays().nth(2).unwrap().is_none());
+}
+
+#[test]
+fn encoding_cached_chars_after_message_edits() {
+    const MESSAGE_MAX_LENGTH: usize = 16;
+
+    // Encoded characters of the message should always match an encoder built from scratch
+    fn assert_fresh(encoder: &morse_codec::encoder::MorseEncoder<MESSAGE_MAX_LENGTH>) {
+        let fresh = Encoder::<MESSAGE_MAX_LENGTH>::new()
+            .with_message(&encoder.message.to_string(), true).build();
+
+        let sdms: Vec<Option<SDMArray>> = encoder.get_encoded_message_as_sdm_arrays().collect();
+        let fresh_sdms: Vec<Option<SDMArray>> = fresh.get_encoded_message_as_sdm_arrays().collect();
+        assert_eq!(sdms, fresh_sdms, "Stale encoding of message {}", encoder.message);
+    }
+
+    let mut encoder = Encoder::<MESSAGE_MAX_LENGTH>::new()
+        .with_message("SOS", true).build();
+    assert_fresh(&encoder);
+
+    encoder.set_char_at(1, &(b'T' as Character)).unwrap();
+    assert_fresh(&encoder);
+
+    encoder.encode_character(&(b'E' as Character)).unwrap();
+    assert_fresh(&encoder);
+
+    // Changes to the message outside of the encoder
+    encoder.message.put_char_at(0, b'A' as Character).unwrap();
+    assert_fresh(&encoder);
+    encoder.message.add_char(b'R' as Character);
+    assert_fresh(&encoder);
+
+    encoder.set_char_at(4, &(b'N' as Character)).unwrap();
+    assert_fresh(&encoder);
+
+    // Empty characters before the edit position become word spaces
+    encoder.message.set_edit_pos(8);
+    encoder.encode_slice("K").unwrap();
+    assert_eq!(encoder.message.as_str(), "ATSEN   K");
+    assert_fresh(&encoder);
+
+    encoder.message.clear();
+    assert_fresh(&encoder);
+    encoder.encode_slice("CQ").unwrap();
+    assert_fresh(&encoder);
+}
+
+#[test]
+fn encoding_with_glyphs() {
+    use morse_codec::encoder::{UNICODE_DAH, UNICODE_DIT};
+
+    let encoder = Encoder::<8, char>::new()
+        .with_message("A B", true)
+        .with_unicode_glyphs()
+        .with_word_separator("||")
+        .build();
+
+    let charrays: Vec<String> = encoder
+        .get_encoded_message_as_morse_charrays()
+        .flatten()
+        .map(|charray| charray.iter().flatten().collect())
+        .collect();
+
+    assert_eq!(UNICODE_DIT, '·');
+    assert_eq!(UNICODE_DAH, '−');
+    assert_eq!(charrays, vec!["·−", "||", "−···"]);
+
+    // Glyphs that can't be represented by u8 are skipped in the separator
+    let encoder = Encoder::<8, u8>::new()
+        .with_message("E T", true)
+        .with_glyphs(b'o', b'=')
+        .with_word_separator("·/")
+        .build();
+
+    let charrays: Vec<String> = encoder
+        .get_encoded_message_as_morse_charrays()
+        .flatten()
+        .map(|charray| charray.iter().flatten().map(|&ch| ch as char).collect())
+        .collect();
+
+    assert_eq!(charrays, vec!["o", "/", "="]);
+}
+
+#[test]
+fn encoding_text_format() {
+    use morse_codec::{decoder::Decoder, text::TextFormat};
+
+    let format = TextFormat {
+        dah: '_',
+        char_separator: '\t',
+        word_separator: "|",
+        ..TextFormat::default()
+    };
+
+    let encoder = Encoder::<16>::new().with_message("SOS CQ", true).build();
+
+    let mut text = String::new();
+    encoder.write_text(&TextFormat::DEFAULT, &mut text).unwrap();
+    assert_eq!(text, "... --- ... / -.-. --.-");
+
+    let mut text = String::new();
+    encoder.write_text(&format, &mut text).unwrap();
+    assert_eq!(text, "...\t___\t...\t|\t_._.\t__._");
+
+    // Decoding the text back with the same format
+    let mut decoder = Decoder::<16>::new().build();
+    decoder.decode_text(&text, &format);
+    assert_eq!(decoder.message.as_str(), "SOS CQ");
+
+    // Prosigns, word separators without spaces and junk
+    let mut decoder = Decoder::<16>::new().build();
+    decoder.decode_text("<AR>/.- <bt> ..x", &TextFormat::DEFAULT);
+    assert_eq!(decoder.message.as_str(), "+ A=?");
+    assert_eq!(decoder.get_error_count(), 1);
+}
+
+#[test]
+fn encoding_prosign_brackets() {
+    let mut encoder = Encoder::<16>::new().build();
+
+    encoder.encode_slice("<bt> HI <AR>").unwrap();
+
+    // Prosigns take a single message position
+    assert_eq!(encoder.message.as_str(), "= HI +");
+    assert_eq!(encoder.message.len(), 6);
+
+    // Signals of AR run together as a single character .-.-.
+    let ar: Vec<SDM> = encoder
+        .get_last_char_as_sdm()
+        .unwrap()
+        .into_iter()
+        .filter(|sdm| *sdm != SDM::Empty)
+        .collect();
+
+    assert_eq!(ar, vec![
+        SDM::High(1), SDM::Low(1), SDM::High(3), SDM::Low(1), SDM::High(1),
+        SDM::Low(1), SDM::High(3), SDM::Low(1), SDM::High(1), SDM::Low(3),
+    ]);
+
+    // SOS prosign is longer than any character in the character set
+    let mut encoder = Encoder::<16>::new().build();
+    assert!(encoder.encode_slice("<SOS> HELLO").is_err());
+    assert!(encoder.message.is_empty());
+}
+
+#[test]
+fn encoding_unknown_char_policy() {
+    use morse_codec::encoder::UnknownCharPolicy;
+
+    // Default policy returns an error
+    let mut encoder = Encoder::<16, char>::new().build();
+    assert!(encoder.encode_slice("hi 🙂").is_err());
+
+    let mut encoder = Encoder::<16, char>::new()
+        .with_unknown_char_policy(UnknownCharPolicy::Skip)
+        .build();
+    encoder.encode_slice("hi 🙂 ok").unwrap();
+    assert_eq!(encoder.message.as_str().to_string(), "HI  OK");
+
+    let mut encoder = Encoder::<16, char>::new()
+        .with_unknown_char_policy(UnknownCharPolicy::SubstituteWith('?'))
+        .build();
+    encoder.encode_slice("hi 🙂").unwrap();
+    assert_eq!(encoder.message.as_str().to_string(), "HI ?");
+
+    // Policy applies to characters already in the message
+    let mut encoder = Encoder::<16>::new()
+        .with_message("A%B", true)
+        .with_unknown_char_policy(UnknownCharPolicy::SubstituteWith(b'X' as Character))
+        .build();
+    assert!(encoder.try_encode_message_all().is_ok());
+
+    encoder.encode_message_all();
+    assert_eq!(encoder.message.as_str(), "AXB");
+
+    let mut encoder = Encoder::<16>::new()
+        .with_message("A%B", true)
+        .with_unknown_char_policy(UnknownCharPolicy::Skip)
+        .build();
+    encoder.encode_message_all();
+    assert_eq!(encoder.get_encoded_message_as_morse_charrays().flatten().count(), 2);
+}
+
+#[cfg(feature = "utf8")]
+#[test]
+fn encoding_with_transliteration() {
+    use morse_codec::charsets::{FRENCH_TRANSLITERATION, GERMAN_TRANSLITERATION};
+
+    let mut encoder = Encoder::<16>::new()
+        .with_transliteration(GERMAN_TRANSLITERATION)
+        .build();
+
+    encoder.encode_slice("Grüße ÄÖ").unwrap();
+    assert_eq!(encoder.message.as_str().to_string(), "GRUESSE AEOE");
+
+    // Transliteration works with u8 encoders too, accented letters are not dropped
+    let mut encoder = Encoder::<16, u8>::new()
+        .with_transliteration(FRENCH_TRANSLITERATION)
+        .build();
+
+    encoder.encode_slice("çà et là").unwrap();
+    encoder.encode_character(&b'!').unwrap_err();
+    assert_eq!(encoder.message.as_str(), "CA ET LA");
+
+    let mut encoder = Encoder::<16>::new()
+        .with_transliteration(&[('œ', "OE")])
+        .build();
+
+    encoder.encode_character(&'Œ').unwrap();
+    assert_eq!(encoder.message.as_str().to_string(), "OE");
+}
+
+#[test]
+fn encoding_accented_characters() {
+    use morse_codec::{
+        charsets::{EXTENDED_MORSE_CODE_SET, EXTENDED_UTF8_CHARACTER_SET},
+        decoder::Decoder,
+        text::TextFormat,
+    };
+
+    let mut encoder = Encoder::<16, char>::new()
+        .with_character_set(EXTENDED_UTF8_CHARACTER_SET)
+        .with_morse_code_set(EXTENDED_MORSE_CODE_SET)
+        .build();
+
+    encoder.encode_slice("Ñandü Ça").unwrap();
+
+    let mut text = String::new();
+    encoder.write_text(&TextFormat::DEFAULT, &mut text).unwrap();
+    assert_eq!(text, "--.-- .- -. -.. ..-- / -.-.. .-");
+
+    let mut decoder = Decoder::<16, char>::new()
+        .with_character_set(EXTENDED_UTF8_CHARACTER_SET)
+        .with_morse_code_set(EXTENDED_MORSE_CODE_SET)
+        .build();
+
+    decoder.decode_text(".-.- ---. ..-..", &TextFormat::DEFAULT);
+    assert_eq!(decoder.message.as_str().to_string(), "ÄÖÉ");
+}
+
+#[test]
+fn encoding_duplicate_characters() {
+    use morse_codec::{
+        text::TextFormat,
+        CharacterSet,
+        MorseCodeSet,
+        MorseSignal::{Long as L, Short as S},
+        MORSE_DEFAULT_CHAR,
+    };
+
+    // A is in the set twice, first one wins like the decoder's duplicate morse codes
+    const CHARACTER_SET: CharacterSet<u8> = b" AAT";
+    const MORSE_CODE_SET: MorseCodeSet = &[
+        MORSE_DEFAULT_CHAR,
+        [Some(S), Some(L), None, None, None, None],
+        [Some(S), Some(S), None, None, None, None],
+        [Some(L), None, None, None, None, None],
+    ];
+
+    let mut encoder = Encoder::<8, u8>::new()
+        .with_character_set(CHARACTER_SET)
+        .with_morse_code_set(MORSE_CODE_SET)
+        .build();
+
+    encoder.encode_slice("TA AT").unwrap();
+    assert!(encoder.encode_slice("E").is_err());
+
+    let mut text = String::new();
+    encoder.write_text(&TextFormat::DEFAULT, &mut text).unwrap();
+    assert_eq!(text, "- .- / .- -");
+}
+
+#[test]
+fn encoding_extra_punctuation() {
+    use morse_codec::{
+        charsets::{PUNCTUATION_EXTENDED_ASCII_CHARACTER_SET, PUNCTUATION_EXTENDED_MORSE_CODE_SET},
+        text::TextFormat,
+    };
+
+    let mut encoder = Encoder::<16, u8>::new()
+        .with_character_set(PUNCTUATION_EXTENDED_ASCII_CHARACTER_SET)
+        .with_morse_code_set(PUNCTUATION_EXTENDED_MORSE_CODE_SET)
+        .build();
+
+    encoder.encode_slice("HI!").unwrap();
+
+    let mut text = String::new();
+    encoder.write_text(&TextFormat::DEFAULT, &mut text).unwrap();
+    assert_eq!(text, ".... .. -.-.--");
+    assert!(encoder.encode_slice("&").is_err());
+
+    // Not in the default character set
+    let mut encoder = Encoder::<16, u8>::new().build();
+    assert!(encoder.encode_slice("!").is_err());
+}
+
+#[test]
+fn encoding_append_message() {
+    use morse_codec::encoder::{Separator, TimeUnit};
+
+    let mut encoder = Encoder::<32>::new().with_message("VVV", false).build();
+    encoder.append_message("DE N0CALL", Separator::Word).unwrap();
+    encoder.append_message("73", Separator::Paragraph).unwrap();
+    assert_eq!(encoder.message.as_str(), "VVV DE N0CALL = 73");
+
+    // Gaps between the messages are word spaces
+    let lows: Vec<u32> = encoder
+        .signal_durations(1, TimeUnit::Milliseconds)
+        .filter(|(_, is_high)| !is_high)
+        .map(|(mul, _)| mul)
+        .filter(|mul| *mul > 3)
+        .collect();
+    assert_eq!(lows, vec![7, 7, 7, 7]);
+
+    // No double word spaces
+    let mut encoder = Encoder::<16>::new().with_message("CQ ", true).build();
+    encoder.append_message("CQ", Separator::Word).unwrap();
+    encoder.append_message("K", Separator::Paragraph).unwrap();
+    assert_eq!(encoder.message.as_str(), "CQ CQ = K");
+
+    // Separator is left out of an empty message
+    let mut encoder = Encoder::<16>::new().build();
+    encoder.append_message("TEST", Separator::Paragraph).unwrap();
+    assert_eq!(encoder.message.as_str(), "TEST");
+
+    // Message is left as it was if they don't fit together
+    let mut encoder = Encoder::<8>::new().with_message("HELLO", true).build();
+    assert!(encoder.append_message("WORLD", Separator::Word).is_err());
+    assert!(encoder.append_message("X", Separator::Paragraph).is_err());
+    assert_eq!(encoder.message.as_str(), "HELLO");
+    encoder.append_message("X", Separator::Word).unwrap();
+    assert_eq!(encoder.message.as_str(), "HELLO X");
+}
+
+#[test]
+fn encoding_with_preamble() {
+    use morse_codec::encoder::{Preamble, TimeUnit};
+
+    let mut encoder = Encoder::<16>::new()
+        .with_message("E", true)
+        .with_preamble(Preamble::Vvv)
+        .build();
+    encoder.encode_message_all();
+
+    // Preamble isn't in the message or the outputs following it
+    assert_eq!(encoder.message.as_str(), "E");
+    assert_eq!(encoder.get_encoded_message_as_sdm_chunks().count(), 2);
+
+    // VVV is ...- three times, then a word space before the message
+    let v = [(1, true), (1, false), (1, true), (1, false), (1, true), (1, false), (3, true)];
+    let mut expected = Vec::new();
+    for index in 0..3 {
+        expected.extend(v);
+        expected.push((if index == 2 { 7 } else { 3 }, false));
+    }
+    expected.extend([(1, true), (3, false)]);
+
+    let durations: Vec<(u32, bool)> = encoder.signal_durations(1, TimeUnit::Milliseconds).collect();
+    assert_eq!(durations, expected);
+
+    let schedule: Vec<(u32, u32, bool)> = encoder.signal_schedule(60).collect();
+    assert_eq!(schedule.len(), expected.len());
+    assert_eq!(schedule.last(), Some(&((9 * 3 + 3 + 3 + 7 + 1) * 60, 180, false)));
+
+    // Preamble goes at the start speed of a speed ramp
+    let mut encoder = Encoder::<16>::new()
+        .with_message("EE", true)
+        .with_speed_ramp(10, 20)
+        .with_preamble(Preamble::Custom("<AR>"))
+        .build();
+    encoder.encode_message_all();
+
+    let durations: Vec<(u32, bool)> = encoder.ramped_durations().collect();
+    assert_eq!(durations, [
+        (120, true), (120, false), (360, true), (120, false), (120, true), (120, false), (360, true), (120, false),
+        (120, true), (840, false),
+        (120, true), (360, false), (60, true), (180, false),
+    ]);
+
+    // Characters not in the character set are left out of the preamble
+    let mut encoder = Encoder::<16>::new()
+        .with_message("T", true)
+        .with_preamble(Preamble::Custom("E\u{1F4E1}"))
+        .build();
+    encoder.encode_message_all();
+
+    let runs: Vec<(u32, bool)> = encoder.timeline_runs().collect();
+    assert_eq!(runs, [(1, true), (7, false), (3, true), (3, false)]);
+}
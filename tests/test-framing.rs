@@ -0,0 +1,84 @@
+use morse_codec::{
+    framing::{
+        Frame,
+        FrameParser,
+        FRAME_START,
+        MAX_FRAME_LENGTH,
+    },
+    Character,
+    SignalEvent,
+};
+
+fn parse_all(parser: &mut FrameParser, bytes: &[u8]) -> Vec<Result<Frame, &'static str>> {
+    bytes.iter().filter_map(|byte| parser.push_byte(*byte).transpose()).collect()
+}
+
+#[test]
+fn framing_round_trip() {
+    let frames = [
+        Frame::Character(b'S' as Character),
+        Frame::Signal(SignalEvent::new(100, true)),
+        Frame::Signal(SignalEvent::new(700, false)),
+        Frame::Character(b'?' as Character),
+    ];
+
+    // Some line noise before the first frame
+    let mut stream: Vec<u8> = vec![0x00, 0xFF, 0x13];
+    let mut buffer = [0u8; MAX_FRAME_LENGTH];
+
+    for frame in frames.iter() {
+        let length = frame.to_bytes(&mut buffer).unwrap();
+        println!("Frame {:?} as bytes: {:02X?}", frame, &buffer[..length]);
+        stream.extend_from_slice(&buffer[..length]);
+    }
+
+    let mut parser = FrameParser::new();
+    let parsed: Vec<Frame> = parse_all(&mut parser, &stream).into_iter().map(Result::unwrap).collect();
+
+    assert_eq!(parsed, frames);
+}
+
+#[test]
+fn framing_utf8_characters() {
+    let mut buffer = [0u8; MAX_FRAME_LENGTH];
+
+    let length = Frame::<char>::Character('Ç').to_bytes(&mut buffer).unwrap();
+    assert_eq!(length, 6);
+
+    let mut parser = FrameParser::<char>::new();
+    let parsed: Vec<_> = buffer[..length].iter().filter_map(|byte| parser.push_byte(*byte).transpose()).collect();
+    assert_eq!(parsed, vec![Ok(Frame::Character('Ç'))]);
+
+    // An ASCII receiver can't represent the character
+    let mut parser = FrameParser::<u8>::new();
+    let parsed: Vec<_> = buffer[..length].iter().filter_map(|byte| parser.push_byte(*byte).transpose()).collect();
+    assert!(parsed[0].is_err());
+}
+
+#[test]
+fn framing_errors() {
+    let mut small_buffer = [0u8; 4];
+    assert!(Frame::<u8>::Signal(SignalEvent::new(100, true)).to_bytes(&mut small_buffer).is_err());
+
+    let mut buffer = [0u8; MAX_FRAME_LENGTH];
+    let length = Frame::<Character>::Signal(SignalEvent::new(300, true)).to_bytes(&mut buffer).unwrap();
+
+    // Flip a bit in the payload
+    let mut corrupted = buffer;
+    corrupted[3] ^= 0x01;
+
+    let mut parser = FrameParser::new();
+    let mut stream = corrupted[..length].to_vec();
+    stream.extend_from_slice(&buffer[..length]);
+
+    let parsed = parse_all(&mut parser, &stream);
+    println!("Parsed corrupted stream: {:?}", parsed);
+
+    assert_eq!(parsed.len(), 2);
+    assert!(parsed[0].is_err());
+    assert_eq!(parsed[1], Ok(Frame::Signal(SignalEvent::new(300, true))));
+
+    // Payload length over the maximum
+    let parsed = parse_all(&mut parser, &[FRAME_START, 0x01, 0x09]);
+    assert!(parsed[0].is_err());
+}
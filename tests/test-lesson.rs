@@ -0,0 +1,102 @@
+use morse_codec::{
+    encoder::Encoder,
+    lesson::{Drill, Lesson, Rng, COMMON_WORDS, KOCH_ORDER, MAX_GROUP_LENGTH},
+};
+
+#[test]
+fn lesson_koch_characters() {
+    assert_eq!(Lesson::koch(0, 1).characters(), "KM");
+    assert_eq!(Lesson::koch(1, 1).characters(), "KM");
+    assert_eq!(Lesson::koch(2, 1).characters(), "KMU");
+    assert_eq!(Lesson::koch(100, 1).characters(), KOCH_ORDER);
+}
+
+#[test]
+fn lesson_groups() {
+    let mut lesson = Lesson::koch(3, 42);
+    let mut buffer = [0u8; 32];
+
+    for _ in 0..20 {
+        let group = lesson.next_group(Drill::Groups, 5, &mut buffer);
+        assert_eq!(group.len(), 5);
+        assert!(group.chars().all(|ch| "KMUR".contains(ch)));
+    }
+
+    let group = lesson.next_group(Drill::Groups, 100, &mut buffer);
+    assert_eq!(group.len(), MAX_GROUP_LENGTH);
+
+    // Cut to the buffer
+    let mut small = [0u8; 3];
+    assert_eq!(lesson.next_group(Drill::Groups, 5, &mut small).len(), 3);
+}
+
+#[test]
+fn lesson_pseudo_words() {
+    let mut lesson = Lesson::new("KMURE5/", 7);
+    let mut buffer = [0u8; 32];
+
+    for _ in 0..20 {
+        let group = lesson.next_group(Drill::PseudoWords, 6, &mut buffer);
+        assert_eq!(group.len(), 6);
+
+        let vowels: Vec<bool> = group.chars().map(|ch| "UE".contains(ch)).collect();
+        assert!(vowels.windows(2).all(|pair| pair[0] != pair[1]), "{group}");
+        assert!(group.chars().all(|ch| "KMURE".contains(ch)));
+    }
+
+    // No vowels to alternate with
+    let mut lesson = Lesson::new("KMR", 7);
+    let group = lesson.next_group(Drill::PseudoWords, 4, &mut buffer);
+    assert_eq!(group.len(), 4);
+}
+
+#[test]
+fn lesson_words() {
+    let mut lesson = Lesson::new("THEANDO", 3);
+    let mut buffer = [0u8; 32];
+
+    for _ in 0..20 {
+        let word = lesson.next_group(Drill::Words(COMMON_WORDS), 5, &mut buffer);
+        assert!(COMMON_WORDS.contains(&word), "{word}");
+        assert!(word.chars().all(|ch| "THEANDO".contains(ch)));
+    }
+
+    let mut lesson = Lesson::koch(1, 3);
+    assert_eq!(lesson.next_group(Drill::Words(COMMON_WORDS), 5, &mut buffer), "");
+}
+
+#[test]
+fn lesson_same_seed() {
+    let mut first = Lesson::koch(10, 99);
+    let mut second = Lesson::koch(10, 99);
+    let mut other = Lesson::koch(10, 100);
+    let (mut buffer1, mut buffer2, mut buffer3) = ([0u8; 8], [0u8; 8], [0u8; 8]);
+
+    let mut differs = false;
+    for _ in 0..10 {
+        let group = first.next_group(Drill::Groups, 5, &mut buffer1);
+        assert_eq!(group, second.next_group(Drill::Groups, 5, &mut buffer2));
+        differs |= group != other.next_group(Drill::Groups, 5, &mut buffer3);
+    }
+    assert!(differs);
+
+    let mut rng = Rng::new(0);
+    assert_ne!(rng.next_u32(), 0);
+    assert!((0..100).all(|_| rng.below(6) < 6));
+    assert_eq!(rng.below(0), 0);
+}
+
+#[test]
+fn lesson_write_to_encoder() {
+    let mut encoder = Encoder::<20, u8>::new().build();
+    let mut lesson = Lesson::koch(5, 11);
+
+    // 3 groups take 17 characters, the fourth doesn't fit
+    assert_eq!(lesson.write_to(&mut encoder, Drill::Groups, 5, 10), 3);
+    assert_eq!(encoder.message.len(), 17);
+
+    let words: Vec<&str> = encoder.message.as_str().split(' ').collect();
+    assert_eq!(words.len(), 3);
+    assert!(words.iter().all(|word| word.len() == 5));
+    assert!(encoder.get_encoded_message_as_morse_charrays().all(|charray| charray.is_some()));
+}
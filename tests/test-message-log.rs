@@ -0,0 +1,66 @@
+use morse_codec::{
+    decoder::{
+        Decoder,
+        Precision,
+    },
+    message::Message,
+    message_log::MessageLog,
+};
+
+#[test]
+fn message_log_ring_buffer() {
+    const MESSAGE_MAX_LENGTH: usize = 8;
+
+    let mut log = MessageLog::<2, MESSAGE_MAX_LENGTH>::new();
+    assert!(log.is_empty());
+    assert!(log.latest().is_none());
+
+    for (timestamp, text) in [(100, "CQ"), (200, "DE"), (300, "K")] {
+        log.push(Message::new(text, true, false), timestamp);
+    }
+
+    let entries: Vec<(u32, String)> = log.iter().map(|(ts, msg)| (ts, msg.to_string())).collect();
+    println!("Log entries: {:?}", entries);
+
+    // Oldest message is dropped
+    assert_eq!(log.len(), 2);
+    assert_eq!(log.capacity(), 2);
+    assert_eq!(entries, vec![(200, "DE".to_string()), (300, "K".to_string())]);
+
+    log.clear();
+    assert!(log.is_empty());
+}
+
+#[test]
+fn message_log_from_decoder() {
+    const MESSAGE_MAX_LENGTH: usize = 8;
+
+    let mut decoder = Decoder::<MESSAGE_MAX_LENGTH>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(100)
+        .with_message_pos_clamping()
+        .build();
+
+    let mut log = MessageLog::<4, MESSAGE_MAX_LENGTH>::new();
+
+    // "T" then a timeout
+    decoder.signal_event(300, true);
+    decoder.signal_event(300, false);
+    decoder.finish_message(&mut log, 1000);
+
+    // "N" with the last character not finished before the timeout
+    decoder.signal_event(300, true);
+    decoder.signal_event(100, false);
+    decoder.signal_event(100, true);
+    decoder.finish_message(&mut log, 2000);
+
+    // Nothing was decoded, nothing is logged
+    decoder.finish_message(&mut log, 3000);
+
+    let entries: Vec<(u32, String)> = log.iter().map(|(ts, msg)| (ts, msg.to_string())).collect();
+    println!("Log entries: {:?}", entries);
+
+    assert_eq!(entries, vec![(1000, "T".to_string()), (2000, "N".to_string())]);
+    assert!(decoder.message.is_empty());
+    assert!(decoder.message.is_edit_clamped());
+}
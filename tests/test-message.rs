@@ -1,6 +1,10 @@
-use morse_codec::decoder::{
-    Decoder,
-    Precision,
+use morse_codec::{
+    decoder::{
+        Decoder,
+        Precision,
+    },
+    message::Message,
+    Character,
 };
 
 // Create a message containing two SOS words separated by a word space
@@ -65,3 +69,381 @@ fn message_iter() {
     }
 }
 
+
+#[test]
+fn message_iter_both_ends() {
+    let message = Message::<8>::new("SOS", true, false);
+
+    let mut iter = message.iter();
+    assert_eq!(iter.len(), 3);
+    assert_eq!(iter.next_back(), Some(&(b'S' as Character)));
+    assert_eq!(iter.next(), Some(&(b'S' as Character)));
+    assert_eq!(iter.len(), 1);
+    assert_eq!(iter.next_back(), Some(&(b'O' as Character)));
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next_back(), None);
+
+    let reversed: Vec<Character> = message.iter().rev().copied().collect();
+    assert_eq!(reversed, [b'S', b'O', b'S'].map(|ch| ch as Character));
+
+    // A reference to the message can be looped over directly
+    let mut count = 0;
+    for ch in &message {
+        assert_ne!(*ch, b' ' as Character);
+        count += 1;
+    }
+    assert_eq!(count, message.len());
+}
+
+#[test]
+fn message_indexing() {
+    let mut message = Message::<8>::new("SOS", true, false);
+
+    assert_eq!(message[0], b'S' as Character);
+    assert_eq!(message[1], b'O' as Character);
+
+    message[1] = b'I' as Character;
+    assert_eq!(message.as_str(), "SIS");
+
+    // Writing right after the end appends
+    message[3] = b'T' as Character;
+    assert_eq!(message.as_str(), "SIST");
+    assert_eq!(message.len(), 4);
+
+    assert_eq!(&message[1..3], [b'I', b'S'].map(|ch| ch as Character));
+    assert_eq!(&message[2..], [b'S', b'T'].map(|ch| ch as Character));
+    assert_eq!(&message[..=1], [b'S', b'I'].map(|ch| ch as Character));
+    assert_eq!(message[..].len(), message.len());
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic]
+fn message_indexing_past_the_end() {
+    let message = Message::<8>::new("SOS", true, false);
+
+    let _ = message[5];
+}
+
+#[test]
+fn message_capacity_and_fullness() {
+    const MESSAGE_MAX_LENGTH: usize = 4;
+
+    let mut message = Message::<MESSAGE_MAX_LENGTH>::new("SO", true, false);
+    assert_eq!(message.capacity(), MESSAGE_MAX_LENGTH);
+    assert_eq!(message.remaining(), 2);
+    assert!(!message.is_full());
+
+    message.set_message("SOS ", true).unwrap();
+    assert_eq!(message.remaining(), 0);
+    assert!(message.is_full());
+
+    // Decoder drops characters once the message is full
+    let mut decoder = Decoder::<MESSAGE_MAX_LENGTH>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(100)
+        .with_message_full_handler(|_ch| false)
+        .build();
+
+    // Five E characters
+    for _ in 0..5 {
+        decoder.signal_event(100, true);
+        decoder.signal_event(300, false);
+    }
+
+    println!("Message when full: {}", decoder.message);
+
+    assert!(decoder.message.is_full());
+    assert_eq!(decoder.message.as_str(), "EEEE");
+    assert_eq!(decoder.message.get_last_changed_index(), 3);
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn message_heapless_interop() {
+    use morse_codec::{
+        encoder::Encoder,
+        message::Message,
+        Character,
+    };
+
+    const MESSAGE_MAX_LENGTH: usize = 16;
+
+    let string: heapless::String<32> = heapless::String::try_from("cq de k1abc").unwrap();
+    let message = Message::<MESSAGE_MAX_LENGTH>::from_heapless(&string, true, false);
+
+    let back: heapless::String<32> = message.to_heapless_string().unwrap();
+    println!("Message back from heapless string: {}", back);
+
+    assert_eq!(back.as_str(), "CQ DE K1ABC");
+    assert_eq!(message.to_heapless_string::<4>().err(), Some(morse_codec::Error::MessageTooLong));
+
+    let mut chars: heapless::Vec<Character, 8> = heapless::Vec::new();
+    chars.extend(b"sos".iter().map(|&ch| ch as Character));
+
+    let mut encoder = Encoder::<MESSAGE_MAX_LENGTH>::new().build();
+    encoder.encode_heapless_vec(&chars).unwrap();
+
+    assert_eq!(encoder.message.to_heapless_string::<8>().unwrap().as_str(), "SOS");
+}
+
+#[test]
+fn message_conversions() {
+    use morse_codec::Error;
+
+    let message = Message::<8>::try_from("sos sos").unwrap();
+    assert_eq!(message.as_str(), "SOS SOS");
+    assert_eq!(message.get_edit_pos(), 7);
+
+    let result = Message::<4>::try_from("sos sos");
+    assert_eq!(result.err(), Some(Error::MessageTooLong));
+
+    #[cfg(feature = "std")]
+    {
+        let string: String = (&message).into();
+        assert_eq!(string, "SOS SOS");
+        assert_eq!(String::from(message), "SOS SOS");
+    }
+
+    #[cfg(feature = "heapless")]
+    {
+        let message = Message::<8>::try_from("cq").unwrap();
+        let string = heapless::String::<8>::try_from(&message).unwrap();
+        assert_eq!(string.as_str(), "CQ");
+        assert_eq!(heapless::String::<1>::try_from(message).err(), Some(Error::MessageTooLong));
+    }
+}
+
+#[test]
+fn message_fallible_access() {
+    use morse_codec::{
+        encoder::Encoder,
+        Error,
+    };
+
+    const MESSAGE_MAX_LENGTH: usize = 4;
+
+    let mut message = Message::<MESSAGE_MAX_LENGTH, u8>::new("AB", true, false);
+
+    assert_eq!(message.try_char_at(1), Ok(b'B'));
+    assert_eq!(message.try_char_at(MESSAGE_MAX_LENGTH), Err(Error::IndexOutOfRange));
+    assert_eq!(message.try_as_str(), Ok("AB"));
+
+    // A byte that's not valid UTF-8 doesn't make as_str panic
+    message.put_char_at(1, 0xFF).unwrap();
+
+    assert_eq!(message.try_as_str(), Err(Error::InvalidUtf8));
+    assert_eq!(message.as_str(), "A");
+
+    let mut encoder = Encoder::<MESSAGE_MAX_LENGTH, u8>::new().with_message("SOS", true).build();
+    assert_eq!(encoder.try_encode_message_all(), Ok(()));
+
+    encoder.message.put_char_at(1, b'%').unwrap();
+    assert_eq!(encoder.try_encode_message_all(), Err(Error::UnknownCharacter));
+    assert!(encoder.encode_slice("%").is_err());
+}
+
+#[test]
+fn message_ref_runtime_capacity() {
+    use morse_codec::{
+        encoder::Encoder,
+        message::MessageRef,
+    };
+
+    let capacity = "SOS SOS".len() + 1;
+    let mut buffer = vec![0u8; capacity];
+
+    let mut message = MessageRef::from_storage(&mut buffer[..]);
+    assert_eq!(message.capacity(), capacity);
+    assert!(message.is_empty());
+
+    message.add_char(b'E');
+    assert_eq!(message.as_str(), "E");
+
+    let mut decoder = Decoder::from_message(message)
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(100)
+        .with_message("SOS", true)
+        .build();
+
+    // T, then a word space
+    decoder.signal_event(300, true);
+    decoder.signal_event(700, false);
+
+    assert_eq!(decoder.message.as_str(), "SOST ");
+    assert_eq!(decoder.message.remaining(), 3);
+
+    let mut buffer = [0u8; 4];
+    let mut encoder = Encoder::from_message(MessageRef::from_storage(&mut buffer[..]))
+        .with_message("SOS", true)
+        .build();
+
+    encoder.encode_message_all();
+    assert_eq!(encoder.get_encoded_message_as_morse_charrays().flatten().count(), 3);
+    assert!(encoder.encode_slice("EE").is_err());
+    assert_eq!(encoder.message.as_str(), "SOS");
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn message_vector_storage() {
+    use morse_codec::{decoder::MorseDecoder, FILLER};
+
+    // Messages of all sizes decoded by the same decoder type
+    fn decoder_of(capacity: usize) -> MorseDecoder<0, Character, Vec<Character>> {
+        Decoder::from_message(Message::from_storage(vec![FILLER; capacity]))
+            .with_precision(Precision::Accurate)
+            .with_reference_short_ms(100)
+            .build()
+    }
+
+    let mut decoders = [decoder_of(2), decoder_of(16)];
+
+    for decoder in decoders.iter_mut() {
+        assert!(decoder.message.is_empty());
+
+        for _ in 0..3 {
+            decoder.signal_event(100, true);
+            decoder.signal_event(300, false);
+        }
+    }
+
+    assert_eq!(decoders[0].message.capacity(), 2);
+    assert_eq!(decoders[0].message.as_str(), "EE");
+    assert_eq!(decoders[1].message.capacity(), 16);
+    assert_eq!(decoders[1].message.as_str(), "EEE");
+
+    #[cfg(feature = "heapless")]
+    {
+        let storage = heapless::Vec::<Character, 8>::from_slice(&[FILLER; 4]).unwrap();
+        let mut message = Message::<0, Character, _>::from_storage(storage);
+        message.set_message("sos", true).unwrap();
+
+        assert_eq!(message.capacity(), 4);
+        assert_eq!(message.as_str(), "SOS");
+    }
+}
+
+#[test]
+fn message_letter_case() {
+    use morse_codec::{
+        encoder::Encoder,
+        message::LetterCase,
+    };
+
+    let mut message = Message::<16>::new("Hello", true, false);
+    assert_eq!(message.letter_case(), LetterCase::Upper);
+    assert_eq!(message.as_str(), "HELLO");
+
+    message.set_letter_case(LetterCase::Preserve);
+    message.set_message("Hello World", true).unwrap();
+    assert_eq!(message.as_str(), "Hello World");
+
+    message.set_letter_case(LetterCase::Upper);
+    assert_eq!(message.as_str(), "HELLO WORLD");
+
+    // Decoded characters in lowercase
+    let mut decoder = Decoder::<16>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(100)
+        .with_letter_case(LetterCase::Lower)
+        .with_message("CQ ", true)
+        .build();
+
+    decoder.signal_event(100, true);
+    decoder.signal_event(100, false);
+    decoder.signal_event(300, true);
+    decoder.signal_event(300, false);
+
+    assert_eq!(decoder.message.as_str(), "cq a");
+
+    // UI text round-trips through the encoder and encodes the same as uppercase text
+    let mut encoder = Encoder::<16>::new()
+        .with_letter_case(LetterCase::Preserve)
+        .with_message("Sos", true)
+        .build();
+    encoder.encode_slice(" sOs").unwrap();
+
+    assert_eq!(encoder.message.as_str(), "Sos sOs");
+
+    let mut uppercase_encoder = Encoder::<16>::new().with_message("SOS SOS", true).build();
+    uppercase_encoder.encode_message_all();
+    encoder.encode_message_all();
+
+    assert!(encoder.get_encoded_message_as_sdm_arrays().eq(uppercase_encoder.get_encoded_message_as_sdm_arrays()));
+}
+
+#[test]
+fn message_without_fillers() {
+    const MESSAGE_MAX_LENGTH: usize = 8;
+
+    let mut message = Message::<MESSAGE_MAX_LENGTH, u8>::new("CQ", true, false);
+    message.set_edit_pos(4);
+    message.add_char(b'K');
+
+    assert_eq!(message.as_charray(), *b"CQ  K###");
+    assert_eq!(message.as_charray_trimmed(), *b"CQ  K   ");
+
+    let slots: Vec<Option<u8>> = message.slots().collect();
+    assert_eq!(slots.len(), MESSAGE_MAX_LENGTH);
+    assert_eq!(slots[..5], [Some(b'C'), Some(b'Q'), Some(b' '), Some(b' '), Some(b'K')]);
+    assert!(slots[5..].iter().all(Option::is_none));
+
+    message.clear();
+    assert!(message.slots().all(|slot| slot.is_none()));
+}
+
+#[test]
+fn message_revision() {
+    use morse_codec::message_log::MessageLog;
+
+    let mut message = Message::<8, u8>::new("CQ", true, false);
+    let mut rendered = message.revision();
+
+    let mut changed = |message: &Message<8, u8>| {
+        let is_changed = message.revision() != rendered;
+        rendered = message.revision();
+
+        is_changed
+    };
+
+    assert!(!changed(&message));
+    message.add_char(b'K');
+    assert!(changed(&message));
+    message.shift_edit_left();
+    assert!(changed(&message));
+    message[0] = b'Q';
+    assert!(changed(&message));
+    assert!(message.pop().is_some());
+    assert!(changed(&message));
+    message.clear();
+    assert!(changed(&message));
+
+    // Reading doesn't change the revision
+    let _ = (message.as_str(), message.len(), message.get_edit_pos());
+    assert!(!changed(&message));
+
+    // Equality doesn't depend on revisions
+    let mut moved = Message::<8, u8>::new("E", true, false);
+    moved.shift_edit_left();
+    moved.shift_edit_right();
+    assert_ne!(moved.revision(), Message::<8, u8>::new("E", true, false).revision());
+    assert_eq!(moved, Message::<8, u8>::new("E", true, false));
+
+    let mut decoder = Decoder::<8>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(100)
+        .build();
+    let mut rendered = decoder.message.revision();
+
+    decoder.signal_event(100, true);
+    assert_eq!(decoder.message.revision(), rendered);
+    decoder.signal_event(300, false);
+    assert_ne!(decoder.message.revision(), rendered);
+    rendered = decoder.message.revision();
+
+    // A new message after finishing the last one still counts as a change
+    let mut log = MessageLog::<2, 8>::new();
+    decoder.finish_message(&mut log, 0);
+    assert_ne!(decoder.message.revision(), rendered);
+}
@@ -0,0 +1,101 @@
+use morse_codec::{
+    contest::CutNumbers,
+    encoder::Encoder,
+    pileup::{Pileup, MAX_CALLSIGN_LENGTH},
+    recognizer::is_callsign,
+    sink::BufferSink,
+};
+
+#[test]
+fn pileup_callsigns() {
+    let mut pileup = Pileup::new(7);
+    let mut buffer = [0u8; MAX_CALLSIGN_LENGTH];
+
+    for _ in 0..200 {
+        let callsign = pileup.next_callsign(&mut buffer);
+        assert!(callsign.len() >= 3, "{callsign}");
+        assert!(is_callsign(callsign.as_bytes()), "{callsign}");
+    }
+
+    // Cut to the buffer
+    let mut small = [0u8; 2];
+    assert_eq!(pileup.next_callsign(&mut small).len(), 2);
+}
+
+#[test]
+fn pileup_stations() {
+    // Maximum speed below the minimum is raised to it
+    let mut pileup = Pileup::new(99).with_speed_range(25, 15).with_max_jitter(200);
+
+    for _ in 0..100 {
+        let station = pileup.next_station();
+        assert_eq!(station.wpm(), 25);
+        assert!(station.jitter_percent() <= 50);
+        assert!((1..=999).contains(&station.serial()));
+    }
+
+    let mut pileup = Pileup::new(99).with_speed_range(15, 35);
+    let wpms: Vec<u16> = (0..100).map(|_| pileup.next_station().wpm()).collect();
+    assert!(wpms.iter().all(|wpm| (15..=35).contains(wpm)));
+    assert!(wpms.iter().any(|&wpm| wpm != wpms[0]));
+
+    // Same seed, same stations
+    assert_eq!(Pileup::new(5).next_station(), Pileup::new(5).next_station());
+}
+
+#[test]
+fn pileup_exchange() {
+    let station = Pileup::new(3).next_station();
+    let mut buffer = [0u8; 32];
+
+    let exchange = station.exchange("{callsign} {rst} {serial}", CutNumbers::Off, &mut buffer).unwrap();
+    let fields: Vec<&str> = exchange.split(' ').collect();
+    assert_eq!(fields[0], station.callsign());
+    assert_eq!(fields[1], "599");
+    assert_eq!(fields[2].parse::<u16>().unwrap(), station.serial());
+    assert!(fields[2].len() >= 3);
+
+    let exchange = station.exchange("{rst}", CutNumbers::ZeroAndNine, &mut buffer).unwrap();
+    assert_eq!(exchange, "5NN");
+
+    assert!(station.exchange("{name}", CutNumbers::Off, &mut buffer).is_err());
+
+    let mut encoder = Encoder::<16, u8>::new().build();
+    station.write_to(&mut encoder, "{callsign}", CutNumbers::Off).unwrap();
+    station.write_to(&mut encoder, "{callsign}", CutNumbers::Off).unwrap();
+    assert_eq!(encoder.message.len(), station.callsign().len() * 2 + 1);
+
+    assert!(station.write_to(&mut encoder, "{callsign} {callsign}", CutNumbers::Off).is_err());
+}
+
+#[test]
+fn pileup_durations() {
+    let mut pileup = Pileup::new(11).with_speed_range(20, 20).with_max_jitter(0);
+    let station = pileup.next_station();
+
+    let mut encoder = Encoder::<16, u8>::new().with_message("EE", true).build();
+    encoder.encode_message_all();
+
+    // At 20 WPM a dit is 60 ms
+    let durations: Vec<(u32, bool)> = station.durations(&encoder).collect();
+    assert_eq!(durations, [(60, true), (180, false), (60, true), (180, false)]);
+
+    let mut pileup = Pileup::new(11).with_speed_range(20, 20).with_max_jitter(50);
+    let station = (0..100)
+        .map(|_| pileup.next_station())
+        .find(|station| station.jitter_percent() >= 20)
+        .unwrap();
+    let jitter = station.jitter_percent() as u32;
+
+    let durations: Vec<(u32, bool)> = station.durations(&encoder).collect();
+    assert_eq!(durations, station.durations(&encoder).collect::<Vec<_>>());
+    assert!(durations.iter().any(|&(duration_ms, _)| duration_ms != 60 && duration_ms != 180));
+    for (&(duration_ms, is_high), nominal_ms) in durations.iter().zip([60, 180, 60, 180]) {
+        assert!(duration_ms.abs_diff(nominal_ms) <= nominal_ms * jitter / 100, "{duration_ms} {is_high}");
+    }
+
+    let mut buffer = [(0, false); 8];
+    let mut sink = BufferSink::new(&mut buffer);
+    station.send_to(&encoder, &mut sink);
+    assert_eq!(sink.as_slice(), durations);
+}
@@ -0,0 +1,63 @@
+use morse_codec::{
+    message::Message,
+    recognizer::{
+        find_patterns,
+        is_callsign,
+        is_rst,
+        PatternKind,
+    },
+    Character,
+};
+
+fn to_chars<const N: usize>(word: &[u8; N]) -> [Character; N] {
+    word.map(|ch| ch as Character)
+}
+
+#[test]
+fn recognize_callsigns() {
+    for call in ["K1ABC", "W1AW", "9A1A", "2E0XYZ", "S51A", "VK2ABC", "DL/K1ABC/P", "K1ABC/QRP"] {
+        let chars: Vec<Character> = call.bytes().map(|ch| ch as Character).collect();
+        println!("Checking callsign: {}", call);
+        assert!(is_callsign(&chars));
+    }
+
+    for word in ["CQ", "DE", "599", "5NN", "K1", "ABC1", "K1ABCDE", "K1ABC/", "A/B/C/K1ABC"] {
+        let chars: Vec<Character> = word.bytes().map(|ch| ch as Character).collect();
+        println!("Checking not a callsign: {}", word);
+        assert!(!is_callsign(&chars));
+    }
+}
+
+#[test]
+fn recognize_rst() {
+    assert!(is_rst(&to_chars(b"599")));
+    assert!(is_rst(&to_chars(b"5NN")));
+    assert!(is_rst(&to_chars(b"337")));
+    assert!(!is_rst(&to_chars(b"699")));
+    assert!(!is_rst(&to_chars(b"509")));
+    assert!(!is_rst(&to_chars(b"59")));
+    assert!(!is_rst(&to_chars(b"5999")));
+}
+
+#[test]
+fn find_patterns_in_message() {
+    const MESSAGE_MAX_LENGTH: usize = 64;
+
+    let message = Message::<MESSAGE_MAX_LENGTH>::new("CQ CQ DE DL/K1ABC/P K  UR RST 579 579 BK", false, false);
+    let spans: Vec<_> = find_patterns(&message).collect();
+
+    for span in spans.iter() {
+        println!("Found {:?} at {}..{}", span.kind, span.start, span.end);
+    }
+
+    assert_eq!(spans.len(), 3);
+    assert_eq!(spans[0].kind, PatternKind::Callsign);
+    assert_eq!((spans[0].start, spans[0].end), (9, 19));
+    assert_eq!(spans[1].kind, PatternKind::Rst);
+    assert_eq!((spans[1].start, spans[1].end), (30, 33));
+    assert_eq!(spans[2].kind, PatternKind::Rst);
+    assert_eq!((spans[2].start, spans[2].end), (34, 37));
+
+    let empty = Message::<MESSAGE_MAX_LENGTH>::default();
+    assert!(find_patterns(&empty).next().is_none());
+}
@@ -0,0 +1,112 @@
+use morse_codec::{
+    decoder::{DecodeEvent, Decoder, Precision, RejectReason},
+    encoder::Encoder,
+    session::{Session, Turn},
+};
+
+fn new_session() -> Session<16, 32, u8> {
+    let decoder = Decoder::<16, u8>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(60)
+        .build();
+    let encoder = Encoder::<16, u8>::new().build();
+
+    Session::new(encoder, decoder, 60)
+}
+
+// Send signals of a character at 20 WPM from the remote side
+fn receive(session: &mut Session<16, 32, u8>, signals: &[bool]) {
+    for (index, &is_dah) in signals.iter().enumerate() {
+        session.signal_event(if is_dah { 180 } else { 60 }, true);
+        session.signal_event(if index + 1 == signals.len() { 180 } else { 60 }, false);
+    }
+}
+
+#[test]
+fn session_taking_turns() {
+    let mut session = new_session();
+    assert_eq!(session.turn(), Turn::Idle);
+    assert_eq!(session.next_duration(), None);
+
+    session.send("TE ").unwrap();
+    assert_eq!(session.turn(), Turn::Sending);
+    assert!(session.send("E").is_err());
+
+    let durations: Vec<(u32, bool)> = core::iter::from_fn(|| session.next_duration()).collect();
+    assert_eq!(durations, [(180, true), (180, false), (60, true), (180, false), (420, false)]);
+    assert_eq!(session.turn(), Turn::Idle);
+    assert!(!session.is_broken_in());
+    assert_eq!(session.transcript().as_str(), "TE");
+
+    // Remote operator answers with N
+    receive(&mut session, &[true, false]);
+    assert_eq!(session.turn(), Turn::Receiving);
+    assert_eq!(session.transcript().as_str(), "TE");
+
+    // Sending ends the remote over
+    session.send("A").unwrap();
+    assert_eq!(session.transcript().as_str(), "TE N");
+    assert!(session.decoder().message.is_empty());
+
+    while session.next_duration().is_some() {}
+    assert_eq!(session.transcript().as_str(), "TE N A");
+}
+
+#[test]
+fn session_break_in() {
+    let mut session = new_session();
+    session.send("ET").unwrap();
+
+    assert_eq!(session.next_duration(), Some((60, true)));
+    assert_eq!(session.next_duration(), Some((180, false)));
+
+    // Own key up gaps heard on the channel are not the remote side
+    assert_eq!(session.signal_event(180, false), DecodeEvent::Rejected(RejectReason::IdleLow));
+    assert_eq!(session.turn(), Turn::Sending);
+
+    receive(&mut session, &[false, false]);
+    assert!(session.is_broken_in());
+    assert_eq!(session.turn(), Turn::Receiving);
+    assert_eq!(session.next_duration(), None);
+
+    session.end_over();
+    assert_eq!(session.turn(), Turn::Idle);
+    assert_eq!(session.transcript().as_str(), "E I");
+
+    // Break-in flag lasts until the next over
+    session.send("T").unwrap();
+    assert!(!session.is_broken_in());
+}
+
+#[test]
+fn session_transcript_full() {
+    let mut session = new_session();
+
+    for _ in 0..10 {
+        session.send("TEST").unwrap();
+        while session.next_duration().is_some() {}
+    }
+
+    assert!(session.transcript().is_full());
+    assert!(session.transcript().as_str().starts_with("TEST TEST"));
+
+    session.transcript_mut().clear();
+    session.send("T").unwrap();
+    while session.next_duration().is_some() {}
+    assert_eq!(session.transcript().as_str(), "T");
+}
+
+#[test]
+fn session_transmission_gap() {
+    let decoder = Decoder::<16, u8>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(60)
+        .with_transmission_gap(5, None)
+        .build();
+    let mut session = Session::<16, 32, u8>::new(Encoder::<16, u8>::new().build(), decoder, 60);
+
+    session.signal_event(60, true);
+    assert_eq!(session.signal_event(3000, false), DecodeEvent::TransmissionEnded);
+    assert_eq!(session.turn(), Turn::Idle);
+    assert_eq!(session.transcript().as_str(), "E");
+}
@@ -0,0 +1,58 @@
+use morse_codec::{
+    encoder::{Encoder, MorseEncoder},
+    sink::{BufferSink, PinSink, SignalSink, ToneSink},
+};
+
+fn new_encoder(message: &str) -> MorseEncoder<16> {
+    let mut encoder = Encoder::<16>::new().with_message(message, true).build();
+    encoder.encode_message_all();
+
+    encoder
+}
+
+#[test]
+fn sink_buffer() {
+    let encoder = new_encoder("ET");
+
+    let mut buffer = [(0, false); 3];
+    let mut sink = BufferSink::new(&mut buffer);
+    encoder.send_to(&mut sink);
+
+    assert_eq!(sink.as_slice(), [(60, true), (180, false), (180, true)]);
+    assert_eq!(sink.dropped(), 1);
+
+    sink.clear();
+    sink.emit(420, false);
+    assert_eq!(sink.as_slice(), [(420, false)]);
+    assert_eq!(sink.dropped(), 0);
+}
+
+#[test]
+fn sink_pin_and_tone() {
+    let encoder = new_encoder("N");
+
+    let mut levels = Vec::new();
+    let mut elapsed_ms = 0;
+    let mut sink = PinSink::new(|is_high| levels.push(is_high), |ms| elapsed_ms += ms);
+    encoder.send_to(&mut sink);
+
+    assert_eq!(levels, [true, false, true, false]);
+    assert_eq!(elapsed_ms, 180 + 60 + 60 + 180);
+
+    let mut tones = Vec::new();
+    let mut sink = ToneSink::new(600, |hz| tones.push(hz), |_| {});
+    encoder.send_to(&mut sink);
+
+    assert_eq!(tones, [600, 0, 600, 0]);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn sink_vec() {
+    let encoder = new_encoder("E E");
+
+    let mut signals: Vec<(u32, bool)> = Vec::new();
+    encoder.send_to(&mut signals);
+
+    assert_eq!(signals, [(60, true), (420, false), (60, true), (180, false)]);
+}
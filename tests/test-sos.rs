@@ -0,0 +1,128 @@
+use morse_codec::{
+    decoder::{DecodeEvent, Decoder, Precision},
+    sos::SosDetector,
+};
+
+// Signals of a continuous SOS, with the space between the signals and after the last one
+fn sos_signals(short_ms: u16, space_ms: u16) -> Vec<(u16, bool)> {
+    [1, 1, 1, 3, 3, 3, 1, 1, 1]
+        .iter()
+        .flat_map(|units| [(units * short_ms, true), (space_ms, false)])
+        .collect()
+}
+
+fn detections(detector: &mut SosDetector, signals: &[(u16, bool)]) -> Vec<usize> {
+    signals
+        .iter()
+        .enumerate()
+        .filter(|&(_, &(duration_ms, is_high))| detector.signal_event(duration_ms, is_high))
+        .map(|(index, _)| index)
+        .collect()
+}
+
+#[test]
+fn sos_any_speed() {
+    for short_ms in [5, 40, 100, 600] {
+        let mut detector = SosDetector::new();
+        assert_eq!(detections(&mut detector, &sos_signals(short_ms, short_ms)), [16], "{short_ms}");
+    }
+
+    // Sloppy keying, dahs only twice as long as dits
+    let mut detector = SosDetector::new();
+    let signals = [(60, true), (50, false), (70, true), (60, false), (55, true), (60, false),
+        (130, true), (60, false), (120, true), (50, false), (140, true), (60, false),
+        (60, true), (70, false), (65, true), (60, false), (60, true)];
+    assert_eq!(detections(&mut detector, &signals), [16]);
+}
+
+#[test]
+fn sos_not_detected() {
+    // S O S as separate characters
+    let mut detector = SosDetector::new();
+    let mut signals = sos_signals(100, 100);
+    signals[5] = (300, false);
+    signals[11] = (300, false);
+    assert!(detections(&mut detector, &signals).is_empty());
+
+    // Signals are all the same
+    let mut detector = SosDetector::new();
+    assert!(detections(&mut detector, &sos_signals(0, 100)).is_empty());
+    let signals: Vec<(u16, bool)> = (0..20).map(|index| (100, index % 2 == 0)).collect();
+    assert!(detections(&mut detector, &signals).is_empty());
+
+    // Wrong order of dits and dahs
+    let mut detector = SosDetector::new();
+    let signals: Vec<(u16, bool)> = [3, 3, 3, 1, 1, 1, 3, 3, 3]
+        .iter()
+        .flat_map(|units| [(units * 100, true), (100, false)])
+        .collect();
+    assert!(detections(&mut detector, &signals).is_empty());
+}
+
+#[test]
+fn sos_stream() {
+    let mut detector = SosDetector::new();
+
+    // Noise before, low signals split up and an SOS right after another
+    let mut signals = vec![(300, true), (100, false), (100, true), (700, false)];
+    let mut sos = sos_signals(80, 80);
+    sos.insert(8, (40, false));
+    sos[7] = (40, false);
+    signals.extend(&sos);
+    signals.extend(&sos);
+
+    assert_eq!(detections(&mut detector, &signals), [4 + 17, 4 + 19 + 17]);
+
+    // Reset forgets the signals so far
+    let sos = sos_signals(80, 80);
+    detections(&mut detector, &sos[..10]);
+    detector.reset();
+    assert!(detections(&mut detector, &sos[10..]).is_empty());
+}
+
+#[test]
+fn sos_decoder_event() {
+    let build = |sos_detection: bool| {
+        let builder = Decoder::<16, u8>::new()
+            .with_precision(Precision::Accurate)
+            .with_reference_short_ms(100);
+
+        if sos_detection { builder.with_sos_detection() } else { builder }.build()
+    };
+
+    // Decoder is calibrated for a much slower speed
+    let signals = sos_signals(30, 30);
+    let mut decoder = build(true);
+    let events: Vec<DecodeEvent<u8>> = signals
+        .iter()
+        .map(|&(duration_ms, is_high)| decoder.signal_event_ex(duration_ms, is_high))
+        .collect();
+
+    assert_eq!(events.iter().filter(|&&event| event == DecodeEvent::SosDetected).count(), 1);
+    assert_eq!(events[16], DecodeEvent::SosDetected);
+
+    // Off by default
+    let mut decoder = build(false);
+    assert!(signals
+        .iter()
+        .all(|&(duration_ms, is_high)| decoder.signal_event_ex(duration_ms, is_high) != DecodeEvent::SosDetected));
+}
+
+#[cfg(not(feature = "small-footprint"))]
+#[test]
+fn sos_after_redecode() {
+    let mut decoder = Decoder::<16, u8>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(100)
+        .with_sos_detection()
+        .build();
+
+    decoder.extend_signals([(100, true), (300, false), (300, true), (700, false)]);
+    decoder.redecode_with(|builder| builder.with_reference_short_ms(80));
+
+    // SOS detection is still on for the rebuilt decoder
+    let signals = sos_signals(30, 30);
+    assert!(signals
+        .iter()
+        .any(|&(duration_ms, is_high)| decoder.signal_event_ex(duration_ms, is_high) == DecodeEvent::SosDetected));
+}
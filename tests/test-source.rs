@@ -0,0 +1,77 @@
+use morse_codec::{
+    decoder::{Decoder, MorseDecoder, Precision},
+    source::{PinSampler, SignalSource, SliceSource},
+};
+
+fn new_decoder() -> MorseDecoder<16> {
+    Decoder::<16>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(100)
+        .build()
+}
+
+#[test]
+fn source_slice() {
+    let signals = [(100, true), (100, false), (300, true), (700, false), (300, true), (300, false)];
+
+    let mut source = SliceSource::new(&signals);
+    assert_eq!(source.next_event(), Some((100, true)));
+
+    let mut decoder = new_decoder();
+    decoder.drain(&mut source);
+    // First dit was taken out of the source above
+    assert_eq!(decoder.message.as_str(), "T T");
+    assert_eq!(source.next_event(), None);
+
+    let mut decoder = new_decoder();
+    decoder.drain(&mut SliceSource::new(&signals));
+    assert_eq!(decoder.message.as_str(), "A T");
+}
+
+#[test]
+fn source_pin_sampler() {
+    // 10 ms samples: a leading low, a dit, a character space, a dah and the key left up
+    let levels = [(3, false), (10, true), (10, false), (30, true), (60, false)];
+    let mut samples = levels
+        .iter()
+        .flat_map(|&(count, level)| core::iter::repeat_n(level, count));
+
+    let mut source = PinSampler::new(move || samples.next().unwrap_or(false), 10, 500);
+
+    assert_eq!(source.next_event(), Some((30, false)));
+    assert_eq!(source.next_event(), Some((100, true)));
+    assert_eq!(source.next_event(), Some((100, false)));
+    assert_eq!(source.next_event(), Some((300, true)));
+    // Key is idle
+    assert_eq!(source.next_event(), Some((500, false)));
+    assert_eq!(source.next_event(), None);
+
+    let mut samples = levels
+        .iter()
+        .flat_map(|&(count, level)| core::iter::repeat_n(level, count));
+    let mut source = PinSampler::new(move || samples.next().unwrap_or(false), 10, 500);
+
+    let mut decoder = new_decoder();
+    decoder.drain(&mut source);
+    assert_eq!(decoder.message.as_str(), "A");
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn source_channel() {
+    use std::{sync::mpsc, thread};
+
+    let (sender, mut receiver) = mpsc::channel();
+
+    let keying = thread::spawn(move || {
+        for event in [(300, true), (100, false), (300, true), (100, false), (300, true), (300, false)] {
+            sender.send(event).unwrap();
+        }
+    });
+
+    let mut decoder = new_decoder();
+    decoder.drain(&mut receiver);
+    keying.join().unwrap();
+
+    assert_eq!(decoder.message.as_str(), "O");
+}
@@ -0,0 +1,105 @@
+use morse_codec::{
+    contest::CutNumbers,
+    decoder::{Decoder, Precision},
+    encoder::{Encoder, TimeUnit},
+    telemetry::{TelemetryFormat, MAX_WIDTH},
+};
+
+#[test]
+fn telemetry_round_trip() {
+    let readings = [0, 7, 42, 999, 1000, 31415, 99999];
+    let mut buffer = [0u8; 64];
+    let mut decoded = [0u32; 8];
+
+    for cut_numbers in [CutNumbers::Off, CutNumbers::ZeroAndNine, CutNumbers::All] {
+        let format = TelemetryFormat::new(5).with_cut_numbers(cut_numbers);
+        let frame = format.encode(&readings, &mut buffer).unwrap();
+        assert_eq!(frame.len(), readings.len() * 5 + 1);
+
+        assert_eq!(format.decode(frame, &mut decoded), Ok(readings.len()));
+        assert_eq!(decoded[..readings.len()], readings);
+    }
+
+    // Widest readings
+    let format = TelemetryFormat::new(20);
+    assert_eq!(format.width(), MAX_WIDTH);
+    let frame = format.encode(&[u32::MAX, 1], &mut buffer).unwrap();
+    assert!(frame.starts_with("42949672950000000001"));
+    assert_eq!(format.decode(frame, &mut decoded), Ok(2));
+    assert_eq!(decoded[..2], [u32::MAX, 1]);
+
+    // Spaces and lowercase letters
+    let format = TelemetryFormat::new(2).with_cut_numbers(CutNumbers::All);
+    let frame = format.encode(&[10, 95], &mut buffer).unwrap().to_ascii_lowercase();
+    assert_eq!(format.decode(&format!(" {frame} "), &mut decoded), Ok(2));
+    assert_eq!(decoded[..2], [10, 95]);
+}
+
+#[test]
+fn telemetry_errors() {
+    let format = TelemetryFormat::new(3);
+    let mut readings = [0u32; 2];
+
+    assert!(format.encode(&[1000], &mut [0u8; 8]).is_err());
+    assert!(format.encode(&[1, 2], &mut [0u8; 6]).is_err());
+
+    let mut buffer = [0u8; 16];
+    let frame = format.encode(&[123, 456], &mut buffer).unwrap();
+    let checksum = &frame[6..];
+
+    assert!(format.decode("", &mut readings).is_err());
+    assert!(format.decode(checksum, &mut readings).is_err());
+    assert!(format.decode(&frame[1..], &mut readings).is_err());
+    assert!(format.decode(&format!("12345X{checksum}"), &mut readings).is_err());
+    assert!(format.decode(frame, &mut [0u32; 1]).is_err());
+
+    // Every wrong digit and swapped neighbour digits are caught
+    let digits: Vec<u8> = frame.bytes().take(6).collect();
+    for index in 0..6 {
+        for digit in b'0'..=b'9' {
+            let mut wrong = digits.clone();
+            wrong[index] = digit;
+
+            let wrong = format!("{}{checksum}", String::from_utf8(wrong).unwrap());
+            assert_eq!(format.decode(&wrong, &mut readings).is_ok(), wrong == frame, "{wrong}");
+        }
+
+        if index < 5 {
+            let mut swapped = digits.clone();
+            swapped.swap(index, index + 1);
+
+            let swapped = format!("{}{checksum}", String::from_utf8(swapped).unwrap());
+            assert!(format.decode(&swapped, &mut readings).is_err(), "{swapped}");
+        }
+    }
+
+    // Cut numbers are digits only if both sides cut them
+    let cut_frame = format.with_cut_numbers(CutNumbers::ZeroAndNine).encode(&[90, 9], &mut buffer).unwrap();
+    assert_eq!(&cut_frame[..6], "TNTTTN");
+    assert!(format.decode(cut_frame, &mut readings).is_err());
+}
+
+#[test]
+fn telemetry_over_morse() {
+    let format = TelemetryFormat::new(4).with_cut_numbers(CutNumbers::All);
+    let mut buffer = [0u8; 32];
+    let frame = format.encode(&[2150, 37, 1013], &mut buffer).unwrap();
+
+    let mut encoder = Encoder::<32, u8>::new().with_message(frame, true).build();
+    encoder.encode_message_all();
+
+    let mut decoder = Decoder::<32, u8>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(20)
+        .build();
+    decoder.extend_signals(
+        encoder
+            .signal_durations(20, TimeUnit::Milliseconds)
+            .map(|(duration_ms, is_high)| (duration_ms as u16, is_high)),
+    );
+    decoder.signal_event_end(true);
+
+    let mut readings = [0u32; 4];
+    assert_eq!(format.decode_message(&decoder.message, &mut readings), Ok(3));
+    assert_eq!(readings[..3], [2150, 37, 1013]);
+}
@@ -0,0 +1,47 @@
+use morse_codec::{
+    decoder::{Decoder, Precision},
+    encoder::{Encoder, TimeUnit},
+};
+
+use std::{
+    sync::{mpsc, Arc, Mutex},
+    thread,
+};
+
+#[test]
+fn threads_encode_and_decode() {
+    let mut encoder = Encoder::<16, u8>::new().with_message("SOS", true).build();
+    let decoder = Decoder::<16, u8>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(100)
+        .build();
+
+    let (sender, receiver) = mpsc::channel();
+
+    // Encoder moves into a worker thread
+    let encoding = thread::spawn(move || {
+        encoder.encode_message_all();
+        for (duration_ms, is_high) in encoder.signal_durations(100, TimeUnit::Milliseconds) {
+            sender.send((duration_ms as u16, is_high)).unwrap();
+        }
+
+        encoder
+    });
+
+    // Decoder is shared behind a lock
+    let decoder = Arc::new(Mutex::new(decoder));
+    let decoding = thread::spawn({
+        let decoder = Arc::clone(&decoder);
+
+        move || {
+            for (duration_ms, is_high) in receiver {
+                decoder.lock().unwrap().signal_event(duration_ms, is_high);
+            }
+        }
+    });
+
+    let encoder = encoding.join().unwrap();
+    decoding.join().unwrap();
+
+    assert_eq!(decoder.lock().unwrap().message.as_str(), encoder.message.as_str());
+}
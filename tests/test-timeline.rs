@@ -0,0 +1,62 @@
+use morse_codec::{
+    decoder::{Decoder, Precision},
+    encoder::{Encoder, TimeUnit},
+    timeline::{expand, quantize},
+};
+
+#[test]
+fn timeline_of_encoder_and_decoding_session() {
+    let mut encoder = Encoder::<16>::new().with_message("SOS", true).build();
+    encoder.encode_message_all();
+
+    let runs: Vec<(u32, bool)> = encoder.timeline_runs().collect();
+    assert_eq!(runs.iter().map(|(units, _)| units).sum::<u32>(), 5 + 3 + 11 + 3 + 5 + 3);
+
+    // Signals a decoder received from the encoder quantize back to the same timeline
+    let signals: Vec<(u16, bool)> = encoder.signal_durations(80, TimeUnit::Milliseconds)
+        .map(|(duration_ms, is_high)| (duration_ms as u16, is_high))
+        .collect();
+
+    let mut decoder = Decoder::<16>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(80)
+        .build();
+    decoder.extend_signals(signals.iter().copied());
+    assert_eq!(decoder.message.as_str(), "SOS");
+
+    assert!(quantize(signals, 80).eq(runs.iter().copied()));
+
+    let bits: Vec<bool> = expand(runs).collect();
+    assert_eq!(&bits[..6], [true, false, true, false, true, false]);
+}
+
+#[test]
+fn timeline_quantize_merges_and_rounds() {
+    // Split up signal periods and very short glitches
+    let signals = [(30, true), (40, true), (10, false), (250, false), (0, true)];
+
+    let runs: Vec<(u32, bool)> = quantize(signals, 100).collect();
+    assert_eq!(runs, [(2, true), (4, false), (1, true)]);
+
+    // Zero short duration doesn't divide by zero
+    assert_eq!(quantize([(5, true)], 0).next(), Some((5, true)));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn timeline_diagrams() {
+    use morse_codec::timeline::{ascii_diagram, svg_diagram};
+
+    let mut encoder = Encoder::<8>::new().with_message("EA", true).build();
+    encoder.encode_message_all();
+
+    let ascii = ascii_diagram(encoder.timeline_runs());
+    println!("{}", ascii);
+    assert_eq!(ascii, "#___#_###___");
+
+    let svg = svg_diagram(encoder.timeline_runs(), 4, 10);
+    println!("{}", svg);
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"48\" height=\"10\""));
+    assert!(svg.contains("d=\"M0,9V1H4V9H16V1H20V9H24V1H36V9H48\""));
+    assert!(svg.ends_with("</svg>"));
+}
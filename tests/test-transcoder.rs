@@ -0,0 +1,88 @@
+use morse_codec::{
+    decoder::{Decoder, Precision},
+    encoder::{Encoder, TimeUnit},
+    transcoder::Transcoder,
+};
+
+#[test]
+fn transcoding_echo() {
+    let decoder = Decoder::<16>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(100)
+        .build();
+    let encoder = Encoder::<16>::new().with_message("IGNORED", true).build();
+
+    let mut transcoder = Transcoder::new(decoder, encoder);
+    assert!(transcoder.message().is_empty());
+
+    transcoder.decoder().decode_text("-.-. --.-", &Default::default());
+    assert_eq!(transcoder.message().as_str(), "CQ");
+
+    // Encoder sees the decoded message and continues at its edit position
+    let durations: Vec<(u32, bool)> = transcoder.with_encoder(|encoder| {
+        encoder.encode_slice(" DE").unwrap();
+        encoder.signal_durations(100, TimeUnit::Milliseconds).collect()
+    });
+    assert_eq!(transcoder.message().as_str(), "CQ DE");
+    assert_eq!(durations.first(), Some(&(300, true)));
+
+    // Edits to the message are seen by both
+    transcoder.message_mut().clear();
+    transcoder.decoder().decode_text("..", &Default::default());
+    assert_eq!(transcoder.with_encoder(|encoder| encoder.message.len()), 1);
+
+    let (decoder, encoder) = transcoder.into_parts();
+    assert_eq!(decoder.message.as_str(), "I");
+    assert!(encoder.message.is_empty());
+}
+
+#[test]
+fn transcoding_relay() {
+    use morse_codec::{transcoder::Relay, Character};
+
+    const SHORT: u16 = 100;
+
+    let decoder = Decoder::<4>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(SHORT)
+        .build();
+
+    // Retransmit twice as fast
+    let mut relay = Relay::new(Transcoder::new(decoder, Encoder::<4>::new().build()), SHORT as u32 / 2);
+
+    // Replace E with I, drop T
+    relay.set_transform(Some(|ch| {
+        if ch == b'E' as Character {
+            Some(b'I' as Character)
+        } else if ch == b'T' as Character {
+            None
+        } else {
+            Some(ch)
+        }
+    }));
+
+    // E T, then a word space
+    relay.signal_event(SHORT, true);
+    relay.signal_event(SHORT * 3, false);
+    relay.signal_event(SHORT * 3, true);
+    relay.signal_event(SHORT * 7, false);
+
+    assert_eq!(relay.transcoder().message().as_str(), "I ");
+
+    let durations: Vec<(u32, bool)> = core::iter::from_fn(|| relay.next_duration()).collect();
+    assert_eq!(durations, [(50, true), (50, false), (50, true), (150, false), (350, false)]);
+
+    // A full message is cleared once it's relayed
+    for _ in 0..2 {
+        relay.signal_event(SHORT * 3, true);
+        relay.signal_event(SHORT, false);
+        relay.signal_event(SHORT * 3, true);
+        relay.signal_event(SHORT * 3, false);
+    }
+    assert_eq!(relay.transcoder().message().as_str(), "I MM");
+
+    while relay.next_duration().is_some() {}
+    relay.signal_event(SHORT * 3, true);
+    relay.signal_event(SHORT * 3, false);
+    assert_eq!(relay.transcoder().message().as_str(), "");
+}
@@ -0,0 +1,50 @@
+#![cfg(all(feature = "std", feature = "encoder", feature = "decoder"))]
+use std::io::Cursor;
+
+use morse_codec::{
+    decoder::{Decoder, Precision},
+    encoder::Encoder,
+    wav::{decode_from_wav, encode_to_wav, WavParams},
+};
+
+#[test]
+fn wav_encode_decode_roundtrip() {
+    const MESSAGE_MAX_LENGTH: usize = 32;
+
+    let mut encoder = Encoder::<MESSAGE_MAX_LENGTH>::new()
+        .with_message("CQ DE K1ABC", true)
+        .build();
+    encoder.encode_message_all();
+
+    let params = WavParams {
+        sample_rate: 11025,
+        tone_hz: 700.0,
+        short_ms: 60,
+        amplitude: 0.5,
+    };
+
+    let mut wav_bytes: Vec<u8> = Vec::new();
+    encode_to_wav(&encoder, &mut wav_bytes, &params).unwrap();
+
+    println!("WAV file size: {} bytes", wav_bytes.len());
+    assert_eq!(&wav_bytes[0..4], b"RIFF");
+
+    let mut decoder = Decoder::<MESSAGE_MAX_LENGTH>::new()
+        .with_precision(Precision::Accurate)
+        .with_reference_short_ms(params.short_ms)
+        .build();
+
+    decode_from_wav(Cursor::new(wav_bytes), &mut decoder, &params).unwrap();
+
+    println!("Message decoded from WAV: {}", decoder.message);
+
+    assert_eq!(decoder.message.to_string(), "CQ DE K1ABC");
+}
+
+#[test]
+fn wav_decode_invalid_file() {
+    let mut decoder = Decoder::<8>::new().build();
+    let result = decode_from_wav(Cursor::new(b"NOT A WAV FILE"), &mut decoder, &WavParams::default());
+
+    assert!(result.is_err());
+}